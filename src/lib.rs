@@ -6,8 +6,10 @@
 #![cfg_attr(feature = "extend_one", feature(extend_one))]
 #![cfg_attr(feature = "hasher_prefixfree_extras", feature(hasher_prefixfree_extras))]
 #![cfg_attr(feature = "inplace_iteration", feature(inplace_iteration))]
+#![cfg_attr(feature = "iter_advance_by", feature(iter_advance_by))]
 #![cfg_attr(feature = "maybe_uninit_slice", feature(maybe_uninit_slice))]
 #![cfg_attr(feature = "new_uninit", feature(new_uninit))]
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
 #![cfg_attr(feature = "slice_ptr_get", feature(slice_ptr_get))]
 #![cfg_attr(feature = "specialization", feature(specialization))]
 #![cfg_attr(feature = "trusted_len", feature(trusted_len))]
@@ -27,15 +29,26 @@ pub mod ripytide;
 
 // port of stdlib implementation
 mod liballoc;
+pub use liballoc::collections::binary_heap;
+pub use liballoc::collections::btree_interval_map;
 pub use liballoc::collections::btree_map;
+pub use liballoc::collections::btree_set;
+pub use liballoc::collections::btree_summary_map;
 
-//#[cfg(not(no_global_oom_handling))]
-//#[doc(no_inline)]
-//pub use binary_heap::BinaryHeap;
+#[doc(no_inline)]
+pub use binary_heap::BinaryHeap;
+
+#[doc(no_inline)]
+pub use btree_interval_map::IntervalMap;
 
 #[doc(no_inline)]
 pub use btree_map::BTreeMap;
 
-//#[cfg(not(no_global_oom_handling))]
-//#[doc(no_inline)]
-//pub use btree_set::BTreeSet;
+#[doc(no_inline)]
+pub use btree_set::BTreeSet;
+
+#[doc(no_inline)]
+pub use btree_summary_map::SummaryMap;
+
+#[doc(no_inline)]
+pub use ripytide::OrderedMap;
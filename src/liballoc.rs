@@ -1,7 +1,11 @@
 #![allow(
     clippy::clone_on_copy,
+    clippy::doc_lazy_continuation,
     clippy::drop_non_drop,
+    clippy::four_forward_slashes,
+    clippy::multiple_bound_locations,
     clippy::needless_borrow,
+    clippy::question_mark,
     clippy::toplevel_ref_arg,
     clippy::type_complexity,
     clippy::unnecessary_mut_passed,
@@ -18,8 +22,10 @@
         clippy::derive_ord_xor_partial_ord,
         clippy::explicit_counter_loop,
         clippy::ifs_same_cond,
+        clippy::manual_hash_one,
         clippy::map_identity,
         clippy::needless_range_loop,
+        clippy::non_canonical_partial_ord_impl,
         clippy::redundant_clone,
         clippy::redundant_closure,
         clippy::single_char_add_str,
@@ -29,8 +35,7 @@
 )]
 
 pub mod collections {
-    //#[cfg(not(no_global_oom_handling))]
-    //pub mod binary_heap;
+    pub mod binary_heap;
 
     mod btree;
 
@@ -39,11 +44,21 @@ pub mod collections {
         pub use super::btree::map::*;
     }
 
-    //#[cfg(not(no_global_oom_handling))]
-    //pub mod btree_set {
-        ////! An ordered set based on a B-Tree.
-        //pub use super::btree::set::*;
-    //}
+    pub mod btree_set {
+        //! An ordered set based on a B-Tree.
+        pub use super::btree::set::*;
+    }
+
+    pub mod btree_interval_map {
+        //! An ordered map of half-open intervals based on a B-Tree.
+        pub use super::btree::interval_map::*;
+    }
+
+    pub mod btree_summary_map {
+        //! An ordered map based on a B-Tree that keeps a summary of its elements
+        //! in every node.
+        pub use super::btree::summary_map::*;
+    }
 
     /// An intermediate trait for specialization of `Extend`.
     #[doc(hidden)]
@@ -17,8 +17,7 @@
 //! [dir_graph]: https://en.wikipedia.org/wiki/Directed_graph
 //!
 //! ```
-//! use copse::{BinaryHeap, SortableBy, TotalOrder};
-//! use std::cmp::Ordering;
+//! use btree_monstrousity::BinaryHeap;
 //!
 //! #[derive(Copy, Clone)]
 //! struct State {
@@ -26,21 +25,6 @@
 //!     position: usize,
 //! }
 //!
-//! // A reversed-cost order for `State`
-//! struct ReversedCostOrder;
-//!
-//! impl TotalOrder for ReversedCostOrder {
-//!     type OrderedType = State;
-//!     fn cmp(&self, this: &State, that: &State) -> Ordering {
-//!         // Notice that the we flip the ordering on costs.
-//!         that.cost.cmp(&this.cost)
-//!     }
-//! }
-//!
-//! impl SortableBy<ReversedCostOrder> for State {
-//!     fn sort_key(&self) -> &State { self }
-//! }
-//!
 //! // Each node is represented as a `usize`, for a shorter implementation.
 //! struct Edge {
 //!     node: usize,
@@ -57,7 +41,9 @@
 //!     // dist[node] = current shortest distance from `start` to `node`
 //!     let mut dist: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
 //!
-//!     let mut heap = BinaryHeap::new(ReversedCostOrder);
+//!     // Notice that the we flip the ordering on costs, so that the heap
+//!     // hands out the cheapest state first.
+//!     let mut heap = BinaryHeap::new_by(|this: &State, that: &State| that.cost.cmp(&this.cost));
 //!
 //!     // We're at `start`, with a zero cost
 //!     dist[start] = 0;
@@ -137,6 +123,7 @@
 
 #![allow(missing_docs)]
 
+use core::cmp::Ordering;
 use core::fmt;
 #[cfg(feature = "trusted_len")]
 use core::iter::TrustedLen;
@@ -154,10 +141,6 @@ use alloc::vec::{self, Vec};
 use cfg_if::cfg_if;
 
 use super::SpecExtend;
-use crate::{
-    default::{OrdStoredKey, OrdTotalOrder},
-    SortableBy, SortableByWithOrder, TotalOrder,
-};
 
 #[cfg(test)]
 mod tests;
@@ -166,9 +149,14 @@ mod tests;
 ///
 /// This will be a max-heap.
 ///
-/// It is a logic error for an item or total order to be modified in such a way
+/// The heap owns the comparator it is ordered by: a closure handed two items that
+/// returns how the first compares to the second. [`BinaryHeap::new`] and friends
+/// use the items' [`Ord`] implementation, while [`BinaryHeap::new_by`] and friends
+/// accept any comparator.
+///
+/// It is a logic error for an item or the comparator to be modified in such a way
 /// that the item's ordering relative to any other item, as determined by that
-/// total order, changes while they are in the heap. This is normally only possible
+/// comparator, changes while they are in the heap. This is normally only possible
 /// through interior mutability, global state, I/O, or unsafe code. The behavior
 /// resulting from such a logic error is not specified, but will be encapsulated
 /// to the `BinaryHeap` that observed the logic error and not result in undefined
@@ -186,11 +174,11 @@ mod tests;
 /// # Examples
 ///
 /// ```
-/// use copse::BinaryHeap;
+/// use btree_monstrousity::BinaryHeap;
 ///
 /// // Type inference lets us omit an explicit type signature (which
-/// // would be `BinaryHeap<i32, OrdTotalOrder<i32>>` in this example).
-/// let mut heap = BinaryHeap::default();
+/// // would be `BinaryHeap<i32>` in this example).
+/// let mut heap = BinaryHeap::new();
 ///
 /// // We can use peek to look at the next item in the heap. In this case,
 /// // there's no items in there yet so we get None.
@@ -229,34 +217,21 @@ mod tests;
 /// A `BinaryHeap` with a known list of items can be initialized from an array:
 ///
 /// ```
-/// use copse::BinaryHeap;
+/// use btree_monstrousity::BinaryHeap;
 ///
 /// let heap = BinaryHeap::from([1, 5, 2]);
 /// ```
 ///
 /// ## Min-heap
 ///
-/// Either [`core::cmp::Reverse`], a custom [`Ord`] implementation or a custom
-/// [`TotalOrder`] implementation can be used to make `BinaryHeap` a min-heap.
-/// This makes `heap.pop()` return the smallest value instead of the greatest one.
+/// Either [`core::cmp::Reverse`], a custom [`Ord`] implementation or a reversed
+/// comparator can be used to make `BinaryHeap` a min-heap. This makes `heap.pop()`
+/// return the smallest value instead of the greatest one.
 ///
 /// ```
-/// use copse::{BinaryHeap, SortableBy, TotalOrder};
-/// use std::cmp::Ordering;
-///
-/// struct ReversedOrder;
-/// impl TotalOrder for ReversedOrder {
-///     type OrderedType = i32;
-///     fn cmp(&self, this: &i32, that: &i32) -> Ordering {
-///         that.cmp(this)
-///     }
-/// }
-///
-/// impl SortableBy<ReversedOrder> for i32 {
-///     fn sort_key(&self) -> &i32 { self }
-/// }
+/// use btree_monstrousity::BinaryHeap;
 ///
-/// let mut heap = BinaryHeap::new(ReversedOrder);
+/// let mut heap = BinaryHeap::new_by(|this: &i32, that: &i32| that.cmp(this));
 ///
 /// heap.push(1);
 /// heap.push(5);
@@ -286,9 +261,9 @@ mod tests;
 /// [pop]: BinaryHeap::pop
 /// [peek]: BinaryHeap::peek
 /// [peek\_mut]: BinaryHeap::peek_mut
-pub struct BinaryHeap<T, O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>> {
+pub struct BinaryHeap<T, C = fn(&T, &T) -> Ordering> {
     data: Vec<T>,
-    order: O,
+    comp: C,
 }
 
 /// Structure wrapping a mutable reference to the greatest item on a
@@ -298,24 +273,20 @@ pub struct BinaryHeap<T, O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>> {
 /// its documentation for more.
 ///
 /// [`peek_mut`]: BinaryHeap::peek_mut
-pub struct PeekMut<
-    'a,
-    T: 'a + SortableByWithOrder<O>,
-    O: TotalOrder = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>,
-> {
-    heap: &'a mut BinaryHeap<T, O>,
+pub struct PeekMut<'a, T: 'a, C: FnMut(&T, &T) -> Ordering = fn(&T, &T) -> Ordering> {
+    heap: &'a mut BinaryHeap<T, C>,
     // If a set_len + sift_down are required, this is Some. If a &mut T has not
     // yet been exposed to peek_mut()'s caller, it's None.
     original_len: Option<NonZeroUsize>,
 }
 
-impl<T: SortableByWithOrder<O> + fmt::Debug, O: TotalOrder> fmt::Debug for PeekMut<'_, T, O> {
+impl<T: fmt::Debug, C: FnMut(&T, &T) -> Ordering> fmt::Debug for PeekMut<'_, T, C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("PeekMut").field(&self.heap.data[0]).finish()
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> Drop for PeekMut<'_, T, O> {
+impl<T, C: FnMut(&T, &T) -> Ordering> Drop for PeekMut<'_, T, C> {
     fn drop(&mut self) {
         if let Some(original_len) = self.original_len {
             // SAFETY: That's how many elements were in the Vec at the time of
@@ -331,7 +302,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> Drop for PeekMut<'_, T, O> {
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> Deref for PeekMut<'_, T, O> {
+impl<T, C: FnMut(&T, &T) -> Ordering> Deref for PeekMut<'_, T, C> {
     type Target = T;
     fn deref(&self) -> &T {
         debug_assert!(!self.heap.is_empty());
@@ -340,7 +311,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> Deref for PeekMut<'_, T, O> {
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> DerefMut for PeekMut<'_, T, O> {
+impl<T, C: FnMut(&T, &T) -> Ordering> DerefMut for PeekMut<'_, T, C> {
     fn deref_mut(&mut self) -> &mut T {
         debug_assert!(!self.heap.is_empty());
 
@@ -368,9 +339,9 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> DerefMut for PeekMut<'_, T, O> {
     }
 }
 
-impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> PeekMut<'a, T, O> {
+impl<'a, T, C: FnMut(&T, &T) -> Ordering> PeekMut<'a, T, C> {
     /// Removes the peeked value from the heap and returns it.
-    pub fn pop(mut this: PeekMut<'a, T, O>) -> T {
+    pub fn pop(mut this: PeekMut<'a, T, C>) -> T {
         if let Some(original_len) = this.original_len.take() {
             // SAFETY: This is how many elements were in the Vec at the time of
             // the BinaryHeap::peek_mut call.
@@ -384,48 +355,50 @@ impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> PeekMut<'a, T, O> {
     }
 }
 
-impl<T: Clone, O: Clone> Clone for BinaryHeap<T, O> {
+impl<T: Clone, C: Clone> Clone for BinaryHeap<T, C> {
     fn clone(&self) -> Self {
-        BinaryHeap { data: self.data.clone(), order: self.order.clone() }
+        BinaryHeap { data: self.data.clone(), comp: self.comp.clone() }
     }
 
     fn clone_from(&mut self, source: &Self) {
         self.data.clone_from(&source.data);
+        self.comp.clone_from(&source.comp);
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder + Default> Default for BinaryHeap<T, O> {
+impl<T: Ord> Default for BinaryHeap<T> {
     /// Creates an empty `BinaryHeap<T>`.
     #[inline]
-    fn default() -> BinaryHeap<T, O> {
-        BinaryHeap::new(O::default())
+    fn default() -> BinaryHeap<T> {
+        BinaryHeap::new()
     }
 }
 
-impl<T: fmt::Debug, O> fmt::Debug for BinaryHeap<T, O> {
+impl<T: fmt::Debug, C> fmt::Debug for BinaryHeap<T, C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.iter()).finish()
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
-    /// Creates an empty `BinaryHeap` as a max-heap.
+impl<T: Ord> BinaryHeap<T> {
+    /// Creates an empty `BinaryHeap` as a max-heap, ordered by [`Ord`].
     ///
     /// # Examples
     ///
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
-    /// let mut heap = BinaryHeap::default();
+    /// use btree_monstrousity::BinaryHeap;
+    /// let mut heap = BinaryHeap::new();
     /// heap.push(4);
     /// ```
     #[must_use]
-    pub fn new(order: O) -> BinaryHeap<T, O> {
-        BinaryHeap { data: vec![], order }
+    pub fn new() -> BinaryHeap<T> {
+        BinaryHeap::new_by(T::cmp)
     }
 
-    /// Creates an empty `BinaryHeap` with at least the specified capacity.
+    /// Creates an empty `BinaryHeap` ordered by [`Ord`] with at least the
+    /// specified capacity.
     ///
     /// The binary heap will be able to hold at least `capacity` elements without
     /// reallocating. This method is allowed to allocate for more elements than
@@ -436,13 +409,78 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
-    /// let mut heap = BinaryHeap::with_capacity(Default::default(), 10);
+    /// use btree_monstrousity::BinaryHeap;
+    /// let mut heap = BinaryHeap::with_capacity(10);
     /// heap.push(4);
     /// ```
     #[must_use]
-    pub fn with_capacity(order: O, capacity: usize) -> BinaryHeap<T, O> {
-        BinaryHeap { data: Vec::with_capacity(capacity), order }
+    pub fn with_capacity(capacity: usize) -> BinaryHeap<T> {
+        BinaryHeap::with_capacity_by(capacity, T::cmp)
+    }
+}
+
+impl<T, C: FnMut(&T, &T) -> Ordering> BinaryHeap<T, C> {
+    /// Creates an empty `BinaryHeap` as a max-heap with respect to `comp`.
+    ///
+    /// `comp` is handed two items and must return how the first compares to
+    /// the second; the heap hands out the greatest item first.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BinaryHeap;
+    ///
+    /// // A min-heap, by reversing the comparison.
+    /// let mut heap = BinaryHeap::new_by(|a: &i32, b: &i32| b.cmp(a));
+    /// heap.push(4);
+    /// heap.push(1);
+    /// assert_eq!(heap.peek(), Some(&1));
+    /// ```
+    #[must_use]
+    pub fn new_by(comp: C) -> BinaryHeap<T, C> {
+        BinaryHeap { data: Vec::new(), comp }
+    }
+
+    /// Creates an empty `BinaryHeap` ordered by `comp` with at least the
+    /// specified capacity.
+    ///
+    /// The binary heap will be able to hold at least `capacity` elements without
+    /// reallocating. This method is allowed to allocate for more elements than
+    /// `capacity`. If `capacity` is 0, the binary heap will not allocate.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BinaryHeap;
+    /// let mut heap = BinaryHeap::with_capacity_by(10, |a: &i32, b: &i32| b.cmp(a));
+    /// heap.push(4);
+    /// ```
+    #[must_use]
+    pub fn with_capacity_by(capacity: usize, comp: C) -> BinaryHeap<T, C> {
+        BinaryHeap { data: Vec::with_capacity(capacity), comp }
+    }
+
+    /// Converts a `Vec<T>` into a `BinaryHeap` ordered by `comp`.
+    ///
+    /// This conversion happens in-place, and has *O*(*n*) time complexity.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BinaryHeap;
+    /// let heap = BinaryHeap::from_vec_by(vec![3, 1, 2], |a: &i32, b: &i32| b.cmp(a));
+    /// assert_eq!(heap.into_sorted_vec(), [3, 2, 1]);
+    /// ```
+    pub fn from_vec_by(vec: Vec<T>, comp: C) -> BinaryHeap<T, C> {
+        let mut heap = BinaryHeap { data: vec, comp };
+        heap.rebuild();
+        heap
     }
 
     /// Returns a mutable reference to the greatest item in the binary heap, or
@@ -457,7 +495,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::default();
     /// assert!(heap.peek_mut().is_none());
     ///
@@ -475,7 +513,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     ///
     /// If the item is modified then the worst case time complexity is *O*(log(*n*)),
     /// otherwise it's *O*(1).
-    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, O>> {
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
         if self.is_empty() { None } else { Some(PeekMut { heap: self, original_len: None }) }
     }
 
@@ -487,7 +525,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::from([1, 3]);
     ///
     /// assert_eq!(heap.pop(), Some(3));
@@ -516,7 +554,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::default();
     /// heap.push(3);
     /// heap.push(5);
@@ -557,7 +595,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     ///
     /// let mut heap = BinaryHeap::from([1, 2, 4, 5, 7]);
     /// heap.push(6);
@@ -611,7 +649,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
             //  and so hole.pos() - 1 can't underflow.
             //  This guarantees that parent < hole.pos() so
             //  it's a valid index and also != hole.pos().
-            if self.order.le(hole.element(), unsafe { hole.get(parent) }) {
+            if (self.comp)(hole.element(), unsafe { hole.get(parent) }).is_le() {
                 break;
             }
 
@@ -642,12 +680,12 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
             //  child + 1 == 2 * hole.pos() + 2 != hole.pos().
             // FIXME: 2 * hole.pos() + 1 or 2 * hole.pos() + 2 could overflow
             //  if T is a ZST
-            child += unsafe { self.order.le(hole.get(child), hole.get(child + 1)) } as usize;
+            child += unsafe { (self.comp)(hole.get(child), hole.get(child + 1)).is_le() } as usize;
 
             // if we are already in order, stop.
             // SAFETY: child is now either the old child or the old child+1
             //  We already proven that both are < self.len() and != hole.pos()
-            if self.order.ge(hole.element(), unsafe { hole.get(child) }) {
+            if (self.comp)(hole.element(), unsafe { hole.get(child) }).is_ge() {
                 return;
             }
 
@@ -658,7 +696,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
 
         // SAFETY: && short circuit, which means that in the
         //  second condition it's already true that child == end - 1 < self.len().
-        if child == end - 1 && self.order.lt(hole.element(), unsafe { hole.get(child) }) {
+        if child == end - 1 && (self.comp)(hole.element(), unsafe { hole.get(child) }).is_lt() {
             // SAFETY: child is already proven to be a valid index and
             //  child == 2 * hole.pos() + 1 != hole.pos().
             unsafe { hole.move_to(child) };
@@ -700,7 +738,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
             //  child + 1 == 2 * hole.pos() + 2 != hole.pos().
             // FIXME: 2 * hole.pos() + 1 or 2 * hole.pos() + 2 could overflow
             //  if T is a ZST
-            child += unsafe { self.order.le(hole.get(child), hole.get(child + 1)) } as usize;
+            child += unsafe { (self.comp)(hole.get(child), hole.get(child + 1)).is_le() } as usize;
 
             // SAFETY: Same as above
             unsafe { hole.move_to(child) };
@@ -770,12 +808,15 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
 
     /// Moves all the elements of `other` into `self`, leaving `other` empty.
     ///
+    /// `self` keeps its own comparator. It is a logic error for `other` to be
+    /// ordered by a comparator that disagrees with it.
+    ///
     /// # Examples
     ///
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     ///
     /// let mut a = BinaryHeap::from([-10, 1, 2, 3, 3]);
     /// let mut b = BinaryHeap::from([-20, 5, 43]);
@@ -787,7 +828,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// ```
     pub fn append(&mut self, other: &mut Self) {
         if self.len() < other.len() {
-            swap(self, other);
+            swap(&mut self.data, &mut other.data);
         }
 
         let start = self.data.len();
@@ -813,7 +854,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     ///
     /// let mut heap = BinaryHeap::from([1, 2, 3, 4, 5]);
     /// assert_eq!(heap.len(), 5);
@@ -823,7 +864,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// ```
     #[inline]
     #[cfg(feature = "binary_heap_drain_sorted")]
-    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, O> {
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, C> {
         DrainSorted { inner: self }
     }
 
@@ -837,7 +878,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     ///
     /// let mut heap = BinaryHeap::from([-10, -5, 1, 2, 4, 13]);
     ///
@@ -850,8 +891,8 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     where
         F: FnMut(&T) -> bool,
     {
-        struct RebuildOnDrop<'a, T: SortableByWithOrder<O>, O: TotalOrder> {
-            heap: &'a mut BinaryHeap<T, O>,
+        struct RebuildOnDrop<'a, T, C: FnMut(&T, &T) -> Ordering> {
+            heap: &'a mut BinaryHeap<T, C>,
             first_removed: usize,
         }
 
@@ -867,7 +908,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
             keep
         });
 
-        impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> Drop for RebuildOnDrop<'a, T, O> {
+        impl<'a, T, C: FnMut(&T, &T) -> Ordering> Drop for RebuildOnDrop<'a, T, C> {
             fn drop(&mut self) {
                 // data[..first_removed] is untouched, so we only need to
                 // rebuild the tail:
@@ -875,46 +916,9 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
             }
         }
     }
-
-    #[doc(hidden)]
-    pub fn get_order(&self) -> &O {
-        &self.order
-    }
-
-    #[doc(hidden)]
-    pub fn get_mut_order(&mut self) -> OrderMut<'_, T, O> {
-        OrderMut(self)
-    }
-
-    #[doc(hidden)]
-    pub fn get_mut_order_unchecked(&mut self) -> &mut O {
-        &mut self.order
-    }
 }
 
-#[doc(hidden)]
-pub struct OrderMut<'a, T: SortableByWithOrder<O>, O: TotalOrder>(&'a mut BinaryHeap<T, O>);
-
-impl<T: SortableByWithOrder<O>, O: TotalOrder> Deref for OrderMut<'_, T, O> {
-    type Target = O;
-    fn deref(&self) -> &Self::Target {
-        &self.0.order
-    }
-}
-
-impl<T: SortableByWithOrder<O>, O: TotalOrder> DerefMut for OrderMut<'_, T, O> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0.order
-    }
-}
-
-impl<T: SortableByWithOrder<O>, O: TotalOrder> Drop for OrderMut<'_, T, O> {
-    fn drop(&mut self) {
-        self.0.rebuild()
-    }
-}
-
-impl<T, O> BinaryHeap<T, O> {
+impl<T, C> BinaryHeap<T, C> {
     /// Returns an iterator visiting all values in the underlying vector, in
     /// arbitrary order.
     ///
@@ -923,7 +927,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let heap = BinaryHeap::from([1, 2, 3, 4]);
     ///
     /// // Print 1, 2, 3, 4 in arbitrary order
@@ -943,13 +947,13 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let heap = BinaryHeap::from([1, 2, 3, 4, 5]);
     ///
     /// assert_eq!(heap.into_iter_sorted().take(2).collect::<Vec<_>>(), [5, 4]);
     /// ```
     #[cfg(feature = "binary_heap_into_iter_sorted")]
-    pub fn into_iter_sorted(self) -> IntoIterSorted<T, O> {
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, C> {
         IntoIterSorted { inner: self }
     }
 
@@ -960,7 +964,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::default();
     /// assert_eq!(heap.peek(), None);
     ///
@@ -976,7 +980,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Cost is *O*(1) in the worst case.
     #[must_use]
     pub fn peek(&self) -> Option<&T> {
-        self.data.get(0)
+        self.data.first()
     }
 
     /// Returns the number of elements the binary heap can hold without reallocating.
@@ -986,8 +990,8 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
-    /// let mut heap = BinaryHeap::with_capacity(Default::default(), 100);
+    /// use btree_monstrousity::BinaryHeap;
+    /// let mut heap = BinaryHeap::with_capacity(100);
     /// assert!(heap.capacity() >= 100);
     /// heap.push(4);
     /// ```
@@ -1014,7 +1018,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::default();
     /// heap.reserve_exact(100);
     /// assert!(heap.capacity() >= 100);
@@ -1041,7 +1045,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::default();
     /// heap.reserve(100);
     /// assert!(heap.capacity() >= 100);
@@ -1072,7 +1076,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// use std::collections::TryReserveError;
     ///
     /// fn find_max_slow(data: &[u32]) -> Result<Option<u32>, TryReserveError> {
@@ -1107,7 +1111,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// use std::collections::TryReserveError;
     ///
     /// fn find_max_slow(data: &[u32]) -> Result<Option<u32>, TryReserveError> {
@@ -1134,8 +1138,8 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
-    /// let mut heap: BinaryHeap<i32> = BinaryHeap::with_capacity(Default::default(), 100);
+    /// use btree_monstrousity::BinaryHeap;
+    /// let mut heap: BinaryHeap<i32> = BinaryHeap::with_capacity(100);
     ///
     /// assert!(heap.capacity() >= 100);
     /// heap.shrink_to_fit();
@@ -1155,8 +1159,8 @@ impl<T, O> BinaryHeap<T, O> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BinaryHeap;
-    /// let mut heap: BinaryHeap<i32> = BinaryHeap::with_capacity(Default::default(), 100);
+    /// use btree_monstrousity::BinaryHeap;
+    /// let mut heap: BinaryHeap<i32> = BinaryHeap::with_capacity(100);
     ///
     /// assert!(heap.capacity() >= 100);
     /// heap.shrink_to(10);
@@ -1175,7 +1179,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// use std::io::{self, Write};
     ///
     /// let heap = BinaryHeap::from([1, 2, 3, 4, 5, 6, 7]);
@@ -1196,7 +1200,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let heap = BinaryHeap::from([1, 2, 3, 4, 5, 6, 7]);
     /// let vec = heap.into_vec();
     ///
@@ -1217,7 +1221,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let heap = BinaryHeap::from([1, 3]);
     ///
     /// assert_eq!(heap.len(), 2);
@@ -1234,7 +1238,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::default();
     ///
     /// assert!(heap.is_empty());
@@ -1262,7 +1266,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::from([1, 3]);
     ///
     /// assert!(!heap.is_empty());
@@ -1285,7 +1289,7 @@ impl<T, O> BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let mut heap = BinaryHeap::from([1, 3]);
     ///
     /// assert!(!heap.is_empty());
@@ -1501,13 +1505,20 @@ cfg_if! {
 }
 #[must_use = "iterators are lazy and do nothing unless consumed"]
 #[cfg(feature = "binary_heap_into_iter_sorted")]
-#[derive(Clone, Debug)]
-pub struct IntoIterSorted<T, O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>> {
-    inner: BinaryHeap<T, O>,
+#[derive(Clone)]
+pub struct IntoIterSorted<T, C = fn(&T, &T) -> Ordering> {
+    inner: BinaryHeap<T, C>,
 }
 
 #[cfg(feature = "binary_heap_into_iter_sorted")]
-impl<T: SortableByWithOrder<O>, O: TotalOrder> Iterator for IntoIterSorted<T, O> {
+impl<T: fmt::Debug, C> fmt::Debug for IntoIterSorted<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntoIterSorted").field("inner", &self.inner).finish()
+    }
+}
+
+#[cfg(feature = "binary_heap_into_iter_sorted")]
+impl<T, C: FnMut(&T, &T) -> Ordering> Iterator for IntoIterSorted<T, C> {
     type Item = T;
 
     #[inline]
@@ -1523,13 +1534,13 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> Iterator for IntoIterSorted<T, O>
 }
 
 #[cfg(feature = "binary_heap_into_iter_sorted")]
-impl<T: SortableByWithOrder<O>, O: TotalOrder> ExactSizeIterator for IntoIterSorted<T, O> {}
+impl<T, C: FnMut(&T, &T) -> Ordering> ExactSizeIterator for IntoIterSorted<T, C> {}
 
 #[cfg(feature = "binary_heap_into_iter_sorted")]
-impl<T: SortableByWithOrder<O>, O: TotalOrder> FusedIterator for IntoIterSorted<T, O> {}
+impl<T, C: FnMut(&T, &T) -> Ordering> FusedIterator for IntoIterSorted<T, C> {}
 
 #[cfg(all(feature = "binary_heap_into_iter_sorted", feature = "trusted_len"))]
-unsafe impl<T: SortableByWithOrder<O>, O: TotalOrder> TrustedLen for IntoIterSorted<T, O> {}
+unsafe impl<T, C: FnMut(&T, &T) -> Ordering> TrustedLen for IntoIterSorted<T, C> {}
 
 /// A draining iterator over the elements of a `BinaryHeap`.
 ///
@@ -1579,24 +1590,26 @@ impl<T> FusedIterator for Drain<'_, T> {}
 ///
 /// [`drain_sorted`]: BinaryHeap::drain_sorted
 #[cfg(feature = "binary_heap_drain_sorted")]
-#[derive(Debug)]
-pub struct DrainSorted<
-    'a,
-    T: SortableByWithOrder<O>,
-    O: TotalOrder = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>,
-> {
-    inner: &'a mut BinaryHeap<T, O>,
+pub struct DrainSorted<'a, T, C: FnMut(&T, &T) -> Ordering = fn(&T, &T) -> Ordering> {
+    inner: &'a mut BinaryHeap<T, C>,
+}
+
+#[cfg(feature = "binary_heap_drain_sorted")]
+impl<T: fmt::Debug, C: FnMut(&T, &T) -> Ordering> fmt::Debug for DrainSorted<'_, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DrainSorted").field("inner", &self.inner).finish()
+    }
 }
 
 #[cfg(feature = "binary_heap_drain_sorted")]
-impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> Drop for DrainSorted<'a, T, O> {
+impl<'a, T, C: FnMut(&T, &T) -> Ordering> Drop for DrainSorted<'a, T, C> {
     /// Removes heap elements in heap order.
     fn drop(&mut self) {
-        struct DropGuard<'r, 'a, T: SortableByWithOrder<O>, O: TotalOrder>(
-            &'r mut DrainSorted<'a, T, O>,
+        struct DropGuard<'r, 'a, T, C: FnMut(&T, &T) -> Ordering>(
+            &'r mut DrainSorted<'a, T, C>,
         );
 
-        impl<'r, 'a, T: SortableByWithOrder<O>, O: TotalOrder> Drop for DropGuard<'r, 'a, T, O> {
+        impl<'r, 'a, T, C: FnMut(&T, &T) -> Ordering> Drop for DropGuard<'r, 'a, T, C> {
             fn drop(&mut self) {
                 while self.0.inner.pop().is_some() {}
             }
@@ -1611,7 +1624,7 @@ impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> Drop for DrainSorted<'a, T, O
 }
 
 #[cfg(feature = "binary_heap_drain_sorted")]
-impl<T: SortableByWithOrder<O>, O: TotalOrder> Iterator for DrainSorted<'_, T, O> {
+impl<T, C: FnMut(&T, &T) -> Ordering> Iterator for DrainSorted<'_, T, C> {
     type Item = T;
 
     #[inline]
@@ -1627,30 +1640,26 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> Iterator for DrainSorted<'_, T, O
 }
 
 #[cfg(feature = "binary_heap_drain_sorted")]
-impl<T: SortableByWithOrder<O>, O: TotalOrder> ExactSizeIterator for DrainSorted<'_, T, O> {}
+impl<T, C: FnMut(&T, &T) -> Ordering> ExactSizeIterator for DrainSorted<'_, T, C> {}
 
 #[cfg(feature = "binary_heap_drain_sorted")]
-impl<T: SortableByWithOrder<O>, O: TotalOrder> FusedIterator for DrainSorted<'_, T, O> {}
+impl<T, C: FnMut(&T, &T) -> Ordering> FusedIterator for DrainSorted<'_, T, C> {}
 
 #[cfg(all(feature = "binary_heap_drain_sorted", feature = "trusted_len"))]
-unsafe impl<T: SortableByWithOrder<O>, O: TotalOrder> TrustedLen for DrainSorted<'_, T, O> {}
+unsafe impl<T, C: FnMut(&T, &T) -> Ordering> TrustedLen for DrainSorted<'_, T, C> {}
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder + Default> From<Vec<T>> for BinaryHeap<T, O> {
+impl<T: Ord> From<Vec<T>> for BinaryHeap<T> {
     /// Converts a `Vec<T>` into a `BinaryHeap<T>`.
     ///
     /// This conversion happens in-place, and has *O*(*n*) time complexity.
-    fn from(vec: Vec<T>) -> BinaryHeap<T, O> {
-        let mut heap = BinaryHeap { data: vec, order: O::default() };
-        heap.rebuild();
-        heap
+    fn from(vec: Vec<T>) -> BinaryHeap<T> {
+        BinaryHeap::from_vec_by(vec, T::cmp)
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder + Default, const N: usize> From<[T; N]>
-    for BinaryHeap<T, O>
-{
+impl<T: Ord, const N: usize> From<[T; N]> for BinaryHeap<T> {
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     ///
     /// let mut h1 = BinaryHeap::from([1, 4, 2, 3]);
     /// let mut h2: BinaryHeap<_> = [1, 4, 2, 3].into();
@@ -1663,23 +1672,23 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder + Default, const N: usize> From<[T
     }
 }
 
-impl<T, O> From<BinaryHeap<T, O>> for Vec<T> {
+impl<T, C> From<BinaryHeap<T, C>> for Vec<T> {
     /// Converts a `BinaryHeap<T>` into a `Vec<T>`.
     ///
     /// This conversion requires no data movement or allocation, and has
     /// constant time complexity.
-    fn from(heap: BinaryHeap<T, O>) -> Vec<T> {
+    fn from(heap: BinaryHeap<T, C>) -> Vec<T> {
         heap.data
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder + Default> FromIterator<T> for BinaryHeap<T, O> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> BinaryHeap<T, O> {
+impl<T: Ord> FromIterator<T> for BinaryHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> BinaryHeap<T> {
         BinaryHeap::from(iter.into_iter().collect::<Vec<_>>())
     }
 }
 
-impl<T, O> IntoIterator for BinaryHeap<T, O> {
+impl<T, C> IntoIterator for BinaryHeap<T, C> {
     type Item = T;
     type IntoIter = IntoIter<T>;
 
@@ -1692,7 +1701,7 @@ impl<T, O> IntoIterator for BinaryHeap<T, O> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BinaryHeap;
+    /// use btree_monstrousity::BinaryHeap;
     /// let heap = BinaryHeap::from([1, 2, 3, 4]);
     ///
     /// // Print 1, 2, 3, 4 in arbitrary order
@@ -1714,7 +1723,7 @@ impl<T, O> IntoIterator for BinaryHeap<T, O> {
     }
 }
 
-impl<'a, T, O> IntoIterator for &'a BinaryHeap<T, O> {
+impl<'a, T, C> IntoIterator for &'a BinaryHeap<T, C> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -1723,7 +1732,7 @@ impl<'a, T, O> IntoIterator for &'a BinaryHeap<T, O> {
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> Extend<T> for BinaryHeap<T, O> {
+impl<T, C: FnMut(&T, &T) -> Ordering> Extend<T> for BinaryHeap<T, C> {
     #[inline]
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         <Self as SpecExtend<I>>::spec_extend(self, iter);
@@ -1744,13 +1753,13 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> Extend<T> for BinaryHeap<T, O> {
 
 cfg_if! {
     if #[cfg(feature = "specialization")] {
-        impl<T: SortableByWithOrder<O>, O: TotalOrder, I: IntoIterator<Item = T>> SpecExtend<I> for BinaryHeap<T, O> {
+        impl<T, C: FnMut(&T, &T) -> Ordering, I: IntoIterator<Item = T>> SpecExtend<I> for BinaryHeap<T, C> {
             default fn spec_extend(&mut self, iter: I) {
-                self.extend_desugared(iter.into_iter());
+                self.extend_desugared(iter);
             }
         }
 
-        impl<T: SortableByWithOrder<O>, O: TotalOrder> SpecExtend<Vec<T>> for BinaryHeap<T, O> {
+        impl<T, C: FnMut(&T, &T) -> Ordering> SpecExtend<Vec<T>> for BinaryHeap<T, C> {
             fn spec_extend(&mut self, ref mut other: Vec<T>) {
                 let start = self.data.len();
                 self.data.append(other);
@@ -1758,21 +1767,21 @@ cfg_if! {
             }
         }
 
-        impl<T: SortableByWithOrder<O>, O: TotalOrder> SpecExtend<BinaryHeap<T, O>> for BinaryHeap<T, O> {
-            fn spec_extend(&mut self, ref mut other: BinaryHeap<T, O>) {
+        impl<T, C: FnMut(&T, &T) -> Ordering> SpecExtend<BinaryHeap<T, C>> for BinaryHeap<T, C> {
+            fn spec_extend(&mut self, ref mut other: BinaryHeap<T, C>) {
                 self.append(other);
             }
         }
     } else {
-        impl<T: SortableByWithOrder<O>, O: TotalOrder, I: IntoIterator<Item = T>> SpecExtend<I> for BinaryHeap<T, O> {
+        impl<T, C: FnMut(&T, &T) -> Ordering, I: IntoIterator<Item = T>> SpecExtend<I> for BinaryHeap<T, C> {
             fn spec_extend(&mut self, iter: I) {
-                self.extend_desugared(iter.into_iter());
+                self.extend_desugared(iter);
             }
         }
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
+impl<T, C: FnMut(&T, &T) -> Ordering> BinaryHeap<T, C> {
     fn extend_desugared<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         let iterator = iter.into_iter();
         let (lower, _) = iterator.size_hint();
@@ -1783,7 +1792,7 @@ impl<T: SortableByWithOrder<O>, O: TotalOrder> BinaryHeap<T, O> {
     }
 }
 
-impl<'a, T: 'a + SortableByWithOrder<O> + Copy, O: TotalOrder> Extend<&'a T> for BinaryHeap<T, O> {
+impl<'a, T: 'a + Copy, C: FnMut(&T, &T) -> Ordering> Extend<&'a T> for BinaryHeap<T, C> {
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
         self.extend(iter.into_iter().cloned());
     }
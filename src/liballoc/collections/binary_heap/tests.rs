@@ -449,6 +449,44 @@ fn test_append_to_empty() {
     assert!(b.is_empty());
 }
 
+#[test]
+fn test_new_by() {
+    let mut heap = BinaryHeap::new_by(|a: &i32, b: &i32| b.cmp(a));
+    heap.extend([5, 9, 3, 7]);
+    assert_eq!(heap.peek(), Some(&3));
+    {
+        let mut top = heap.peek_mut().unwrap();
+        *top = 8;
+    }
+    assert_eq!(heap.pop(), Some(5));
+    assert_eq!(heap.into_sorted_vec(), [9, 8, 7]);
+}
+
+#[test]
+fn test_new_by_stateful() {
+    let mut comparisons = 0;
+    let mut heap = BinaryHeap::new_by(|a: &i32, b: &i32| {
+        comparisons += 1;
+        a.cmp(b)
+    });
+    heap.extend([2, 4, 6, 2, 1, 8, 10, 3, 5, 7, 0, 9, 1]);
+    assert_eq!(heap.pop(), Some(10));
+    drop(heap);
+    assert!(comparisons > 0);
+}
+
+#[test]
+fn test_append_by() {
+    let reversed = |a: &i32, b: &i32| b.cmp(a);
+    let mut a = BinaryHeap::from_vec_by(vec![1, 5], reversed);
+    let mut b = BinaryHeap::from_vec_by(vec![4, 2, 3], reversed);
+
+    a.append(&mut b);
+
+    assert_eq!(a.into_sorted_vec(), [5, 4, 3, 2, 1]);
+    assert!(b.is_empty());
+}
+
 #[test]
 fn test_extend_specialization() {
     let mut a = BinaryHeap::from(vec![-10, 1, 2, 3, 3]);
@@ -525,7 +563,7 @@ fn panic_safe() {
 
     static DROP_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
-    #[derive(Eq, PartialEq, Ord, Clone, Debug)]
+    #[derive(Eq, PartialEq, Clone, Debug)]
     struct PanicOrd<T>(T, bool);
 
     impl<T> Drop for PanicOrd<T> {
@@ -543,6 +581,13 @@ fn panic_safe() {
             self.0.partial_cmp(&other.0)
         }
     }
+
+    // The heap compares through `Ord`, so that is where the panic has to come from.
+    impl<T: Ord> Ord for PanicOrd<T> {
+        fn cmp(&self, other: &Self) -> cmp::Ordering {
+            self.partial_cmp(other).unwrap()
+        }
+    }
     let mut rng = crate::liballoc::test_helpers::test_rng();
     const DATASZ: usize = 32;
     // Miri is too slow
@@ -0,0 +1,173 @@
+// Queries on trees whose nodes cache a summary of the subtree below them.
+// The folds here visit the nodes along the borders of a range, combining the
+// cached summaries of the subtrees in between, and so take time proportional
+// to the height of the tree times the node capacity.
+
+use core::cmp::Ordering;
+
+use super::node::{marker, ForceResult::*, Handle, NodeRef};
+use super::search::Linear;
+use super::search::SearchBound::{self, *};
+use super::summary_map::Summary;
+
+impl<'a, K: 'a, V: 'a, S: Summary<K, V> + 'a, const B: usize>
+    NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
+    /// Combines into `acc` the summaries of the key-value pairs of the subtree
+    /// headed by the node that lie within the range, in ascending order.
+    ///
+    /// The result is meaningful only if the tree is ordered by key.
+    pub fn fold_range<C1, C2>(
+        self,
+        mut lower_comp: C1,
+        lower_bound: SearchBound,
+        mut upper_comp: C2,
+        upper_bound: SearchBound,
+        acc: &mut S,
+    ) where
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+    {
+        let (node, lower_edge_idx, upper_edge_idx, lower_child_bound, upper_child_bound) =
+            match self.search_tree_for_bifurcation::<Linear, _, _>(
+                &mut lower_comp,
+                lower_bound,
+                &mut upper_comp,
+                upper_bound,
+            ) {
+                Ok(bifurcation) => bifurcation,
+                Err(_) => return,
+            };
+        match node.force() {
+            Leaf(leaf) => {
+                for idx in lower_edge_idx..upper_edge_idx {
+                    acc.combine(&unsafe { Handle::new_kv(leaf, idx) }.summarize());
+                }
+            }
+            Internal(internal) => {
+                let lower_child = unsafe { Handle::new_edge(internal, lower_edge_idx) }.descend();
+                lower_child.fold_suffix(&mut lower_comp, lower_child_bound, acc);
+                for idx in lower_edge_idx..upper_edge_idx {
+                    acc.combine(&unsafe { Handle::new_kv(internal, idx) }.summarize());
+                    if idx + 1 < upper_edge_idx {
+                        let child = unsafe { Handle::new_edge(internal, idx + 1) }.descend();
+                        acc.combine(child.summary());
+                    }
+                }
+                let upper_child = unsafe { Handle::new_edge(internal, upper_edge_idx) }.descend();
+                upper_child.fold_prefix(&mut upper_comp, upper_child_bound, acc);
+            }
+        }
+    }
+
+    /// Combines into `acc` the summaries of the key-value pairs of the subtree
+    /// that lie above the lower bound of a range.
+    fn fold_suffix<C>(self, comp: &mut C, bound: SearchBound, acc: &mut S)
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        match bound {
+            AllIncluded => return acc.combine(self.summary()),
+            AllExcluded => return,
+            Included | Excluded => {}
+        }
+        let (edge, child_bound) = self.find_lower_bound_edge::<Linear, _>(&mut *comp, bound);
+        let len = self.len();
+        match edge.force() {
+            Leaf(edge) => {
+                for idx in edge.idx()..len {
+                    acc.combine(&unsafe { Handle::new_kv(edge.into_node(), idx) }.summarize());
+                }
+            }
+            Internal(edge) => {
+                let (node, edge_idx) = (edge.into_node(), edge.idx());
+                edge.descend().fold_suffix(comp, child_bound, acc);
+                for idx in edge_idx..len {
+                    acc.combine(&unsafe { Handle::new_kv(node, idx) }.summarize());
+                    acc.combine(unsafe { Handle::new_edge(node, idx + 1) }.descend().summary());
+                }
+            }
+        }
+    }
+
+    /// Combines into `acc` the summaries of the key-value pairs of the subtree
+    /// that lie below the upper bound of a range.
+    fn fold_prefix<C>(self, comp: &mut C, bound: SearchBound, acc: &mut S)
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        match bound {
+            AllIncluded => return acc.combine(self.summary()),
+            AllExcluded => return,
+            Included | Excluded => {}
+        }
+        let (edge, child_bound) = self.find_upper_bound_edge::<Linear, _>(&mut *comp, bound);
+        match edge.force() {
+            Leaf(edge) => {
+                for idx in 0..edge.idx() {
+                    acc.combine(&unsafe { Handle::new_kv(edge.into_node(), idx) }.summarize());
+                }
+            }
+            Internal(edge) => {
+                let node = edge.into_node();
+                for idx in 0..edge.idx() {
+                    acc.combine(unsafe { Handle::new_edge(node, idx) }.descend().summary());
+                    acc.combine(&unsafe { Handle::new_kv(node, idx) }.summarize());
+                }
+                edge.descend().fold_prefix(comp, child_bound, acc);
+            }
+        }
+    }
+
+    /// Finds the first key-value pair for which `pred` holds on the summary of
+    /// all pairs up to and including it, assuming that `pred` holds on the
+    /// summaries of all longer prefixes as well.
+    pub fn find_by_prefix_summary<P>(self, mut pred: P) -> Option<Handle<Self, marker::KV>>
+    where
+        P: FnMut(&S) -> bool,
+        S: Clone,
+    {
+        let mut acc = S::empty();
+        let mut node = self;
+        'descend: loop {
+            let internal = match node.force() {
+                Leaf(leaf) => {
+                    for idx in 0..leaf.len() {
+                        let kv = unsafe { Handle::new_kv(leaf, idx) };
+                        acc.combine(&kv.summarize());
+                        if pred(&acc) {
+                            return Some(unsafe { Handle::new_kv(node, idx) });
+                        }
+                    }
+                    return None;
+                }
+                Internal(internal) => internal,
+            };
+            for idx in 0..internal.len() {
+                let child = unsafe { Handle::new_edge(internal, idx) }.descend();
+                let mut prefix = acc.clone();
+                prefix.combine(child.summary());
+                if pred(&prefix) {
+                    node = child;
+                    continue 'descend;
+                }
+                acc = prefix;
+                acc.combine(&unsafe { Handle::new_kv(internal, idx) }.summarize());
+                if pred(&acc) {
+                    return Some(unsafe { Handle::new_kv(node, idx) });
+                }
+            }
+            node = unsafe { Handle::new_edge(internal, internal.len()) }.descend();
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a, NodeType, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Immut<'a>, K, V, NodeType, S, B>, marker::KV>
+{
+    /// Computes the summary of the single key-value pair.
+    fn summarize(self) -> S {
+        let (key, val) = self.into_kv();
+        S::of(key, val)
+    }
+}
@@ -1,10 +1,11 @@
 use super::merge_iter::MergeIterInner;
 use super::node::{self, Root};
+use super::summary_map::Summary;
 use crate::polyfill::*;
 use core::cmp::Ordering;
 use core::iter::FusedIterator;
 
-impl<K, V> Root<K, V> {
+impl<K, V, S: Summary<K, V>, const B: usize> Root<K, V, S, B> {
     /// Appends all key-value pairs from the union of two ascending iterators,
     /// incrementing a `length` variable along the way. The latter makes it
     /// easier for the caller to avoid a leak when a drop handler panicks.
@@ -21,13 +22,31 @@ impl<K, V> Root<K, V> {
         left: I,
         right: I,
         length: &mut usize,
-        order: impl Fn(&(K, V), &(K, V)) -> Ordering,
+        order: impl FnMut(&(K, V), &(K, V)) -> Ordering,
+        alloc: A,
+    ) where
+        I: Iterator<Item = (K, V)> + FusedIterator,
+    {
+        self.merge_from_sorted_iters(left, right, length, order, |_, right| right, alloc)
+    }
+
+    /// Appends all key-value pairs from the union of two ascending iterators,
+    /// like `append_from_sorted_iters`, except that if both iterators produce
+    /// the same key, this method appends what `resolve` makes of the pair from
+    /// the left iterator and the pair from the right iterator.
+    pub fn merge_from_sorted_iters<I, A: Allocator + Clone>(
+        &mut self,
+        left: I,
+        right: I,
+        length: &mut usize,
+        order: impl FnMut(&(K, V), &(K, V)) -> Ordering,
+        resolve: impl FnMut((K, V), (K, V)) -> (K, V),
         alloc: A,
     ) where
         I: Iterator<Item = (K, V)> + FusedIterator,
     {
         // We prepare to merge `left` and `right` into a sorted sequence in linear time.
-        let iter = MergeIter(MergeIterInner::new(left, right), order);
+        let iter = MergeIter(MergeIterInner::new(left, right), order, resolve);
 
         // Meanwhile, we build a tree from the sorted sequence in linear time.
         self.bulk_push(iter, length, alloc)
@@ -44,22 +63,24 @@ impl<K, V> Root<K, V> {
         // Iterate through all key-value pairs, pushing them into nodes at the right level.
         for (key, value) in iter {
             // Try to push key-value pair into the current leaf node.
-            if cur_node.len() < node::CAPACITY {
+            if cur_node.len() < node::Consts::<B>::CAPACITY {
                 cur_node.push(key, value);
             } else {
-                // No space left, go up and push there.
+                // No space left, go up and push there, leaving the full leaf complete.
+                cur_node.update_cache();
                 let mut open_node;
                 let mut test_node = cur_node.forget_type();
                 loop {
                     match test_node.ascend() {
                         Ok(parent) => {
-                            let parent = parent.into_node();
-                            if parent.len() < node::CAPACITY {
+                            let mut parent = parent.into_node();
+                            if parent.len() < node::Consts::<B>::CAPACITY {
                                 // Found a node with space left, push here.
                                 open_node = parent;
                                 break;
                             } else {
-                                // Go up again.
+                                // Go up again, leaving the full node complete.
+                                parent.update_cache();
                                 test_node = parent.forget_type();
                             }
                         }
@@ -87,23 +108,29 @@ impl<K, V> Root<K, V> {
             // the appended elements even if advancing the iterator panicks.
             *length += 1;
         }
+        // Nodes on the right border were pushed into without updating their caches.
+        cur_node.forget_type().update_caches_upward();
         self.fix_right_border_of_plentiful();
     }
 }
 
 // An iterator for merging two sorted sequences into one
-struct MergeIter<K, V, O, I: Iterator<Item = (K, V)>>(MergeIterInner<I>, O);
+struct MergeIter<K, V, O, R, I: Iterator<Item = (K, V)>>(MergeIterInner<I>, O, R);
 
-impl<K, V, O, I> Iterator for MergeIter<K, V, O, I>
+impl<K, V, O, R, I> Iterator for MergeIter<K, V, O, R, I>
 where
-    O: Fn(&(K, V), &(K, V)) -> Ordering,
+    O: FnMut(&(K, V), &(K, V)) -> Ordering,
+    R: FnMut((K, V), (K, V)) -> (K, V),
     I: Iterator<Item = (K, V)> + FusedIterator,
 {
     type Item = (K, V);
 
-    /// If two keys are equal, returns the key-value pair from the right source.
+    /// If two keys are equal, returns the resolution of the key-value pairs
+    /// from both sources.
     fn next(&mut self) -> Option<(K, V)> {
-        let (a_next, b_next) = self.0.nexts(|this, that| self.1(this, that));
-        b_next.or(a_next)
+        match self.0.nexts(|this, that| (self.1)(this, that)) {
+            (Some(a), Some(b)) => Some((self.2)(a, b)),
+            (a_next, b_next) => b_next.or(a_next),
+        }
     }
 }
@@ -0,0 +1,195 @@
+use core::fmt::{self, Debug};
+
+use super::node::{self, marker, Consts, ForceResult::*, Handle, NodeRef, Root};
+
+/// The reason a [`BTreeMap`](super::map::BTreeMap) failed
+/// [`check_order_by`](super::map::BTreeMap::check_order_by).
+pub enum OrderViolation<'a, K> {
+    /// Two adjacent keys are not in strictly ascending order according to the
+    /// comparator that was checked against.
+    Unordered {
+        /// The key that comes first in the map.
+        previous: &'a K,
+        /// The key right after `previous`, which should compare greater.
+        next: &'a K,
+    },
+    /// The nodes of the tree are malformed; see [`StructureViolation`].
+    Structure(StructureViolation),
+}
+
+impl<K: Debug> Debug for OrderViolation<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderViolation::Unordered { previous, next } => {
+                f.debug_struct("Unordered").field("previous", previous).field("next", next).finish()
+            }
+            OrderViolation::Structure(violation) => {
+                f.debug_tuple("Structure").field(violation).finish()
+            }
+        }
+    }
+}
+
+impl<K: Debug> fmt::Display for OrderViolation<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderViolation::Unordered { previous, next } => {
+                write!(f, "key {:?} is not ordered before the next key {:?}", previous, next)
+            }
+            OrderViolation::Structure(violation) => fmt::Display::fmt(violation, f),
+        }
+    }
+}
+
+#[cfg(feature = "error_in_core")]
+impl<K: Debug> core::error::Error for OrderViolation<'_, K> {}
+
+#[cfg(all(feature = "std", not(feature = "error_in_core")))]
+impl<K: Debug> std::error::Error for OrderViolation<'_, K> {}
+
+/// A way in which the nodes of a [`BTreeMap`](super::map::BTreeMap) are malformed.
+///
+/// Heights count up from the leaves, which are at height 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StructureViolation {
+    /// A node holds more elements than fit in a node.
+    Overfull {
+        /// The height of the node.
+        height: usize,
+        /// The number of elements in the node.
+        len: usize,
+    },
+    /// A node other than the root holds fewer elements than the minimum, or the
+    /// root is an internal node without elements.
+    Underfull {
+        /// The height of the node.
+        height: usize,
+        /// The number of elements in the node.
+        len: usize,
+    },
+    /// A child node does not link back to the parent edge it hangs from.
+    BrokenParentLink {
+        /// The height of the child node.
+        height: usize,
+        /// The index of the edge in the parent node.
+        idx: usize,
+    },
+    /// The length recorded by the map differs from the number of elements in
+    /// its nodes.
+    LengthMismatch {
+        /// The length recorded by the map.
+        recorded: usize,
+        /// The number of elements found in the nodes.
+        counted: usize,
+    },
+    /// An internal node records a different number of elements in its subtree
+    /// than it holds. Only detected with the `order_statistics` feature.
+    CountMismatch {
+        /// The height of the node.
+        height: usize,
+        /// The number of elements recorded by the node.
+        recorded: usize,
+        /// The number of elements found in the subtree.
+        counted: usize,
+    },
+}
+
+impl fmt::Display for StructureViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            StructureViolation::Overfull { height, len } => {
+                write!(f, "node at height {} holds {} elements, more than fit", height, len)
+            }
+            StructureViolation::Underfull { height, len } => {
+                write!(f, "node at height {} holds only {} elements", height, len)
+            }
+            StructureViolation::BrokenParentLink { height, idx } => write!(
+                f,
+                "node at height {} does not link back to edge {} of its parent",
+                height, idx
+            ),
+            StructureViolation::LengthMismatch { recorded, counted } => {
+                write!(f, "map records {} elements but its nodes hold {}", recorded, counted)
+            }
+            StructureViolation::CountMismatch { height, recorded, counted } => write!(
+                f,
+                "node at height {} records {} elements in its subtree but holds {}",
+                height, recorded, counted
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "error_in_core")]
+impl core::error::Error for StructureViolation {}
+
+#[cfg(all(feature = "std", not(feature = "error_in_core")))]
+impl std::error::Error for StructureViolation {}
+
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
+    /// Checks the lengths of this node and of all nodes below it, which must
+    /// hold at least `min_len` and `MIN_LEN` elements respectively, and that
+    /// each child links back to its parent. Returns the number of elements.
+    fn check_structure(self, min_len: usize) -> Result<usize, StructureViolation> {
+        let (height, len) = (self.height(), self.len());
+        if len > node::Consts::<B>::CAPACITY {
+            return Err(StructureViolation::Overfull { height, len });
+        }
+        if len < min_len {
+            return Err(StructureViolation::Underfull { height, len });
+        }
+        let mut count = len;
+        if let Internal(node) = self.force() {
+            for idx in 0..=len {
+                // SAFETY: `idx` is within the edges of `node`.
+                let edge = unsafe { Handle::new_edge(node, idx) };
+                let child = edge.descend();
+                if child.ascend().ok() != Some(edge) {
+                    return Err(StructureViolation::BrokenParentLink { height: height - 1, idx });
+                }
+                count += child.check_structure(Consts::<B>::MIN_LEN)?;
+            }
+        }
+        #[cfg(feature = "order_statistics")]
+        if self.subtree_len() != count {
+            return Err(StructureViolation::CountMismatch {
+                height,
+                recorded: self.subtree_len(),
+                counted: count,
+            });
+        }
+        Ok(count)
+    }
+}
+
+/// Checks the nodes of a tree holding `length` elements, without relying on
+/// the navigation code that a malformed tree could derail.
+pub fn check_structure<K, V, S, const B: usize>(
+    root: Option<&Root<K, V, S, B>>,
+    length: usize,
+) -> Result<(), StructureViolation> {
+    let counted = match root {
+        None => 0,
+        Some(root) => {
+            let root_node = root.reborrow();
+            root_node.check_structure(if root_node.height() > 0 { 1 } else { 0 })?
+        }
+    };
+    if counted != length {
+        return Err(StructureViolation::LengthMismatch { recorded: length, counted });
+    }
+    Ok(())
+}
+
+/// With the `paranoid` feature, panics if the tree fails [`check_structure`].
+/// Called at the end of every method that modifies a tree.
+#[inline]
+#[allow(unused_variables)]
+pub fn paranoid_check<K, V, S, const B: usize>(root: Option<&Root<K, V, S, B>>, length: usize) {
+    #[cfg(feature = "paranoid")]
+    if let Err(violation) = check_structure(root, length) {
+        panic!("BTreeMap is corrupted: {}", violation);
+    }
+}
@@ -1,51 +1,94 @@
+use core::cmp::Ordering;
 use core::iter::Peekable;
 
+/// Decides what happens to entries with equal keys when a map is built in bulk,
+/// as by [`BTreeMap::from_sorted_iter_by`][1] and [`BTreeMap::from_unsorted_by`][2].
+///
+/// [`Keep::First`] and [`Keep::Last`] keep one of the entries as it is. Any
+/// `FnMut(&mut V, V)` closure is a policy too: it is handed the value kept so
+/// far together with the next duplicate's value, and combines them. The key of
+/// the first entry is kept in that case.
+///
+/// [1]: super::map::BTreeMap::from_sorted_iter_by
+/// [2]: super::map::BTreeMap::from_unsorted_by
+pub trait DuplicatePolicy<K, V> {
+    /// Folds `next` into `kept`, both having the same key.
+    fn resolve(&mut self, kept: &mut (K, V), next: (K, V));
+}
+
+/// A [`DuplicatePolicy`] keeping a single entry out of each run of equal keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Keep {
+    /// Keep the entry that came first, dropping the later ones.
+    First,
+    /// Keep the entry that came last, like repeated calls to `insert` would,
+    /// except that the key is replaced as well.
+    Last,
+}
+
+impl<K, V> DuplicatePolicy<K, V> for Keep {
+    #[inline]
+    fn resolve(&mut self, kept: &mut (K, V), next: (K, V)) {
+        if let Keep::Last = self {
+            *kept = next;
+        }
+    }
+}
+
+impl<K, V, F> DuplicatePolicy<K, V> for F
+where
+    F: FnMut(&mut V, V),
+{
+    #[inline]
+    fn resolve(&mut self, kept: &mut (K, V), next: (K, V)) {
+        self(&mut kept.1, next.1)
+    }
+}
+
 // A iterator for deduping the key of a sorted iterator.
-// When encountering the duplicated key, only the last key-value pair is yielded.
+// Each run of equal keys is folded into a single key-value pair by the policy.
 //
 // Used by [`BTreeMap::bulk_build_from_sorted_iter`][1].
 //
-// [1]: crate::collections::BTreeMap::bulk_build_from_sorted_iter
-//struct DeleteMe;
-
-//todo consider turning me back on
-//pub struct DedupSortedIter<K, V, I>
-//where
-    //I: Iterator<Item = (K, V)>,
-//{
-    //iter: Peekable<I>,
-//}
-
-//impl<'a, K, V, I> DedupSortedIter<K, V, I>
-//where
-    //I: Iterator<Item = (K, V)>,
-//{
-    //pub fn new(iter: I) -> Self {
-        //Self { iter: iter.peekable() }
-    //}
-//}
-
-//impl<K, V, I> Iterator for DedupSortedIter<K, V, I>
-//where
-    //I: Iterator<Item = (K, V)>,
-//{
-    //type Item = (K, V);
-
-    //fn next(&mut self) -> Option<(K, V)> {
-        //loop {
-            //let next = match self.iter.next() {
-                //Some(next) => next,
-                //None => return None,
-            //};
-
-            //let peeked = match self.iter.peek() {
-                //Some(peeked) => peeked,
-                //None => return Some(next),
-            //};
-
-            //if self.order.ne(&next.0, &peeked.0) {
-                //return Some(next);
-            //}
-        //}
-    //}
-//}
+// [1]: super::map::BTreeMap::bulk_build_from_sorted_iter
+pub struct DedupSortedIter<K, V, I, C, P>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    iter: Peekable<I>,
+    comp: C,
+    policy: P,
+}
+
+impl<K, V, I, C, P> DedupSortedIter<K, V, I, C, P>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    pub fn new(iter: I, comp: C, policy: P) -> Self {
+        Self { iter: iter.peekable(), comp, policy }
+    }
+}
+
+impl<K, V, I, C, P> Iterator for DedupSortedIter<K, V, I, C, P>
+where
+    I: Iterator<Item = (K, V)>,
+    C: FnMut(&K, &K) -> Ordering,
+    P: DuplicatePolicy<K, V>,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let mut next = self.iter.next()?;
+        while let Some(duplicate) =
+            self.iter.next_if(|peeked| (self.comp)(&next.0, &peeked.0).is_eq())
+        {
+            self.policy.resolve(&mut next, duplicate);
+        }
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (lower.min(1), upper)
+    }
+}
@@ -1,18 +1,20 @@
-use super::map::MIN_LEN;
-use super::node::{marker, ForceResult::*, Handle, LeftOrRight::*, NodeRef, Root};
+use super::node::{marker, Consts, ForceResult::*, Handle, LeftOrRight::*, NodeRef, Root};
+use super::summary_map::Summary;
 use crate::polyfill::*;
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
     /// Stocks up a possibly underfull node by merging with or stealing from a
     /// sibling. If successful but at the cost of shrinking the parent node,
     /// returns that shrunk parent node. Returns an `Err` if the node is
     /// an empty root.
-    fn fix_node_through_parent<A: Allocator + Clone>(
+    pub fn fix_node_through_parent<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> Result<Option<NodeRef<marker::Mut<'a>, K, V, marker::Internal>>, Self> {
+    ) -> Result<Option<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>>, Self> {
         let len = self.len();
-        if len >= MIN_LEN {
+        if len >= Consts::<B>::MIN_LEN {
             Ok(None)
         } else {
             match self.choose_parent_kv() {
@@ -21,7 +23,7 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
                         let parent = left_parent_kv.merge_tracking_parent(alloc);
                         Ok(Some(parent))
                     } else {
-                        left_parent_kv.bulk_steal_left(MIN_LEN - len);
+                        left_parent_kv.bulk_steal_left(Consts::<B>::MIN_LEN - len);
                         Ok(None)
                     }
                 }
@@ -30,7 +32,7 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
                         let parent = right_parent_kv.merge_tracking_parent(alloc);
                         Ok(Some(parent))
                     } else {
-                        right_parent_kv.bulk_steal_right(MIN_LEN - len);
+                        right_parent_kv.bulk_steal_right(Consts::<B>::MIN_LEN - len);
                         Ok(None)
                     }
                 }
@@ -46,7 +48,9 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
     /// Stocks up a possibly underfull node, and if that causes its parent node
     /// to shrink, stocks up the parent, recursively.
     /// Returns `true` if it fixed the tree, `false` if it couldn't because the
@@ -65,7 +69,7 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
     }
 }
 
-impl<K, V> Root<K, V> {
+impl<K, V, S: Summary<K, V>, const B: usize> Root<K, V, S, B> {
     /// Removes empty levels on the top, but keeps an empty leaf if the entire tree is empty.
     pub fn fix_top<A: Allocator + Clone>(&mut self, alloc: A) {
         while self.height() > 0 && self.len() == 0 {
@@ -101,11 +105,11 @@ impl<K, V> Root<K, V> {
         while let Internal(internal) = cur_node.force() {
             // Check if right-most child is underfull.
             let mut last_kv = internal.last_kv().consider_for_balancing();
-            debug_assert!(last_kv.left_child_len() >= MIN_LEN * 2);
+            debug_assert!(last_kv.left_child_len() >= Consts::<B>::MIN_LEN * 2);
             let right_child_len = last_kv.right_child_len();
-            if right_child_len < MIN_LEN {
+            if right_child_len < Consts::<B>::MIN_LEN {
                 // We need to steal.
-                last_kv.bulk_steal_left(MIN_LEN - right_child_len);
+                last_kv.bulk_steal_left(Consts::<B>::MIN_LEN - right_child_len);
             }
 
             // Go further down.
@@ -114,23 +118,27 @@ impl<K, V> Root<K, V> {
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::KV>
+{
     fn fix_left_border_of_left_edge<A: Allocator + Clone>(mut self, alloc: A) {
         while let Internal(internal_kv) = self.force() {
             self = internal_kv.fix_left_child(alloc.clone()).first_kv();
-            debug_assert!(self.reborrow().into_node().len() > MIN_LEN);
+            debug_assert!(self.reborrow().into_node().len() > Consts::<B>::MIN_LEN);
         }
     }
 
     fn fix_right_border_of_right_edge<A: Allocator + Clone>(mut self, alloc: A) {
         while let Internal(internal_kv) = self.force() {
             self = internal_kv.fix_right_child(alloc.clone()).last_kv();
-            debug_assert!(self.reborrow().into_node().len() > MIN_LEN);
+            debug_assert!(self.reborrow().into_node().len() > Consts::<B>::MIN_LEN);
         }
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::KV> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::KV>
+{
     /// Stocks up the left child, assuming the right child isn't underfull, and
     /// provisions an extra element to allow merging its children in turn
     /// without becoming underfull.
@@ -138,15 +146,15 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
     fn fix_left_child<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    ) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B> {
         let mut internal_kv = self.consider_for_balancing();
         let left_len = internal_kv.left_child_len();
-        debug_assert!(internal_kv.right_child_len() >= MIN_LEN);
+        debug_assert!(internal_kv.right_child_len() >= Consts::<B>::MIN_LEN);
         if internal_kv.can_merge() {
             internal_kv.merge_tracking_child(alloc)
         } else {
             // `MIN_LEN + 1` to avoid readjust if merge happens on the next level.
-            let count = (MIN_LEN + 1).saturating_sub(left_len);
+            let count = (Consts::<B>::MIN_LEN + 1).saturating_sub(left_len);
             if count > 0 {
                 internal_kv.bulk_steal_right(count);
             }
@@ -161,15 +169,15 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
     fn fix_right_child<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    ) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B> {
         let mut internal_kv = self.consider_for_balancing();
         let right_len = internal_kv.right_child_len();
-        debug_assert!(internal_kv.left_child_len() >= MIN_LEN);
+        debug_assert!(internal_kv.left_child_len() >= Consts::<B>::MIN_LEN);
         if internal_kv.can_merge() {
             internal_kv.merge_tracking_child(alloc)
         } else {
             // `MIN_LEN + 1` to avoid readjust if merge happens on the next level.
-            let count = (MIN_LEN + 1).saturating_sub(right_len);
+            let count = (Consts::<B>::MIN_LEN + 1).saturating_sub(right_len);
             if count > 0 {
                 internal_kv.bulk_steal_left(count);
             }
@@ -0,0 +1,119 @@
+//! Searching nodes for primitive integer keys by comparing them directly,
+//! rather than one at a time through a comparator closure.
+
+use core::ops::Bound;
+
+use super::search::{NodeSearch, SearchBound, Seek};
+
+#[cfg(feature = "portable_simd")]
+use core::simd::{cmp::SimdPartialOrd, Simd};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A primitive integer type, whose keys [`BTreeMap::get_int`] and the other
+/// `_int` methods of the map compare without a comparator.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+///
+/// [`BTreeMap::get_int`]: super::map::BTreeMap::get_int
+pub trait PrimitiveInt: Copy + Ord + Default + sealed::Sealed {
+    /// Counts the keys in `keys` that are smaller than `target`.
+    #[doc(hidden)]
+    fn count_less(keys: &[Self], target: Self) -> usize;
+}
+
+// Doesn't branch on the outcome of each comparison, so that compilers turn
+// the loop into vector compares on their own.
+#[inline]
+fn count_less_branchless<T: Copy + Ord>(keys: &[T], target: T) -> usize {
+    keys.iter().map(|&k| usize::from(k < target)).sum()
+}
+
+#[cfg(not(feature = "portable_simd"))]
+macro_rules! count_less_simd {
+    ($t:ty, $lanes:literal, $keys:expr, $target:expr) => {
+        count_less_branchless($keys, $target)
+    };
+}
+
+// Pads the last chunk with the largest key, which is never smaller than the
+// target.
+#[cfg(feature = "portable_simd")]
+macro_rules! count_less_simd {
+    ($t:ty, $lanes:literal, $keys:expr, $target:expr) => {{
+        let target = Simd::<$t, $lanes>::splat($target);
+        let padding = Simd::splat(<$t>::MAX);
+        $keys
+            .chunks($lanes)
+            .map(|chunk| Simd::load_or(chunk, padding).simd_lt(target).to_bitmask().count_ones())
+            .sum::<u32>() as usize
+    }};
+}
+
+macro_rules! primitive_int {
+    ($($t:ty => $lanes:literal),* ; $($wide:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl PrimitiveInt for $t {
+                #[inline]
+                fn count_less(keys: &[Self], target: Self) -> usize {
+                    count_less_simd!($t, $lanes, keys, target)
+                }
+            }
+        )*
+        $(
+            impl sealed::Sealed for $wide {}
+
+            impl PrimitiveInt for $wide {
+                #[inline]
+                fn count_less(keys: &[Self], target: Self) -> usize {
+                    count_less_branchless(keys, target)
+                }
+            }
+        )*
+    };
+}
+
+primitive_int! {
+    u8 => 16, u16 => 16, u32 => 8, u64 => 4, usize => 4,
+    i8 => 16, i16 => 16, i32 => 8, i64 => 4, isize => 4;
+    u128, i128
+}
+
+/// Seeks a primitive integer key in each node by counting the keys smaller
+/// than it, which takes no comparator calls.
+///
+/// The node search strategy of the map is ignored, since the keys of a node
+/// are all compared at once.
+pub struct IntTarget<K>(pub K);
+
+impl<K: PrimitiveInt> IntTarget<K> {
+    /// Splits a range bound into the target and the bound that the search
+    /// functions take. An absent bound is never compared against.
+    pub fn from_range(bound: Bound<&K>) -> (Self, SearchBound) {
+        let target = match bound {
+            Bound::Included(&k) | Bound::Excluded(&k) => k,
+            Bound::Unbounded => K::default(),
+        };
+        (IntTarget(target), SearchBound::from_range(bound))
+    }
+}
+
+impl<K: PrimitiveInt> Seek<K> for IntTarget<K> {
+    #[inline]
+    fn seek<N: NodeSearch<K>>(&mut self, keys: &[K]) -> Result<usize, usize> {
+        // The keys are ascending and unique, so the target can only be
+        // right after the smaller keys.
+        let idx = K::count_less(keys, self.0);
+        match keys.get(idx) {
+            Some(&k) if k == self.0 => Ok(idx),
+            _ => Err(idx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
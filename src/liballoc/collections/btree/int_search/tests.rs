@@ -0,0 +1,51 @@
+use super::*;
+use crate::liballoc::collections::btree::search::Linear;
+use alloc::vec::Vec;
+
+// Checks `IntTarget` against a comparator search for every target in and
+// around a run of keys of each length a node may hold and more.
+fn check_against_comparator<K: PrimitiveInt + TryFrom<u8>>() {
+    let keys: Vec<K> = (0..40u8).map(|i| K::try_from(i * 3).ok().unwrap()).collect();
+    for len in 0..keys.len() {
+        let keys = &keys[..len];
+        for target in 0..(len * 3 + 2) as u8 {
+            let target = K::try_from(target).ok().unwrap();
+            let expected = keys.binary_search(&target);
+            assert_eq!(IntTarget(target).seek::<Linear>(keys), expected);
+        }
+    }
+}
+
+#[test]
+fn test_unsigned() {
+    check_against_comparator::<u8>();
+    check_against_comparator::<u16>();
+    check_against_comparator::<u32>();
+    check_against_comparator::<u64>();
+    check_against_comparator::<u128>();
+    check_against_comparator::<usize>();
+}
+
+#[test]
+fn test_signed() {
+    let keys = [i32::MIN, -5, -1, 0, 7, i32::MAX];
+    for (idx, &k) in keys.iter().enumerate() {
+        assert_eq!(IntTarget(k).seek::<Linear>(&keys), Ok(idx));
+    }
+    assert_eq!(IntTarget(-3).seek::<Linear>(&keys), Err(2));
+    assert_eq!(IntTarget(1).seek::<Linear>(&keys), Err(4));
+    check_against_comparator::<i16>();
+    check_against_comparator::<i64>();
+    check_against_comparator::<i128>();
+    check_against_comparator::<isize>();
+}
+
+#[test]
+fn test_extremes() {
+    // The largest key is what the last chunk is padded with.
+    let keys = [0, 1, u8::MAX];
+    assert_eq!(IntTarget(u8::MAX).seek::<Linear>(&keys), Ok(2));
+    assert_eq!(IntTarget(u8::MAX - 1).seek::<Linear>(&keys), Err(2));
+    assert_eq!(IntTarget(0).seek::<Linear>(&keys), Ok(0));
+    assert_eq!(IntTarget(i8::MIN).seek::<Linear>(&[i8::MIN]), Ok(0));
+}
@@ -0,0 +1,419 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use super::node::{marker, ForceResult::*, Handle, NodeRef};
+use super::summary_map::{Iter, Summary, SummaryMap, ValueMut};
+use crate::ripytide::{Comparator, OrdComparator};
+
+#[cfg(test)]
+mod tests;
+
+/// The summary that an [`IntervalMap`] keeps in every node: the greatest end
+/// of the intervals in the subtree headed by the node.
+pub struct MaxEnd<K, C> {
+    end: Option<K>,
+    _comp: PhantomData<C>,
+}
+
+impl<K, C> MaxEnd<K, C> {
+    /// Returns the greatest end of the summarized intervals, or `None` if there
+    /// are none.
+    pub fn get(&self) -> Option<&K> {
+        self.end.as_ref()
+    }
+}
+
+impl<K: Clone, C> Clone for MaxEnd<K, C> {
+    fn clone(&self) -> Self {
+        MaxEnd { end: self.end.clone(), _comp: PhantomData }
+    }
+}
+
+impl<K: Debug, C> Debug for MaxEnd<K, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MaxEnd").field(&self.end).finish()
+    }
+}
+
+impl<K: Clone, V, C: Comparator<K> + Default> Summary<Range<K>, V> for MaxEnd<K, C> {
+    fn empty() -> Self {
+        MaxEnd { end: None, _comp: PhantomData }
+    }
+
+    fn of(key: &Range<K>, _value: &V) -> Self {
+        MaxEnd { end: Some(key.end.clone()), _comp: PhantomData }
+    }
+
+    fn combine(&mut self, next: &Self) {
+        if let Some(next_end) = &next.end {
+            match &self.end {
+                Some(end) if C::default().compare(end, next_end) != Ordering::Less => {}
+                _ => self.end = Some(next_end.clone()),
+            }
+        }
+    }
+}
+
+/// An ordered map from half-open intervals to values, which finds the intervals
+/// overlapping a range or containing a point without visiting every interval.
+///
+/// Intervals are ordered by their start, then by their end, and every node of
+/// the underlying B-Tree caches the greatest end below it, so that the queries
+/// skip the subtrees whose intervals all end too early.
+///
+/// The order of the bounds is given by the comparator type `C`, which defaults
+/// to the [`Ord`] implementation of `K`. Unlike the comparators that
+/// [`BTreeMap`](super::map::BTreeMap) methods take with every call, this one
+/// is part of the type: the cached ends are recombined whenever nodes are
+/// split, merged or rebalanced, which [`Summary::combine`] does without access
+/// to the map or to the arguments of the call. A custom order is therefore a
+/// type implementing [`Comparator`], created through [`Default`] whenever two
+/// bounds are compared.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::IntervalMap;
+///
+/// let mut meetings = IntervalMap::<u32, &str>::new();
+/// meetings.insert(9..10, "standup");
+/// meetings.insert(10..12, "review");
+/// meetings.insert(11..15, "workshop");
+///
+/// let during: Vec<_> = meetings.overlapping(&9, &11).map(|(_, name)| *name).collect();
+/// assert_eq!(during, ["standup", "review"]);
+///
+/// let at_noon: Vec<_> = meetings.stabbing(&11).map(|(_, name)| *name).collect();
+/// assert_eq!(at_noon, ["review", "workshop"]);
+/// ```
+///
+/// Ordering the bounds with a comparator of the caller's:
+///
+/// ```
+/// use btree_monstrousity::ripytide::Comparator;
+/// use btree_monstrousity::IntervalMap;
+/// use std::cmp::Ordering;
+///
+/// // Orders times of day that wrap around at midnight, with the day starting at 6.
+/// #[derive(Default)]
+/// struct FromSix;
+///
+/// impl Comparator<u32> for FromSix {
+///     fn compare(&self, a: &u32, b: &u32) -> Ordering {
+///         ((a + 18) % 24).cmp(&((b + 18) % 24))
+///     }
+/// }
+///
+/// let mut shifts = IntervalMap::<u32, &str, FromSix>::new();
+/// shifts.insert(6..14, "early");
+/// shifts.insert(22..4, "night");
+/// let at_two: Vec<_> = shifts.stabbing(&2).map(|(_, name)| *name).collect();
+/// assert_eq!(at_two, ["night"]);
+/// ```
+pub struct IntervalMap<K, V, C = OrdComparator> {
+    map: SummaryMap<Range<K>, V, MaxEnd<K, C>>,
+}
+
+impl<K, V, C> IntervalMap<K, V, C> {
+    /// Makes a new, empty `IntervalMap`.
+    ///
+    /// Does not allocate anything on its own.
+    #[must_use]
+    pub const fn new() -> Self {
+        IntervalMap { map: SummaryMap::new() }
+    }
+
+    /// Returns the number of intervals in the map.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no intervals.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Clears the map, removing all intervals.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by interval.
+    pub fn iter(&self) -> Iter<'_, Range<K>, V, MaxEnd<K, C>> {
+        self.map.iter()
+    }
+}
+
+impl<K: Clone, V, C: Comparator<K> + Default> IntervalMap<K, V, C> {
+    /// Inserts an interval with its value into the map.
+    ///
+    /// If the map did not have this interval present, `None` is returned.
+    ///
+    /// If the map did have this interval present, the value is updated, and the
+    /// old value is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interval is empty, i.e., if its start is not less than its
+    /// end.
+    pub fn insert(&mut self, interval: Range<K>, value: V) -> Option<V> {
+        let comp = C::default();
+        if comp.compare(&interval.start, &interval.end) != Ordering::Less {
+            panic!("interval start is not less than its end");
+        }
+        self.map.insert(interval, value, |a, b| compare_intervals(&comp, a, b))
+    }
+
+    /// Returns a reference to the value of an interval.
+    pub fn get(&self, interval: &Range<K>) -> Option<&V> {
+        let comp = C::default();
+        self.map.get(|k| compare_intervals(&comp, interval, k))
+    }
+
+    /// Returns a guard through which the value of an interval can be changed.
+    pub fn get_mut(
+        &mut self,
+        interval: &Range<K>,
+    ) -> Option<ValueMut<'_, Range<K>, V, MaxEnd<K, C>>> {
+        let comp = C::default();
+        self.map.get_mut(|k| compare_intervals(&comp, interval, k))
+    }
+
+    /// Returns `true` if the map contains the interval.
+    pub fn contains(&self, interval: &Range<K>) -> bool {
+        self.get(interval).is_some()
+    }
+
+    /// Removes an interval from the map, returning its value if the interval
+    /// was previously in the map.
+    pub fn remove(&mut self, interval: &Range<K>) -> Option<V> {
+        let comp = C::default();
+        self.map.remove(|k| compare_intervals(&comp, interval, k))
+    }
+
+    /// Gets an iterator over the entries whose intervals overlap the half-open
+    /// range from `start` to `end`, sorted by interval. An empty range, with
+    /// `start == end`, holds no points and so overlaps nothing; use
+    /// [`stabbing`](IntervalMap::stabbing) for the intervals containing a point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start > end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::IntervalMap;
+    ///
+    /// let mut map = IntervalMap::<i32, char>::new();
+    /// map.insert(0..100, 'a');
+    /// map.insert(10..20, 'b');
+    /// map.insert(30..40, 'c');
+    ///
+    /// let found: Vec<_> = map.overlapping(&15, &30).map(|(_, v)| *v).collect();
+    /// assert_eq!(found, ['a', 'b']);
+    /// ```
+    pub fn overlapping<'a>(&'a self, start: &'a K, end: &'a K) -> Overlapping<'a, K, V, C> {
+        match C::default().compare(start, end) {
+            Ordering::Less => Overlapping::new(self, start, end, false),
+            Ordering::Equal => {
+                Overlapping { position: None, lower: start, upper: end, upper_included: false }
+            }
+            Ordering::Greater => panic!("range start is greater than range end in IntervalMap"),
+        }
+    }
+
+    /// Gets an iterator over the entries whose intervals contain `point`,
+    /// sorted by interval.
+    pub fn stabbing<'a>(&'a self, point: &'a K) -> Overlapping<'a, K, V, C> {
+        Overlapping::new(self, point, point, true)
+    }
+}
+
+/// Orders intervals by their start, then by their end.
+fn compare_intervals<K, C: Comparator<K>>(comp: &C, a: &Range<K>, b: &Range<K>) -> Ordering {
+    comp.compare(&a.start, &b.start).then_with(|| comp.compare(&a.end, &b.end))
+}
+
+impl<K, V, C> Default for IntervalMap<K, V, C> {
+    /// Creates an empty `IntervalMap`.
+    fn default() -> Self {
+        IntervalMap::new()
+    }
+}
+
+impl<K: Clone, V: Clone, C: Comparator<K> + Default> Clone for IntervalMap<K, V, C> {
+    fn clone(&self) -> Self {
+        IntervalMap { map: self.map.clone() }
+    }
+}
+
+impl<K: Debug, V: Debug, C> Debug for IntervalMap<K, V, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl<K: Clone, V, C: Comparator<K> + Default> Extend<(Range<K>, V)> for IntervalMap<K, V, C> {
+    #[inline]
+    fn extend<T: IntoIterator<Item = (Range<K>, V)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<K: Clone, V, C: Comparator<K> + Default> FromIterator<(Range<K>, V)>
+    for IntervalMap<K, V, C>
+{
+    fn from_iter<T: IntoIterator<Item = (Range<K>, V)>>(iter: T) -> Self {
+        let mut map = IntervalMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, K, V, C> IntoIterator for &'a IntervalMap<K, V, C> {
+    type Item = (&'a Range<K>, &'a V);
+    type IntoIter = Iter<'a, Range<K>, V, MaxEnd<K, C>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+type EdgeHandle<'a, K, V, C> = Handle<
+    NodeRef<marker::Immut<'a>, Range<K>, V, marker::LeafOrInternal, MaxEnd<K, C>>,
+    marker::Edge,
+>;
+
+/// Where an [`Overlapping`] iterator resumes its walk of the tree.
+enum Position<'a, K: 'a, V: 'a, C: 'a> {
+    /// Before the subtree below the edge, which has not been visited yet.
+    Descend(EdgeHandle<'a, K, V, C>),
+    /// After the subtree below the edge, which has been visited already.
+    Resume(EdgeHandle<'a, K, V, C>),
+}
+
+impl<'a, K: 'a, V: 'a, C: 'a> Clone for Position<'a, K, V, C> {
+    fn clone(&self) -> Self {
+        match *self {
+            Position::Descend(edge) => Position::Descend(edge),
+            Position::Resume(edge) => Position::Resume(edge),
+        }
+    }
+}
+
+/// An iterator over the entries of an `IntervalMap` that overlap a range or
+/// contain a point.
+///
+/// This `struct` is created by the [`overlapping`] and [`stabbing`] methods on
+/// [`IntervalMap`]. See their documentation for more.
+///
+/// [`overlapping`]: IntervalMap::overlapping
+/// [`stabbing`]: IntervalMap::stabbing
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Overlapping<'a, K: 'a, V: 'a, C: 'a> {
+    position: Option<Position<'a, K, V, C>>,
+    /// Intervals must end after this bound.
+    lower: &'a K,
+    /// Intervals must start before this bound, or at it if `upper_included`.
+    upper: &'a K,
+    upper_included: bool,
+}
+
+impl<'a, K: 'a, V: 'a, C: Comparator<K> + Default> Overlapping<'a, K, V, C> {
+    fn new(
+        map: &'a IntervalMap<K, V, C>,
+        lower: &'a K,
+        upper: &'a K,
+        upper_included: bool,
+    ) -> Self {
+        let mut iter = Overlapping { position: None, lower, upper, upper_included };
+        if let Some(root) = &map.map.root {
+            let root = root.reborrow();
+            if iter.ends_after_lower(root.summary().get()) {
+                iter.position = Some(Position::Descend(root.first_edge()));
+            }
+        }
+        iter
+    }
+
+    /// Returns whether an interval, or any interval of a subtree, ending at
+    /// `end` may overlap the query.
+    fn ends_after_lower(&self, end: Option<&K>) -> bool {
+        matches!(end, Some(end) if C::default().compare(self.lower, end) == Ordering::Less)
+    }
+
+    /// Returns whether an interval starting at `start` may overlap the query.
+    fn starts_before_upper(&self, start: &K) -> bool {
+        match C::default().compare(start, self.upper) {
+            Ordering::Less => true,
+            Ordering::Equal => self.upper_included,
+            Ordering::Greater => false,
+        }
+    }
+}
+
+impl<K, V, C> Clone for Overlapping<'_, K, V, C> {
+    fn clone(&self) -> Self {
+        Overlapping {
+            position: self.position.clone(),
+            lower: self.lower,
+            upper: self.upper,
+            upper_included: self.upper_included,
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, C: Comparator<K> + Default> Debug for Overlapping<'_, K, V, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, C: Comparator<K> + Default> Iterator for Overlapping<'a, K, V, C> {
+    type Item = (&'a Range<K>, &'a V);
+
+    fn next(&mut self) -> Option<(&'a Range<K>, &'a V)> {
+        loop {
+            let edge = match self.position.take()? {
+                Position::Descend(edge) => {
+                    if let Internal(internal_edge) = edge.force() {
+                        let child = internal_edge.descend();
+                        if self.ends_after_lower(child.summary().get()) {
+                            self.position = Some(Position::Descend(child.first_edge()));
+                            continue;
+                        }
+                    }
+                    edge
+                }
+                Position::Resume(edge) => edge,
+            };
+            match edge.right_kv() {
+                Ok(kv) => {
+                    let (interval, value) = kv.into_kv();
+                    // Every interval from here on starts at least as late.
+                    if !self.starts_before_upper(&interval.start) {
+                        return None;
+                    }
+                    self.position = Some(Position::Descend(kv.right_edge()));
+                    if self.ends_after_lower(Some(&interval.end)) {
+                        return Some((interval, value));
+                    }
+                }
+                Err(last_edge) => {
+                    let parent_edge = last_edge.into_node().ascend().ok()?;
+                    self.position = Some(Position::Resume(parent_edge.forget_node_type()));
+                }
+            }
+        }
+    }
+}
+
+impl<K, V, C: Comparator<K> + Default> FusedIterator for Overlapping<'_, K, V, C> {}
@@ -0,0 +1,135 @@
+use super::*;
+use crate::liballoc::testing::rng::DeterministicRng;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+fn brute_force(map: &IntervalMap<u32, u32>, start: u32, end: u32) -> Vec<(Range<u32>, u32)> {
+    map.iter()
+        .filter(|(k, _)| start < end && k.start < end && start < k.end)
+        .map(|(k, v)| (k.clone(), *v))
+        .collect()
+}
+
+fn overlapping(map: &IntervalMap<u32, u32>, start: u32, end: u32) -> Vec<(Range<u32>, u32)> {
+    map.overlapping(&start, &end).map(|(k, v)| (k.clone(), *v)).collect()
+}
+
+fn stabbing(map: &IntervalMap<u32, u32>, point: u32) -> Vec<(Range<u32>, u32)> {
+    map.stabbing(&point).map(|(k, v)| (k.clone(), *v)).collect()
+}
+
+fn random_map(rng: &mut DeterministicRng, len: u32) -> IntervalMap<u32, u32> {
+    let mut map = IntervalMap::new();
+    for i in 0..len {
+        let start = rng.next() % 1000;
+        // Mostly short intervals, with the odd long one.
+        let width = if i % 17 == 0 { rng.next() % 500 } else { rng.next() % 20 } + 1;
+        map.insert(start..start + width, i);
+    }
+    map
+}
+
+#[test]
+fn test_basic() {
+    let mut map = IntervalMap::<u32, char>::new();
+    assert!(map.is_empty());
+    assert_eq!(map.insert(1..3, 'a'), None);
+    assert_eq!(map.insert(1..5, 'b'), None);
+    assert_eq!(map.insert(1..3, 'c'), Some('a'));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&(1..3)), Some(&'c'));
+    assert!(map.contains(&(1..5)));
+    assert!(!map.contains(&(1..4)));
+    *map.get_mut(&(1..5)).unwrap() = 'd';
+    assert_eq!(map.remove(&(1..3)), Some('c'));
+    assert_eq!(map.remove(&(1..3)), None);
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(&(1..5), &'d')]);
+}
+
+#[test]
+#[should_panic]
+fn test_insert_empty_interval() {
+    IntervalMap::<u32, ()>::new().insert(3..3, ());
+}
+
+#[test]
+#[should_panic]
+fn test_overlapping_backwards() {
+    let _ = IntervalMap::<u32, ()>::new().overlapping(&5, &3);
+}
+
+#[test]
+fn test_empty() {
+    let map = IntervalMap::<u32, u32>::new();
+    assert_eq!(overlapping(&map, 0, 100), []);
+    assert_eq!(stabbing(&map, 0), []);
+}
+
+#[test]
+fn test_half_open() {
+    let map: IntervalMap<u32, u32> = [(10..20, 0)].into_iter().collect();
+    assert_eq!(stabbing(&map, 9), []);
+    assert_eq!(stabbing(&map, 10), [(10..20, 0)]);
+    assert_eq!(stabbing(&map, 19), [(10..20, 0)]);
+    assert_eq!(stabbing(&map, 20), []);
+    assert_eq!(overlapping(&map, 0, 10), []);
+    assert_eq!(overlapping(&map, 20, 30), []);
+}
+
+#[test]
+fn test_overlapping_empty_range() {
+    let map: IntervalMap<u32, u32> = [(0..10, 0), (5..6, 1), (10..20, 2)].into_iter().collect();
+    for point in [0, 5, 6, 10, 15, 20, 30] {
+        assert_eq!(overlapping(&map, point, point), []);
+    }
+}
+
+#[test]
+fn test_random_queries() {
+    let mut rng = DeterministicRng::new();
+    let mut map = random_map(&mut rng, 2000);
+    for _ in 0..2 {
+        for _ in 0..200 {
+            let start = rng.next() % 1100;
+            let end = start + rng.next() % 50;
+            assert_eq!(overlapping(&map, start, end), brute_force(&map, start, end));
+            assert_eq!(stabbing(&map, start), brute_force(&map, start, start + 1));
+        }
+        // Remove every other interval, which merges and steals between nodes.
+        let keys: Vec<_> = map.iter().map(|(k, _)| k.clone()).step_by(2).collect();
+        for key in keys {
+            map.remove(&key);
+        }
+    }
+}
+
+#[test]
+fn test_iterator_is_fused() {
+    let map: IntervalMap<u32, u32> = (0..100).map(|i| (i..i + 2, i)).collect();
+    let mut iter = map.stabbing(&50);
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+}
+
+#[derive(Default)]
+struct Descending;
+
+impl Comparator<u32> for Descending {
+    fn compare(&self, this: &u32, that: &u32) -> Ordering {
+        Reverse(this).cmp(&Reverse(that))
+    }
+}
+
+#[test]
+fn test_custom_comparator() {
+    let mut map = IntervalMap::<u32, char, Descending>::new();
+    // Intervals run from high to low numbers under the reversed order.
+    map.insert(Range { start: 20, end: 10 }, 'a');
+    map.insert(Range { start: 15, end: 5 }, 'b');
+    map.insert(Range { start: 4, end: 0 }, 'c');
+    let found: Vec<_> = map.overlapping(&12, &4).map(|(_, v)| *v).collect();
+    assert_eq!(found, ['a', 'b']);
+    let found: Vec<_> = map.stabbing(&4).map(|(_, v)| *v).collect();
+    assert_eq!(found, ['c']);
+}
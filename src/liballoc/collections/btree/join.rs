@@ -0,0 +1,151 @@
+use core::mem;
+
+use super::node::{marker, Consts, ForceResult::*, Handle, NodeRef, Root, SplitResult};
+use super::summary_map::Summary;
+use crate::polyfill::*;
+
+impl<K, V, S: Summary<K, V>, const B: usize> Root<K, V, S, B> {
+    /// Appends the key-value pairs of `right` to the tree, taking time in
+    /// proportion to the heights of both trees rather than their lengths.
+    /// The result is meaningful only if the keys of `right` are all greater
+    /// than the keys of `self`.
+    /// If both trees respect all `BTreeMap` tree invariants, then so will the
+    /// joined tree.
+    pub fn append_tree<A: Allocator + Clone>(&mut self, mut right: Self, alloc: A) {
+        if right.is_empty_leaf() {
+            return right.deallocate_empty_leaf(alloc);
+        }
+        if self.is_empty_leaf() {
+            mem::swap(self, &mut right);
+            return right.deallocate_empty_leaf(alloc);
+        }
+
+        // The smallest pair of `right` goes in between the two trees.
+        let (key, val) = right.pop_first(alloc.clone());
+        if right.is_empty_leaf() {
+            right.deallocate_empty_leaf(alloc.clone());
+            return self.push_last(key, val, alloc);
+        }
+
+        let left_height = self.height();
+        let right_height = right.height();
+        if left_height == right_height {
+            // Both trees hang off a new root, and as former roots either of
+            // them may be underfull.
+            let mut top = self.push_internal_level(alloc.clone());
+            top.push(key, val, right);
+            let mut middle_kv = top.first_kv().consider_for_balancing();
+            if middle_kv.can_merge() {
+                middle_kv.merge_tracking_parent(alloc.clone());
+            } else {
+                let left_len = middle_kv.left_child_len();
+                let right_len = middle_kv.right_child_len();
+                if left_len < Consts::<B>::MIN_LEN {
+                    middle_kv.bulk_steal_right(Consts::<B>::MIN_LEN - left_len);
+                } else if right_len < Consts::<B>::MIN_LEN {
+                    middle_kv.bulk_steal_left(Consts::<B>::MIN_LEN - right_len);
+                }
+            }
+        } else {
+            // The shorter tree hangs off the border of the taller one that
+            // faces it, next to the separating pair.
+            let mut root_split = None;
+            let shorter = if left_height > right_height {
+                let shorter = right.borrow_mut().dormant();
+                let parent =
+                    self.borrow_mut().border_node_at(right_height + 1, |node| node.last_edge());
+                parent.last_edge().insert_recursing(key, val, right, alloc.clone(), |split| {
+                    root_split = Some(split.detach());
+                });
+                shorter
+            } else {
+                let mut left = mem::replace(self, right);
+                let shorter = left.borrow_mut().dormant();
+                let parent =
+                    self.borrow_mut().border_node_at(left_height + 1, |node| node.first_edge());
+                // Puts `left` in front of the first child, and the first child
+                // back after the separating pair.
+                let mut edge = parent.first_edge();
+                let first_child = edge.replace_child(left);
+                edge.insert_recursing(key, val, first_child, alloc.clone(), |split| {
+                    root_split = Some(split.detach());
+                });
+                shorter
+            };
+            if let Some(((key, val), right)) = root_split {
+                self.push_internal_level(alloc.clone()).push(key, val, right);
+            }
+            // SAFETY: The former root of the shorter tree is a node of the
+            // joined tree now, and nothing else refers to the tree.
+            unsafe { shorter.awaken() }.fix_node_and_affected_ancestors(alloc.clone());
+        }
+        self.fix_top(alloc);
+    }
+
+    fn is_empty_leaf(&self) -> bool {
+        self.height() == 0 && self.len() == 0
+    }
+
+    fn deallocate_empty_leaf<A: Allocator + Clone>(self, alloc: A) {
+        debug_assert!(self.is_empty_leaf());
+        // SAFETY: The node has no parent and no elements to drop.
+        unsafe { self.into_dying().deallocate_and_ascend(alloc) };
+    }
+
+    /// Removes the smallest key-value pair of a tree that is not empty.
+    fn pop_first<A: Allocator + Clone>(&mut self, alloc: A) -> (K, V) {
+        let mut emptied_internal_root = false;
+        let first_kv = self.borrow_mut().first_leaf_edge().right_kv().ok().unwrap();
+        let (kv, _) = first_kv
+            .forget_node_type()
+            .remove_kv_tracking(|| emptied_internal_root = true, alloc.clone());
+        if emptied_internal_root {
+            self.pop_internal_level(alloc);
+        }
+        kv
+    }
+
+    /// Adds a key-value pair after all others.
+    fn push_last<A: Allocator + Clone>(&mut self, key: K, val: V, alloc: A) {
+        let mut root_split = None;
+        let edge = self.borrow_mut().last_leaf_edge();
+        edge.insert_recursing(key, val, alloc.clone(), |split| root_split = Some(split.detach()));
+        if let Some(((key, val), right)) = root_split {
+            self.push_internal_level(alloc).push(key, val, right);
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
+    /// Descends from the node along the edges chosen by `border_edge` until the
+    /// internal node at the given height, which must not be above the node.
+    fn border_node_at<F>(
+        self,
+        height: usize,
+        mut border_edge: F,
+    ) -> NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>
+    where
+        F: FnMut(
+            NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>,
+        )
+            -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::Edge>,
+    {
+        let mut node = self;
+        loop {
+            match node.force() {
+                Internal(internal) if internal.height() == height => return internal,
+                Internal(internal) => node = border_edge(internal).descend(),
+                Leaf(_) => unreachable!("no border node at height {}", height),
+            }
+        }
+    }
+}
+
+impl<'a, K, V, S, const B: usize> SplitResult<'a, K, V, marker::LeafOrInternal, S, B> {
+    /// Lets go of the split node, keeping what was split off it.
+    fn detach(self) -> ((K, V), Root<K, V, S, B>) {
+        (self.kv, self.right)
+    }
+}
@@ -1,21 +1,33 @@
 use alloc::vec::Vec;
 use cfg_if::cfg_if;
+use core::any::TypeId;
+use core::borrow::Borrow;
+use core::cell::RefCell;
 use core::cmp::Ordering;
 use core::fmt::{self, Debug};
 use core::hash::{Hash, Hasher};
-use core::iter::{FromIterator, FusedIterator};
+use core::iter::{self, FromIterator, FusedIterator};
 use core::marker::PhantomData;
 use core::mem::{self, ManuallyDrop};
+#[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+use core::num::NonZeroUsize;
 use core::ops::{Bound, Deref, DerefMut, Index, RangeBounds};
 use core::ptr;
 
 use crate::polyfill::*;
+use crate::ripytide::Query;
 
 use super::borrow::DormantMutRef;
+use super::check::{check_structure, paranoid_check};
+use super::dedup_sorted_iter::DedupSortedIter;
+use super::int_search::IntTarget;
+use super::merge_iter::MergeIterInner;
 use super::navigate::{LazyLeafRange, LeafRange};
 use super::node::{self, marker, ForceResult::*, Handle, NodeRef, Root};
 use super::search::{SearchBound, SearchResult::*};
 use super::set_val::SetValZST;
+#[cfg(feature = "btree_cursors")]
+use super::split::SplitPath;
 
 mod entry;
 
@@ -23,13 +35,78 @@ mod entry;
 pub use entry::OccupiedError;
 pub use entry::{Entry, OccupiedEntry, VacantEntry};
 
+pub use super::check::{OrderViolation, StructureViolation};
+pub use super::dedup_sorted_iter::{DuplicatePolicy, Keep};
+pub use super::int_search::PrimitiveInt;
+pub use super::search::{Binary, Position, Midrange, Linear, NodeSearch};
+
+/// The allocator of maps that are not given one, named in the type of maps
+/// with a non-default branching factor.
+#[doc(no_inline)]
+pub use crate::polyfill::Global;
+
 use Entry::*;
 
-/// Minimum number of elements in a node that is not a root.
-/// We might temporarily have fewer elements during methods.
-pub(super) const MIN_LEN: usize = node::MIN_LEN_AFTER_SPLIT;
+impl<const B: usize> node::Consts<B> {
+    /// Minimum number of elements in a node that is not a root.
+    /// We might temporarily have fewer elements during methods.
+    pub(super) const MIN_LEN: usize = Self::MIN_LEN_AFTER_SPLIT;
+}
+
+// This constant is used by functions that compare two maps or sets.
+// It estimates the relative size at which searching performs better
+// than iterating, based on the benchmarks in
+// https://github.com/ssomers/rust_bench_btreeset_intersection.
+// It's used to divide rather than multiply sizes, to rule out overflow,
+// and it's a power of two to make that division cheap.
+pub(super) const ITER_PERFORMANCE_TIPPING_SIZE_DIFF: usize = 16;
+
+// Whether `extend_by` should merge a batch into the map rather than insert it
+// pair by pair. Inserting costs about `batch_len * log2(map_len)` comparisons,
+// while merging rebuilds the whole tree, costing about `map_len + batch_len`.
+fn merge_beats_inserting<const B: usize>(map_len: usize, batch_len: usize) -> bool {
+    let log2_map_len = (usize::BITS - map_len.leading_zeros()) as usize;
+    batch_len > node::Consts::<B>::CAPACITY && batch_len.saturating_mul(log2_map_len) >= map_len
+}
+
+// Panics on the inverted bounds that the standard library rejects, comparing
+// the bounds to each other with `cmp`.
+fn check_range_bounds<Q: ?Sized, C>(start: Bound<&Q>, end: Bound<&Q>, cmp: &mut C)
+where
+    C: FnMut(&Q, &Q) -> Ordering,
+{
+    match (start, end) {
+        (Bound::Excluded(s), Bound::Excluded(e)) if cmp(s, e) == Ordering::Equal => {
+            panic!("range start and end are equal and excluded in BTreeMap")
+        }
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
+            if cmp(s, e) == Ordering::Greater =>
+        {
+            panic!("range start is greater than range end in BTreeMap")
+        }
+        _ => {}
+    }
+}
+
+// Turns one end of a range into the one-argument comparator that the search
+// functions take, sharing `cmp` with the other end. An unbounded end is never
+// compared against.
+fn range_end_comp<'a, K, Q: ?Sized, C>(
+    bound: Bound<&'a Q>,
+    cmp: &'a RefCell<C>,
+) -> impl FnMut(&K) -> Ordering + 'a
+where
+    K: Borrow<Q> + 'a,
+    C: FnMut(&Q, &Q) -> Ordering,
+{
+    move |k| match bound {
+        Bound::Included(q) | Bound::Excluded(q) => (cmp.borrow_mut())(q, k.borrow()),
+        Bound::Unbounded => unreachable!("compared against an unbounded end of a range"),
+    }
+}
 
 /// ripytide's bodge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SearchBoundCustom {
     /// An inclusive bound to look for, just like `Bound::Included(T)`.
     Included,
@@ -73,26 +150,31 @@ impl From<SearchBoundCustom> for SearchBound {
 ///
 /// A B-Tree instead makes each node contain B-1 to 2B-1 elements in a contiguous array. By doing
 /// this, we reduce the number of allocations by a factor of B, and improve cache efficiency in
-/// searches. However, this does mean that searches will have to do *more* comparisons on average.
+/// searches. B is the last type parameter and defaults to 6; maps with another B are created by
+/// [`BTreeMap::with_branching_factor`], and [`branching_factor`] suggests one for given key and
+/// value types. However, this does mean that searches will have to do *more* comparisons on average.
 /// The precise number of comparisons depends on the node search strategy used. For optimal cache
 /// efficiency, one could search the nodes linearly. For optimal comparisons, one could search
 /// the node using binary search. As a compromise, one could also perform a linear search
 /// that initially only checks every i<sup>th</sup> element for some choice of i.
 ///
-/// Currently, our implementation simply performs naive linear search. This provides excellent
-/// performance on *small* nodes of elements which are cheap to compare. However in the future we
-/// would like to further explore choosing the optimal search strategy based on the choice of B,
-/// and possibly other factors. Using linear search, searching for a random element is expected
-/// to take B * log(n) comparisons, which is generally worse than a BST. In practice,
-/// however, performance is excellent.
+/// By default, our implementation performs naive linear search. This provides excellent
+/// performance on *small* nodes of elements which are cheap to compare. Using linear search,
+/// searching for a random element is expected to take B * log(n) comparisons, which is generally
+/// worse than a BST. In practice, however, performance is excellent. When comparisons are
+/// expensive, the [`NodeSearch`] strategy named by the type parameter after B can be switched to
+/// [`Binary`] or [`Midrange`] search, for instance with [`BTreeMap::with_node_search`].
 ///
-/// It is a logic error for a key or total order to be modified in such a way that the key's
-/// ordering relative to any other key, as determined by that total order, changes while they are
-/// in the map. This is normally only possible through [`Cell`], [`RefCell`], global state, I/O,
-/// or unsafe code. The behavior resulting from such a logic error is not specified, but will be
-/// encapsulated to the `BTreeMap` that observed the logic error and not result in undefined
-/// behavior. This could include panics, incorrect results, aborts, memory leaks, and
-/// non-termination.
+/// The map does not store an ordering of its own: every method that needs to compare keys takes
+/// a comparator closure, and it is a logic error to pass comparators that disagree with each
+/// other on the same map. It is also a logic error for a key to be modified in such a way that
+/// the key's ordering relative to any other key, as determined by those comparators, changes
+/// while it is in the map. This is normally only possible through [`Cell`], [`RefCell`], global
+/// state, I/O, or unsafe code. The behavior resulting from such a logic error is not specified,
+/// but will be encapsulated to the `BTreeMap` that observed the logic error and not result in
+/// undefined behavior. This could include panics, incorrect results, aborts, memory leaks, and
+/// non-termination. To have the comparator fixed once at construction instead, use
+/// [`OrderedMap`](crate::OrderedMap).
 ///
 /// Iterators obtained from functions such as [`BTreeMap::iter`], [`BTreeMap::values`], or
 /// [`BTreeMap::keys`] produce their items in order by key, and take worst-case logarithmic and
@@ -105,38 +187,38 @@ impl From<SearchBoundCustom> for SearchBound {
 /// # Examples
 ///
 /// ```
-/// use copse::BTreeMap;
+/// use btree_monstrousity::BTreeMap;
 ///
 /// // type inference lets us omit an explicit type signature (which
 /// // would be `BTreeMap<&str, &str>` in this example).
 /// let mut movie_reviews = BTreeMap::default();
 ///
 /// // review some movies.
-/// movie_reviews.insert("Office Space",       "Deals with real issues in the workplace.");
-/// movie_reviews.insert("Pulp Fiction",       "Masterpiece.");
-/// movie_reviews.insert("The Godfather",      "Very enjoyable.");
-/// movie_reviews.insert("The Blues Brothers", "Eye lyked it a lot.");
+/// movie_reviews.insert("Office Space", "Deals with real issues in the workplace.", Ord::cmp);
+/// movie_reviews.insert("Pulp Fiction", "Masterpiece.", Ord::cmp);
+/// movie_reviews.insert("The Godfather", "Very enjoyable.", Ord::cmp);
+/// movie_reviews.insert("The Blues Brothers", "Eye lyked it a lot.", Ord::cmp);
 ///
 /// // check for a specific one.
-/// if !movie_reviews.contains_key("Les Misérables") {
+/// if !movie_reviews.contains_key(|k| "Les Misérables".cmp(k)) {
 ///     println!("We've got {} reviews, but Les Misérables ain't one.",
 ///              movie_reviews.len());
 /// }
 ///
 /// // oops, this review has a lot of spelling mistakes, let's delete it.
-/// movie_reviews.remove("The Blues Brothers");
+/// movie_reviews.remove(|k| "The Blues Brothers".cmp(k));
 ///
 /// // look up the values associated with some keys.
 /// let to_find = ["Up!", "Office Space"];
 /// for movie in &to_find {
-///     match movie_reviews.get(movie) {
+///     match movie_reviews.get(|k| movie.cmp(k)) {
 ///        Some(review) => println!("{movie}: {review}"),
 ///        None => println!("{movie} is unreviewed.")
 ///     }
 /// }
 ///
 /// // Look up the value for a key (will panic if the key is not found).
-/// println!("Movie review: {}", movie_reviews["Office Space"]);
+/// println!("Movie review: {}", movie_reviews.get(|k| "Office Space".cmp(k)).unwrap());
 ///
 /// // iterate over everything.
 /// for (movie, review) in &movie_reviews {
@@ -144,17 +226,20 @@ impl From<SearchBoundCustom> for SearchBound {
 /// }
 /// ```
 ///
-/// A `BTreeMap` with a known list of items can be initialized from an array:
+/// A `BTreeMap` with a known list of items can be filled from an array:
 ///
 /// ```
-/// use copse::BTreeMap;
+/// use btree_monstrousity::BTreeMap;
 ///
-/// let solar_distance = BTreeMap::<_, _>::from([
+/// let mut solar_distance = BTreeMap::new();
+/// for (planet, distance) in [
 ///     ("Mercury", 0.4),
 ///     ("Venus", 0.7),
 ///     ("Earth", 1.0),
 ///     ("Mars", 1.5),
-/// ]);
+/// ] {
+///     solar_distance.insert(planet, distance, Ord::cmp);
+/// }
 /// ```
 ///
 /// `BTreeMap` implements an [`Entry API`], which allows for complex
@@ -163,7 +248,7 @@ impl From<SearchBoundCustom> for SearchBound {
 /// [`Entry API`]: BTreeMap::entry
 ///
 /// ```
-/// use copse::BTreeMap;
+/// use btree_monstrousity::BTreeMap;
 ///
 /// // type inference lets us omit an explicit type signature (which
 /// // would be `BTreeMap<&str, u8>` in this example).
@@ -176,40 +261,41 @@ impl From<SearchBoundCustom> for SearchBound {
 /// }
 ///
 /// // insert a key only if it doesn't already exist
-/// player_stats.entry("health").or_insert(100);
+/// player_stats.entry("health", Ord::cmp).or_insert(100);
 ///
 /// // insert a key using a function that provides a new value only if it
 /// // doesn't already exist
-/// player_stats.entry("defence").or_insert_with(random_stat_buff);
+/// player_stats.entry("defence", Ord::cmp).or_insert_with(random_stat_buff);
 ///
 /// // update a key, guarding against the key possibly not being set
-/// let stat = player_stats.entry("attack").or_insert(100);
+/// let stat = player_stats.entry("attack", Ord::cmp).or_insert(100);
 /// *stat += random_stat_buff();
 ///
 /// // modify an entry before an insert with in-place mutation
-/// player_stats.entry("mana").and_modify(|mana| *mana += 200).or_insert(100);
+/// player_stats.entry("mana", Ord::cmp).and_modify(|mana| *mana += 200).or_insert(100);
 /// ```
 #[cfg_attr(feature = "rustc_attrs", rustc_insignificant_dtor)]
-pub struct BTreeMap<K, V, A: Allocator + Clone = Global> {
-    root: Option<Root<K, V>>,
+pub struct BTreeMap<K, V, A: Allocator + Clone = Global, const B: usize = 6, N = Linear> {
+    root: Option<Root<K, V, (), B>>,
     length: usize,
     /// `ManuallyDrop` to control drop order (needs to be dropped after all the nodes).
     pub(super) alloc: ManuallyDrop<A>,
-    // For dropck; the `Box` avoids making the `Unpin` impl more strict than before
-    _marker: PhantomData<alloc::boxed::Box<(K, V)>>,
+    // For dropck; the `Box` avoids making the `Unpin` impl more strict than before.
+    // The node search strategy is only ever named, so it does not affect auto traits.
+    _marker: PhantomData<(alloc::boxed::Box<(K, V)>, fn() -> N)>,
 }
 
 cfg_if! {
     if #[cfg(feature = "dropck_eyepatch")] {
-        unsafe impl<#[may_dangle] K, #[may_dangle] V, A: Allocator + Clone> Drop
-            for BTreeMap<K, V, A>
+        unsafe impl<#[may_dangle] K, #[may_dangle] V, A: Allocator + Clone, const B: usize, N> Drop
+            for BTreeMap<K, V, A, B, N>
         {
             fn drop(&mut self) {
                 drop(unsafe { ptr::read(self) }.into_iter())
             }
         }
     } else {
-        impl<K, V, A: Allocator + Clone> Drop for BTreeMap<K, V, A> {
+        impl<K, V, A: Allocator + Clone, const B: usize, N> Drop for BTreeMap<K, V, A, B, N> {
             fn drop(&mut self) {
                 drop(unsafe { ptr::read(self) }.into_iter())
             }
@@ -221,7 +307,8 @@ cfg_if! {
 // (The bounds of the automatic `UnwindSafe` implementation have been like this since Rust 1.50.)
 // Maybe we can fix it nonetheless with a crater run, or if the `UnwindSafe`
 // traits are deprecated, or disarmed (no longer causing hard errors) in the future.
-impl<K, V, A: Allocator + Clone> core::panic::UnwindSafe for BTreeMap<K, V, A>
+impl<K, V, A: Allocator + Clone, const B: usize, N> core::panic::UnwindSafe
+    for BTreeMap<K, V, A, B, N>
 where
     A: core::panic::UnwindSafe,
     K: core::panic::RefUnwindSafe,
@@ -229,12 +316,14 @@ where
 {
 }
 
-impl<K: Clone, V: Clone, A: Allocator + Clone> Clone for BTreeMap<K, V, A> {
-    fn clone(&self) -> BTreeMap<K, V, A> {
-        fn clone_subtree<'a, K: Clone, V: Clone, A: Allocator + Clone>(
-            node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>,
+impl<K: Clone, V: Clone, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Clone
+    for BTreeMap<K, V, A, B, N>
+{
+    fn clone(&self) -> BTreeMap<K, V, A, B, N> {
+        fn clone_subtree<'a, K: Clone, V: Clone, A: Allocator + Clone, const B: usize, N>(
+            node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, (), B>,
             alloc: A,
-        ) -> BTreeMap<K, V, A>
+        ) -> BTreeMap<K, V, A, B, N>
         where
             K: 'a,
             V: 'a,
@@ -281,7 +370,8 @@ impl<K: Clone, V: Clone, A: Allocator + Clone> Clone for BTreeMap<K, V, A> {
 
                             let k = (*k).clone();
                             let v = (*v).clone();
-                            let subtree = clone_subtree(in_edge.descend(), alloc.clone());
+                            let subtree =
+                                clone_subtree::<K, V, A, B, N>(in_edge.descend(), alloc.clone());
 
                             // We can't destructure subtree directly
                             // because BTreeMap implements Drop
@@ -307,31 +397,36 @@ impl<K: Clone, V: Clone, A: Allocator + Clone> Clone for BTreeMap<K, V, A> {
         }
 
         if self.is_empty() {
-            BTreeMap::new_in((*self.alloc).clone())
+            BTreeMap::with_branching_factor_in((*self.alloc).clone())
         } else {
             clone_subtree(self.root.as_ref().unwrap().reborrow(), (*self.alloc).clone()) // unwrap succeeds because not empty
         }
     }
 }
 
-impl<K, C, A: Allocator + Clone> super::Recover<C> for BTreeMap<K, SetValZST, A>
-where
-    C: FnMut(&K) -> Ordering,
+impl<K, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> super::Recover
+    for BTreeMap<K, SetValZST, A, B, N>
 {
     type Key = K;
 
-    fn get(&self, comp: C) -> Option<&K> {
+    fn get<C>(&self, comp: C) -> Option<&K>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
         let root_node = self.root.as_ref()?.reborrow();
-        match root_node.search_tree(comp) {
+        match root_node.search_tree::<N, _>(comp) {
             Found(handle) => Some(handle.into_kv().0),
             GoDown(_) => None,
         }
     }
 
-    fn take(&mut self, comp: C) -> Option<K> {
+    fn take<C>(&mut self, comp: C) -> Option<K>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
         let (map, dormant_map) = DormantMutRef::new(self);
         let root_node = map.root.as_mut()?.borrow_mut();
-        match root_node.search_tree(comp) {
+        match root_node.search_tree::<N, _>(comp) {
             Found(handle) => Some(
                 OccupiedEntry {
                     handle,
@@ -346,11 +441,14 @@ where
         }
     }
 
-    fn replace(&mut self, key: K, comp: C) -> Option<K> {
+    fn replace<C>(&mut self, key: K, mut double_comp: C) -> Option<K>
+    where
+        C: FnMut(&K, &K) -> Ordering,
+    {
         let (map, dormant_map) = DormantMutRef::new(self);
         let root_node =
             map.root.get_or_insert_with(|| Root::new((*map.alloc).clone())).borrow_mut();
-        match root_node.search_tree(comp) {
+        match root_node.search_tree::<N, _>(|k: &K| double_comp(&key, k)) {
             Found(mut kv) => Some(mem::replace(kv.key_mut(), key)),
             GoDown(handle) => {
                 VacantEntry {
@@ -360,7 +458,7 @@ where
                     alloc: (*map.alloc).clone(),
                     _marker: PhantomData,
                 }
-                .insert(SetValZST::default());
+                .insert(SetValZST);
                 None
             }
         }
@@ -374,12 +472,12 @@ where
 ///
 /// [`iter`]: BTreeMap::iter
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct Iter<'a, K: 'a, V: 'a> {
-    range: LazyLeafRange<marker::Immut<'a>, K, V>,
+pub struct Iter<'a, K: 'a, V: 'a, const B: usize = 6> {
+    range: LazyLeafRange<marker::Immut<'a>, K, V, (), B>,
     length: usize,
 }
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, const B: usize> fmt::Debug for Iter<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
@@ -391,8 +489,8 @@ impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
 /// documentation for more.
 ///
 /// [`iter_mut`]: BTreeMap::iter_mut
-pub struct IterMut<'a, K: 'a, V: 'a> {
-    range: LazyLeafRange<marker::ValMut<'a>, K, V>,
+pub struct IterMut<'a, K: 'a, V: 'a, const B: usize = 6> {
+    range: LazyLeafRange<marker::ValMut<'a>, K, V, (), B>,
     length: usize,
 
     // Be invariant in `K` and `V`
@@ -400,7 +498,7 @@ pub struct IterMut<'a, K: 'a, V: 'a> {
 }
 
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IterMut<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, const B: usize> fmt::Debug for IterMut<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let range = Iter { range: self.range.reborrow(), length: self.length };
         f.debug_list().entries(range).finish()
@@ -415,22 +513,22 @@ impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IterMut<'_, K, V> {
 /// [`into_iter`]: IntoIterator::into_iter
 /// [`IntoIterator`]: core::iter::IntoIterator
 #[cfg_attr(feature = "rustc_attrs", rustc_insignificant_dtor)]
-pub struct IntoIter<K, V, A: Allocator + Clone = Global> {
-    range: LazyLeafRange<marker::Dying, K, V>,
+pub struct IntoIter<K, V, A: Allocator + Clone = Global, const B: usize = 6> {
+    range: LazyLeafRange<marker::Dying, K, V, (), B>,
     length: usize,
     /// The BTreeMap will outlive this IntoIter so we don't care about drop order for `alloc`.
     alloc: A,
 }
 
-impl<K, V, A: Allocator + Clone> IntoIter<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> IntoIter<K, V, A, B> {
     /// Returns an iterator of references over the remaining items.
     #[inline]
-    pub(super) fn iter(&self) -> Iter<'_, K, V> {
+    pub(super) fn iter(&self) -> Iter<'_, K, V, B> {
         Iter { range: self.range.reborrow(), length: self.length }
     }
 }
 
-impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for IntoIter<K, V, A> {
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize> Debug for IntoIter<K, V, A, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.iter()).finish()
     }
@@ -443,11 +541,11 @@ impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for IntoIter<K, V, A> {
 ///
 /// [`keys`]: BTreeMap::keys
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct Keys<'a, K, V> {
-    inner: Iter<'a, K, V>,
+pub struct Keys<'a, K, V, const B: usize = 6> {
+    inner: Iter<'a, K, V, B>,
 }
 
-impl<K: fmt::Debug, V> fmt::Debug for Keys<'_, K, V> {
+impl<K: fmt::Debug, V, const B: usize> fmt::Debug for Keys<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
@@ -460,11 +558,11 @@ impl<K: fmt::Debug, V> fmt::Debug for Keys<'_, K, V> {
 ///
 /// [`values`]: BTreeMap::values
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct Values<'a, K, V> {
-    inner: Iter<'a, K, V>,
+pub struct Values<'a, K, V, const B: usize = 6> {
+    inner: Iter<'a, K, V, B>,
 }
 
-impl<K, V: fmt::Debug> fmt::Debug for Values<'_, K, V> {
+impl<K, V: fmt::Debug, const B: usize> fmt::Debug for Values<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
@@ -477,11 +575,11 @@ impl<K, V: fmt::Debug> fmt::Debug for Values<'_, K, V> {
 ///
 /// [`values_mut`]: BTreeMap::values_mut
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct ValuesMut<'a, K, V> {
-    inner: IterMut<'a, K, V>,
+pub struct ValuesMut<'a, K, V, const B: usize = 6> {
+    inner: IterMut<'a, K, V, B>,
 }
 
-impl<K, V: fmt::Debug> fmt::Debug for ValuesMut<'_, K, V> {
+impl<K, V: fmt::Debug, const B: usize> fmt::Debug for ValuesMut<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.inner.iter().map(|(_, val)| val)).finish()
     }
@@ -494,11 +592,11 @@ impl<K, V: fmt::Debug> fmt::Debug for ValuesMut<'_, K, V> {
 ///
 /// [`into_keys`]: BTreeMap::into_keys
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct IntoKeys<K, V, A: Allocator + Clone = Global> {
-    inner: IntoIter<K, V, A>,
+pub struct IntoKeys<K, V, A: Allocator + Clone = Global, const B: usize = 6> {
+    inner: IntoIter<K, V, A, B>,
 }
 
-impl<K: fmt::Debug, V, A: Allocator + Clone> fmt::Debug for IntoKeys<K, V, A> {
+impl<K: fmt::Debug, V, A: Allocator + Clone, const B: usize> fmt::Debug for IntoKeys<K, V, A, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.inner.iter().map(|(key, _)| key)).finish()
     }
@@ -511,16 +609,32 @@ impl<K: fmt::Debug, V, A: Allocator + Clone> fmt::Debug for IntoKeys<K, V, A> {
 ///
 /// [`into_values`]: BTreeMap::into_values
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct IntoValues<K, V, A: Allocator + Clone = Global> {
-    inner: IntoIter<K, V, A>,
+pub struct IntoValues<K, V, A: Allocator + Clone = Global, const B: usize = 6> {
+    inner: IntoIter<K, V, A, B>,
 }
 
-impl<K, V: fmt::Debug, A: Allocator + Clone> fmt::Debug for IntoValues<K, V, A> {
+impl<K, V: fmt::Debug, A: Allocator + Clone, const B: usize> fmt::Debug for IntoValues<K, V, A, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.inner.iter().map(|(_, val)| val)).finish()
     }
 }
 
+/// An owning iterator over a sub-range of entries removed from a `BTreeMap`.
+///
+/// This `struct` is created by the [`drain_range`] method on [`BTreeMap`].
+/// See its documentation for more.
+///
+/// [`drain_range`]: BTreeMap::drain_range
+pub struct DrainRange<K, V, A: Allocator + Clone = Global, const B: usize = 6> {
+    inner: IntoIter<K, V, A, B>,
+}
+
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize> Debug for DrainRange<K, V, A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.inner.iter()).finish()
+    }
+}
+
 /// An iterator over a sub-range of entries in a `BTreeMap`.
 ///
 /// This `struct` is created by the [`range`] method on [`BTreeMap`]. See its
@@ -528,11 +642,11 @@ impl<K, V: fmt::Debug, A: Allocator + Clone> fmt::Debug for IntoValues<K, V, A>
 ///
 /// [`range`]: BTreeMap::range
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct Range<'a, K: 'a, V: 'a> {
-    inner: LeafRange<marker::Immut<'a>, K, V>,
+pub struct Range<'a, K: 'a, V: 'a, const B: usize = 6> {
+    inner: LeafRange<marker::Immut<'a>, K, V, (), B>,
 }
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Range<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, const B: usize> fmt::Debug for Range<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
@@ -545,14 +659,14 @@ impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Range<'_, K, V> {
 ///
 /// [`range_mut`]: BTreeMap::range_mut
 #[must_use = "iterators are lazy and do nothing unless consumed"]
-pub struct RangeMut<'a, K: 'a, V: 'a> {
-    inner: LeafRange<marker::ValMut<'a>, K, V>,
+pub struct RangeMut<'a, K: 'a, V: 'a, const B: usize = 6> {
+    inner: LeafRange<marker::ValMut<'a>, K, V, (), B>,
 
     // Be invariant in `K` and `V`
     _marker: PhantomData<&'a mut (K, V)>,
 }
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RangeMut<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, const B: usize> fmt::Debug for RangeMut<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let range = Range { inner: self.inner.reborrow() };
         f.debug_list().entries(range).finish()
@@ -560,7 +674,7 @@ impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RangeMut<'_, K, V> {
 }
 
 impl<K, V> BTreeMap<K, V> {
-    /// Makes a new, empty `BTreeMap` ordered by the given `order`.
+    /// Makes a new, empty `BTreeMap`.
     ///
     /// Does not allocate anything on its own.
     ///
@@ -569,47 +683,211 @@ impl<K, V> BTreeMap<K, V> {
     /// Basic usage:
     ///
     /// ```
-    /// # use copse::{BTreeMap, SortableBy, TotalOrder};
-    /// # use std::cmp::Ordering;
-    /// #
-    /// // define a total order
-    /// struct OrderByNthByte {
-    ///     n: usize, // runtime state
-    /// }
-    ///
-    /// impl TotalOrder for OrderByNthByte {
-    ///     // etc
-    /// #     type OrderedType = [u8];
-    /// #     fn cmp(&self, this: &[u8], that: &[u8]) -> Ordering {
-    /// #         this.get(self.n).cmp(&that.get(self.n))
-    /// #     }
-    /// }
+    /// use btree_monstrousity::BTreeMap;
     ///
-    /// // define lookup key types for collections sorted by our total order
-    /// # impl SortableBy<OrderByNthByte> for [u8] {
-    /// #     fn sort_key(&self) -> &[u8] { self }
-    /// # }
-    /// # impl SortableBy<OrderByNthByte> for str {
-    /// #     fn sort_key(&self) -> &[u8] { self.as_bytes() }
-    /// # }
-    /// impl SortableBy<OrderByNthByte> for String {
-    ///     // etc
-    /// #     fn sort_key(&self) -> &[u8] { SortableBy::<OrderByNthByte>::sort_key(self.as_str()) }
-    /// }
-    ///
-    /// // create a map using our total order
-    /// let mut map = BTreeMap::new(OrderByNthByte { n: 9 });
+    /// let mut map = BTreeMap::new();
     ///
     /// // entries can now be inserted into the empty map
-    /// assert!(map.insert("abcdefghij".to_string(), ()).is_none());
+    /// map.insert(1, "a", Ord::cmp);
+    ///
+    /// // any comparator works, as long as the same order is used throughout
+    /// let mut by_len = BTreeMap::new();
+    /// by_len.insert("three", 3, |a: &&str, b: &&str| a.len().cmp(&b.len()));
+    /// assert_eq!(by_len.get(|k| 5.cmp(&k.len())), Some(&3));
     /// ```
     #[must_use]
     pub const fn new() -> BTreeMap<K, V> {
+        BTreeMap::with_branching_factor()
+    }
+
+    /// Makes a `BTreeMap` from an iterator whose keys are already sorted by
+    /// `double_comp`.
+    ///
+    /// The tree is built bottom-up with full nodes in a single pass, which takes
+    /// O(n) time instead of the O(n log n) of inserting the entries one by one.
+    /// Runs of equal keys are folded into one entry by `policy`, which is
+    /// either [`Keep::First`], [`Keep::Last`] or a closure combining the values;
+    /// see [`DuplicatePolicy`].
+    ///
+    /// It is a logic error for `iter` to yield keys out of order. The behavior
+    /// resulting from such a logic error is not specified, but will be
+    /// encapsulated to the `BTreeMap` that observed the logic error and not
+    /// result in undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Keep;
+    ///
+    /// let rows = [(1, "a"), (2, "b"), (2, "c"), (3, "d")];
+    ///
+    /// let map = BTreeMap::from_sorted_iter_by(rows, Ord::cmp, Keep::First);
+    /// assert_eq!(map.values().copied().collect::<String>(), "abd");
+    ///
+    /// let map = BTreeMap::from_sorted_iter_by(rows, Ord::cmp, Keep::Last);
+    /// assert_eq!(map.values().copied().collect::<String>(), "acd");
+    ///
+    /// let counts = [("x", 1), ("x", 2), ("y", 3)];
+    /// let map = BTreeMap::from_sorted_iter_by(counts, Ord::cmp, |sum: &mut i32, n| *sum += n);
+    /// assert_eq!(map.get(|k| "x".cmp(k)), Some(&3));
+    /// ```
+    pub fn from_sorted_iter_by<I, C, P>(iter: I, double_comp: C, policy: P) -> BTreeMap<K, V>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        C: FnMut(&K, &K) -> Ordering,
+        P: DuplicatePolicy<K, V>,
+    {
+        BTreeMap::bulk_build_from_sorted_iter(iter, double_comp, policy, Global)
+    }
+
+    /// Makes a `BTreeMap` from the entries of `entries`, in any order.
+    ///
+    /// The entries are sorted by `double_comp` first and then built into a tree
+    /// as by [`from_sorted_iter_by`]. The sort is stable, so [`Keep::First`] and
+    /// [`Keep::Last`] refer to the order the entries had in `entries`, and a
+    /// combining closure sees the values in that order too.
+    ///
+    /// [`from_sorted_iter_by`]: BTreeMap::from_sorted_iter_by
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Keep;
+    ///
+    /// let map = BTreeMap::from_unsorted_by(vec![(3, 'c'), (1, 'a'), (3, 'C')], Ord::cmp, Keep::Last);
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 'a'), (3, 'C')]);
+    ///
+    /// let by_len = |a: &&str, b: &&str| a.len().cmp(&b.len());
+    /// let words = vec![("ab", 1), ("c", 2), ("de", 3)];
+    /// let map = BTreeMap::from_unsorted_by(words, by_len, Keep::First);
+    /// assert_eq!(map.into_keys().collect::<Vec<_>>(), ["c", "ab"]);
+    /// ```
+    pub fn from_unsorted_by<C, P>(
+        mut entries: Vec<(K, V)>,
+        mut double_comp: C,
+        policy: P,
+    ) -> BTreeMap<K, V>
+    where
+        C: FnMut(&K, &K) -> Ordering,
+        P: DuplicatePolicy<K, V>,
+    {
+        if entries.is_empty() {
+            return BTreeMap::new();
+        }
+
+        // use stable sort to preserve the insertion order.
+        entries.sort_by(|a, b| double_comp(&a.0, &b.0));
+        BTreeMap::bulk_build_from_sorted_iter(entries, double_comp, policy, Global)
+    }
+}
+
+impl<K, V, const B: usize, N> BTreeMap<K, V, Global, B, N> {
+    /// Makes a new, empty `BTreeMap` whose nodes hold between `B - 1` and
+    /// `2 * B - 1` elements.
+    ///
+    /// [`new`](BTreeMap::new) uses the default of 6, which suits small keys
+    /// and values. [`branching_factor`] picks one to fit nodes into a number of
+    /// cache lines instead. `B` must be at least 2.
+    ///
+    /// Does not allocate anything on its own.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::<u64, char, _, 32>::with_branching_factor();
+    /// map.insert(1, 'a', Ord::cmp);
+    /// assert_eq!(map.get(|k| 1.cmp(k)), Some(&'a'));
+    /// ```
+    #[must_use]
+    pub const fn with_branching_factor() -> BTreeMap<K, V, Global, B, N> {
         BTreeMap { root: None, length: 0, alloc: ManuallyDrop::new(Global), _marker: PhantomData }
     }
 }
 
-impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+/// Returns a branching factor `B` for a `BTreeMap<K, V, A, B>` whose leaf
+/// nodes fill about `cache_lines` cache lines of 64 bytes.
+///
+/// A leaf node stores up to `2 * B - 1` keys and values next to a parent
+/// pointer and two lengths. The result is clamped to `2..=1024`, so maps of
+/// large or zero-sized entries still get a usable factor.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::btree_map::{branching_factor, BTreeMap};
+///
+/// const B: usize = branching_factor::<u32, u32>(4);
+/// // Leaves of `2 * B - 1` entries of 8 bytes fill the 256 bytes as far as
+/// // they can, next to the header of a pointer and two `u16` lengths.
+/// let header = std::mem::size_of::<usize>() + 4;
+/// assert!(header + (2 * B - 1) * 8 <= 4 * 64);
+/// assert!(header + (2 * B + 1) * 8 > 4 * 64);
+///
+/// let mut map = BTreeMap::<u32, u32, _, B>::with_branching_factor();
+/// map.insert(7, 49, Ord::cmp);
+/// ```
+pub const fn branching_factor<K, V>(cache_lines: usize) -> usize {
+    const MIN: usize = 2;
+    const MAX: usize = 1024;
+
+    let header = mem::size_of::<usize>() + 2 * mem::size_of::<u16>();
+    let elt_size = mem::size_of::<K>() + mem::size_of::<V>();
+    if elt_size == 0 {
+        return MAX;
+    }
+    let capacity = (cache_lines * 64).saturating_sub(header) / elt_size;
+    let b = capacity.div_ceil(2);
+    if b < MIN {
+        MIN
+    } else if b > MAX {
+        MAX
+    } else {
+        b
+    }
+}
+
+impl<K, V, A: Allocator + Clone, const B: usize, N> BTreeMap<K, V, A, B, N> {
+    /// Converts the map into one that looks for keys within each node with
+    /// the strategy `M`, keeping its elements, branching factor and allocator.
+    ///
+    /// This takes constant time, because the tree does not depend on how
+    /// nodes are searched.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::btree_map::{Binary, BTreeMap};
+    ///
+    /// // Imagine each comparison decoding a serialized key.
+    /// let mut map = BTreeMap::new().with_node_search::<Binary>();
+    /// for i in 0..100 {
+    ///     map.insert(i, i * i, Ord::cmp);
+    /// }
+    /// assert_eq!(map.get(|k| 9.cmp(k)), Some(&81));
+    /// ```
+    pub fn with_node_search<M: NodeSearch<K>>(self) -> BTreeMap<K, V, A, B, M> {
+        let mut this = ManuallyDrop::new(self);
+        BTreeMap {
+            root: this.root.take(),
+            length: this.length,
+            // SAFETY: `this` is never dropped, so the allocator is not used twice.
+            alloc: unsafe { ptr::read(&this.alloc) },
+            _marker: PhantomData,
+        }
+    }
+
     /// Clears the map, removing all elements.
     ///
     /// # Examples
@@ -617,17 +895,17 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "a");
+    /// a.insert(1, "a", Ord::cmp);
     /// a.clear();
     /// assert!(a.is_empty());
     /// ```
     pub fn clear(&mut self) {
         // avoid moving the allocator
-        mem::drop(BTreeMap {
-            root: mem::replace(&mut self.root, None),
+        mem::drop(Self {
+            root: self.root.take(),
             length: mem::replace(&mut self.length, 0),
             alloc: self.alloc.clone(),
             _marker: PhantomData,
@@ -636,7 +914,9 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
 
     decorate_if! {
         if #[cfg(feature = "btreemap_alloc")] {
-            /// Makes a new empty BTreeMap with a reasonable choice for B, ordered by the given `order`.
+            /// Makes a new, empty `BTreeMap` allocating in `alloc`, whose nodes
+            /// hold between `B - 1` and `2 * B - 1` elements, like
+            /// [`with_branching_factor`](BTreeMap::with_branching_factor).
             ///
             /// # Examples
             ///
@@ -644,17 +924,15 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
             ///
             /// ```
             /// # #![feature(allocator_api)]
-            /// use copse::{BTreeMap, default::OrdTotalOrder};
+            /// use btree_monstrousity::BTreeMap;
             /// use std::alloc::Global;
             ///
-            /// let mut map = BTreeMap::new_in(OrdTotalOrder::default(), Global);
-            ///
-            /// // entries can now be inserted into the empty map
-            /// map.insert(1, "a");
+            /// let mut map = BTreeMap::<_, _, _, 16>::with_branching_factor_in(Global);
+            /// map.insert(1, "a", Ord::cmp);
             /// ```
             pub
         }
-        fn new_in(alloc: A) -> BTreeMap<K, V, A> {
+        fn with_branching_factor_in(alloc: A) -> BTreeMap<K, V, A, B, N> {
             BTreeMap {
                 root: None,
                 length: 0,
@@ -666,6 +944,33 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
 }
 
 impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+    decorate_if! {
+        if #[cfg(feature = "btreemap_alloc")] {
+            /// Makes a new empty BTreeMap with a reasonable choice for B.
+            ///
+            /// # Examples
+            ///
+            /// Basic usage:
+            ///
+            /// ```
+            /// # #![feature(allocator_api)]
+            /// use btree_monstrousity::BTreeMap;
+            /// use std::alloc::Global;
+            ///
+            /// let mut map = BTreeMap::new_in(Global);
+            ///
+            /// // entries can now be inserted into the empty map
+            /// map.insert(1, "a", Ord::cmp);
+            /// ```
+            pub
+        }
+        fn new_in(alloc: A) -> BTreeMap<K, V, A> {
+            BTreeMap::with_branching_factor_in(alloc)
+        }
+    }
+}
+
+impl<K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> BTreeMap<K, V, A, B, N> {
     /// Returns a reference to the value corresponding to the key.
     ///
     /// The key may be any borrowed form of the map's key type, but the ordering
@@ -676,24 +981,44 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.get(&1), Some(&"a"));
-    /// assert_eq!(map.get(&2), None);
+    /// map.insert(1, "a", Ord::cmp);
+    /// assert_eq!(map.get(|k| 1.cmp(k)), Some(&"a"));
+    /// assert_eq!(map.get(|k| 2.cmp(k)), None);
     /// ```
     pub fn get<C>(&self, comp: C) -> Option<&V>
     where
         C: FnMut(&K) -> Ordering,
     {
         let root_node = self.root.as_ref()?.reborrow();
-        match root_node.search_tree(comp) {
+        match root_node.search_tree::<N, _>(comp) {
             Found(handle) => Some(handle.into_kv().1),
             GoDown(_) => None,
         }
     }
 
+    /// Returns a reference to the value corresponding to the key, like
+    /// [`get`](Self::get), but takes any [`Query`] rather than only a closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert("a".to_string(), 1, Ord::cmp);
+    /// assert_eq!(map.get_q("a"), Some(&1));
+    /// assert_eq!(map.get_q("b"), None);
+    /// ```
+    pub fn get_q<Q>(&self, mut query: Q) -> Option<&V>
+    where
+        Q: Query<K>,
+    {
+        self.get(|k| query.compare(k))
+    }
+
     /// Returns the key-value pair corresponding to the supplied key.
     ///
     /// The supplied key may be any borrowed form of the map's key type, but the ordering
@@ -702,24 +1027,80 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.get_key_value(&1), Some((&1, &"a")));
-    /// assert_eq!(map.get_key_value(&2), None);
+    /// map.insert(1, "a", Ord::cmp);
+    /// assert_eq!(map.get_key_value(|k| 1.cmp(k)), Some((&1, &"a")));
+    /// assert_eq!(map.get_key_value(|k| 2.cmp(k)), None);
     /// ```
     pub fn get_key_value<C>(&self, comp: C) -> Option<(&K, &V)>
     where
         C: FnMut(&K) -> Ordering,
     {
         let root_node = self.root.as_ref()?.reborrow();
-        match root_node.search_tree(comp) {
+        match root_node.search_tree::<N, _>(comp) {
             Found(handle) => Some(handle.into_kv()),
             GoDown(_) => None,
         }
     }
 
+    /// Returns a reference to the value corresponding to a primitive integer
+    /// key, comparing the keys of each node to it all at once instead of
+    /// through a comparator.
+    ///
+    /// The map must be ordered by [`Ord::cmp`], as it is when every insertion
+    /// passes that comparator; otherwise the result is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(1u32, "a", Ord::cmp);
+    /// assert_eq!(map.get_int(1), Some(&"a"));
+    /// assert_eq!(map.get_int(2), None);
+    /// ```
+    pub fn get_int(&self, key: K) -> Option<&V>
+    where
+        K: PrimitiveInt,
+    {
+        let root_node = self.root.as_ref()?.reborrow();
+        match root_node.search_tree::<N, _>(IntTarget(key)) {
+            Found(handle) => Some(handle.into_kv().1),
+            GoDown(_) => None,
+        }
+    }
+
+    /// Returns the key-value pair at position `index` in the map, counting from
+    /// the minimum key, or `None` if `index` is out of bounds.
+    ///
+    /// Takes O(log n) time, instead of the O(n) of `iter().nth(index)` without
+    /// the `order_statistics` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(3, "c", Ord::cmp);
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(2, "b", Ord::cmp);
+    /// assert_eq!(map.get_index(0), Some((&1, &"a")));
+    /// assert_eq!(map.get_index(2), Some((&3, &"c")));
+    /// assert_eq!(map.get_index(3), None);
+    /// ```
+    #[cfg(feature = "order_statistics")]
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        if index >= self.length {
+            return None;
+        }
+        let root_node = self.root.as_ref()?.reborrow();
+        Some(root_node.select_kv(index).into_kv())
+    }
+
     /// Returns the first key-value pair in the map.
     /// The key in this pair is the minimum key in the map.
     ///
@@ -728,12 +1109,12 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
     /// assert_eq!(map.first_key_value(), None);
-    /// map.insert(1, "b");
-    /// map.insert(2, "a");
+    /// map.insert(1, "b", Ord::cmp);
+    /// map.insert(2, "a", Ord::cmp);
     /// assert_eq!(map.first_key_value(), Some((&1, &"b")));
     /// ```
     pub fn first_key_value(&self) -> Option<(&K, &V)> {
@@ -747,20 +1128,20 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// map.insert(2, "b");
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(2, "b", Ord::cmp);
     /// if let Some(mut entry) = map.first_entry() {
     ///     if *entry.key() > 0 {
     ///         entry.insert("first");
     ///     }
     /// }
-    /// assert_eq!(*map.get(&1).unwrap(), "first");
-    /// assert_eq!(*map.get(&2).unwrap(), "b");
+    /// assert_eq!(*map.get(|k| 1.cmp(k)).unwrap(), "first");
+    /// assert_eq!(*map.get(|k| 2.cmp(k)).unwrap(), "b");
     /// ```
-    pub fn first_entry(&mut self) -> Option<OccupiedEntry<'_, K, V, A>> {
+    pub fn first_entry(&mut self) -> Option<OccupiedEntry<'_, K, V, A, B, N>> {
         let (map, dormant_map) = DormantMutRef::new(self);
         let root_node = map.root.as_mut()?.borrow_mut();
         let kv = root_node.first_leaf_edge().right_kv().ok()?;
@@ -780,11 +1161,11 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Draining elements in ascending order, while keeping a usable map each iteration.
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// map.insert(2, "b");
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(2, "b", Ord::cmp);
     /// while let Some((key, _val)) = map.pop_first() {
     ///     assert!(map.iter().all(|(k, _v)| *k > key));
     /// }
@@ -802,11 +1183,11 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "b");
-    /// map.insert(2, "a");
+    /// map.insert(1, "b", Ord::cmp);
+    /// map.insert(2, "a", Ord::cmp);
     /// assert_eq!(map.last_key_value(), Some((&2, &"a")));
     /// ```
     pub fn last_key_value(&self) -> Option<(&K, &V)> {
@@ -820,20 +1201,20 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// map.insert(2, "b");
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(2, "b", Ord::cmp);
     /// if let Some(mut entry) = map.last_entry() {
     ///     if *entry.key() > 0 {
     ///         entry.insert("last");
     ///     }
     /// }
-    /// assert_eq!(*map.get(&1).unwrap(), "a");
-    /// assert_eq!(*map.get(&2).unwrap(), "last");
+    /// assert_eq!(*map.get(|k| 1.cmp(k)).unwrap(), "a");
+    /// assert_eq!(*map.get(|k| 2.cmp(k)).unwrap(), "last");
     /// ```
-    pub fn last_entry(&mut self) -> Option<OccupiedEntry<'_, K, V, A>> {
+    pub fn last_entry(&mut self) -> Option<OccupiedEntry<'_, K, V, A, B, N>> {
         let (map, dormant_map) = DormantMutRef::new(self);
         let root_node = map.root.as_mut()?.borrow_mut();
         let kv = root_node.last_leaf_edge().left_kv().ok()?;
@@ -853,11 +1234,11 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Draining elements in descending order, while keeping a usable map each iteration.
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// map.insert(2, "b");
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(2, "b", Ord::cmp);
     /// while let Some((key, _val)) = map.pop_last() {
     ///     assert!(map.iter().all(|(k, _v)| *k < key));
     /// }
@@ -877,12 +1258,12 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.contains_key(&1), true);
-    /// assert_eq!(map.contains_key(&2), false);
+    /// map.insert(1, "a", Ord::cmp);
+    /// assert_eq!(map.contains_key(|k| 1.cmp(k)), true);
+    /// assert_eq!(map.contains_key(|k| 2.cmp(k)), false);
     /// ```
     pub fn contains_key<C>(&self, comp: C) -> bool
     where
@@ -891,6 +1272,28 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         self.get(comp).is_some()
     }
 
+    /// Returns `true` if the map contains a value for the specified key, like
+    /// [`contains_key`](Self::contains_key), but takes any [`Query`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::ripytide::compare::ascii_case_insensitive;
+    ///
+    /// let by_case = ascii_case_insensitive::<str, str>;
+    /// let mut map = BTreeMap::default();
+    /// map.insert("Hello".to_string(), 1, |a: &String, b: &String| by_case(a, b));
+    /// assert!(map.contains_key_q(("HELLO", by_case)));
+    /// assert!(!map.contains_key_q(("world", by_case)));
+    /// ```
+    pub fn contains_key_q<Q>(&self, query: Q) -> bool
+    where
+        Q: Query<K>,
+    {
+        self.get_q(query).is_some()
+    }
+
     /// Returns a mutable reference to the value corresponding to the key.
     ///
     /// The key may be any borrowed form of the map's key type, but the ordering
@@ -901,14 +1304,14 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// if let Some(x) = map.get_mut(&1) {
+    /// map.insert(1, "a", Ord::cmp);
+    /// if let Some(x) = map.get_mut(|k| 1.cmp(k)) {
     ///     *x = "b";
     /// }
-    /// assert_eq!(map[&1], "b");
+    /// assert_eq!(map.get(|k| 1.cmp(k)), Some(&"b"));
     /// ```
     // See `get` for implementation notes, this is basically a copy-paste with mut's added
     pub fn get_mut<C>(&mut self, comp: C) -> Option<&mut V>
@@ -916,12 +1319,34 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         C: FnMut(&K) -> Ordering,
     {
         let root_node = self.root.as_mut()?.borrow_mut();
-        match root_node.search_tree(comp) {
+        match root_node.search_tree::<N, _>(comp) {
             Found(handle) => Some(handle.into_val_mut()),
             GoDown(_) => None,
         }
     }
 
+    /// Returns a mutable reference to the value corresponding to the key, like
+    /// [`get_mut`](Self::get_mut), but takes any [`Query`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(vec![1, 2], "a", Ord::cmp);
+    /// if let Some(x) = map.get_mut_q(&[1, 2][..]) {
+    ///     *x = "b";
+    /// }
+    /// assert_eq!(map.get_q(&[1, 2][..]), Some(&"b"));
+    /// ```
+    pub fn get_mut_q<Q>(&mut self, mut query: Q) -> Option<&mut V>
+    where
+        Q: Query<K>,
+    {
+        self.get_mut(|k| query.compare(k))
+    }
+
     /// Inserts a key-value pair into the map.
     ///
     /// If the map did not have this key present, `None` is returned.
@@ -938,15 +1363,15 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// assert_eq!(map.insert(37, "a"), None);
+    /// assert_eq!(map.insert(37, "a", Ord::cmp), None);
     /// assert_eq!(map.is_empty(), false);
     ///
-    /// map.insert(37, "b");
-    /// assert_eq!(map.insert(37, "c"), Some("b"));
-    /// assert_eq!(map[&37], "c");
+    /// map.insert(37, "b", Ord::cmp);
+    /// assert_eq!(map.insert(37, "c", Ord::cmp), Some("b"));
+    /// assert_eq!(map.get(|k| 37.cmp(k)), Some(&"c"));
     /// ```
     pub fn insert<C>(&mut self, key: K, value: V, double_comp: C) -> Option<V>
     where
@@ -972,23 +1397,28 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// assert_eq!(map.try_insert(37, "a").unwrap(), &"a");
+    /// assert_eq!(map.try_insert(37, "a", Ord::cmp).unwrap(), &"a");
     ///
-    /// let err = map.try_insert(37, "b").unwrap_err();
+    /// let err = map.try_insert(37, "b", Ord::cmp).unwrap_err();
     /// assert_eq!(err.entry.key(), &37);
     /// assert_eq!(err.entry.get(), &"a");
     /// assert_eq!(err.value, "b");
+    /// assert_eq!(err.to_string(), r#"failed to insert "b", key 37 already exists with value "a""#);
     /// ```
     #[cfg(feature = "map_try_insert")]
-    pub fn try_insert(&mut self, key: K, value: V) -> Result<&mut V, OccupiedError<'_, K, V, A>>
+    pub fn try_insert<C>(
+        &mut self,
+        key: K,
+        value: V,
+        double_comp: C,
+    ) -> Result<&mut V, OccupiedError<'_, K, V, A, B, N>>
     where
-        K: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&K, &K) -> Ordering,
     {
-        match self.entry(key) {
+        match self.entry(key, double_comp) {
             Occupied(entry) => Err(OccupiedError { entry, value }),
             Vacant(entry) => Ok(entry.insert(value)),
         }
@@ -1005,12 +1435,12 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.remove(&1), Some("a"));
-    /// assert_eq!(map.remove(&1), None);
+    /// map.insert(1, "a", Ord::cmp);
+    /// assert_eq!(map.remove(|k| 1.cmp(k)), Some("a"));
+    /// assert_eq!(map.remove(|k| 1.cmp(k)), None);
     /// ```
     pub fn remove<C>(&mut self, comp: C) -> Option<V>
     where
@@ -1019,6 +1449,27 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         self.remove_entry(comp).map(|(_, v)| v)
     }
 
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map, like [`remove`](Self::remove), but takes any
+    /// [`Query`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert("a".to_string(), 1, Ord::cmp);
+    /// assert_eq!(map.remove_q("a"), Some(1));
+    /// assert_eq!(map.remove_q("a"), None);
+    /// ```
+    pub fn remove_q<Q>(&mut self, mut query: Q) -> Option<V>
+    where
+        Q: Query<K>,
+    {
+        self.remove(|k| query.compare(k))
+    }
+
     /// Removes a key from the map, returning the stored key and value if the key
     /// was previously in the map.
     ///
@@ -1030,12 +1481,12 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(1, "a");
-    /// assert_eq!(map.remove_entry(&1), Some((1, "a")));
-    /// assert_eq!(map.remove_entry(&1), None);
+    /// map.insert(1, "a", Ord::cmp);
+    /// assert_eq!(map.remove_entry(|k| 1.cmp(k)), Some((1, "a")));
+    /// assert_eq!(map.remove_entry(|k| 1.cmp(k)), None);
     /// ```
     pub fn remove_entry<C>(&mut self, comp: C) -> Option<(K, V)>
     where
@@ -1043,7 +1494,7 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     {
         let (map, dormant_map) = DormantMutRef::new(self);
         let root_node = map.root.as_mut()?.borrow_mut();
-        match root_node.search_tree(comp) {
+        match root_node.search_tree::<N, _>(comp) {
             Found(handle) => Some(
                 OccupiedEntry {
                     handle,
@@ -1065,9 +1516,12 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
-    /// let mut map: BTreeMap<i32, i32> = (0..8).map(|x| (x, x*10)).collect();
+    /// let mut map = BTreeMap::new();
+    /// for x in 0..8 {
+    ///     map.insert(x, x * 10, Ord::cmp);
+    /// }
     /// // Keep only the elements with even-numbered keys.
     /// map.retain(|&k, _| k % 2 == 0);
     /// assert!(map.into_iter().eq(vec![(0, 0), (2, 20), (4, 40), (6, 60)]));
@@ -1080,6 +1534,43 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         self.drain_filter(|k, v| !f(k, v));
     }
 
+    /// Retains only the elements in a sub-range of the map specified by the
+    /// predicate, leaving the elements outside of the range alone.
+    ///
+    /// In other words, remove all pairs `(k, v)` in the range for which
+    /// `f(&k, &mut v)` returns `false`. The ends of the range are given as for
+    /// [`BTreeMap::range`], and only the elements in the range are visited, in
+    /// ascending key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for x in 0..8 {
+    ///     map.insert(x, x * 10, Ord::cmp);
+    /// }
+    /// // Keep only the elements with even-numbered keys from 2 up to 6.
+    /// map.retain_range(|k| 2.cmp(k), Included, |k| 6.cmp(k), Excluded, |&k, _| k % 2 == 0);
+    /// assert!(map.keys().eq(&[0, 1, 2, 4, 6, 7]));
+    /// ```
+    pub fn retain_range<C1, C2, F>(
+        &mut self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+        mut f: F,
+    ) where
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.extract_if(lower_comp, lower_bound, upper_comp, upper_bound, |k, v| !f(k, v));
+    }
+
     /// Moves all elements from `other` into `self`, leaving `other` empty.
     ///
     /// If a key from `other` is already present in `self`, the respective
@@ -1088,28 +1579,28 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "a");
-    /// a.insert(2, "b");
-    /// a.insert(3, "c"); // Note: Key (3) also present in b.
+    /// a.insert(1, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp); // Note: Key (3) also present in b.
     ///
     /// let mut b = BTreeMap::default();
-    /// b.insert(3, "d"); // Note: Key (3) also present in a.
-    /// b.insert(4, "e");
-    /// b.insert(5, "f");
+    /// b.insert(3, "d", Ord::cmp); // Note: Key (3) also present in a.
+    /// b.insert(4, "e", Ord::cmp);
+    /// b.insert(5, "f", Ord::cmp);
     ///
-    /// a.append(&mut b);
+    /// a.append(&mut b, |a, b| a.0.cmp(&b.0));
     ///
     /// assert_eq!(a.len(), 5);
     /// assert_eq!(b.len(), 0);
     ///
-    /// assert_eq!(a[&1], "a");
-    /// assert_eq!(a[&2], "b");
-    /// assert_eq!(a[&3], "d"); // Note: "c" has been overwritten.
-    /// assert_eq!(a[&4], "e");
-    /// assert_eq!(a[&5], "f");
+    /// assert_eq!(a.get(|k| 1.cmp(k)), Some(&"a"));
+    /// assert_eq!(a.get(|k| 2.cmp(k)), Some(&"b"));
+    /// assert_eq!(a.get(|k| 3.cmp(k)), Some(&"d")); // Note: "c" has been overwritten.
+    /// assert_eq!(a.get(|k| 4.cmp(k)), Some(&"e"));
+    /// assert_eq!(a.get(|k| 5.cmp(k)), Some(&"f"));
     /// ```
     pub fn append<C>(&mut self, other: &mut Self, mega_comp: C)
     where
@@ -1127,8 +1618,8 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
             return;
         }
 
-        let self_iter = mem::replace(self, Self::new_in((*self.alloc).clone())).into_iter();
-        let other_iter = mem::replace(other, Self::new_in((*self.alloc).clone())).into_iter();
+        let self_iter = mem::replace(self, Self::with_branching_factor_in((*self.alloc).clone())).into_iter();
+        let other_iter = mem::replace(other, Self::with_branching_factor_in((*self.alloc).clone())).into_iter();
         let root = self.root.get_or_insert_with(|| Root::new((*self.alloc).clone()));
         root.append_from_sorted_iters(
             self_iter,
@@ -1136,37 +1627,450 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
             &mut self.length,
             |a: &(K, V), b: &(K, V)| mega_comp(a, b),
             (*self.alloc).clone(),
-        )
+        );
+        self.paranoid_check();
     }
 
-    /// Constructs a double-ended iterator over a sub-range of elements in the map.
-    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
-    /// yield elements from min (inclusive) to max (exclusive).
-    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
-    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
-    /// range from 4 to 10.
+    /// Moves all elements from `other` into `self`, combining the values of
+    /// keys present in both maps with `resolve`.
     ///
-    /// # Panics
+    /// Keys are compared with `key_comp`. For a key present in both maps,
+    /// `resolve` is passed the key and the values from `self` and `other`, in
+    /// that order, and the key from `self` is kept with the value it returns.
     ///
-    /// Panics if range `start > end`.
-    /// Panics if range `start == end` and both bounds are `Excluded`.
+    /// The maps are merged in a single pass, in O(n + m) time.
     ///
     /// # Examples
     ///
-    /// Basic usage:
-    ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use std::ops::Bound::Included;
+    /// use btree_monstrousity::BTreeMap;
     ///
-    /// let mut map = BTreeMap::default();
-    /// map.insert(3, "a");
-    /// map.insert(5, "b");
-    /// map.insert(8, "c");
-    /// for (&key, &value) in map.range::<i32, _>((Included(&4), Included(&8))) {
+    /// let mut a = BTreeMap::new();
+    /// a.insert("apple", 3, Ord::cmp);
+    /// a.insert("pear", 1, Ord::cmp);
+    ///
+    /// let mut b = BTreeMap::new();
+    /// b.insert("apple", 2, Ord::cmp);
+    /// b.insert("plum", 5, Ord::cmp);
+    ///
+    /// a.merge_with(b, Ord::cmp, |_, x, y| x + y);
+    /// assert!(a.into_iter().eq([("apple", 5), ("pear", 1), ("plum", 5)]));
+    /// ```
+    pub fn merge_with<C, R>(&mut self, other: Self, mut key_comp: C, mut resolve: R)
+    where
+        C: FnMut(&K, &K) -> Ordering,
+        R: FnMut(&K, V, V) -> V,
+    {
+        // Do we have to merge anything at all?
+        if other.is_empty() {
+            return;
+        }
+
+        // We can just take `other` if `self` is empty.
+        if self.is_empty() {
+            *self = other;
+            return;
+        }
+
+        let self_iter = mem::replace(self, Self::with_branching_factor_in((*self.alloc).clone())).into_iter();
+        let other_iter = other.into_iter();
+        let root = self.root.get_or_insert_with(|| Root::new((*self.alloc).clone()));
+        root.merge_from_sorted_iters(
+            self_iter,
+            other_iter,
+            &mut self.length,
+            |a: &(K, V), b: &(K, V)| key_comp(&a.0, &b.0),
+            |(k, a), (_, b)| {
+                let v = resolve(&k, a, b);
+                (k, v)
+            },
+            (*self.alloc).clone(),
+        );
+        self.paranoid_check();
+    }
+
+    /// Returns a new map holding clones of the elements of both `self` and
+    /// `other`, combining the values of keys present in both maps with
+    /// `resolve`.
+    ///
+    /// Keys are compared with `key_comp`. For a key present in both maps,
+    /// `resolve` is passed the key and the values from `self` and `other`, in
+    /// that order, and the key from `self` is kept with the value it returns.
+    ///
+    /// The maps are merged in a single pass, in O(n + m) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert("apple", 3, Ord::cmp);
+    /// a.insert("pear", 1, Ord::cmp);
+    ///
+    /// let mut b = BTreeMap::new();
+    /// b.insert("apple", 2, Ord::cmp);
+    /// b.insert("plum", 5, Ord::cmp);
+    ///
+    /// let union = a.union_with(&b, Ord::cmp, |_, x, y| x.max(y));
+    /// assert!(union.into_iter().eq([("apple", 3), ("pear", 1), ("plum", 5)]));
+    /// assert_eq!(a.len(), 2);
+    /// ```
+    pub fn union_with<C, R>(&self, other: &Self, mut key_comp: C, mut resolve: R) -> Self
+    where
+        K: Clone,
+        V: Clone,
+        C: FnMut(&K, &K) -> Ordering,
+        R: FnMut(&K, V, V) -> V,
+    {
+        let cloned = |(k, v): (&K, &V)| (k.clone(), v.clone());
+        let mut union = Self::with_branching_factor_in((*self.alloc).clone());
+        let root = union.root.insert(Root::new((*self.alloc).clone()));
+        root.merge_from_sorted_iters(
+            self.iter().map(cloned),
+            other.iter().map(cloned),
+            &mut union.length,
+            |a: &(K, V), b: &(K, V)| key_comp(&a.0, &b.0),
+            |(k, a), (_, b)| {
+                let v = resolve(&k, a, b);
+                (k, v)
+            },
+            (*self.alloc).clone(),
+        );
+        union.paranoid_check();
+        union
+    }
+
+    /// Moves all elements from `other` to the end of `self`, leaving `other`
+    /// empty, where every key of `other` is greater than every key of `self`.
+    ///
+    /// Rather than merging the elements one by one, like [`BTreeMap::append`]
+    /// does, the tree of `other` is grafted onto that of `self`, which takes
+    /// O(log n) time. Only maps using the [`Global`] allocator can share nodes
+    /// like that; with any other allocator, the elements of `other` are first
+    /// moved into new nodes from the allocator of `self`, in O(m) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `double_comp` finds the last key of `self` not to be less than
+    /// the first key of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// let mut b = BTreeMap::new();
+    /// for i in 0..5 {
+    ///     a.insert(i, "a", Ord::cmp);
+    ///     b.insert(i + 5, "b", Ord::cmp);
+    /// }
+    ///
+    /// a.concat(b, Ord::cmp);
+    /// assert_eq!(a.len(), 10);
+    /// assert!(a.keys().copied().eq(0..10));
+    /// ```
+    pub fn concat<C>(&mut self, mut other: Self, mut double_comp: C)
+    where
+        A: 'static,
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        if let (Some((last, _)), Some((first, _))) =
+            (self.last_key_value(), other.first_key_value())
+        {
+            assert!(
+                double_comp(last, first) == Ordering::Less,
+                "keys of the concatenated maps overlap in BTreeMap"
+            );
+        }
+        if !Self::allocator_is_global() && !other.is_empty() {
+            other = Self::bulk_build_from_strictly_sorted_iter(
+                other.into_iter(),
+                (*self.alloc).clone(),
+            );
+        }
+        let Some(right_root) = other.root.take() else { return };
+        let right_len = mem::take(&mut other.length);
+        match self.root.as_mut() {
+            None => self.root = Some(right_root),
+            Some(root) => root.append_tree(right_root, (*self.alloc).clone()),
+        }
+        self.length += right_len;
+        self.paranoid_check();
+    }
+
+    /// Returns a new map holding the keys present in both `self` and `other`,
+    /// each with the value that `combine` makes of its values in both maps.
+    ///
+    /// Keys are compared with `key_comp`, and the keys of the new map are
+    /// clones of those in `self`. `combine` is passed the key and the values
+    /// from `self` and `other`, in that order.
+    ///
+    /// Maps of similar sizes are walked side by side in O(n + m) time. If one
+    /// map is much smaller than the other, its keys are looked up in the other
+    /// map instead, which takes O(min(n, m) * log(max(n, m))) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut stock = BTreeMap::new();
+    /// stock.insert("apple", 3, Ord::cmp);
+    /// stock.insert("pear", 0, Ord::cmp);
+    /// stock.insert("plum", 7, Ord::cmp);
+    ///
+    /// let mut order = BTreeMap::new();
+    /// order.insert("apple", 5, Ord::cmp);
+    /// order.insert("plum", 2, Ord::cmp);
+    ///
+    /// let shipped = stock.intersection_by(&order, Ord::cmp, |_, &s, &o| s.min(o));
+    /// assert!(shipped.into_iter().eq([("apple", 3), ("plum", 2)]));
+    /// ```
+    pub fn intersection_by<C, F>(&self, other: &Self, mut key_comp: C, mut combine: F) -> Self
+    where
+        K: Clone,
+        C: FnMut(&K, &K) -> Ordering,
+        F: FnMut(&K, &V, &V) -> V,
+    {
+        let alloc = (*self.alloc).clone();
+        if self.len() <= other.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF {
+            let iter = self.iter().filter_map(|(k, a)| {
+                let b = other.get(|o| key_comp(k, o))?;
+                Some((k.clone(), combine(k, a, b)))
+            });
+            Self::bulk_build_from_strictly_sorted_iter(iter, alloc)
+        } else if other.len() <= self.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF {
+            let iter = other.iter().filter_map(|(o, b)| {
+                let (k, a) = self.get_key_value(|k| key_comp(k, o).reverse())?;
+                Some((k.clone(), combine(k, a, b)))
+            });
+            Self::bulk_build_from_strictly_sorted_iter(iter, alloc)
+        } else {
+            let mut merge = MergeIterInner::new(self.iter(), other.iter());
+            let iter = iter::from_fn(|| loop {
+                match merge.nexts(|a, b| key_comp(a.0, b.0)) {
+                    (Some((k, a)), Some((_, b))) => return Some((k.clone(), combine(k, a, b))),
+                    (None, None) => return None,
+                    _ => {}
+                }
+            });
+            Self::bulk_build_from_strictly_sorted_iter(iter, alloc)
+        }
+    }
+
+    /// Returns a new map holding clones of the elements of `self` whose keys
+    /// are not present in `other`.
+    ///
+    /// Keys are compared with `key_comp`. Maps of similar sizes are walked side
+    /// by side in O(n + m) time. If `self` is much smaller than `other`, its
+    /// keys are looked up in `other` instead, which takes O(n * log(m)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// let mut b = BTreeMap::new();
+    /// for i in 0..6 {
+    ///     a.insert(i, i * 10, Ord::cmp);
+    ///     b.insert(i * 2, 0, Ord::cmp);
+    /// }
+    ///
+    /// let odd = a.difference_by(&b, |x, y| x.cmp(y));
+    /// assert!(odd.into_iter().eq([(1, 10), (3, 30), (5, 50)]));
+    /// ```
+    pub fn difference_by<C>(&self, other: &Self, mut key_comp: C) -> Self
+    where
+        K: Clone,
+        V: Clone,
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let alloc = (*self.alloc).clone();
+        if self.len() <= other.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF {
+            let iter = self
+                .iter()
+                .filter(|(k, _)| !other.contains_key(|o| key_comp(k, o)))
+                .map(|(k, v)| (k.clone(), v.clone()));
+            Self::bulk_build_from_strictly_sorted_iter(iter, alloc)
+        } else {
+            let mut merge = MergeIterInner::new(self.iter(), other.iter());
+            let iter = iter::from_fn(|| loop {
+                match merge.nexts(|a, b| key_comp(a.0, b.0)) {
+                    (Some((k, v)), None) => return Some((k.clone(), v.clone())),
+                    (None, None) => return None,
+                    _ => {}
+                }
+            });
+            Self::bulk_build_from_strictly_sorted_iter(iter, alloc)
+        }
+    }
+
+    /// Returns a new map holding clones of the elements whose keys are present
+    /// in `self` or in `other`, but not in both.
+    ///
+    /// Keys are compared with `key_comp`. Both maps are walked side by side, in
+    /// O(n + m) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a", Ord::cmp);
+    /// a.insert(2, "a", Ord::cmp);
+    ///
+    /// let mut b = BTreeMap::new();
+    /// b.insert(2, "b", Ord::cmp);
+    /// b.insert(3, "b", Ord::cmp);
+    ///
+    /// let either = a.symmetric_difference_by(&b, Ord::cmp);
+    /// assert!(either.into_iter().eq([(1, "a"), (3, "b")]));
+    /// ```
+    pub fn symmetric_difference_by<C>(&self, other: &Self, mut key_comp: C) -> Self
+    where
+        K: Clone,
+        V: Clone,
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let mut merge = MergeIterInner::new(self.iter(), other.iter());
+        let iter = iter::from_fn(|| loop {
+            match merge.nexts(|a, b| key_comp(a.0, b.0)) {
+                (Some((k, v)), None) | (None, Some((k, v))) => return Some((k.clone(), v.clone())),
+                (None, None) => return None,
+                (Some(_), Some(_)) => {}
+            }
+        });
+        Self::bulk_build_from_strictly_sorted_iter(iter, (*self.alloc).clone())
+    }
+
+    /// Extends the map with the key-value pairs from `iter`, ordered by
+    /// `double_comp`.
+    ///
+    /// A small batch is inserted pair by pair. A batch that is large compared
+    /// to the map is sorted and merged with the existing contents instead, which
+    /// takes O(n + m) time rather than O(m log n).
+    ///
+    /// Either way, a pair whose key is already present replaces the existing
+    /// pair, key included, just like [`append`] does; among pairs of `iter`
+    /// with equal keys the last one wins. To add a single pair, use [`insert`].
+    ///
+    /// [`append`]: BTreeMap::append
+    /// [`insert`]: BTreeMap::insert
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(1, "a", Ord::cmp);
+    ///
+    /// map.extend_by([(3, "c"), (2, "b"), (1, "z")], Ord::cmp);
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, "z"), (2, "b"), (3, "c")]);
+    ///
+    /// let mut big = BTreeMap::default();
+    /// big.extend_by((0..1000).rev().map(|i| (i, i * 2)), Ord::cmp);
+    /// assert_eq!(big.len(), 1000);
+    /// assert_eq!(big.first_key_value(), Some((&0, &0)));
+    /// ```
+    pub fn extend_by<I, C>(&mut self, iter: I, mut double_comp: C)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let mut batch: Vec<(K, V)> = iter.into_iter().collect();
+
+        if !merge_beats_inserting::<B>(self.len(), batch.len()) {
+            for (key, value) in batch {
+                self.replace_or_insert(key, value, &mut double_comp);
+            }
+            return;
+        }
+
+        // use stable sort to preserve the insertion order.
+        batch.sort_by(|a, b| double_comp(&a.0, &b.0));
+        let mut other = BTreeMap::bulk_build_from_sorted_iter(
+            batch,
+            &mut double_comp,
+            Keep::Last,
+            (*self.alloc).clone(),
+        );
+
+        // We can just swap `self` and `other` if `self` is empty.
+        if self.is_empty() {
+            mem::swap(self, &mut other);
+            return;
+        }
+
+        let self_iter = mem::replace(self, Self::with_branching_factor_in((*self.alloc).clone())).into_iter();
+        let root = self.root.insert(Root::new((*self.alloc).clone()));
+        root.append_from_sorted_iters(
+            self_iter,
+            other.into_iter(),
+            &mut self.length,
+            |a: &(K, V), b: &(K, V)| double_comp(&a.0, &b.0),
+            (*self.alloc).clone(),
+        );
+        self.paranoid_check();
+    }
+
+    // Like `insert`, except that an existing key is replaced along with its value.
+    pub(crate) fn replace_or_insert<C>(&mut self, key: K, value: V, mut double_comp: C)
+    where
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let alloc = (*map.alloc).clone();
+        let handle = match map.root {
+            None => None,
+            Some(ref mut root) => {
+                match root.borrow_mut().search_tree::<N, _>(|k: &K| double_comp(&key, k)) {
+                    Found(mut handle) => {
+                        let (k, v) = handle.kv_mut();
+                        *k = key;
+                        *v = value;
+                        return;
+                    }
+                    GoDown(handle) => Some(handle),
+                }
+            }
+        };
+        VacantEntry { key, handle, dormant_map, alloc, _marker: PhantomData }.insert(value);
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the map.
+    ///
+    /// Each end of the range is given as a comparator closure, which returns how
+    /// the bound compares to the key it is passed, together with a
+    /// [`SearchBoundCustom`] saying whether that bound is included, excluded or
+    /// absent. For example, `|k| 4.cmp(k)` with `SearchBoundCustom::Excluded` as
+    /// the lower end and `|k| 10.cmp(k)` with `SearchBoundCustom::Included` as the
+    /// upper end yields a left-exclusive, right-inclusive range from 4 to 10.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{AllIncluded, Included};
+    /// use std::cmp::Ordering;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(3, "a", Ord::cmp);
+    /// map.insert(5, "b", Ord::cmp);
+    /// map.insert(8, "c", Ord::cmp);
+    /// for (&key, &value) in map.range(|k| 4.cmp(k), Included, |k| 8.cmp(k), Included) {
     ///     println!("{key}: {value}");
     /// }
-    /// assert_eq!(Some((&5, &"b")), map.range(4..).next());
+    /// let mut tail = map.range(|k| 4.cmp(k), Included, |_| Ordering::Equal, AllIncluded);
+    /// assert_eq!(Some((&5, &"b")), tail.next());
     /// ```
     pub fn range<C1, C2>(
         &self,
@@ -1174,14 +2078,14 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         lower_bound: SearchBoundCustom,
         upper_comp: C2,
         upper_bound: SearchBoundCustom,
-    ) -> Range<'_, K, V>
+    ) -> Range<'_, K, V, B>
     where
         C1: FnMut(&K) -> Ordering,
         C2: FnMut(&K) -> Ordering,
     {
         if let Some(root) = &self.root {
             Range {
-                inner: root.reborrow().range_search(
+                inner: root.reborrow().range_search::<N, _, _>(
                     lower_comp,
                     SearchBound::from(lower_bound),
                     upper_comp,
@@ -1193,57 +2097,314 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         }
     }
 
+    /// Constructs a double-ended iterator over a sub-range of elements in the
+    /// map, like [`range`](Self::range), but takes any [`Query`] for either end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let mut map = BTreeMap::default();
+    /// for word in ["apple", "banana", "cherry", "date"] {
+    ///     map.insert(word.to_string(), word.len(), Ord::cmp);
+    /// }
+    /// let middle = map.range_q("b", Included, "d", Excluded);
+    /// assert!(middle.map(|(k, _)| k.as_str()).eq(["banana", "cherry"]));
+    /// ```
+    pub fn range_q<Q1, Q2>(
+        &self,
+        mut lower_query: Q1,
+        lower_bound: SearchBoundCustom,
+        mut upper_query: Q2,
+        upper_bound: SearchBoundCustom,
+    ) -> Range<'_, K, V, B>
+    where
+        Q1: Query<K>,
+        Q2: Query<K>,
+    {
+        self.range(|k| lower_query.compare(k), lower_bound, |k| upper_query.compare(k), upper_bound)
+    }
+
     /// Constructs a mutable double-ended iterator over a sub-range of elements in the map.
-    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
-    /// yield elements from min (inclusive) to max (exclusive).
-    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
-    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
-    /// range from 4 to 10.
+    ///
+    /// The ends of the range are given as for [`BTreeMap::range`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for name in ["Alice", "Bob", "Carol", "Cheryl"] {
+    ///     map.insert(name, 0, Ord::cmp);
+    /// }
+    /// for (_, balance) in map.range_mut(|k| "B".cmp(k), Included, |k| "Cheryl".cmp(k), Excluded) {
+    ///     *balance += 100;
+    /// }
+    /// for (name, balance) in &map {
+    ///     println!("{name} => {balance}");
+    /// }
+    /// ```
+    pub fn range_mut<C1, C2>(
+        &mut self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+    ) -> RangeMut<'_, K, V, B>
+    where
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+    {
+        if let Some(root) = &mut self.root {
+            RangeMut {
+                inner: root.borrow_valmut().range_search::<N, _, _>(
+                    lower_comp,
+                    SearchBound::from(lower_bound),
+                    upper_comp,
+                    SearchBound::from(upper_bound),
+                ),
+                _marker: PhantomData,
+            }
+        } else {
+            RangeMut { inner: LeafRange::none(), _marker: PhantomData }
+        }
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the
+    /// map, given as a standard range of bounds and a comparator.
+    ///
+    /// The bounds may be of any [borrowed](Borrow) form `Q` of the key type, and
+    /// `cmp` compares two values of that form, so the comparator the map was
+    /// built with serves for a range of keys. The ordering of the borrowed form
+    /// *must* match the ordering of the keys.
+    ///
+    /// # Panics
+    ///
+    /// Like the standard library, panics if range `start > end`, or if range
+    /// `start == end` and both bounds are `Excluded`, as told by `cmp`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use std::ops::Bound::{Excluded, Included, Unbounded};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for name in ["Alice", "Bob", "Carol", "Cheryl"] {
+    ///     map.insert(name.to_string(), name.len(), Ord::cmp);
+    /// }
+    /// let range = map.range_by((Included("B"), Excluded("Ch")), str::cmp);
+    /// assert!(range.map(|(k, _)| k).eq(["Bob", "Carol"]));
+    /// assert_eq!(map.range_by((Unbounded, Excluded("Bob")), str::cmp).count(), 1);
+    /// ```
+    pub fn range_by<Q, R, C>(&self, range: R, mut cmp: C) -> Range<'_, K, V, B>
+    where
+        Q: ?Sized,
+        R: RangeBounds<Q>,
+        K: Borrow<Q>,
+        C: FnMut(&Q, &Q) -> Ordering,
+    {
+        let (start, end) = (range.start_bound(), range.end_bound());
+        check_range_bounds(start, end, &mut cmp);
+        let cmp = RefCell::new(cmp);
+        if let Some(root) = &self.root {
+            Range {
+                inner: root.reborrow().range_search::<N, _, _>(
+                    range_end_comp(start, &cmp),
+                    SearchBound::from_range(start),
+                    range_end_comp(end, &cmp),
+                    SearchBound::from_range(end),
+                ),
+            }
+        } else {
+            Range { inner: LeafRange::none() }
+        }
+    }
+
+    /// Constructs a mutable double-ended iterator over a sub-range of elements
+    /// in the map, with the range given as for [`BTreeMap::range_by`].
+    ///
+    /// # Panics
+    ///
+    /// Panics on the same inverted bounds as [`BTreeMap::range_by`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for i in 0..10 {
+    ///     map.insert(i, 0, Ord::cmp);
+    /// }
+    /// for (_, v) in map.range_mut_by(3..=5, Ord::cmp) {
+    ///     *v += 1;
+    /// }
+    /// assert_eq!(map.values().sum::<i32>(), 3);
+    /// ```
+    pub fn range_mut_by<Q, R, C>(&mut self, range: R, mut cmp: C) -> RangeMut<'_, K, V, B>
+    where
+        Q: ?Sized,
+        R: RangeBounds<Q>,
+        K: Borrow<Q>,
+        C: FnMut(&Q, &Q) -> Ordering,
+    {
+        let (start, end) = (range.start_bound(), range.end_bound());
+        check_range_bounds(start, end, &mut cmp);
+        let cmp = RefCell::new(cmp);
+        if let Some(root) = &mut self.root {
+            RangeMut {
+                inner: root.borrow_valmut().range_search::<N, _, _>(
+                    range_end_comp(start, &cmp),
+                    SearchBound::from_range(start),
+                    range_end_comp(end, &cmp),
+                    SearchBound::from_range(end),
+                ),
+                _marker: PhantomData,
+            }
+        } else {
+            RangeMut { inner: LeafRange::none(), _marker: PhantomData }
+        }
+    }
+
+    /// Constructs a double-ended iterator over the elements of a map with
+    /// primitive integer keys whose keys lie in `range`, comparing the keys of
+    /// each node to the bounds all at once instead of through comparators.
+    ///
+    /// The map must be ordered by [`Ord::cmp`], like for [`BTreeMap::get_int`].
     ///
     /// # Panics
     ///
     /// Panics if range `start > end`.
     /// Panics if range `start == end` and both bounds are `Excluded`.
     ///
-    /// # Examples
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(3u8, "a", Ord::cmp);
+    /// map.insert(5, "b", Ord::cmp);
+    /// map.insert(8, "c", Ord::cmp);
+    /// let found: Vec<_> = map.range_int(4..=8).map(|(_, &v)| v).collect();
+    /// assert_eq!(found, ["b", "c"]);
+    /// ```
+    pub fn range_int<R>(&self, range: R) -> Range<'_, K, V, B>
+    where
+        K: PrimitiveInt,
+        R: RangeBounds<K>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in BTreeMap")
+            }
+            (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
+                if s > e =>
+            {
+                panic!("range start is greater than range end in BTreeMap")
+            }
+            _ => {}
+        }
+        if let Some(root) = &self.root {
+            let (lower_target, lower_bound) = IntTarget::from_range(range.start_bound());
+            let (upper_target, upper_bound) = IntTarget::from_range(range.end_bound());
+            Range {
+                inner: root.reborrow().range_search::<N, _, _>(
+                    lower_target,
+                    lower_bound,
+                    upper_target,
+                    upper_bound,
+                ),
+            }
+        } else {
+            Range { inner: LeafRange::none() }
+        }
+    }
+
+    /// Counts the keys in the map that are ordered before the given key.
+    ///
+    /// Returns `Ok` with the position of the key if it is present, and `Err`
+    /// with the position it would be inserted at otherwise, like
+    /// [`slice::binary_search_by`] does. Takes O(log n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(10, "a", Ord::cmp);
+    /// map.insert(20, "b", Ord::cmp);
+    /// map.insert(30, "c", Ord::cmp);
+    /// assert_eq!(map.rank(|k| 20.cmp(k)), Ok(1));
+    /// assert_eq!(map.rank(|k| 25.cmp(k)), Err(2));
+    /// assert_eq!(map.rank(|k| 5.cmp(k)), Err(0));
+    /// ```
+    #[cfg(feature = "order_statistics")]
+    pub fn rank<C>(&self, comp: C) -> Result<usize, usize>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        let root_node = match &self.root {
+            Some(root) => root.reborrow(),
+            None => return Err(0),
+        };
+        match root_node.search_tree::<N, _>(comp) {
+            Found(kv) => Ok(kv.right_edge().position() - 1),
+            GoDown(edge) => Err(edge.forget_node_type().position()),
+        }
+    }
+
+    /// Counts the elements in a sub-range of the map in O(log n) time, with the
+    /// ends of the range given as for [`BTreeMap::range`].
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`BTreeMap::range`] does.
     ///
-    /// Basic usage:
+    /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
     ///
-    /// let mut map: BTreeMap<&str, i32> =
-    ///     [("Alice", 0), ("Bob", 0), ("Carol", 0), ("Cheryl", 0)].into();
-    /// for (_, balance) in map.range_mut("B".."Cheryl") {
-    ///     *balance += 100;
-    /// }
-    /// for (name, balance) in &map {
-    ///     println!("{name} => {balance}");
+    /// let mut map = BTreeMap::default();
+    /// for i in 0..100 {
+    ///     map.insert(i, (), Ord::cmp);
     /// }
+    /// assert_eq!(map.range_len(|k| 10.cmp(k), Included, |k| 20.cmp(k), Excluded), 10);
+    /// assert_eq!(map.range_len(|k| 95.cmp(k), Excluded, |k| 200.cmp(k), Included), 4);
     /// ```
-    pub fn range_mut<C1, C2>(
-        &mut self,
+    #[cfg(feature = "order_statistics")]
+    pub fn range_len<C1, C2>(
+        &self,
         lower_comp: C1,
         lower_bound: SearchBoundCustom,
         upper_comp: C2,
         upper_bound: SearchBoundCustom,
-    ) -> RangeMut<'_, K, V>
+    ) -> usize
     where
         C1: FnMut(&K) -> Ordering,
         C2: FnMut(&K) -> Ordering,
     {
-        if let Some(root) = &mut self.root {
-            RangeMut {
-                inner: root.borrow_valmut().range_search(
+        match &self.root {
+            Some(root) => root
+                .reborrow()
+                .range_search::<N, _, _>(
                     lower_comp,
                     SearchBound::from(lower_bound),
                     upper_comp,
                     SearchBound::from(upper_bound),
-                ),
-                _marker: PhantomData,
-            }
-        } else {
-            RangeMut { inner: LeafRange::none(), _marker: PhantomData }
+                )
+                .count(),
+            None => 0,
         }
     }
 
@@ -1254,20 +2415,20 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut count: BTreeMap<&str, usize> = BTreeMap::default();
     ///
     /// // count the number of occurrences of letters in the vec
     /// for x in ["a", "b", "a", "c", "a", "b"] {
-    ///     count.entry(x).and_modify(|curr| *curr += 1).or_insert(1);
+    ///     count.entry(x, Ord::cmp).and_modify(|curr| *curr += 1).or_insert(1);
     /// }
     ///
-    /// assert_eq!(count["a"], 3);
-    /// assert_eq!(count["b"], 2);
-    /// assert_eq!(count["c"], 1);
+    /// assert_eq!(count.get(|k| "a".cmp(k)), Some(&3));
+    /// assert_eq!(count.get(|k| "b".cmp(k)), Some(&2));
+    /// assert_eq!(count.get(|k| "c".cmp(k)), Some(&1));
     /// ```
-    pub fn entry<C>(&mut self, key: K, mut double_comp: C) -> Entry<'_, K, V, A>
+    pub fn entry<C>(&mut self, key: K, mut double_comp: C) -> Entry<'_, K, V, A, B, N>
     where
         C: FnMut(&K, &K) -> Ordering,
     {
@@ -1280,21 +2441,23 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
                 alloc: (*map.alloc).clone(),
                 _marker: PhantomData,
             }),
-            Some(ref mut root) => match root.borrow_mut().search_tree(|k| double_comp(k, &key)) {
-                Found(handle) => Occupied(OccupiedEntry {
-                    handle,
-                    dormant_map,
-                    alloc: (*map.alloc).clone(),
-                    _marker: PhantomData,
-                }),
-                GoDown(handle) => Vacant(VacantEntry {
-                    key,
-                    handle: Some(handle),
-                    dormant_map,
-                    alloc: (*map.alloc).clone(),
-                    _marker: PhantomData,
-                }),
-            },
+            Some(ref mut root) => {
+                match root.borrow_mut().search_tree::<N, _>(|k: &K| double_comp(&key, k)) {
+                    Found(handle) => Occupied(OccupiedEntry {
+                        handle,
+                        dormant_map,
+                        alloc: (*map.alloc).clone(),
+                        _marker: PhantomData,
+                    }),
+                    GoDown(handle) => Vacant(VacantEntry {
+                        key,
+                        handle: Some(handle),
+                        dormant_map,
+                        alloc: (*map.alloc).clone(),
+                        _marker: PhantomData,
+                    }),
+                }
+            }
         }
     }
 
@@ -1306,49 +2469,372 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "a");
-    /// a.insert(2, "b");
-    /// a.insert(3, "c");
-    /// a.insert(17, "d");
-    /// a.insert(41, "e");
+    /// a.insert(1, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp);
+    /// a.insert(17, "d", Ord::cmp);
+    /// a.insert(41, "e", Ord::cmp);
     ///
-    /// let b = a.split_off(&3);
+    /// let b = a.split_off(|k| 3.cmp(k));
     ///
     /// assert_eq!(a.len(), 2);
     /// assert_eq!(b.len(), 3);
     ///
-    /// assert_eq!(a[&1], "a");
-    /// assert_eq!(a[&2], "b");
+    /// assert_eq!(a.get(|k| 1.cmp(k)), Some(&"a"));
+    /// assert_eq!(a.get(|k| 2.cmp(k)), Some(&"b"));
     ///
-    /// assert_eq!(b[&3], "c");
-    /// assert_eq!(b[&17], "d");
-    /// assert_eq!(b[&41], "e");
+    /// assert_eq!(b.get(|k| 3.cmp(k)), Some(&"c"));
+    /// assert_eq!(b.get(|k| 17.cmp(k)), Some(&"d"));
+    /// assert_eq!(b.get(|k| 41.cmp(k)), Some(&"e"));
     /// ```
     pub fn split_off<C>(&mut self, comp: C) -> Self
     where
         C: FnMut(&K) -> Ordering,
     {
         if self.is_empty() {
-            return Self::new_in((*self.alloc).clone());
+            return Self::with_branching_factor_in((*self.alloc).clone());
         }
 
         let total_num = self.len();
         let left_root = self.root.as_mut().unwrap(); // unwrap succeeds because not empty
 
-        let right_root = left_root.split_off(comp, (*self.alloc).clone());
+        let right_root = left_root.split_off::<N, _, _>(comp, (*self.alloc).clone());
 
         let (new_left_len, right_len) = Root::calc_split_length(total_num, &left_root, &right_root);
         self.length = new_left_len;
+        self.paranoid_check();
 
-        BTreeMap {
+        let right = BTreeMap {
             root: Some(right_root),
             length: right_len,
             alloc: self.alloc.clone(),
             _marker: PhantomData,
+        };
+        right.paranoid_check();
+        right
+    }
+
+    /// Splits the collection into two at the given key, like
+    /// [`split_off`](Self::split_off), but takes any [`Query`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::default();
+    /// for word in ["apple", "banana", "cherry"] {
+    ///     a.insert(word.to_string(), word.len(), Ord::cmp);
+    /// }
+    /// let b = a.split_off_q("b");
+    /// assert!(a.keys().eq(["apple"]));
+    /// assert!(b.keys().eq(["banana", "cherry"]));
+    /// ```
+    pub fn split_off_q<Q>(&mut self, mut query: Q) -> Self
+    where
+        Q: Query<K>,
+    {
+        self.split_off(|k| query.compare(k))
+    }
+
+    /// Splits the collection into two at the given position. Returns everything
+    /// from position `at` onward, i.e., all but the first `at` elements.
+    ///
+    /// Finds the split point in O(log n) time, where [`BTreeMap::split_off`]
+    /// would need a key to search for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut a = BTreeMap::default();
+    /// for (key, value) in [(1, "a"), (2, "b"), (3, "c"), (17, "d"), (41, "e")] {
+    ///     a.insert(key, value, Ord::cmp);
+    /// }
+    ///
+    /// let b = a.split_off_at_index(2);
+    ///
+    /// assert_eq!(a.into_iter().collect::<Vec<_>>(), [(1, "a"), (2, "b")]);
+    /// assert_eq!(b.into_iter().collect::<Vec<_>>(), [(3, "c"), (17, "d"), (41, "e")]);
+    /// ```
+    #[cfg(feature = "order_statistics")]
+    pub fn split_off_at_index(&mut self, at: usize) -> Self {
+        assert!(
+            at <= self.length,
+            "`at` split index (is {}) should be <= len (is {})",
+            at,
+            self.length
+        );
+        if self.is_empty() {
+            return Self::with_branching_factor_in((*self.alloc).clone());
+        }
+
+        let total_num = self.len();
+        let left_root = self.root.as_mut().unwrap(); // unwrap succeeds because not empty
+
+        let right_root = left_root.split_off_at_index(at, (*self.alloc).clone());
+
+        self.length = at;
+        self.paranoid_check();
+
+        let right = BTreeMap {
+            root: Some(right_root),
+            length: total_num - at,
+            alloc: self.alloc.clone(),
+            _marker: PhantomData,
+        };
+        right.paranoid_check();
+        right
+    }
+
+    /// Removes the elements in a sub-range of the map and returns them as a
+    /// new map.
+    ///
+    /// The ends of the range are given as for [`BTreeMap::range`]. The range is
+    /// cut out of the tree and the rest is joined back together, which takes
+    /// O(log n) time, plus O(k) to count the k removed elements when the
+    /// `order_statistics` feature is disabled.
+    ///
+    /// Like [`BTreeMap::range`], removes nothing if the lower end of the range
+    /// is greater than the upper end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for i in 0..10 {
+    ///     map.insert(i, i * 10, Ord::cmp);
+    /// }
+    /// let removed = map.remove_range(|k| 3.cmp(k), Included, |k| 7.cmp(k), Excluded);
+    /// assert!(removed.keys().eq(&[3, 4, 5, 6]));
+    /// assert!(map.keys().eq(&[0, 1, 2, 7, 8, 9]));
+    /// ```
+    pub fn remove_range<C1, C2>(
+        &mut self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+    ) -> Self
+    where
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+    {
+        if self.is_empty() {
+            return Self::with_branching_factor_in((*self.alloc).clone());
+        }
+
+        let total_num = self.len();
+        let alloc = (*self.alloc).clone();
+        let left_root = self.root.as_mut().unwrap(); // unwrap succeeds because not empty
+
+        let mut middle_root = left_root.split_off_lower_bound::<N, _, _>(
+            lower_comp,
+            SearchBound::from(lower_bound),
+            alloc.clone(),
+        );
+        let right_root = middle_root.split_off_upper_bound::<N, _, _>(
+            upper_comp,
+            SearchBound::from(upper_bound),
+            alloc.clone(),
+        );
+        left_root.append_tree(right_root, alloc);
+
+        // Only the removed part is walked, however the rest of the tree is split.
+        #[cfg(feature = "order_statistics")]
+        let middle_len = middle_root.subtree_len();
+        #[cfg(not(feature = "order_statistics"))]
+        let middle_len = middle_root.reborrow().calc_length();
+        self.length = total_num - middle_len;
+        self.paranoid_check();
+
+        let middle = BTreeMap {
+            root: Some(middle_root),
+            length: middle_len,
+            alloc: self.alloc.clone(),
+            _marker: PhantomData,
+        };
+        middle.paranoid_check();
+        middle
+    }
+
+    /// Removes the elements in a sub-range of the map and returns them as an
+    /// owning iterator, which yields them in ascending key order from the front
+    /// and in descending order from the back.
+    ///
+    /// The ends of the range are given as for [`BTreeMap::range`]. The range is
+    /// cut out of the map right away, as by [`BTreeMap::remove_range`], so the
+    /// map is consistent and usable whatever becomes of the iterator. Dropping
+    /// the iterator drops the elements it has not yielded; leaking it leaks
+    /// them, without affecting the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for i in 0..10 {
+    ///     map.insert(i, i * 10, Ord::cmp);
+    /// }
+    /// let mut drained = map.drain_range(|k| 3.cmp(k), Included, |k| 7.cmp(k), Excluded);
+    /// assert_eq!(drained.next(), Some((3, 30)));
+    /// assert_eq!(drained.next_back(), Some((6, 60)));
+    /// assert_eq!(drained.len(), 2);
+    /// drop(drained);
+    /// assert!(map.keys().eq(&[0, 1, 2, 7, 8, 9]));
+    /// ```
+    pub fn drain_range<C1, C2>(
+        &mut self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+    ) -> DrainRange<K, V, A, B>
+    where
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+    {
+        let removed = self.remove_range(lower_comp, lower_bound, upper_comp, upper_bound);
+        DrainRange { inner: removed.into_iter() }
+    }
+
+    /// Moves the elements in a sub-range of `self` into `other`, which must not
+    /// hold any keys in the range.
+    ///
+    /// The ends of the range are given as for [`BTreeMap::range`]. The range is
+    /// cut out of `self` and grafted into `other` as a whole, which takes
+    /// O(log n) time, plus O(k) to count the k moved elements when the
+    /// `order_statistics` feature is disabled. Only maps using the [`Global`]
+    /// allocator can share nodes like that; with any other allocator, the moved
+    /// elements are first put into new nodes from the allocator of `other`, in
+    /// O(k) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` holds a key in the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let mut a = BTreeMap::new();
+    /// let mut b = BTreeMap::new();
+    /// for i in 0..10 {
+    ///     a.insert(i, "a", Ord::cmp);
+    ///     b.insert(i + 100, "b", Ord::cmp);
+    /// }
+    ///
+    /// a.transfer_range(&mut b, |k| 3.cmp(k), Included, |k| 7.cmp(k), Excluded);
+    /// assert!(a.keys().eq(&[0, 1, 2, 7, 8, 9]));
+    /// assert_eq!(b.len(), 14);
+    /// assert_eq!(b.first_key_value(), Some((&3, &"a")));
+    /// ```
+    pub fn transfer_range<C1, C2>(
+        &mut self,
+        other: &mut Self,
+        mut lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        mut upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+    ) where
+        A: 'static,
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+    {
+        let mut in_other = other.range(&mut lower_comp, lower_bound, &mut upper_comp, upper_bound);
+        assert!(in_other.next().is_none(), "the map to transfer to holds keys in the range");
+
+        let mut moved = self.remove_range(&mut lower_comp, lower_bound, upper_comp, upper_bound);
+        if !Self::allocator_is_global() && !moved.is_empty() {
+            moved = Self::bulk_build_from_strictly_sorted_iter(
+                moved.into_iter(),
+                (*other.alloc).clone(),
+            );
+        }
+        let Some(moved_root) = moved.root.take() else { return };
+        let moved_len = mem::take(&mut moved.length);
+        let alloc = (*other.alloc).clone();
+        match other.root.as_mut() {
+            None => other.root = Some(moved_root),
+            Some(root) => {
+                // `other` holds no keys in the range, so the moved elements fit
+                // in right where it splits at the lower end.
+                let right_root = root.split_off_lower_bound::<N, _, _>(
+                    lower_comp,
+                    SearchBound::from(lower_bound),
+                    alloc.clone(),
+                );
+                root.append_tree(moved_root, alloc.clone());
+                root.append_tree(right_root, alloc);
+            }
         }
+        other.length += moved_len;
+        other.paranoid_check();
+    }
+
+    /// Removes all elements before the given key, keeping the key itself if it
+    /// is in the map.
+    ///
+    /// Like [`BTreeMap::split_off`], this takes O(log n) time on top of the
+    /// time to drop the removed elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for i in 0..10 {
+    ///     map.insert(i, i * 10, Ord::cmp);
+    /// }
+    /// map.truncate_front(|k| 6.cmp(k));
+    /// assert!(map.keys().eq(&[6, 7, 8, 9]));
+    /// ```
+    pub fn truncate_front<C>(&mut self, comp: C)
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        let back = self.split_off(comp);
+        drop(mem::replace(self, back));
+    }
+
+    /// Removes the given key, if it is in the map, and all elements after it.
+    ///
+    /// Like [`BTreeMap::split_off`], this takes O(log n) time on top of the
+    /// time to drop the removed elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for i in 0..10 {
+    ///     map.insert(i, i * 10, Ord::cmp);
+    /// }
+    /// map.truncate_back(|k| 6.cmp(k));
+    /// assert!(map.keys().eq(&[0, 1, 2, 3, 4, 5]));
+    /// ```
+    pub fn truncate_back<C>(&mut self, comp: C)
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        drop(self.split_off(comp));
     }
 
     decorate_if! {
@@ -1376,17 +2862,20 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
             /// Splitting a map into even and odd keys, reusing the original map:
             ///
             /// ```
-            /// use copse::BTreeMap;
+            /// use btree_monstrousity::BTreeMap;
             ///
-            /// let mut map: BTreeMap<i32, i32> = (0..8).map(|x| (x, x)).collect();
-            /// let evens: BTreeMap<_, _> = map.drain_filter(|k, _v| k % 2 == 0).collect();
+            /// let mut map = BTreeMap::new();
+            /// for x in 0..8 {
+            ///     map.insert(x, x, Ord::cmp);
+            /// }
+            /// let evens: Vec<_> = map.drain_filter(|k, _v| k % 2 == 0).map(|(k, _v)| k).collect();
             /// let odds = map;
-            /// assert_eq!(evens.keys().copied().collect::<Vec<_>>(), [0, 2, 4, 6]);
+            /// assert_eq!(evens, [0, 2, 4, 6]);
             /// assert_eq!(odds.keys().copied().collect::<Vec<_>>(), [1, 3, 5, 7]);
             /// ```
             pub
         }
-        fn drain_filter<F>(&mut self, pred: F) -> DrainFilter<'_, K, V, F, A>
+        fn drain_filter<F>(&mut self, pred: F) -> DrainFilter<'_, K, V, F, A, B>
         where
             F: FnMut(&K, &mut V) -> bool,
         {
@@ -1395,10 +2884,81 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         }
     }
 
-    pub(super) fn drain_filter_inner(&mut self) -> (DrainFilterInner<'_, K, V>, A) {
+    /// Creates an iterator that visits the elements (key-value pairs) in a
+    /// sub-range of the map in ascending key order, and uses a closure to
+    /// determine if an element should be removed. If the closure returns `true`,
+    /// the element is removed from the map and yielded. If the closure returns
+    /// `false`, or panics, the element remains in the map and will not be
+    /// yielded.
+    ///
+    /// The ends of the range are given as for [`BTreeMap::range`]. Elements
+    /// outside of the range are not visited, so the iterator takes time in
+    /// proportion to the number of elements in the range rather than in the
+    /// map.
+    ///
+    /// The iterator also lets you mutate the value of each element in the
+    /// closure, regardless of whether you choose to keep or remove it.
+    ///
+    /// If the iterator is only partially consumed or not consumed at all, each
+    /// of the remaining elements in the range is still subjected to the
+    /// closure, which may change its value and, by returning `true`, have the
+    /// element removed and dropped.
+    ///
+    /// It is unspecified how many more elements will be subjected to the
+    /// closure if a panic occurs in the closure or in `upper_comp`, or a panic
+    /// occurs while dropping an element, or if the `ExtractIf` value is leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// for x in 0..8 {
+    ///     map.insert(x, x, Ord::cmp);
+    /// }
+    /// let evens: Vec<_> = map
+    ///     .extract_if(|k| 2.cmp(k), Included, |k| 6.cmp(k), Excluded, |k, _v| k % 2 == 0)
+    ///     .map(|(k, _v)| k)
+    ///     .collect();
+    /// assert_eq!(evens, [2, 4]);
+    /// assert!(map.keys().eq(&[0, 1, 3, 5, 6, 7]));
+    /// ```
+    pub fn extract_if<C1, C2, F>(
+        &mut self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+        pred: F,
+    ) -> ExtractIf<'_, K, V, C2, F, A, B>
+    where
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let (inner, alloc) = self.drain_filter_inner_from(|root| {
+            root.lower_bound::<N, _>(lower_comp, SearchBound::from(lower_bound))
+        });
+        ExtractIf { pred, upper_comp, upper_bound: SearchBound::from(upper_bound), inner, alloc }
+    }
+
+    pub(super) fn drain_filter_inner(&mut self) -> (DrainFilterInner<'_, K, V, B>, A) {
+        self.drain_filter_inner_from(|root| root.first_leaf_edge())
+    }
+
+    // Starts draining at the leaf edge that `front` picks out of the tree.
+    fn drain_filter_inner_from<'a, E>(&'a mut self, front: E) -> (DrainFilterInner<'a, K, V, B>, A)
+    where
+        E: FnOnce(
+            NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, (), B>,
+        )
+            -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, (), B>, marker::Edge>,
+    {
         if let Some(root) = self.root.as_mut() {
             let (root, dormant_root) = DormantMutRef::new(root);
-            let front = root.borrow_mut().first_leaf_edge();
+            let front = front(root.borrow_mut());
             (
                 DrainFilterInner {
                     length: &mut self.length,
@@ -1426,17 +2986,17 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(2, "b");
-    /// a.insert(1, "a");
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(1, "a", Ord::cmp);
     ///
     /// let keys: Vec<i32> = a.into_keys().collect();
     /// assert_eq!(keys, [1, 2]);
     /// ```
     #[inline]
-    pub fn into_keys(self) -> IntoKeys<K, V, A> {
+    pub fn into_keys(self) -> IntoKeys<K, V, A, B> {
         IntoKeys { inner: self.into_iter() }
     }
 
@@ -1447,43 +3007,58 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "hello");
-    /// a.insert(2, "goodbye");
+    /// a.insert(1, "hello", Ord::cmp);
+    /// a.insert(2, "goodbye", Ord::cmp);
     ///
     /// let values: Vec<&str> = a.into_values().collect();
     /// assert_eq!(values, ["hello", "goodbye"]);
     /// ```
     #[inline]
-    pub fn into_values(self) -> IntoValues<K, V, A> {
+    pub fn into_values(self) -> IntoValues<K, V, A, B> {
         IntoValues { inner: self.into_iter() }
     }
 
-    //todo consider turning me back on
-    // /// Makes a `BTreeMap` from a sorted iterator.
-    //pub(crate) fn bulk_build_from_sorted_iter<I>(
-    //iter: I,
-    //order: O,
-    //alloc: A,
-    //) -> BTreeMap<K, V, A>
-    //where
-    //K: SortableByWithOrder<O>,
-    //O: TotalOrder,
-    //I: IntoIterator<Item = (K, V)>,
-    //{
-    //let mut root = Root::new(alloc.clone());
-    //let mut length = 0;
-    //root.bulk_push(DedupSortedIter::new(iter.into_iter(), &order), &mut length, alloc.clone());
-    //BTreeMap {
-    //root: Some(root),
-    //length,
-    //order,
-    //alloc: ManuallyDrop::new(alloc),
-    //_marker: PhantomData,
-    //}
-    //}
+    /// Makes a `BTreeMap` from a sorted iterator, folding runs of equal keys
+    /// with `policy`.
+    pub(crate) fn bulk_build_from_sorted_iter<I, C, P>(
+        iter: I,
+        double_comp: C,
+        policy: P,
+        alloc: A,
+    ) -> BTreeMap<K, V, A, B, N>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        C: FnMut(&K, &K) -> Ordering,
+        P: DuplicatePolicy<K, V>,
+    {
+        let iter = DedupSortedIter::new(iter.into_iter(), double_comp, policy);
+        BTreeMap::bulk_build_from_strictly_sorted_iter(iter, alloc)
+    }
+
+    // Whether a node allocated for one map may be freed by another. Instances
+    // of any other allocator may hand out memory that only they can free.
+    fn allocator_is_global() -> bool
+    where
+        A: 'static,
+    {
+        TypeId::of::<A>() == TypeId::of::<Global>()
+    }
+
+    // Builds a map from pairs whose keys are already strictly ascending.
+    fn bulk_build_from_strictly_sorted_iter<I>(iter: I, alloc: A) -> BTreeMap<K, V, A, B, N>
+    where
+        I: Iterator<Item = (K, V)>,
+    {
+        // Build inside the map, so that a panic while iterating drops what was pushed.
+        let mut map = BTreeMap::with_branching_factor_in(alloc.clone());
+        let root = map.root.insert(Root::new(alloc.clone()));
+        root.bulk_push(iter, &mut map.length, alloc);
+        map.paranoid_check();
+        map
+    }
 
     //#[doc(hidden)]
     //pub fn get_order(&self) -> &O {
@@ -1543,16 +3118,18 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
 //}
 //}
 
-impl<'a, K, V, A: Allocator + Clone> IntoIterator for &'a BTreeMap<K, V, A> {
+impl<'a, K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> IntoIterator
+    for &'a BTreeMap<K, V, A, B, N>
+{
     type Item = (&'a K, &'a V);
-    type IntoIter = Iter<'a, K, V>;
+    type IntoIter = Iter<'a, K, V, B>;
 
-    fn into_iter(self) -> Iter<'a, K, V> {
+    fn into_iter(self) -> Iter<'a, K, V, B> {
         self.iter()
     }
 }
 
-impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
+impl<'a, K: 'a, V: 'a, const B: usize> Iterator for Iter<'a, K, V, B> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<(&'a K, &'a V)> {
@@ -1568,6 +3145,26 @@ impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
         (self.length, Some(self.length))
     }
 
+    #[cfg(feature = "order_statistics")]
+    fn nth(&mut self, n: usize) -> Option<(&'a K, &'a V)> {
+        if n >= self.length {
+            self.length = 0;
+            None
+        } else {
+            self.length -= n + 1;
+            Some(unsafe { self.range.nth_unchecked(n) })
+        }
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        let missing = n.saturating_sub(self.length);
+        if n > 0 {
+            self.nth(n - 1);
+        }
+        NonZeroUsize::new(missing).map_or(Ok(()), Err)
+    }
+
     fn last(mut self) -> Option<(&'a K, &'a V)> {
         self.next_back()
     }
@@ -1581,9 +3178,9 @@ impl<'a, K: 'a, V: 'a> Iterator for Iter<'a, K, V> {
     }
 }
 
-impl<K, V> FusedIterator for Iter<'_, K, V> {}
+impl<K, V, const B: usize> FusedIterator for Iter<'_, K, V, B> {}
 
-impl<'a, K: 'a, V: 'a> DoubleEndedIterator for Iter<'a, K, V> {
+impl<'a, K: 'a, V: 'a, const B: usize> DoubleEndedIterator for Iter<'a, K, V, B> {
     fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
         if self.length == 0 {
             None
@@ -1592,30 +3189,52 @@ impl<'a, K: 'a, V: 'a> DoubleEndedIterator for Iter<'a, K, V> {
             Some(unsafe { self.range.next_back_unchecked() })
         }
     }
+
+    #[cfg(feature = "order_statistics")]
+    fn nth_back(&mut self, n: usize) -> Option<(&'a K, &'a V)> {
+        if n >= self.length {
+            self.length = 0;
+            None
+        } else {
+            self.length -= n + 1;
+            Some(unsafe { self.range.nth_back_unchecked(n) })
+        }
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_back_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        let missing = n.saturating_sub(self.length);
+        if n > 0 {
+            self.nth_back(n - 1);
+        }
+        NonZeroUsize::new(missing).map_or(Ok(()), Err)
+    }
 }
 
-impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+impl<K, V, const B: usize> ExactSizeIterator for Iter<'_, K, V, B> {
     fn len(&self) -> usize {
         self.length
     }
 }
 
-impl<K, V> Clone for Iter<'_, K, V> {
+impl<K, V, const B: usize> Clone for Iter<'_, K, V, B> {
     fn clone(&self) -> Self {
         Iter { range: self.range.clone(), length: self.length }
     }
 }
 
-impl<'a, K, V, A: Allocator + Clone> IntoIterator for &'a mut BTreeMap<K, V, A> {
+impl<'a, K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> IntoIterator
+    for &'a mut BTreeMap<K, V, A, B, N>
+{
     type Item = (&'a K, &'a mut V);
-    type IntoIter = IterMut<'a, K, V>;
+    type IntoIter = IterMut<'a, K, V, B>;
 
-    fn into_iter(self) -> IterMut<'a, K, V> {
+    fn into_iter(self) -> IterMut<'a, K, V, B> {
         self.iter_mut()
     }
 }
 
-impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+impl<'a, K, V, const B: usize> Iterator for IterMut<'a, K, V, B> {
     type Item = (&'a K, &'a mut V);
 
     fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
@@ -1631,6 +3250,26 @@ impl<'a, K, V> Iterator for IterMut<'a, K, V> {
         (self.length, Some(self.length))
     }
 
+    #[cfg(feature = "order_statistics")]
+    fn nth(&mut self, n: usize) -> Option<(&'a K, &'a mut V)> {
+        if n >= self.length {
+            self.length = 0;
+            None
+        } else {
+            self.length -= n + 1;
+            Some(unsafe { self.range.nth_unchecked(n) })
+        }
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        let missing = n.saturating_sub(self.length);
+        if n > 0 {
+            self.nth(n - 1);
+        }
+        NonZeroUsize::new(missing).map_or(Ok(()), Err)
+    }
+
     fn last(mut self) -> Option<(&'a K, &'a mut V)> {
         self.next_back()
     }
@@ -1644,7 +3283,7 @@ impl<'a, K, V> Iterator for IterMut<'a, K, V> {
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+impl<'a, K, V, const B: usize> DoubleEndedIterator for IterMut<'a, K, V, B> {
     fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
         if self.length == 0 {
             None
@@ -1653,29 +3292,49 @@ impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
             Some(unsafe { self.range.next_back_unchecked() })
         }
     }
+
+    #[cfg(feature = "order_statistics")]
+    fn nth_back(&mut self, n: usize) -> Option<(&'a K, &'a mut V)> {
+        if n >= self.length {
+            self.length = 0;
+            None
+        } else {
+            self.length -= n + 1;
+            Some(unsafe { self.range.nth_back_unchecked(n) })
+        }
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_back_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        let missing = n.saturating_sub(self.length);
+        if n > 0 {
+            self.nth_back(n - 1);
+        }
+        NonZeroUsize::new(missing).map_or(Ok(()), Err)
+    }
 }
 
-impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {
+impl<K, V, const B: usize> ExactSizeIterator for IterMut<'_, K, V, B> {
     fn len(&self) -> usize {
         self.length
     }
 }
 
-impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+impl<K, V, const B: usize> FusedIterator for IterMut<'_, K, V, B> {}
 
-impl<'a, K, V> IterMut<'a, K, V> {
+impl<'a, K, V, const B: usize> IterMut<'a, K, V, B> {
     /// Returns an iterator of references over the remaining items.
     #[inline]
-    pub(super) fn iter(&self) -> Iter<'_, K, V> {
+    pub(super) fn iter(&self) -> Iter<'_, K, V, B> {
         Iter { range: self.range.reborrow(), length: self.length }
     }
 }
 
-impl<K, V, A: Allocator + Clone> IntoIterator for BTreeMap<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize, N> IntoIterator for BTreeMap<K, V, A, B, N> {
     type Item = (K, V);
-    type IntoIter = IntoIter<K, V, A>;
+    type IntoIter = IntoIter<K, V, A, B>;
 
-    fn into_iter(self) -> IntoIter<K, V, A> {
+    fn into_iter(self) -> IntoIter<K, V, A, B> {
         let mut me = ManuallyDrop::new(self);
         if let Some(root) = me.root.take() {
             let full_range = root.into_dying().full_range();
@@ -1695,11 +3354,13 @@ impl<K, V, A: Allocator + Clone> IntoIterator for BTreeMap<K, V, A> {
     }
 }
 
-impl<K, V, A: Allocator + Clone> Drop for IntoIter<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> Drop for IntoIter<K, V, A, B> {
     fn drop(&mut self) {
-        struct DropGuard<'a, K, V, A: Allocator + Clone>(&'a mut IntoIter<K, V, A>);
+        struct DropGuard<'a, K, V, A: Allocator + Clone, const B: usize>(
+            &'a mut IntoIter<K, V, A, B>,
+        );
 
-        impl<'a, K, V, A: Allocator + Clone> Drop for DropGuard<'a, K, V, A> {
+        impl<'a, K, V, A: Allocator + Clone, const B: usize> Drop for DropGuard<'a, K, V, A, B> {
             fn drop(&mut self) {
                 // Continue the same loop we perform below. This only runs when unwinding, so we
                 // don't have to care about panics this time (they'll abort).
@@ -1719,12 +3380,13 @@ impl<K, V, A: Allocator + Clone> Drop for IntoIter<K, V, A> {
     }
 }
 
-impl<K, V, A: Allocator + Clone> IntoIter<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> IntoIter<K, V, A, B> {
     /// Core of a `next` method returning a dying KV handle,
     /// invalidated by further calls to this function and some others.
     fn dying_next(
         &mut self,
-    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV>> {
+    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, (), B>, marker::KV>>
+    {
         if self.length == 0 {
             self.range.deallocating_end(self.alloc.clone());
             None
@@ -1738,7 +3400,8 @@ impl<K, V, A: Allocator + Clone> IntoIter<K, V, A> {
     /// invalidated by further calls to this function and some others.
     fn dying_next_back(
         &mut self,
-    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV>> {
+    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, (), B>, marker::KV>>
+    {
         if self.length == 0 {
             self.range.deallocating_end(self.alloc.clone());
             None
@@ -1749,7 +3412,7 @@ impl<K, V, A: Allocator + Clone> IntoIter<K, V, A> {
     }
 }
 
-impl<K, V, A: Allocator + Clone> Iterator for IntoIter<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> Iterator for IntoIter<K, V, A, B> {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<(K, V)> {
@@ -1762,22 +3425,22 @@ impl<K, V, A: Allocator + Clone> Iterator for IntoIter<K, V, A> {
     }
 }
 
-impl<K, V, A: Allocator + Clone> DoubleEndedIterator for IntoIter<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> DoubleEndedIterator for IntoIter<K, V, A, B> {
     fn next_back(&mut self) -> Option<(K, V)> {
         // SAFETY: we consume the dying handle immediately.
         self.dying_next_back().map(unsafe { |kv| kv.into_key_val() })
     }
 }
 
-impl<K, V, A: Allocator + Clone> ExactSizeIterator for IntoIter<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> ExactSizeIterator for IntoIter<K, V, A, B> {
     fn len(&self) -> usize {
         self.length
     }
 }
 
-impl<K, V, A: Allocator + Clone> FusedIterator for IntoIter<K, V, A> {}
+impl<K, V, A: Allocator + Clone, const B: usize> FusedIterator for IntoIter<K, V, A, B> {}
 
-impl<'a, K, V> Iterator for Keys<'a, K, V> {
+impl<'a, K, V, const B: usize> Iterator for Keys<'a, K, V, B> {
     type Item = &'a K;
 
     fn next(&mut self) -> Option<&'a K> {
@@ -1788,6 +3451,16 @@ impl<'a, K, V> Iterator for Keys<'a, K, V> {
         self.inner.size_hint()
     }
 
+    #[cfg(feature = "order_statistics")]
+    fn nth(&mut self, n: usize) -> Option<&'a K> {
+        self.inner.nth(n).map(|(k, _)| k)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.inner.advance_by(n)
+    }
+
     fn last(mut self) -> Option<&'a K> {
         self.next_back()
     }
@@ -1801,27 +3474,37 @@ impl<'a, K, V> Iterator for Keys<'a, K, V> {
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+impl<'a, K, V, const B: usize> DoubleEndedIterator for Keys<'a, K, V, B> {
     fn next_back(&mut self) -> Option<&'a K> {
         self.inner.next_back().map(|(k, _)| k)
     }
+
+    #[cfg(feature = "order_statistics")]
+    fn nth_back(&mut self, n: usize) -> Option<&'a K> {
+        self.inner.nth_back(n).map(|(k, _)| k)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_back_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.inner.advance_back_by(n)
+    }
 }
 
-impl<K, V> ExactSizeIterator for Keys<'_, K, V> {
+impl<K, V, const B: usize> ExactSizeIterator for Keys<'_, K, V, B> {
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
 
-impl<K, V> FusedIterator for Keys<'_, K, V> {}
+impl<K, V, const B: usize> FusedIterator for Keys<'_, K, V, B> {}
 
-impl<K, V> Clone for Keys<'_, K, V> {
+impl<K, V, const B: usize> Clone for Keys<'_, K, V, B> {
     fn clone(&self) -> Self {
         Keys { inner: self.inner.clone() }
     }
 }
 
-impl<'a, K, V> Iterator for Values<'a, K, V> {
+impl<'a, K, V, const B: usize> Iterator for Values<'a, K, V, B> {
     type Item = &'a V;
 
     fn next(&mut self) -> Option<&'a V> {
@@ -1832,26 +3515,46 @@ impl<'a, K, V> Iterator for Values<'a, K, V> {
         self.inner.size_hint()
     }
 
+    #[cfg(feature = "order_statistics")]
+    fn nth(&mut self, n: usize) -> Option<&'a V> {
+        self.inner.nth(n).map(|(_, v)| v)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.inner.advance_by(n)
+    }
+
     fn last(mut self) -> Option<&'a V> {
         self.next_back()
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+impl<'a, K, V, const B: usize> DoubleEndedIterator for Values<'a, K, V, B> {
     fn next_back(&mut self) -> Option<&'a V> {
         self.inner.next_back().map(|(_, v)| v)
     }
+
+    #[cfg(feature = "order_statistics")]
+    fn nth_back(&mut self, n: usize) -> Option<&'a V> {
+        self.inner.nth_back(n).map(|(_, v)| v)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_back_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.inner.advance_back_by(n)
+    }
 }
 
-impl<K, V> ExactSizeIterator for Values<'_, K, V> {
+impl<K, V, const B: usize> ExactSizeIterator for Values<'_, K, V, B> {
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
 
-impl<K, V> FusedIterator for Values<'_, K, V> {}
+impl<K, V, const B: usize> FusedIterator for Values<'_, K, V, B> {}
 
-impl<K, V> Clone for Values<'_, K, V> {
+impl<K, V, const B: usize> Clone for Values<'_, K, V, B> {
     fn clone(&self) -> Self {
         Values { inner: self.inner.clone() }
     }
@@ -1860,40 +3563,57 @@ impl<K, V> Clone for Values<'_, K, V> {
 decorate_if! {
     /// An iterator produced by calling `drain_filter` on BTreeMap.
     if #[cfg(feature = "btree_drain_filter")] { pub }
-    struct DrainFilter<'a, K, V, F, A: Allocator + Clone = Global>
+    struct DrainFilter<'a, K, V, F, A: Allocator + Clone = Global, const B: usize = 6>
     where
         F: 'a + FnMut(&K, &mut V) -> bool,
     {
         pred: F,
-        inner: DrainFilterInner<'a, K, V>,
+        inner: DrainFilterInner<'a, K, V, B>,
         /// The BTreeMap will outlive this IntoIter so we don't care about drop order for `alloc`.
         alloc: A,
     }
 }
+
+/// An iterator produced by calling `extract_if` on BTreeMap.
+pub struct ExtractIf<'a, K, V, C, F, A: Allocator + Clone = Global, const B: usize = 6>
+where
+    C: FnMut(&K) -> Ordering,
+    F: 'a + FnMut(&K, &mut V) -> bool,
+{
+    pred: F,
+    upper_comp: C,
+    upper_bound: SearchBound,
+    inner: DrainFilterInner<'a, K, V, B>,
+    /// The BTreeMap will outlive this IntoIter so we don't care about drop order for `alloc`.
+    alloc: A,
+}
+
 /// Most of the implementation of DrainFilter are generic over the type
 /// of the predicate, thus also serving for BTreeSet::DrainFilter.
-pub(super) struct DrainFilterInner<'a, K, V> {
+pub(super) struct DrainFilterInner<'a, K, V, const B: usize = 6> {
     /// Reference to the length field in the borrowed map, updated live.
     length: &'a mut usize,
     /// Buried reference to the root field in the borrowed map.
     /// Wrapped in `Option` to allow drop handler to `take` it.
-    dormant_root: Option<DormantMutRef<'a, Root<K, V>>>,
+    dormant_root: Option<DormantMutRef<'a, Root<K, V, (), B>>>,
     /// Contains a leaf edge preceding the next element to be returned, or the last leaf edge.
     /// Empty if the map has no root, if iteration went beyond the last leaf edge,
     /// or if a panic occurred in the predicate.
-    cur_leaf_edge: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>>,
+    cur_leaf_edge:
+        Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, (), B>, marker::Edge>>,
 }
 
-impl<K, V, F, A: Allocator + Clone> Drop for DrainFilter<'_, K, V, F, A>
+impl<K, V, F, A: Allocator + Clone, const B: usize> Drop for DrainFilter<'_, K, V, F, A, B>
 where
     F: FnMut(&K, &mut V) -> bool,
 {
     fn drop(&mut self) {
         self.for_each(drop);
+        self.inner.paranoid_check();
     }
 }
 
-impl<K, V, F> fmt::Debug for DrainFilter<'_, K, V, F>
+impl<K, V, F, const B: usize> fmt::Debug for DrainFilter<'_, K, V, F, Global, B>
 where
     K: fmt::Debug,
     V: fmt::Debug,
@@ -1904,7 +3624,7 @@ where
     }
 }
 
-impl<K, V, F, A: Allocator + Clone> Iterator for DrainFilter<'_, K, V, F, A>
+impl<K, V, F, A: Allocator + Clone, const B: usize> Iterator for DrainFilter<'_, K, V, F, A, B>
 where
     F: FnMut(&K, &mut V) -> bool,
 {
@@ -1919,7 +3639,15 @@ where
     }
 }
 
-impl<'a, K, V> DrainFilterInner<'a, K, V> {
+impl<'a, K, V, const B: usize> DrainFilterInner<'a, K, V, B> {
+    /// Runs the structural check of the `paranoid` feature, once iteration is over.
+    pub(super) fn paranoid_check(&mut self) {
+        if let Some(root) = self.dormant_root.take() {
+            // SAFETY: iteration has ended, so no handle into the tree remains.
+            paranoid_check(Some(unsafe { root.awaken() }), *self.length);
+        }
+    }
+
     /// Allow Debug implementations to predict the next element.
     pub(super) fn peek(&self) -> Option<(&K, &V)> {
         let edge = self.cur_leaf_edge.as_ref()?;
@@ -1930,9 +3658,27 @@ impl<'a, K, V> DrainFilterInner<'a, K, V> {
     pub(super) fn next<F, A: Allocator + Clone>(&mut self, pred: &mut F, alloc: A) -> Option<(K, V)>
     where
         F: FnMut(&K, &mut V) -> bool,
+    {
+        self.next_until(pred, |_| false, alloc)
+    }
+
+    /// Like `next`, but ends the iteration at the first key for which `at_end`
+    /// returns true, without visiting that key or any after it.
+    pub(super) fn next_until<F, E, A: Allocator + Clone>(
+        &mut self,
+        pred: &mut F,
+        mut at_end: E,
+        alloc: A,
+    ) -> Option<(K, V)>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+        E: FnMut(&K) -> bool,
     {
         while let Ok(mut kv) = self.cur_leaf_edge.take()?.next_kv() {
             let (k, v) = kv.kv_mut();
+            if at_end(k) {
+                return None;
+            }
             if pred(k, v) {
                 *self.length -= 1;
                 let (kv, pos) = kv.remove_kv_tracking(
@@ -1963,9 +3709,66 @@ impl<'a, K, V> DrainFilterInner<'a, K, V> {
     }
 }
 
-impl<K, V, F> FusedIterator for DrainFilter<'_, K, V, F> where F: FnMut(&K, &mut V) -> bool {}
+impl<K, V, F, const B: usize> FusedIterator for DrainFilter<'_, K, V, F, Global, B> where
+    F: FnMut(&K, &mut V) -> bool
+{
+}
+
+impl<K, V, C, F, A: Allocator + Clone, const B: usize> Drop for ExtractIf<'_, K, V, C, F, A, B>
+where
+    C: FnMut(&K) -> Ordering,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+        self.inner.paranoid_check();
+    }
+}
+
+impl<K, V, C, F, A: Allocator + Clone, const B: usize> fmt::Debug
+    for ExtractIf<'_, K, V, C, F, A, B>
+where
+    C: FnMut(&K) -> Ordering,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The next element may lie beyond the range, which only `upper_comp` can tell.
+        f.debug_struct("ExtractIf").finish_non_exhaustive()
+    }
+}
+
+impl<K, V, C, F, A: Allocator + Clone, const B: usize> Iterator for ExtractIf<'_, K, V, C, F, A, B>
+where
+    C: FnMut(&K) -> Ordering,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let (upper_comp, upper_bound) = (&mut self.upper_comp, self.upper_bound);
+        let beyond_range = |k: &K| match upper_bound {
+            SearchBound::Included => upper_comp(k) == Ordering::Less,
+            SearchBound::Excluded => upper_comp(k) != Ordering::Greater,
+            SearchBound::AllIncluded => false,
+            SearchBound::AllExcluded => true,
+        };
+        self.inner.next_until(&mut self.pred, beyond_range, self.alloc.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, C, F, A: Allocator + Clone, const B: usize> FusedIterator
+    for ExtractIf<'_, K, V, C, F, A, B>
+where
+    C: FnMut(&K) -> Ordering,
+    F: FnMut(&K, &mut V) -> bool,
+{
+}
 
-impl<'a, K, V> Iterator for Range<'a, K, V> {
+impl<'a, K, V, const B: usize> Iterator for Range<'a, K, V, B> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<(&'a K, &'a V)> {
@@ -1985,7 +3788,7 @@ impl<'a, K, V> Iterator for Range<'a, K, V> {
     }
 }
 
-impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+impl<'a, K, V, const B: usize> Iterator for ValuesMut<'a, K, V, B> {
     type Item = &'a mut V;
 
     fn next(&mut self) -> Option<&'a mut V> {
@@ -1996,26 +3799,46 @@ impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
         self.inner.size_hint()
     }
 
+    #[cfg(feature = "order_statistics")]
+    fn nth(&mut self, n: usize) -> Option<&'a mut V> {
+        self.inner.nth(n).map(|(_, v)| v)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.inner.advance_by(n)
+    }
+
     fn last(mut self) -> Option<&'a mut V> {
         self.next_back()
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+impl<'a, K, V, const B: usize> DoubleEndedIterator for ValuesMut<'a, K, V, B> {
     fn next_back(&mut self) -> Option<&'a mut V> {
         self.inner.next_back().map(|(_, v)| v)
     }
+
+    #[cfg(feature = "order_statistics")]
+    fn nth_back(&mut self, n: usize) -> Option<&'a mut V> {
+        self.inner.nth_back(n).map(|(_, v)| v)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_back_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.inner.advance_back_by(n)
+    }
 }
 
-impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {
+impl<K, V, const B: usize> ExactSizeIterator for ValuesMut<'_, K, V, B> {
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
 
-impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+impl<K, V, const B: usize> FusedIterator for ValuesMut<'_, K, V, B> {}
 
-impl<K, V, A: Allocator + Clone> Iterator for IntoKeys<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> Iterator for IntoKeys<K, V, A, B> {
     type Item = K;
 
     fn next(&mut self) -> Option<K> {
@@ -2039,21 +3862,21 @@ impl<K, V, A: Allocator + Clone> Iterator for IntoKeys<K, V, A> {
     }
 }
 
-impl<K, V, A: Allocator + Clone> DoubleEndedIterator for IntoKeys<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> DoubleEndedIterator for IntoKeys<K, V, A, B> {
     fn next_back(&mut self) -> Option<K> {
         self.inner.next_back().map(|(k, _)| k)
     }
 }
 
-impl<K, V, A: Allocator + Clone> ExactSizeIterator for IntoKeys<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> ExactSizeIterator for IntoKeys<K, V, A, B> {
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
 
-impl<K, V, A: Allocator + Clone> FusedIterator for IntoKeys<K, V, A> {}
+impl<K, V, A: Allocator + Clone, const B: usize> FusedIterator for IntoKeys<K, V, A, B> {}
 
-impl<K, V, A: Allocator + Clone> Iterator for IntoValues<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> Iterator for IntoValues<K, V, A, B> {
     type Item = V;
 
     fn next(&mut self) -> Option<V> {
@@ -2069,35 +3892,61 @@ impl<K, V, A: Allocator + Clone> Iterator for IntoValues<K, V, A> {
     }
 }
 
-impl<K, V, A: Allocator + Clone> DoubleEndedIterator for IntoValues<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> DoubleEndedIterator for IntoValues<K, V, A, B> {
     fn next_back(&mut self) -> Option<V> {
         self.inner.next_back().map(|(_, v)| v)
     }
 }
 
-impl<K, V, A: Allocator + Clone> ExactSizeIterator for IntoValues<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize> ExactSizeIterator for IntoValues<K, V, A, B> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, A: Allocator + Clone, const B: usize> FusedIterator for IntoValues<K, V, A, B> {}
+
+impl<K, V, A: Allocator + Clone, const B: usize> Iterator for DrainRange<K, V, A, B> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V, A: Allocator + Clone, const B: usize> DoubleEndedIterator for DrainRange<K, V, A, B> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V, A: Allocator + Clone, const B: usize> ExactSizeIterator for DrainRange<K, V, A, B> {
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
 
-impl<K, V, A: Allocator + Clone> FusedIterator for IntoValues<K, V, A> {}
+impl<K, V, A: Allocator + Clone, const B: usize> FusedIterator for DrainRange<K, V, A, B> {}
 
-impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+impl<'a, K, V, const B: usize> DoubleEndedIterator for Range<'a, K, V, B> {
     fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
         self.inner.next_back_checked()
     }
 }
 
-impl<K, V> FusedIterator for Range<'_, K, V> {}
+impl<K, V, const B: usize> FusedIterator for Range<'_, K, V, B> {}
 
-impl<K, V> Clone for Range<'_, K, V> {
+impl<K, V, const B: usize> Clone for Range<'_, K, V, B> {
     fn clone(&self) -> Self {
         Range { inner: self.inner.clone() }
     }
 }
 
-impl<'a, K, V> Iterator for RangeMut<'a, K, V> {
+impl<'a, K, V, const B: usize> Iterator for RangeMut<'a, K, V, B> {
     type Item = (&'a K, &'a mut V);
 
     fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
@@ -2117,13 +3966,13 @@ impl<'a, K, V> Iterator for RangeMut<'a, K, V> {
     }
 }
 
-impl<'a, K, V> DoubleEndedIterator for RangeMut<'a, K, V> {
+impl<'a, K, V, const B: usize> DoubleEndedIterator for RangeMut<'a, K, V, B> {
     fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
         self.inner.next_back_checked()
     }
 }
 
-impl<K, V> FusedIterator for RangeMut<'_, K, V> {}
+impl<K, V, const B: usize> FusedIterator for RangeMut<'_, K, V, B> {}
 
 //todo consider turning me back on
 //impl<K: SortableByWithOrder<O>, V, O: TotalOrder + Default> FromIterator<(K, V)>
@@ -2143,39 +3992,9 @@ impl<K, V> FusedIterator for RangeMut<'_, K, V> {}
 //}
 //}
 
-//todo consider turning me back on
-//impl<K: SortableByWithOrder<O>, V, O: TotalOrder, A: Allocator + Clone> Extend<(K, V)>
-//for BTreeMap<K, V, A>
-//{
-//#[inline]
-//fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
-//iter.into_iter().for_each(move |(k, v)| {
-//self.insert(k, v, comp);
-//});
-//}
-
-//#[inline]
-//#[cfg(feature = "extend_one")]
-//fn extend_one(&mut self, (k, v): (K, V)) {
-//self.insert(k, v);
-//}
-//}
-
-//impl<'a, K: SortableByWithOrder<O> + Copy, V: Copy, O: TotalOrder, A: Allocator + Clone>
-//Extend<(&'a K, &'a V)> for BTreeMap<K, V, A>
-//{
-//fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
-//self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
-//}
-
-//#[inline]
-//#[cfg(feature = "extend_one")]
-//fn extend_one(&mut self, (&k, &v): (&'a K, &'a V)) {
-//self.insert(k, v);
-//}
-//}
-
-impl<K: Hash, V: Hash, A: Allocator + Clone> Hash for BTreeMap<K, V, A> {
+impl<K: Hash, V: Hash, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Hash
+    for BTreeMap<K, V, A, B, N>
+{
     fn hash<H: Hasher>(&self, state: &mut H) {
         state.write_length_prefix(self.len());
         for elt in self {
@@ -2191,29 +4010,40 @@ impl<K, V> Default for BTreeMap<K, V> {
     }
 }
 
-impl<K: PartialEq, V: PartialEq, A: Allocator + Clone> PartialEq for BTreeMap<K, V, A> {
-    fn eq(&self, other: &BTreeMap<K, V, A>) -> bool {
+impl<K: PartialEq, V: PartialEq, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> PartialEq
+    for BTreeMap<K, V, A, B, N>
+{
+    fn eq(&self, other: &BTreeMap<K, V, A, B, N>) -> bool {
         self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
     }
 }
 
-impl<K: Eq, V: Eq, A: Allocator + Clone> Eq for BTreeMap<K, V, A> {}
+impl<K: Eq, V: Eq, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Eq
+    for BTreeMap<K, V, A, B, N>
+{
+}
 
-impl<K: PartialOrd, V: PartialOrd, A: Allocator + Clone> PartialOrd for BTreeMap<K, V, A> {
+impl<K: PartialOrd, V: PartialOrd, A: Allocator + Clone, const B: usize, N: NodeSearch<K>>
+    PartialOrd for BTreeMap<K, V, A, B, N>
+{
     #[inline]
-    fn partial_cmp(&self, other: &BTreeMap<K, V, A>) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &BTreeMap<K, V, A, B, N>) -> Option<Ordering> {
         self.iter().partial_cmp(other.iter())
     }
 }
 
-impl<K: Ord, V: Ord, A: Allocator + Clone> Ord for BTreeMap<K, V, A> {
+impl<K: Ord, V: Ord, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Ord
+    for BTreeMap<K, V, A, B, N>
+{
     #[inline]
-    fn cmp(&self, other: &BTreeMap<K, V, A>) -> Ordering {
+    fn cmp(&self, other: &BTreeMap<K, V, A, B, N>) -> Ordering {
         self.iter().cmp(other.iter())
     }
 }
 
-impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for BTreeMap<K, V, A> {
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Debug
+    for BTreeMap<K, V, A, B, N>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_map().entries(self.iter()).finish()
     }
@@ -2239,32 +4069,7 @@ impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for BTreeMap<K, V, A> {
 //}
 //}
 
-//todo consider turning me back on
-//impl<K, V, const N: usize> From<[(K, V); N]>
-//for BTreeMap<K, V>
-//{
-///// Converts a `[(K, V); N]` into a `BTreeMap<(K, V)>`.
-/////
-///// ```
-///// use copse::BTreeMap;
-/////
-///// let map1 = BTreeMap::from([(1, 2), (3, 4)]);
-///// let map2: BTreeMap<_, _> = [(1, 2), (3, 4)].into();
-///// assert_eq!(map1, map2);
-///// ```
-//fn from(mut arr: [(K, V); N]) -> Self {
-//if N == 0 {
-//return BTreeMap::new();
-//}
-
-//// use stable sort to preserve the insertion order.
-//let order = O::default();
-//arr.sort_by(|a, b| order.cmp_any(&a.0, &b.0));
-//BTreeMap::bulk_build_from_sorted_iter(arr, order, Global)
-//}
-//}
-
-impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+impl<K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> BTreeMap<K, V, A, B, N> {
     /// Gets an iterator over the entries of the map, sorted by key.
     ///
     /// # Examples
@@ -2272,12 +4077,12 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map = BTreeMap::default();
-    /// map.insert(3, "c");
-    /// map.insert(2, "b");
-    /// map.insert(1, "a");
+    /// map.insert(3, "c", Ord::cmp);
+    /// map.insert(2, "b", Ord::cmp);
+    /// map.insert(1, "a", Ord::cmp);
     ///
     /// for (key, value) in map.iter() {
     ///     println!("{key}: {value}");
@@ -2286,7 +4091,7 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// let (first_key, first_value) = map.iter().next().unwrap();
     /// assert_eq!((*first_key, *first_value), (1, "a"));
     /// ```
-    pub fn iter(&self) -> Iter<'_, K, V> {
+    pub fn iter(&self) -> Iter<'_, K, V, B> {
         if let Some(root) = &self.root {
             let full_range = root.reborrow().full_range();
 
@@ -2303,13 +4108,12 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
-    /// let mut map = BTreeMap::<_, _>::from([
-    ///    ("a", 1),
-    ///    ("b", 2),
-    ///    ("c", 3),
-    /// ]);
+    /// let mut map = BTreeMap::new();
+    /// map.insert("a", 1, Ord::cmp);
+    /// map.insert("b", 2, Ord::cmp);
+    /// map.insert("c", 3, Ord::cmp);
     ///
     /// // add 10 to the value if the key isn't "a"
     /// for (key, value) in map.iter_mut() {
@@ -2318,7 +4122,7 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     ///     }
     /// }
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, B> {
         if let Some(root) = &mut self.root {
             let full_range = root.borrow_valmut().full_range();
 
@@ -2335,16 +4139,16 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(2, "b");
-    /// a.insert(1, "a");
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(1, "a", Ord::cmp);
     ///
     /// let keys: Vec<_> = a.keys().cloned().collect();
     /// assert_eq!(keys, [1, 2]);
     /// ```
-    pub fn keys(&self) -> Keys<'_, K, V> {
+    pub fn keys(&self) -> Keys<'_, K, V, B> {
         Keys { inner: self.iter() }
     }
 
@@ -2355,16 +4159,16 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "hello");
-    /// a.insert(2, "goodbye");
+    /// a.insert(1, "hello", Ord::cmp);
+    /// a.insert(2, "goodbye", Ord::cmp);
     ///
     /// let values: Vec<&str> = a.values().cloned().collect();
     /// assert_eq!(values, ["hello", "goodbye"]);
     /// ```
-    pub fn values(&self) -> Values<'_, K, V> {
+    pub fn values(&self) -> Values<'_, K, V, B> {
         Values { inner: self.iter() }
     }
 
@@ -2375,11 +4179,11 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, String::from("hello"));
-    /// a.insert(2, String::from("goodbye"));
+    /// a.insert(1, String::from("hello"), Ord::cmp);
+    /// a.insert(2, String::from("goodbye"), Ord::cmp);
     ///
     /// for value in a.values_mut() {
     ///     value.push_str("!");
@@ -2389,7 +4193,7 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// assert_eq!(values, [String::from("hello!"),
     ///                     String::from("goodbye!")]);
     /// ```
-    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V, B> {
         ValuesMut { inner: self.iter_mut() }
     }
 
@@ -2401,11 +4205,11 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         /// Basic usage:
         ///
         /// ```
-        /// use copse::BTreeMap;
+        /// use btree_monstrousity::BTreeMap;
         ///
         /// let mut a = BTreeMap::default();
         /// assert_eq!(a.len(), 0);
-        /// a.insert(1, "a");
+        /// a.insert(1, "a", Ord::cmp);
         /// assert_eq!(a.len(), 1);
         /// ```
         #[must_use]
@@ -2424,11 +4228,11 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         /// Basic usage:
         ///
         /// ```
-        /// use copse::BTreeMap;
+        /// use btree_monstrousity::BTreeMap;
         ///
         /// let mut a = BTreeMap::default();
         /// assert!(a.is_empty());
-        /// a.insert(1, "a");
+        /// a.insert(1, "a", Ord::cmp);
         /// assert!(!a.is_empty());
         /// ```
         #[must_use]
@@ -2439,13 +4243,66 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         }
     }
 
+    /// Checks that the map is intact and that its keys are in strictly
+    /// ascending order according to `double_comp`.
+    ///
+    /// The nodes of the tree are checked first: their lengths, the links from
+    /// each node back to its parent, and the total length recorded by the map.
+    /// Then each key is compared with the next one, and the first pair that
+    /// `double_comp` does not consider [`Less`](Ordering::Less) is returned.
+    ///
+    /// A map that fails this check was built or searched with a comparator that
+    /// disagrees with `double_comp`, or with itself. This takes O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::OrderViolation;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(2, "b", Ord::cmp);
+    /// map.insert(3, "c", Ord::cmp);
+    /// assert!(map.check_order_by(Ord::cmp).is_ok());
+    ///
+    /// match map.check_order_by(|a: &i32, b: &i32| b.cmp(a)) {
+    ///     Err(OrderViolation::Unordered { previous, next }) => assert_eq!((previous, next), (&1, &2)),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn check_order_by<C>(&self, mut double_comp: C) -> Result<(), OrderViolation<'_, K>>
+    where
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        check_structure(self.root.as_ref(), self.length).map_err(OrderViolation::Structure)?;
+        let mut keys = self.keys();
+        if let Some(mut previous) = keys.next() {
+            for next in keys {
+                if !double_comp(previous, next).is_lt() {
+                    return Err(OrderViolation::Unordered { previous, next });
+                }
+                previous = next;
+            }
+        }
+        Ok(())
+    }
+
+    // Runs the structural half of `check_order_by` with the `paranoid` feature.
+    #[inline]
+    fn paranoid_check(&self) {
+        paranoid_check(self.root.as_ref(), self.length)
+    }
+
     /// Returns a [`Cursor`] pointing at the first element that is above the
     /// given bound.
     ///
     /// If no such element exists then a cursor pointing at the "ghost"
     /// non-element is returned.
     ///
-    /// Passing [`Bound::Unbounded`] will return a cursor pointing at the first
+    /// Passing [`SearchBoundCustom::AllIncluded`] will return a cursor pointing at the first
     /// element of the map.
     ///
     /// # Examples
@@ -2453,19 +4310,19 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use std::ops::Bound;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "a");
-    /// a.insert(2, "b");
-    /// a.insert(3, "c");
-    /// a.insert(4, "c");
-    /// let cursor = a.lower_bound(Bound::Excluded(&2));
+    /// a.insert(1, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp);
+    /// a.insert(4, "c", Ord::cmp);
+    /// let cursor = a.lower_bound(|k| 2.cmp(k), SearchBoundCustom::Excluded);
     /// assert_eq!(cursor.key(), Some(&3));
     /// ```
     #[cfg(feature = "btree_cursors")]
-    pub fn lower_bound<C>(&self, comp: C, bound: SearchBoundCustom) -> Cursor<'_, K, V>
+    pub fn lower_bound<C>(&self, comp: C, bound: SearchBoundCustom) -> Cursor<'_, K, V, B>
     where
         C: FnMut(&K) -> Ordering,
     {
@@ -2473,17 +4330,42 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
             None => return Cursor { current: None, root: None },
             Some(root) => root.reborrow(),
         };
-        let edge = root_node.lower_bound(comp, SearchBound::from(bound));
+        let edge = root_node.lower_bound::<N, _>(comp, SearchBound::from(bound));
         Cursor { current: edge.next_kv().ok(), root: self.root.as_ref() }
     }
 
+    /// Returns a [`Cursor`] pointing at the first element that is above the
+    /// given bound, like [`lower_bound`](Self::lower_bound), but takes any
+    /// [`Query`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    ///
+    /// let mut a = BTreeMap::default();
+    /// for word in ["apple", "banana", "cherry"] {
+    ///     a.insert(word.to_string(), word.len(), Ord::cmp);
+    /// }
+    /// let cursor = a.lower_bound_q("b", SearchBoundCustom::Included);
+    /// assert_eq!(cursor.key().map(String::as_str), Some("banana"));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn lower_bound_q<Q>(&self, mut query: Q, bound: SearchBoundCustom) -> Cursor<'_, K, V, B>
+    where
+        Q: Query<K>,
+    {
+        self.lower_bound(|k| query.compare(k), bound)
+    }
+
     /// Returns a [`CursorMut`] pointing at the first element that is above the
     /// given bound.
     ///
     /// If no such element exists then a cursor pointing at the "ghost"
     /// non-element is returned.
     ///
-    /// Passing [`Bound::Unbounded`] will return a cursor pointing at the first
+    /// Passing [`SearchBoundCustom::AllIncluded`] will return a cursor pointing at the first
     /// element of the map.
     ///
     /// # Examples
@@ -2491,15 +4373,15 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use std::ops::Bound;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "a");
-    /// a.insert(2, "b");
-    /// a.insert(3, "c");
-    /// a.insert(4, "c");
-    /// let cursor = a.lower_bound_mut(Bound::Excluded(&2));
+    /// a.insert(1, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp);
+    /// a.insert(4, "c", Ord::cmp);
+    /// let cursor = a.lower_bound_mut(|k| 2.cmp(k), SearchBoundCustom::Excluded);
     /// assert_eq!(cursor.key(), Some(&3));
     /// ```
     #[cfg(feature = "btree_cursors")]
@@ -2507,7 +4389,7 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
         &mut self,
         comp: C,
         bound: SearchBoundCustom,
-    ) -> CursorMut<'_, K, V, A>
+    ) -> CursorMut<'_, K, V, A, B, N>
     where
         C: FnMut(&K) -> Ordering,
     {
@@ -2519,16 +4401,18 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
                     root: dormant_root,
                     length: &mut self.length,
                     alloc: &mut *self.alloc,
+                    _marker: PhantomData,
                 };
             }
             Some(root) => root.borrow_mut(),
         };
-        let edge = root_node.lower_bound(comp, SearchBound::from(bound));
+        let edge = root_node.lower_bound::<N, _>(comp, SearchBound::from(bound));
         CursorMut {
             current: edge.next_kv().ok(),
             root: dormant_root,
             length: &mut self.length,
             alloc: &mut *self.alloc,
+            _marker: PhantomData,
         }
     }
 
@@ -2538,7 +4422,7 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// If no such element exists then a cursor pointing at the "ghost"
     /// non-element is returned.
     ///
-    /// Passing [`Bound::Unbounded`] will return a cursor pointing at the last
+    /// Passing [`SearchBoundCustom::AllIncluded`] will return a cursor pointing at the last
     /// element of the map.
     ///
     /// # Examples
@@ -2546,19 +4430,19 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use std::ops::Bound;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "a");
-    /// a.insert(2, "b");
-    /// a.insert(3, "c");
-    /// a.insert(4, "c");
-    /// let cursor = a.upper_bound(Bound::Excluded(&3));
+    /// a.insert(1, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp);
+    /// a.insert(4, "c", Ord::cmp);
+    /// let cursor = a.upper_bound(|k| 3.cmp(k), SearchBoundCustom::Excluded);
     /// assert_eq!(cursor.key(), Some(&2));
     /// ```
     #[cfg(feature = "btree_cursors")]
-    pub fn upper_bound<C>(&self, comp: C, bound: SearchBoundCustom) -> Cursor<'_, K, V>
+    pub fn upper_bound<C>(&self, comp: C, bound: SearchBoundCustom) -> Cursor<'_, K, V, B>
     where
         C: FnMut(&K) -> Ordering,
     {
@@ -2566,17 +4450,42 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
             None => return Cursor { current: None, root: None },
             Some(root) => root.reborrow(),
         };
-        let edge = root_node.upper_bound(comp, SearchBound::from(bound));
+        let edge = root_node.upper_bound::<N, _>(comp, SearchBound::from(bound));
         Cursor { current: edge.next_back_kv().ok(), root: self.root.as_ref() }
     }
 
+    /// Returns a [`Cursor`] pointing at the last element that is below the
+    /// given bound, like [`upper_bound`](Self::upper_bound), but takes any
+    /// [`Query`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    ///
+    /// let mut a = BTreeMap::default();
+    /// for word in ["apple", "banana", "cherry"] {
+    ///     a.insert(word.to_string(), word.len(), Ord::cmp);
+    /// }
+    /// let cursor = a.upper_bound_q("banana", SearchBoundCustom::Excluded);
+    /// assert_eq!(cursor.key().map(String::as_str), Some("apple"));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn upper_bound_q<Q>(&self, mut query: Q, bound: SearchBoundCustom) -> Cursor<'_, K, V, B>
+    where
+        Q: Query<K>,
+    {
+        self.upper_bound(|k| query.compare(k), bound)
+    }
+
     /// Returns a [`CursorMut`] pointing at the last element that is below the
     /// given bound.
     ///
     /// If no such element exists then a cursor pointing at the "ghost"
     /// non-element is returned.
     ///
-    /// Passing [`Bound::Unbounded`] will return a cursor pointing at the last
+    /// Passing [`SearchBoundCustom::AllIncluded`] will return a cursor pointing at the last
     /// element of the map.
     ///
     /// # Examples
@@ -2584,45 +4493,113 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    ///
+    /// let mut a = BTreeMap::default();
+    /// a.insert(1, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp);
+    /// a.insert(4, "c", Ord::cmp);
+    /// let cursor = a.upper_bound_mut(|k| 3.cmp(k), SearchBoundCustom::Excluded);
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn upper_bound_mut<C>(
+        &mut self,
+        comp: C,
+        bound: SearchBoundCustom,
+    ) -> CursorMut<'_, K, V, A, B, N>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        let (root, dormant_root) = DormantMutRef::new(&mut self.root);
+        let root_node = match root.as_mut() {
+            None => {
+                return CursorMut {
+                    current: None,
+                    root: dormant_root,
+                    length: &mut self.length,
+                    alloc: &mut *self.alloc,
+                    _marker: PhantomData,
+                };
+            }
+            Some(root) => root.borrow_mut(),
+        };
+        let edge = root_node.upper_bound::<N, _>(comp, SearchBound::from(bound));
+        CursorMut {
+            current: edge.next_back_kv().ok(),
+            root: dormant_root,
+            length: &mut self.length,
+            alloc: &mut *self.alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a [`Cursor`] pointing at the first element above `bound` in a
+    /// map with primitive integer keys, comparing the keys of each node to it
+    /// all at once instead of through a comparator.
+    ///
+    /// The map must be ordered by [`Ord::cmp`], like for [`BTreeMap::get_int`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::default();
+    /// a.insert(1i64, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp);
+    /// let cursor = a.lower_bound_int(Bound::Excluded(2));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn lower_bound_int(&self, bound: Bound<K>) -> Cursor<'_, K, V, B>
+    where
+        K: PrimitiveInt,
+    {
+        let root_node = match self.root.as_ref() {
+            None => return Cursor { current: None, root: None },
+            Some(root) => root.reborrow(),
+        };
+        let (target, bound) = IntTarget::from_range(bound.as_ref());
+        let edge = root_node.lower_bound::<N, _>(target, bound);
+        Cursor { current: edge.next_kv().ok(), root: self.root.as_ref() }
+    }
+
+    /// Returns a [`Cursor`] pointing at the last element below `bound` in a
+    /// map with primitive integer keys, comparing the keys of each node to it
+    /// all at once instead of through a comparator.
+    ///
+    /// The map must be ordered by [`Ord::cmp`], like for [`BTreeMap::get_int`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
     /// use std::ops::Bound;
     ///
     /// let mut a = BTreeMap::default();
-    /// a.insert(1, "a");
-    /// a.insert(2, "b");
-    /// a.insert(3, "c");
-    /// a.insert(4, "c");
-    /// let cursor = a.upper_bound_mut(Bound::Excluded(&3));
+    /// a.insert(1i64, "a", Ord::cmp);
+    /// a.insert(2, "b", Ord::cmp);
+    /// a.insert(3, "c", Ord::cmp);
+    /// let cursor = a.upper_bound_int(Bound::Excluded(3));
     /// assert_eq!(cursor.key(), Some(&2));
     /// ```
     #[cfg(feature = "btree_cursors")]
-    pub fn upper_bound_mut<C>(
-        &mut self,
-        comp: C,
-        bound: SearchBoundCustom,
-    ) -> CursorMut<'_, K, V, A>
+    pub fn upper_bound_int(&self, bound: Bound<K>) -> Cursor<'_, K, V, B>
     where
-        C: FnMut(&K) -> Ordering,
+        K: PrimitiveInt,
     {
-        let (root, dormant_root) = DormantMutRef::new(&mut self.root);
-        let root_node = match root.as_mut() {
-            None => {
-                return CursorMut {
-                    current: None,
-                    root: dormant_root,
-                    length: &mut self.length,
-                    alloc: &mut *self.alloc,
-                };
-            }
-            Some(root) => root.borrow_mut(),
+        let root_node = match self.root.as_ref() {
+            None => return Cursor { current: None, root: None },
+            Some(root) => root.reborrow(),
         };
-        let edge = root_node.upper_bound(comp, SearchBound::from(bound));
-        CursorMut {
-            current: edge.next_back_kv().ok(),
-            root: dormant_root,
-            length: &mut self.length,
-            alloc: &mut *self.alloc,
-        }
+        let (target, bound) = IntTarget::from_range(bound.as_ref());
+        let edge = root_node.upper_bound::<N, _>(target, bound);
+        Cursor { current: edge.next_back_kv().ok(), root: self.root.as_ref() }
     }
 }
 
@@ -2636,13 +4613,14 @@ impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
 ///
 /// A `Cursor` is created with the [`BTreeMap::lower_bound`] and [`BTreeMap::upper_bound`] methods.
 #[cfg(feature = "btree_cursors")]
-pub struct Cursor<'a, K: 'a, V: 'a> {
-    current: Option<Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
-    root: Option<&'a node::Root<K, V>>,
+pub struct Cursor<'a, K: 'a, V: 'a, const B: usize = 6> {
+    current:
+        Option<Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, (), B>, marker::KV>>,
+    root: Option<&'a node::Root<K, V, (), B>>,
 }
 
 #[cfg(feature = "btree_cursors")]
-impl<K, V> Clone for Cursor<'_, K, V> {
+impl<K, V, const B: usize> Clone for Cursor<'_, K, V, B> {
     fn clone(&self) -> Self {
         let Cursor { current, root } = *self;
         Cursor { current, root }
@@ -2650,7 +4628,7 @@ impl<K, V> Clone for Cursor<'_, K, V> {
 }
 
 #[cfg(feature = "btree_cursors")]
-impl<K: Debug, V: Debug> Debug for Cursor<'_, K, V> {
+impl<K: Debug, V: Debug, const B: usize> Debug for Cursor<'_, K, V, B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Cursor").field(&self.key_value()).finish()
     }
@@ -2670,22 +4648,108 @@ impl<K: Debug, V: Debug> Debug for Cursor<'_, K, V> {
 /// A `Cursor` is created with the [`BTreeMap::lower_bound_mut`] and [`BTreeMap::upper_bound_mut`]
 /// methods.
 #[cfg(feature = "btree_cursors")]
-pub struct CursorMut<'a, K: 'a, V: 'a, A = Global> {
-    current: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
-    root: DormantMutRef<'a, Option<node::Root<K, V>>>,
+pub struct CursorMut<'a, K: 'a, V: 'a, A = Global, const B: usize = 6, N = Linear> {
+    current:
+        Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, (), B>, marker::KV>>,
+    root: DormantMutRef<'a, Option<node::Root<K, V, (), B>>>,
     length: &'a mut usize,
     alloc: &'a mut A,
+    _marker: PhantomData<fn() -> N>,
 }
 
 #[cfg(feature = "btree_cursors")]
-impl<K: Debug, V: Debug, A> Debug for CursorMut<'_, K, V, A> {
+impl<K: Debug, V: Debug, A, const B: usize, N> Debug for CursorMut<'_, K, V, A, B, N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("CursorMut").field(&self.key_value()).finish()
     }
 }
 
+/// The error returned by [`CursorMut::insert_after_by`] and
+/// [`CursorMut::insert_before_by`] when the key does not belong between the
+/// neighbors it would be inserted between, handing back the element.
+#[cfg(feature = "btree_cursors")]
+pub struct UnorderedKeyError<K, V> {
+    /// The key which was not inserted.
+    pub key: K,
+    /// The value which was not inserted.
+    pub value: V,
+}
+
+#[cfg(feature = "btree_cursors")]
+impl<K: Debug, V: Debug> Debug for UnorderedKeyError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnorderedKeyError")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+#[cfg(feature = "btree_cursors")]
+impl<K: Debug, V> fmt::Display for UnorderedKeyError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key {:?} is not properly ordered relative to the neighbors of the cursor",
+            self.key
+        )
+    }
+}
+
+#[cfg(feature = "btree_cursors")]
+#[cfg(feature = "error_in_core")]
+impl<K: Debug, V: Debug> core::error::Error for UnorderedKeyError<K, V> {}
+
+#[cfg(feature = "btree_cursors")]
+#[cfg(all(feature = "std", not(feature = "error_in_core")))]
+impl<K: Debug, V: Debug> std::error::Error for UnorderedKeyError<K, V> {}
+
+/// The error returned by [`CursorMut::splice_after`] and
+/// [`CursorMut::splice_before`] when the keys of the map to splice in do not
+/// belong between the neighbors of the cursor, handing back the map.
+#[cfg(feature = "btree_cursors")]
+pub struct UnorderedMapError<K, V, A: Allocator + Clone = Global, const B: usize = 6, N = Linear> {
+    /// The map whose elements were not inserted.
+    pub map: BTreeMap<K, V, A, B, N>,
+}
+
+#[cfg(feature = "btree_cursors")]
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Debug
+    for UnorderedMapError<K, V, A, B, N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnorderedMapError").field("map", &self.map).finish()
+    }
+}
+
+#[cfg(feature = "btree_cursors")]
+impl<K, V, A: Allocator + Clone, const B: usize, N> fmt::Display
+    for UnorderedMapError<K, V, A, B, N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "keys of the map are not properly ordered relative to the neighbors of the cursor"
+        )
+    }
+}
+
+#[cfg(feature = "btree_cursors")]
+#[cfg(feature = "error_in_core")]
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> core::error::Error
+    for UnorderedMapError<K, V, A, B, N>
+{
+}
+
 #[cfg(feature = "btree_cursors")]
-impl<'a, K, V> Cursor<'a, K, V> {
+#[cfg(all(feature = "std", not(feature = "error_in_core")))]
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> std::error::Error
+    for UnorderedMapError<K, V, A, B, N>
+{
+}
+
+#[cfg(feature = "btree_cursors")]
+impl<'a, K, V, const B: usize> Cursor<'a, K, V, B> {
     /// Moves the cursor to the next element of the `BTreeMap`.
     ///
     /// If the cursor is pointing to the "ghost" non-element then this will move it to
@@ -2780,7 +4844,7 @@ impl<'a, K, V> Cursor<'a, K, V> {
 }
 
 #[cfg(feature = "btree_cursors")]
-impl<'a, K, V, A> CursorMut<'a, K, V, A> {
+impl<'a, K, V, A, const B: usize, N> CursorMut<'a, K, V, A, B, N> {
     /// Moves the cursor to the next element of the `BTreeMap`.
     ///
     /// If the cursor is pointing to the "ghost" non-element then this will move it to
@@ -2932,8 +4996,8 @@ impl<'a, K, V, A> CursorMut<'a, K, V, A> {
                 unsafe { self.root.reborrow() }
                     .as_mut()?
                     .borrow_mut()
-                    .first_leaf_edge()
-                    .next_kv()
+                    .last_leaf_edge()
+                    .next_back_kv()
                     .ok()?
                     .into_kv_valmut()
             }
@@ -2955,7 +5019,7 @@ impl<'a, K, V, A> CursorMut<'a, K, V, A> {
     /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
     /// `CursorMut` is frozen for the lifetime of the `Cursor`.
     #[cfg(feature = "btree_cursors")]
-    pub fn as_cursor(&self) -> Cursor<'_, K, V> {
+    pub fn as_cursor(&self) -> Cursor<'_, K, V, B> {
         Cursor {
             // SAFETY: The tree is immutable while the cursor exists.
             root: unsafe { self.root.reborrow_shared().as_ref() },
@@ -2966,7 +5030,16 @@ impl<'a, K, V, A> CursorMut<'a, K, V, A> {
 
 // Now the tree editing operations
 #[cfg(feature = "btree_cursors")]
-impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
+impl<'a, K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>>
+    CursorMut<'a, K, V, A, B, N>
+{
+    // Runs the structural half of `BTreeMap::check_order_by` with the `paranoid` feature.
+    #[inline]
+    fn paranoid_check(&self) {
+        // SAFETY: The check only reads the tree, which the current handle allows.
+        paranoid_check(unsafe { self.root.reborrow_shared() }.as_ref(), *self.length)
+    }
+
     /// Inserts a new element into the `BTreeMap` after the current one.
     ///
     /// If the cursor is pointing at the "ghost" non-element then the new element is
@@ -2979,6 +5052,10 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
     ///
     /// * The key of the newly inserted element must be unique in the tree.
     /// * All keys in the tree must remain in sorted order.
+    ///
+    /// The order of the keys cannot be checked here, even with debug
+    /// assertions, since the map keeps no comparator. Use
+    /// [`CursorMut::insert_after_by`] to have it checked.
     #[cfg(feature = "btree_cursors")]
     pub unsafe fn insert_after_unchecked(&mut self, key: K, value: V) {
         let edge = match self.current.take() {
@@ -3008,6 +5085,7 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
         });
         self.current = handle.left_edge().next_back_kv().ok();
         *self.length += 1;
+        self.paranoid_check();
     }
 
     /// Inserts a new element into the `BTreeMap` before the current one.
@@ -3022,6 +5100,10 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
     ///
     /// * The key of the newly inserted element must be unique in the tree.
     /// * All keys in the tree must remain in sorted order.
+    ///
+    /// The order of the keys cannot be checked here, even with debug
+    /// assertions, since the map keeps no comparator. Use
+    /// [`CursorMut::insert_before_by`] to have it checked.
     #[cfg(feature = "btree_cursors")]
     pub unsafe fn insert_before_unchecked(&mut self, key: K, value: V) {
         let edge = match self.current.take() {
@@ -3051,6 +5133,7 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
         });
         self.current = handle.right_edge().next_kv().ok();
         *self.length += 1;
+        self.paranoid_check();
     }
 
     /// Inserts a new element into the `BTreeMap` after the current one.
@@ -3058,13 +5141,10 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
     /// If the cursor is pointing at the "ghost" non-element then the new element is
     /// inserted at the front of the `BTreeMap`.
     ///
-    /// # Panics
-    ///
-    /// This function panics if:
-    /// - the given key compares less than or equal to the current element (if
-    ///   any).
-    /// - the given key compares greater than or equal to the next element (if
-    ///   any).
+    /// Without a comparator, the key cannot be checked to belong there, so this
+    /// is a logic error unless the key compares greater than the current
+    /// element and less than the next one. [`CursorMut::insert_after_by`]
+    /// checks this.
     #[cfg(feature = "btree_cursors")]
     pub fn insert_after(&mut self, key: K, value: V) {
         unsafe {
@@ -3077,13 +5157,10 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
     /// If the cursor is pointing at the "ghost" non-element then the new element is
     /// inserted at the end of the `BTreeMap`.
     ///
-    /// # Panics
-    ///
-    /// This function panics if:
-    /// - the given key compares greater than or equal to the current element
-    ///   (if any).
-    /// - the given key compares less than or equal to the previous element (if
-    ///   any).
+    /// Without a comparator, the key cannot be checked to belong there, so this
+    /// is a logic error unless the key compares less than the current element
+    /// and greater than the previous one. [`CursorMut::insert_before_by`]
+    /// checks this.
     #[cfg(feature = "btree_cursors")]
     pub fn insert_before(&mut self, key: K, value: V) {
         unsafe {
@@ -3091,6 +5168,172 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
         }
     }
 
+    /// Inserts a new element into the `BTreeMap` after the current one, if its
+    /// key belongs there according to `double_comp`.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `BTreeMap`.
+    ///
+    /// # Errors
+    ///
+    /// Hands the element back in an [`UnorderedKeyError`], leaving the map and
+    /// the cursor untouched, if the key does not compare greater than the
+    /// current element and less than the next element, where there are any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(4, "d", Ord::cmp);
+    ///
+    /// let mut cursor = map.lower_bound_mut(|k| 1.cmp(k), SearchBoundCustom::Included);
+    /// assert!(cursor.insert_after_by(3, "c", Ord::cmp).is_ok());
+    /// let err = cursor.insert_after_by(5, "e", Ord::cmp).unwrap_err();
+    /// assert_eq!((err.key, err.value), (5, "e"));
+    /// assert!(cursor.insert_after_by(2, "b", Ord::cmp).is_ok());
+    /// assert_eq!(cursor.key(), Some(&1));
+    /// assert!(map.keys().copied().eq(1..=4));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn insert_after_by<C>(
+        &mut self,
+        key: K,
+        value: V,
+        mut double_comp: C,
+    ) -> Result<(), UnorderedKeyError<K, V>>
+    where
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let after_current = self.key().is_none_or(|current| double_comp(current, &key).is_lt());
+        let before_next = after_current
+            && self.peek_next().is_none_or(|(next, _)| double_comp(&key, next).is_lt());
+        if !before_next {
+            return Err(UnorderedKeyError { key, value });
+        }
+        unsafe { self.insert_after_unchecked(key, value) };
+        Ok(())
+    }
+
+    /// Inserts a new element into the `BTreeMap` before the current one, if its
+    /// key belongs there according to `double_comp`.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `BTreeMap`.
+    ///
+    /// # Errors
+    ///
+    /// Hands the element back in an [`UnorderedKeyError`], leaving the map and
+    /// the cursor untouched, if the key does not compare less than the current
+    /// element and greater than the previous element, where there are any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(4, "d", Ord::cmp);
+    ///
+    /// let mut cursor = map.upper_bound_mut(|k| 9.cmp(k), SearchBoundCustom::Included);
+    /// assert!(cursor.insert_before_by(2, "b", Ord::cmp).is_ok());
+    /// assert!(cursor.insert_before_by(1, "A", Ord::cmp).is_err());
+    /// assert!(cursor.insert_before_by(3, "c", Ord::cmp).is_ok());
+    /// assert_eq!(cursor.key(), Some(&4));
+    /// assert!(map.keys().copied().eq(1..=4));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn insert_before_by<C>(
+        &mut self,
+        key: K,
+        value: V,
+        mut double_comp: C,
+    ) -> Result<(), UnorderedKeyError<K, V>>
+    where
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let before_current = self.key().is_none_or(|current| double_comp(&key, current).is_lt());
+        let after_prev = before_current
+            && self.peek_prev().is_none_or(|(prev, _)| double_comp(prev, &key).is_lt());
+        if !after_prev {
+            return Err(UnorderedKeyError { key, value });
+        }
+        unsafe { self.insert_before_unchecked(key, value) };
+        Ok(())
+    }
+
+    /// Inserts a new element into the `BTreeMap` where it belongs according to
+    /// `double_comp`, unless the key is already present.
+    ///
+    /// The position is searched from the root, so it does not matter where the
+    /// cursor points to beforehand. On success, the cursor is moved to the new
+    /// element and a mutable reference to its value is returned. Otherwise
+    /// nothing is inserted, the cursor is moved to the element that already has
+    /// the key, and `value` is handed back.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// map.insert(1, "a", Ord::cmp);
+    /// map.insert(3, "c", Ord::cmp);
+    ///
+    /// let mut cursor = map.lower_bound_mut(|k| 1.cmp(k), SearchBoundCustom::Included);
+    /// assert_eq!(cursor.try_insert(2, "b", Ord::cmp), Ok(&mut "b"));
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// assert_eq!(cursor.try_insert(3, "C", Ord::cmp), Err("C"));
+    /// assert_eq!(cursor.key_value(), Some((&3, &"c")));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn try_insert<C>(&mut self, key: K, value: V, mut double_comp: C) -> Result<&mut V, V>
+    where
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        self.current = None;
+        // SAFETY: We have no other reference to the tree.
+        let edge = match unsafe { self.root.reborrow() } {
+            root @ None => {
+                // Tree is empty, allocate a new root.
+                let mut node = NodeRef::new_leaf(self.alloc.clone());
+                node.borrow_mut().push(key, value);
+                *root = Some(node.forget_type());
+                *self.length += 1;
+                self.move_next();
+                return Ok(self.value_mut().unwrap());
+            }
+            Some(root) => match root.borrow_mut().search_tree::<N, _>(|k: &K| double_comp(&key, k))
+            {
+                Found(handle) => {
+                    self.current = Some(handle);
+                    return Err(value);
+                }
+                GoDown(edge) => edge,
+            },
+        };
+
+        let handle = edge.insert_recursing(key, value, self.alloc.clone(), |ins| {
+            drop(ins.left);
+            // SAFETY: The handle to the newly inserted value is always on a
+            // leaf node, so adding a new root node doesn't invalidate it.
+            let root = unsafe { self.root.reborrow().as_mut().unwrap() };
+            root.push_internal_level(self.alloc.clone()).push(ins.kv.0, ins.kv.1, ins.right)
+        });
+        self.current = Some(handle.forget_node_type());
+        *self.length += 1;
+        self.paranoid_check();
+        Ok(self.value_mut().unwrap())
+    }
+
     /// Removes the current element from the `BTreeMap`.
     ///
     /// The element that was removed is returned, and the cursor is
@@ -3112,6 +5355,7 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
             let root = unsafe { self.root.reborrow().as_mut().unwrap() };
             root.pop_internal_level(self.alloc.clone());
         }
+        self.paranoid_check();
         Some(kv)
     }
 
@@ -3136,8 +5380,318 @@ impl<'a, K, V, A: Allocator + Clone> CursorMut<'a, K, V, A> {
             let root = unsafe { self.root.reborrow().as_mut().unwrap() };
             root.pop_internal_level(self.alloc.clone());
         }
+        self.paranoid_check();
         Some(kv)
     }
+
+    /// Splits the `BTreeMap` into two after the current element. Returns a new
+    /// `BTreeMap` holding everything after the cursor, while the original one
+    /// keeps everything up to and including the current element.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire
+    /// contents of the `BTreeMap` are moved.
+    ///
+    /// The tree is cut along the path down to the cursor, so unlike
+    /// [`BTreeMap::split_off`] this neither compares any keys nor searches the
+    /// tree again. The cut takes O(log n) time, plus O(min(k, n - k)) to count
+    /// the elements of the smaller part when the `order_statistics` feature is
+    /// disabled, where k is the number of elements up to the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    /// use std::cmp::Ordering;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// for (key, value) in [(1, 10), (2, 25), (3, 45), (4, 30)] {
+    ///     map.insert(key, value, Ord::cmp);
+    /// }
+    ///
+    /// // Splits after the first value above 40.
+    /// let mut cursor = map.lower_bound_mut(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
+    /// while cursor.value().is_some_and(|&v| v <= 40) {
+    ///     cursor.move_next();
+    /// }
+    /// let tail = cursor.split_after();
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// assert!(map.keys().copied().eq(1..=3));
+    /// assert!(tail.keys().copied().eq(4..=4));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn split_after(&mut self) -> BTreeMap<K, V, A, B, N> {
+        let split_path = match self.current.take() {
+            Some(current) => SplitPath::after(current.reborrow()),
+            None => return self.split_all(),
+        };
+        let right = self.split_along(split_path);
+        // SAFETY: The split handed back the root by giving up its borrow.
+        let root = unsafe { self.root.reborrow() }.as_mut().unwrap();
+        self.current = root.borrow_mut().last_leaf_edge().next_back_kv().ok();
+        right
+    }
+
+    /// Splits the `BTreeMap` into two before the current element. Returns a
+    /// new `BTreeMap` holding everything before the cursor, while the original
+    /// one keeps the current element and everything after it.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire
+    /// contents of the `BTreeMap` are moved.
+    ///
+    /// The tree is cut along the path down to the cursor, so unlike
+    /// [`BTreeMap::split_off`] this neither compares any keys nor searches the
+    /// tree again. The cut takes O(log n) time, plus O(min(k, n - k)) to count
+    /// the elements of the smaller part when the `order_statistics` feature is
+    /// disabled, where k is the number of elements up to the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    /// use std::cmp::Ordering;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// for (key, value) in [(1, 10), (2, 25), (3, 45), (4, 30)] {
+    ///     map.insert(key, value, Ord::cmp);
+    /// }
+    ///
+    /// // Splits before the first value above 20.
+    /// let mut cursor = map.lower_bound_mut(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
+    /// while cursor.value().is_some_and(|&v| v <= 20) {
+    ///     cursor.move_next();
+    /// }
+    /// let head = cursor.split_before();
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// assert!(head.keys().copied().eq(1..=1));
+    /// assert!(map.keys().copied().eq(2..=4));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn split_before(&mut self) -> BTreeMap<K, V, A, B, N> {
+        let split_path = match self.current.take() {
+            Some(current) => SplitPath::before(current.reborrow()),
+            None => return self.split_all(),
+        };
+        let mut left = self.split_along(split_path);
+        // SAFETY: The split handed back the root by giving up its borrow.
+        let root = unsafe { self.root.reborrow() };
+        mem::swap(root, &mut left.root);
+        mem::swap(self.length, &mut left.length);
+        self.current = root.as_mut().unwrap().borrow_mut().first_leaf_edge().next_kv().ok();
+        left
+    }
+
+    // Cuts the tree along `split_path`, keeping the left part and returning
+    // the right part. The cursor must not be pointing at any element.
+    #[cfg(feature = "btree_cursors")]
+    fn split_along(&mut self, split_path: SplitPath) -> BTreeMap<K, V, A, B, N> {
+        // SAFETY: The cursor holds no other reference to the tree.
+        let left_root = unsafe { self.root.reborrow() }.as_mut().unwrap();
+        let right_root = left_root.split_off_along(split_path, self.alloc.clone());
+        let (left_len, right_len) = Root::calc_split_length(*self.length, left_root, &right_root);
+        *self.length = left_len;
+        self.paranoid_check();
+
+        let right = BTreeMap {
+            root: Some(right_root),
+            length: right_len,
+            alloc: ManuallyDrop::new(self.alloc.clone()),
+            _marker: PhantomData,
+        };
+        right.paranoid_check();
+        right
+    }
+
+    // Moves the entire tree into a new map, for splits at the ghost.
+    #[cfg(feature = "btree_cursors")]
+    fn split_all(&mut self) -> BTreeMap<K, V, A, B, N> {
+        BTreeMap {
+            // SAFETY: The cursor is pointing at the ghost, so it holds no
+            // other reference to the tree.
+            root: unsafe { self.root.reborrow() }.take(),
+            length: mem::take(self.length),
+            alloc: ManuallyDrop::new(self.alloc.clone()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Moves the elements of `other` in after the current element, keeping the
+    /// cursor where it is, if their keys belong there according to
+    /// `double_comp`.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the elements
+    /// are inserted at the front of the `BTreeMap`.
+    ///
+    /// The tree is cut at the cursor and joined back together around `other`,
+    /// which takes O(log n + log m) time where m is the length of `other`,
+    /// plus O(m) to move its elements into new nodes unless both maps use the
+    /// [`Global`] allocator.
+    ///
+    /// # Errors
+    ///
+    /// Hands `other` back in an [`UnorderedMapError`], leaving the map and the
+    /// cursor untouched, if the keys of `other` do not all compare greater than
+    /// the current element and less than the next element, where there are
+    /// any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom;
+    ///
+    /// let mut map = BTreeMap::default();
+    /// let mut other = BTreeMap::default();
+    /// for key in [1, 5] {
+    ///     map.insert(key, (), Ord::cmp);
+    /// }
+    /// for key in [2, 3, 4] {
+    ///     other.insert(key, (), Ord::cmp);
+    /// }
+    ///
+    /// let mut cursor = map.lower_bound_mut(|k| 5.cmp(k), SearchBoundCustom::Included);
+    /// let other = cursor.splice_after(other, Ord::cmp).unwrap_err().map;
+    /// cursor.move_prev();
+    /// assert!(cursor.splice_after(other, Ord::cmp).is_ok());
+    /// assert_eq!(cursor.key(), Some(&1));
+    /// assert!(map.keys().copied().eq(1..=5));
+    /// ```
+    #[cfg(feature = "btree_cursors")]
+    pub fn splice_after<C>(
+        &mut self,
+        mut other: BTreeMap<K, V, A, B, N>,
+        mut double_comp: C,
+    ) -> Result<(), UnorderedMapError<K, V, A, B, N>>
+    where
+        A: 'static,
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let fits = match (other.first_key_value(), other.last_key_value()) {
+            (Some((first, _)), Some((last, _))) => {
+                self.key().is_none_or(|current| double_comp(current, first).is_lt())
+                    && self.peek_next().is_none_or(|(next, _)| double_comp(last, next).is_lt())
+            }
+            _ => return Ok(()),
+        };
+        if !fits {
+            return Err(UnorderedMapError { map: other });
+        }
+
+        // The first element is kept out of the joins and inserted last, which
+        // finds the cursor's element back right before it.
+        let (key, value) = other.pop_first().unwrap();
+        let split_path = self.current.take().map(|current| SplitPath::after(current.reborrow()));
+        self.join_around(split_path, other, true);
+        // SAFETY: The joins have ended, and the cursor holds no other
+        // reference to the tree.
+        self.current = unsafe { self.root.reborrow() }.as_mut().and_then(|root| {
+            match root.borrow_mut().search_tree::<N, _>(|k: &K| double_comp(&key, k)) {
+                GoDown(edge) => edge.next_back_kv().ok(),
+                Found(kv) => Some(kv),
+            }
+        });
+        unsafe { self.insert_after_unchecked(key, value) };
+        Ok(())
+    }
+
+    /// Moves the elements of `other` in before the current element, keeping
+    /// the cursor where it is, if their keys belong there according to
+    /// `double_comp`.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the elements
+    /// are inserted at the end of the `BTreeMap`.
+    ///
+    /// Like [`CursorMut::splice_after`], this takes O(log n + log m) time,
+    /// plus O(m) unless both maps use the [`Global`] allocator.
+    ///
+    /// # Errors
+    ///
+    /// Hands `other` back in an [`UnorderedMapError`], leaving the map and the
+    /// cursor untouched, if the keys of `other` do not all compare less than
+    /// the current element and greater than the previous element, where there
+    /// are any.
+    #[cfg(feature = "btree_cursors")]
+    pub fn splice_before<C>(
+        &mut self,
+        mut other: BTreeMap<K, V, A, B, N>,
+        mut double_comp: C,
+    ) -> Result<(), UnorderedMapError<K, V, A, B, N>>
+    where
+        A: 'static,
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let fits = match (other.first_key_value(), other.last_key_value()) {
+            (Some((first, _)), Some((last, _))) => {
+                self.key().is_none_or(|current| double_comp(last, current).is_lt())
+                    && self.peek_prev().is_none_or(|(prev, _)| double_comp(prev, first).is_lt())
+            }
+            _ => return Ok(()),
+        };
+        if !fits {
+            return Err(UnorderedMapError { map: other });
+        }
+
+        // The last element is kept out of the joins and inserted last, which
+        // finds the cursor's element back right after it.
+        let (key, value) = other.pop_last().unwrap();
+        let split_path = self.current.take().map(|current| SplitPath::before(current.reborrow()));
+        self.join_around(split_path, other, false);
+        // SAFETY: The joins have ended, and the cursor holds no other
+        // reference to the tree.
+        self.current = unsafe { self.root.reborrow() }.as_mut().and_then(|root| {
+            match root.borrow_mut().search_tree::<N, _>(|k: &K| double_comp(&key, k)) {
+                GoDown(edge) => edge.next_kv().ok(),
+                Found(kv) => Some(kv),
+            }
+        });
+        unsafe { self.insert_before_unchecked(key, value) };
+        Ok(())
+    }
+
+    // Cuts the tree along `split_path` and joins it back together with `other`
+    // in the cut, or in front or at the back of the tree without a path. The
+    // cursor must not be pointing at any element.
+    #[cfg(feature = "btree_cursors")]
+    fn join_around(
+        &mut self,
+        split_path: Option<SplitPath>,
+        mut other: BTreeMap<K, V, A, B, N>,
+        front_without_path: bool,
+    ) where
+        A: 'static,
+    {
+        if !BTreeMap::<K, V, A, B, N>::allocator_is_global() && !other.is_empty() {
+            other = BTreeMap::bulk_build_from_strictly_sorted_iter(
+                other.into_iter(),
+                self.alloc.clone(),
+            );
+        }
+        let Some(mut middle) = other.root.take() else {
+            return;
+        };
+        *self.length += mem::take(&mut other.length);
+        // SAFETY: The cursor holds no other reference to the tree.
+        let root = match unsafe { self.root.reborrow() } {
+            Some(root) => root,
+            root @ None => {
+                *root = Some(middle);
+                return;
+            }
+        };
+        match split_path {
+            Some(split_path) => {
+                let right = root.split_off_along(split_path, self.alloc.clone());
+                root.append_tree(middle, self.alloc.clone());
+                root.append_tree(right, self.alloc.clone());
+            }
+            None if front_without_path => {
+                mem::swap(root, &mut middle);
+                root.append_tree(middle, self.alloc.clone());
+            }
+            None => root.append_tree(middle, self.alloc.clone()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -8,7 +8,7 @@ use crate::{
 
 use super::super::borrow::DormantMutRef;
 use super::super::node::{marker, Handle, NodeRef};
-use super::BTreeMap;
+use super::{BTreeMap, Linear, NodeSearch};
 
 use Entry::*;
 
@@ -17,15 +17,17 @@ use Entry::*;
 /// This `enum` is constructed from the [`entry`] method on [`BTreeMap`].
 ///
 /// [`entry`]: BTreeMap::entry
-pub enum Entry<'a, K: 'a, V: 'a, A: Allocator + Clone = Global> {
+pub enum Entry<'a, K: 'a, V: 'a, A: Allocator + Clone = Global, const B: usize = 6, N = Linear> {
     /// A vacant entry.
-    Vacant(VacantEntry<'a, K, V, A>),
+    Vacant(VacantEntry<'a, K, V, A, B, N>),
 
     /// An occupied entry.
-    Occupied(OccupiedEntry<'a, K, V, A>),
+    Occupied(OccupiedEntry<'a, K, V, A, B, N>),
 }
 
-impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for Entry<'_, K, V, A> {
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Debug
+    for Entry<'_, K, V, A, B, N>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
@@ -36,16 +38,12 @@ impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for Entry<'_, K, V, A> {
 
 /// A view into a vacant entry in a `BTreeMap`.
 /// It is part of the [`Entry`] enum.
-pub struct VacantEntry<
-    'a,
-    K,
-    V,
-    A: Allocator + Clone = Global,
-> {
+pub struct VacantEntry<'a, K, V, A: Allocator + Clone = Global, const B: usize = 6, N = Linear> {
     pub(super) key: K,
     /// `None` for a (empty) map without root
-    pub(super) handle: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>>,
-    pub(super) dormant_map: DormantMutRef<'a, BTreeMap<K, V, A>>,
+    pub(super) handle:
+        Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, (), B>, marker::Edge>>,
+    pub(super) dormant_map: DormantMutRef<'a, BTreeMap<K, V, A, B, N>>,
 
     /// The BTreeMap will outlive this IntoIter so we don't care about drop order for `alloc`.
     pub(super) alloc: A,
@@ -54,7 +52,9 @@ pub struct VacantEntry<
     pub(super) _marker: PhantomData<&'a mut (K, V)>,
 }
 
-impl<K: Debug, V, A: Allocator + Clone> Debug for VacantEntry<'_, K, V, A> {
+impl<K: Debug, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Debug
+    for VacantEntry<'_, K, V, A, B, N>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("VacantEntry").field(self.key()).finish()
     }
@@ -62,14 +62,10 @@ impl<K: Debug, V, A: Allocator + Clone> Debug for VacantEntry<'_, K, V, A> {
 
 /// A view into an occupied entry in a `BTreeMap`.
 /// It is part of the [`Entry`] enum.
-pub struct OccupiedEntry<
-    'a,
-    K,
-    V,
-    A: Allocator + Clone = Global,
-> {
-    pub(super) handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>,
-    pub(super) dormant_map: DormantMutRef<'a, BTreeMap<K, V, A>>,
+pub struct OccupiedEntry<'a, K, V, A: Allocator + Clone = Global, const B: usize = 6, N = Linear> {
+    pub(super) handle:
+        Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, (), B>, marker::KV>,
+    pub(super) dormant_map: DormantMutRef<'a, BTreeMap<K, V, A, B, N>>,
 
     /// The BTreeMap will outlive this IntoIter so we don't care about drop order for `alloc`.
     pub(super) alloc: A,
@@ -78,7 +74,9 @@ pub struct OccupiedEntry<
     pub(super) _marker: PhantomData<&'a mut (K, V)>,
 }
 
-impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for OccupiedEntry<'_, K, V, A> {
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Debug
+    for OccupiedEntry<'_, K, V, A, B, N>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("OccupiedEntry").field("key", self.key()).field("value", self.get()).finish()
     }
@@ -92,17 +90,20 @@ pub struct OccupiedError<
     'a,
     K: 'a,
     V: 'a,
-    O = OrdTotalOrder<<K as OrdStoredKey>::OrdKeyType>,
     A: Allocator + Clone = Global,
+    const B: usize = 6,
+    N = Linear,
 > {
     /// The entry in the map that was already occupied.
-    pub entry: OccupiedEntry<'a, K, V, A>,
+    pub entry: OccupiedEntry<'a, K, V, A, B, N>,
     /// The value which was not inserted, because the entry was already occupied.
     pub value: V,
 }
 
 #[cfg(feature = "map_try_insert")]
-impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for OccupiedError<'_, K, V, A> {
+impl<K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Debug
+    for OccupiedError<'_, K, V, A, B, N>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("OccupiedError")
             .field("key", self.entry.key())
@@ -113,8 +114,8 @@ impl<K: Debug, V: Debug, A: Allocator + Clone> Debug for OccupiedError<'_, K, V,
 }
 
 #[cfg(feature = "map_try_insert")]
-impl<'a, K: Debug, V: Debug, A: Allocator + Clone> fmt::Display
-    for OccupiedError<'a, K, V, A>
+impl<'a, K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> fmt::Display
+    for OccupiedError<'a, K, V, A, B, N>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -129,8 +130,8 @@ impl<'a, K: Debug, V: Debug, A: Allocator + Clone> fmt::Display
 
 #[cfg(feature = "map_try_insert")]
 #[cfg(feature = "error_in_core")]
-impl<'a, K: core::fmt::Debug, V: core::fmt::Debug, O> core::error::Error
-    for OccupiedError<'a, K, V, O>
+impl<'a, K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>>
+    core::error::Error for OccupiedError<'a, K, V, A, B, N>
 {
     #[allow(deprecated)]
     fn description(&self) -> &str {
@@ -138,19 +139,30 @@ impl<'a, K: core::fmt::Debug, V: core::fmt::Debug, O> core::error::Error
     }
 }
 
-impl<'a, K, V, A: Allocator + Clone> Entry<'a, K, V, A> {
+#[cfg(feature = "map_try_insert")]
+#[cfg(all(feature = "std", not(feature = "error_in_core")))]
+impl<'a, K: Debug, V: Debug, A: Allocator + Clone, const B: usize, N: NodeSearch<K>>
+    std::error::Error for OccupiedError<'a, K, V, A, B, N>
+{
+    #[allow(deprecated)]
+    fn description(&self) -> &str {
+        "key already exists"
+    }
+}
+
+impl<'a, K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>> Entry<'a, K, V, A, B, N> {
     /// Ensures a value is in the entry by inserting the default if empty, and returns
     /// a mutable reference to the value in the entry.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
     ///
-    /// assert_eq!(map["poneyland"], 12);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&12));
     /// ```
     pub fn or_insert(self, default: V) -> &'a mut V {
         match self {
@@ -165,14 +177,14 @@ impl<'a, K, V, A: Allocator + Clone> Entry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, String> = BTreeMap::default();
     /// let s = "hoho".to_string();
     ///
-    /// map.entry("poneyland").or_insert_with(|| s);
+    /// map.entry("poneyland", Ord::cmp).or_insert_with(|| s);
     ///
-    /// assert_eq!(map["poneyland"], "hoho".to_string());
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&"hoho".to_string()));
     /// ```
     pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
         match self {
@@ -191,13 +203,13 @@ impl<'a, K, V, A: Allocator + Clone> Entry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
     ///
-    /// map.entry("poneyland").or_insert_with_key(|key| key.chars().count());
+    /// map.entry("poneyland", Ord::cmp).or_insert_with_key(|key| key.chars().count());
     ///
-    /// assert_eq!(map["poneyland"], 9);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&9));
     /// ```
     #[inline]
     pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
@@ -215,10 +227,10 @@ impl<'a, K, V, A: Allocator + Clone> Entry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// assert_eq!(map.entry("poneyland").key(), &"poneyland");
+    /// assert_eq!(map.entry("poneyland", Ord::cmp).key(), &"poneyland");
     /// ```
     pub fn key(&self) -> &K {
         match *self {
@@ -233,19 +245,19 @@ impl<'a, K, V, A: Allocator + Clone> Entry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
     ///
-    /// map.entry("poneyland")
+    /// map.entry("poneyland", Ord::cmp)
     ///    .and_modify(|e| { *e += 1 })
     ///    .or_insert(42);
-    /// assert_eq!(map["poneyland"], 42);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&42));
     ///
-    /// map.entry("poneyland")
+    /// map.entry("poneyland", Ord::cmp)
     ///    .and_modify(|e| { *e += 1 })
     ///    .or_insert(42);
-    /// assert_eq!(map["poneyland"], 43);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&43));
     /// ```
     pub fn and_modify<F>(self, f: F) -> Self
     where
@@ -261,19 +273,21 @@ impl<'a, K, V, A: Allocator + Clone> Entry<'a, K, V, A> {
     }
 }
 
-impl<'a, K, V: Default, A: Allocator + Clone> Entry<'a, K, V, A> {
+impl<'a, K, V: Default, A: Allocator + Clone, const B: usize, N: NodeSearch<K>>
+    Entry<'a, K, V, A, B, N>
+{
     /// Ensures a value is in the entry by inserting the default value if empty,
     /// and returns a mutable reference to the value in the entry.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, Option<usize>> = BTreeMap::default();
-    /// map.entry("poneyland").or_default();
+    /// map.entry("poneyland", Ord::cmp).or_default();
     ///
-    /// assert_eq!(map["poneyland"], None);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&None));
     /// ```
     pub fn or_default(self) -> &'a mut V {
         match self {
@@ -283,17 +297,19 @@ impl<'a, K, V: Default, A: Allocator + Clone> Entry<'a, K, V, A> {
     }
 }
 
-impl<'a, K, V, A: Allocator + Clone> VacantEntry<'a, K, V, A> {
+impl<'a, K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>>
+    VacantEntry<'a, K, V, A, B, N>
+{
     /// Gets a reference to the key that would be used when inserting a value
     /// through the VacantEntry.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// assert_eq!(map.entry("poneyland").key(), &"poneyland");
+    /// assert_eq!(map.entry("poneyland", Ord::cmp).key(), &"poneyland");
     /// ```
     pub fn key(&self) -> &K {
         &self.key
@@ -304,12 +320,12 @@ impl<'a, K, V, A: Allocator + Clone> VacantEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
     ///
-    /// if let Entry::Vacant(v) = map.entry("poneyland") {
+    /// if let Entry::Vacant(v) = map.entry("poneyland", Ord::cmp) {
     ///     v.into_key();
     /// }
     /// ```
@@ -323,15 +339,15 @@ impl<'a, K, V, A: Allocator + Clone> VacantEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, u32> = BTreeMap::default();
     ///
-    /// if let Entry::Vacant(o) = map.entry("poneyland") {
+    /// if let Entry::Vacant(o) = map.entry("poneyland", Ord::cmp) {
     ///     o.insert(37);
     /// }
-    /// assert_eq!(map["poneyland"], 37);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&37));
     /// ```
     pub fn insert(mut self, value: V) -> &'a mut V {
         let out_ptr = match self.handle {
@@ -342,6 +358,7 @@ impl<'a, K, V, A: Allocator + Clone> VacantEntry<'a, K, V, A> {
                 let val_ptr = root.borrow_mut().push(self.key, value) as *mut V;
                 map.root = Some(root.forget_type());
                 map.length = 1;
+                map.paranoid_check();
                 val_ptr
             }
             Some(handle) => {
@@ -361,6 +378,7 @@ impl<'a, K, V, A: Allocator + Clone> VacantEntry<'a, K, V, A> {
                 // SAFETY: We have consumed self.handle.
                 let map = unsafe { self.dormant_map.awaken() };
                 map.length += 1;
+                map.paranoid_check();
                 val_ptr
             }
         };
@@ -371,17 +389,19 @@ impl<'a, K, V, A: Allocator + Clone> VacantEntry<'a, K, V, A> {
     }
 }
 
-impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
+impl<'a, K, V, A: Allocator + Clone, const B: usize, N: NodeSearch<K>>
+    OccupiedEntry<'a, K, V, A, B, N>
+{
     /// Gets a reference to the key in the entry.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
+    /// use btree_monstrousity::BTreeMap;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
-    /// assert_eq!(map.entry("poneyland").key(), &"poneyland");
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
+    /// assert_eq!(map.entry("poneyland", Ord::cmp).key(), &"poneyland");
     /// ```
     #[must_use]
     pub fn key(&self) -> &K {
@@ -393,19 +413,19 @@ impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
     ///
-    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    /// if let Entry::Occupied(o) = map.entry("poneyland", Ord::cmp) {
     ///     // We delete the entry from the map.
     ///     o.remove_entry();
     /// }
     ///
     /// // If now try to get the value, it will panic:
-    /// // println!("{}", map["poneyland"]);
+    /// // println!("{}", map.get(|k| "poneyland".cmp(k)).unwrap());
     /// ```
     pub fn remove_entry(self) -> (K, V) {
         self.remove_kv()
@@ -416,13 +436,13 @@ impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
     ///
-    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    /// if let Entry::Occupied(o) = map.entry("poneyland", Ord::cmp) {
     ///     assert_eq!(o.get(), &12);
     /// }
     /// ```
@@ -441,21 +461,21 @@ impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
     ///
-    /// assert_eq!(map["poneyland"], 12);
-    /// if let Entry::Occupied(mut o) = map.entry("poneyland") {
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&12));
+    /// if let Entry::Occupied(mut o) = map.entry("poneyland", Ord::cmp) {
     ///     *o.get_mut() += 10;
     ///     assert_eq!(*o.get(), 22);
     ///
     ///     // We can use the same Entry multiple times.
     ///     *o.get_mut() += 2;
     /// }
-    /// assert_eq!(map["poneyland"], 24);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&24));
     /// ```
     pub fn get_mut(&mut self) -> &mut V {
         self.handle.kv_mut().1
@@ -470,17 +490,17 @@ impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
     ///
-    /// assert_eq!(map["poneyland"], 12);
-    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&12));
+    /// if let Entry::Occupied(o) = map.entry("poneyland", Ord::cmp) {
     ///     *o.into_mut() += 10;
     /// }
-    /// assert_eq!(map["poneyland"], 22);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&22));
     /// ```
     #[must_use = "`self` will be dropped if the result is not used"]
     pub fn into_mut(self) -> &'a mut V {
@@ -493,16 +513,16 @@ impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
     ///
-    /// if let Entry::Occupied(mut o) = map.entry("poneyland") {
+    /// if let Entry::Occupied(mut o) = map.entry("poneyland", Ord::cmp) {
     ///     assert_eq!(o.insert(15), 12);
     /// }
-    /// assert_eq!(map["poneyland"], 15);
+    /// assert_eq!(map.get(|k| "poneyland".cmp(k)), Some(&15));
     /// ```
     pub fn insert(&mut self, value: V) -> V {
         mem::replace(self.get_mut(), value)
@@ -513,17 +533,17 @@ impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeMap;
-    /// use copse::btree_map::Entry;
+    /// use btree_monstrousity::BTreeMap;
+    /// use btree_monstrousity::btree_map::Entry;
     ///
     /// let mut map: BTreeMap<&str, usize> = BTreeMap::default();
-    /// map.entry("poneyland").or_insert(12);
+    /// map.entry("poneyland", Ord::cmp).or_insert(12);
     ///
-    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    /// if let Entry::Occupied(o) = map.entry("poneyland", Ord::cmp) {
     ///     assert_eq!(o.remove(), 12);
     /// }
     /// // If we try to get "poneyland"'s value, it'll panic:
-    /// // println!("{}", map["poneyland"]);
+    /// // println!("{}", map.get(|k| "poneyland".cmp(k)).unwrap());
     /// ```
     pub fn remove(self) -> V {
         self.remove_kv().1
@@ -541,6 +561,7 @@ impl<'a, K, V, A: Allocator + Clone> OccupiedEntry<'a, K, V, A> {
             let root = map.root.as_mut().unwrap();
             root.pop_internal_level(self.alloc);
         }
+        map.paranoid_check();
         old_kv
     }
 }
@@ -8,6 +8,7 @@ use alloc::fmt::Debug;
 use alloc::rc::Rc;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::borrow::Borrow;
 use std::cmp::Ordering;
 use std::convert::TryFrom;
 use std::iter::{self, FromIterator};
@@ -17,10 +18,13 @@ use std::ops::RangeBounds;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 
+// The node sizes of maps with the default branching factor, used by most tests.
+type Consts = node::Consts<6>;
+
 // Minimum number of elements to insert, to guarantee a tree with 2 levels,
 // i.e., a tree who's root is an internal node at height 1, with edges to leaf nodes.
 // It's not the minimum size: removing an element from such a tree does not always reduce height.
-const MIN_INSERTS_HEIGHT_1: usize = node::CAPACITY + 1;
+const MIN_INSERTS_HEIGHT_1: usize = Consts::CAPACITY + 1;
 
 // Minimum number of elements to insert in ascending order, to guarantee a tree with 3 levels,
 // i.e., a tree who's root is an internal node at height 2, with edges to more internal nodes.
@@ -41,7 +45,7 @@ fn test_all_refs<'a, T: 'a>(dummy: &mut T, iter: impl Iterator<Item = &'a mut T>
     }
 }
 
-impl<K: OrdStoredKey, V> BTreeMap<K, V> {
+impl<K, V, const B: usize, N: NodeSearch<K>> BTreeMap<K, V, Global, B, N> {
     // Panics if the map (or the code navigating it) is corrupted.
     fn check_invariants(&self) {
         if let Some(root) = &self.root {
@@ -57,6 +61,10 @@ impl<K: OrdStoredKey, V> BTreeMap<K, V> {
 
             // Lastly, check the invariant causing the least harm.
             root_node.assert_min_len(if root_node.height() > 0 { 1 } else { 0 });
+
+            // Check the subtree counts kept for positional queries.
+            #[cfg(feature = "order_statistics")]
+            assert_eq!(check_structure(Some(root), self.length), Ok(()));
         } else {
             assert_eq!(self.length, 0);
         }
@@ -71,7 +79,7 @@ impl<K: OrdStoredKey, V> BTreeMap<K, V> {
     // guarantee that all keys are unique, just that adjacent keys are unique.
     fn check(&self)
     where
-        K::OrdKeyType: Debug,
+        K: Debug + Ord,
     {
         self.check_invariants();
         self.assert_strictly_ascending();
@@ -96,17 +104,12 @@ impl<K: OrdStoredKey, V> BTreeMap<K, V> {
     // Panics if the keys are not in strictly ascending order.
     fn assert_strictly_ascending(&self)
     where
-        K::OrdKeyType: Debug,
+        K: Debug + Ord,
     {
         let mut keys = self.keys();
         if let Some(mut previous) = keys.next() {
             for next in keys {
-                assert!(
-                    self.order.lt(previous, next),
-                    "{:?} >= {:?}",
-                    previous.sort_key(),
-                    next.sort_key()
-                );
+                assert!(previous < next, "{:?} >= {:?}", previous, next);
                 previous = next;
             }
         }
@@ -116,27 +119,105 @@ impl<K: OrdStoredKey, V> BTreeMap<K, V> {
     // are mostly filled up to their capacity. The same compact tree could have
     // been obtained by inserting keys in a shrewd order.
     fn compact(&mut self) {
-        let iter = mem::take(self).into_iter();
+        let iter = mem::replace(self, BTreeMap::with_branching_factor()).into_iter();
         if !iter.is_empty() {
             self.root.insert(Root::new(*self.alloc)).bulk_push(iter, &mut self.length, *self.alloc);
         }
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal> {
+// Most tests below were written against the `Ord` based interface of the
+// standard library's `BTreeMap`. These stand-ins provide the parts of that
+// interface the map itself does not have, in terms of its comparator based
+// methods, so that the tests can stay close to their origin.
+impl<K: Ord, V> BTreeMap<K, V> {
+    // Inserts the pairs in the order given; later duplicates win.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = BTreeMap::new();
+        for (k, v) in iter {
+            map.insert(k, v, Ord::cmp);
+        }
+        map
+    }
+
+    fn from<const N: usize>(arr: [(K, V); N]) -> Self {
+        Self::from_iter(arr)
+    }
+
+    fn range_ord<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, lower_bound, end, upper_bound) = ord_bounds(&range);
+        self.range(
+            |k| start.map_or(Ordering::Equal, |s| s.cmp(k.borrow())),
+            lower_bound,
+            |k| end.map_or(Ordering::Equal, |e| e.cmp(k.borrow())),
+            upper_bound,
+        )
+    }
+
+    fn range_mut_ord<Q, R>(&mut self, range: R) -> RangeMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, lower_bound, end, upper_bound) = ord_bounds(&range);
+        self.range_mut(
+            |k| start.map_or(Ordering::Equal, |s| s.cmp(k.borrow())),
+            lower_bound,
+            |k| end.map_or(Ordering::Equal, |e| e.cmp(k.borrow())),
+            upper_bound,
+        )
+    }
+}
+
+// Splits a range into the keys and `SearchBoundCustom`s that `range` takes,
+// panicking on impossible bounds like the standard library does.
+fn ord_bounds<Q: Ord + ?Sized, R: RangeBounds<Q>>(
+    range: &R,
+) -> (Option<&Q>, SearchBoundCustom, Option<&Q>, SearchBoundCustom) {
+    match (range.start_bound(), range.end_bound()) {
+        (Excluded(s), Excluded(e)) if s == e => {
+            panic!("range start and end are equal and excluded in BTreeMap")
+        }
+        (Included(s) | Excluded(s), Included(e) | Excluded(e)) if s > e => {
+            panic!("range start is greater than range end in BTreeMap")
+        }
+        _ => {}
+    }
+    let (start, lower_bound) = match range.start_bound() {
+        Included(s) => (Some(s), SearchBoundCustom::Included),
+        Excluded(s) => (Some(s), SearchBoundCustom::Excluded),
+        Unbounded => (None, SearchBoundCustom::AllIncluded),
+    };
+    let (end, upper_bound) = match range.end_bound() {
+        Included(e) => (Some(e), SearchBoundCustom::Included),
+        Excluded(e) => (Some(e), SearchBoundCustom::Excluded),
+        Unbounded => (None, SearchBoundCustom::AllIncluded),
+    };
+    (start, lower_bound, end, upper_bound)
+}
+
+impl<'a, K: 'a, V: 'a, const B: usize>
+    NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, (), B>
+{
     fn assert_min_len(self, min_len: usize) {
         assert!(self.len() >= min_len, "node len {} < {}", self.len(), min_len);
         if let node::ForceResult::Internal(node) = self.force() {
             for idx in 0..=node.len() {
                 let edge = unsafe { Handle::new_edge(node, idx) };
-                edge.descend().assert_min_len(MIN_LEN);
+                edge.descend().assert_min_len(node::Consts::<B>::MIN_LEN);
             }
         }
     }
 }
 
 // Tests our value of MIN_INSERTS_HEIGHT_2. Failure may mean you just need to
-// adapt that value to match a change in node::CAPACITY or the choices made
+// adapt that value to match a change in the default node capacity or the choices made
 // during insertion, otherwise other test cases may fail or be less useful.
 #[test]
 fn test_levels() {
@@ -145,10 +226,10 @@ fn test_levels() {
     assert_eq!(map.height(), None);
     assert_eq!(map.len(), 0);
 
-    map.insert(0, ());
+    map.insert(0, (), Ord::cmp);
     while map.height() == Some(0) {
         let last_key = *map.last_key_value().unwrap().0;
-        map.insert(last_key + 1, ());
+        map.insert(last_key + 1, (), Ord::cmp);
     }
     map.check();
     // Structure:
@@ -160,7 +241,7 @@ fn test_levels() {
 
     while map.height() == Some(1) {
         let last_key = *map.last_key_value().unwrap().0;
-        map.insert(last_key + 1, ());
+        map.insert(last_key + 1, (), Ord::cmp);
     }
     map.check();
     // Structure:
@@ -193,6 +274,66 @@ fn test_check_invariants_ord_chaos() {
     map.check_invariants();
 }
 
+#[test]
+fn test_check_order_by() {
+    let mut map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_2).map(|i| (i, ())));
+    assert!(map.check_order_by(Ord::cmp).is_ok());
+    match map.check_order_by(|a: &usize, b: &usize| (a / 10).cmp(&(b / 10))) {
+        Err(OrderViolation::Unordered { previous, next }) => assert_eq!((*previous, *next), (0, 1)),
+        other => panic!("{:?}", other),
+    }
+
+    let gov = Governor::new();
+    let chaos = BTreeMap::from([(Governed(1, &gov), ()), (Governed(2, &gov), ())]);
+    gov.flip();
+    match chaos.check_order_by(Ord::cmp) {
+        Err(OrderViolation::Unordered { previous, next }) => {
+            assert_eq!((previous.0, next.0), (1, 2))
+        }
+        other => panic!("{:?}", other),
+    }
+    let err = map.check_order_by(|a: &usize, b: &usize| b.cmp(a)).unwrap_err();
+    assert_eq!(err.to_string(), "key 0 is not ordered before the next key 1");
+
+    map.length += 1;
+    let err = map.check_order_by(Ord::cmp).unwrap_err();
+    assert!(matches!(
+        err,
+        OrderViolation::Structure(StructureViolation::LengthMismatch { recorded, counted })
+            if recorded == MIN_INSERTS_HEIGHT_2 + 1 && counted == MIN_INSERTS_HEIGHT_2
+    ));
+    map.length -= 1;
+
+    // Hide some elements of the first leaf, which leaves it underfull.
+    let mut leaf = map.root.as_mut().unwrap().borrow_mut().first_leaf_edge().into_node();
+    let len = mem::replace(leaf.len_mut(), Consts::MIN_LEN as u16 - 1);
+    let hidden = len as usize - (Consts::MIN_LEN - 1);
+    map.length -= hidden;
+    let err = map.check_order_by(Ord::cmp).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("node at height 0 holds only {} elements", Consts::MIN_LEN - 1)
+    );
+    // Put them back, otherwise the map leaks them.
+    let mut leaf = map.root.as_mut().unwrap().borrow_mut().first_leaf_edge().into_node();
+    *leaf.len_mut() = len;
+    map.length += hidden;
+    map.check();
+}
+
+#[test]
+#[cfg(feature = "paranoid")]
+fn test_paranoid() {
+    let mut map = BTreeMap::default();
+    map.insert(0, (), Ord::cmp);
+    map.length = 2;
+    let result = catch_unwind(AssertUnwindSafe(|| map.insert(1, (), Ord::cmp)));
+    // Agree with the nodes again, so that the map can be dropped.
+    map.length = 2;
+    let message = result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(*message, "BTreeMap is corrupted: map records 3 elements but its nodes hold 2");
+}
+
 #[test]
 fn test_basic_large() {
     let mut map = BTreeMap::default();
@@ -202,7 +343,7 @@ fn test_basic_large() {
     assert_eq!(map.len(), 0);
 
     for i in 0..size {
-        assert_eq!(map.insert(i, 10 * i), None);
+        assert_eq!(map.insert(i, 10 * i, Ord::cmp), None);
         assert_eq!(map.len(), i + 1);
     }
 
@@ -212,35 +353,35 @@ fn test_basic_large() {
     assert_eq!(map.last_entry().unwrap().key(), &(size - 1));
 
     for i in 0..size {
-        assert_eq!(map.get(&i).unwrap(), &(i * 10));
+        assert_eq!(map.get(|k| i.cmp(k)).unwrap(), &(i * 10));
     }
 
     for i in size..size * 2 {
-        assert_eq!(map.get(&i), None);
+        assert_eq!(map.get(|k| i.cmp(k)), None);
     }
 
     for i in 0..size {
-        assert_eq!(map.insert(i, 100 * i), Some(10 * i));
+        assert_eq!(map.insert(i, 100 * i, Ord::cmp), Some(10 * i));
         assert_eq!(map.len(), size);
     }
 
     for i in 0..size {
-        assert_eq!(map.get(&i).unwrap(), &(i * 100));
+        assert_eq!(map.get(|k| i.cmp(k)).unwrap(), &(i * 100));
     }
 
     for i in 0..size / 2 {
-        assert_eq!(map.remove(&(i * 2)), Some(i * 200));
+        assert_eq!(map.remove(|k| (i * 2).cmp(k)), Some(i * 200));
         assert_eq!(map.len(), size - i - 1);
     }
 
     for i in 0..size / 2 {
-        assert_eq!(map.get(&(2 * i)), None);
-        assert_eq!(map.get(&(2 * i + 1)).unwrap(), &(i * 200 + 100));
+        assert_eq!(map.get(|k| (2 * i).cmp(k)), None);
+        assert_eq!(map.get(|k| (2 * i + 1).cmp(k)).unwrap(), &(i * 200 + 100));
     }
 
     for i in 0..size / 2 {
-        assert_eq!(map.remove(&(2 * i)), None);
-        assert_eq!(map.remove(&(2 * i + 1)), Some(i * 200 + 100));
+        assert_eq!(map.remove(|k| (2 * i).cmp(k)), None);
+        assert_eq!(map.remove(|k| (2 * i + 1).cmp(k)), Some(i * 200 + 100));
         assert_eq!(map.len(), size / 2 - i - 1);
     }
     map.check();
@@ -250,84 +391,84 @@ fn test_basic_large() {
 fn test_basic_small() {
     let mut map = BTreeMap::default();
     // Empty, root is absent (None):
-    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.remove(|k| 1.cmp(k)), None);
     assert_eq!(map.len(), 0);
-    assert_eq!(map.get(&1), None);
-    assert_eq!(map.get_mut(&1), None);
+    assert_eq!(map.get(|k| 1.cmp(k)), None);
+    assert_eq!(map.get_mut(|k| 1.cmp(k)), None);
     assert_eq!(map.first_key_value(), None);
     assert_eq!(map.last_key_value(), None);
     assert_eq!(map.keys().count(), 0);
     assert_eq!(map.values().count(), 0);
-    assert_eq!(map.range::<i32, _>(..).next(), None);
-    assert_eq!(map.range(..1).next(), None);
-    assert_eq!(map.range(1..).next(), None);
-    assert_eq!(map.range(1..=1).next(), None);
-    assert_eq!(map.range(1..2).next(), None);
+    assert_eq!(map.range_ord::<i32, _>(..).next(), None);
+    assert_eq!(map.range_ord(..1).next(), None);
+    assert_eq!(map.range_ord(1..).next(), None);
+    assert_eq!(map.range_ord(1..=1).next(), None);
+    assert_eq!(map.range_ord(1..2).next(), None);
     assert_eq!(map.height(), None);
-    assert_eq!(map.insert(1, 1), None);
+    assert_eq!(map.insert(1, 1, Ord::cmp), None);
     assert_eq!(map.height(), Some(0));
     map.check();
 
     // 1 key-value pair:
     assert_eq!(map.len(), 1);
-    assert_eq!(map.get(&1), Some(&1));
-    assert_eq!(map.get_mut(&1), Some(&mut 1));
+    assert_eq!(map.get(|k| 1.cmp(k)), Some(&1));
+    assert_eq!(map.get_mut(|k| 1.cmp(k)), Some(&mut 1));
     assert_eq!(map.first_key_value(), Some((&1, &1)));
     assert_eq!(map.last_key_value(), Some((&1, &1)));
     assert_eq!(map.keys().collect::<Vec<_>>(), vec![&1]);
     assert_eq!(map.values().collect::<Vec<_>>(), vec![&1]);
-    assert_eq!(map.insert(1, 2), Some(1));
+    assert_eq!(map.insert(1, 2, Ord::cmp), Some(1));
     assert_eq!(map.len(), 1);
-    assert_eq!(map.get(&1), Some(&2));
-    assert_eq!(map.get_mut(&1), Some(&mut 2));
+    assert_eq!(map.get(|k| 1.cmp(k)), Some(&2));
+    assert_eq!(map.get_mut(|k| 1.cmp(k)), Some(&mut 2));
     assert_eq!(map.first_key_value(), Some((&1, &2)));
     assert_eq!(map.last_key_value(), Some((&1, &2)));
     assert_eq!(map.keys().collect::<Vec<_>>(), vec![&1]);
     assert_eq!(map.values().collect::<Vec<_>>(), vec![&2]);
-    assert_eq!(map.insert(2, 4), None);
+    assert_eq!(map.insert(2, 4, Ord::cmp), None);
     assert_eq!(map.height(), Some(0));
     map.check();
 
     // 2 key-value pairs:
     assert_eq!(map.len(), 2);
-    assert_eq!(map.get(&2), Some(&4));
-    assert_eq!(map.get_mut(&2), Some(&mut 4));
+    assert_eq!(map.get(|k| 2.cmp(k)), Some(&4));
+    assert_eq!(map.get_mut(|k| 2.cmp(k)), Some(&mut 4));
     assert_eq!(map.first_key_value(), Some((&1, &2)));
     assert_eq!(map.last_key_value(), Some((&2, &4)));
     assert_eq!(map.keys().collect::<Vec<_>>(), vec![&1, &2]);
     assert_eq!(map.values().collect::<Vec<_>>(), vec![&2, &4]);
-    assert_eq!(map.remove(&1), Some(2));
+    assert_eq!(map.remove(|k| 1.cmp(k)), Some(2));
     assert_eq!(map.height(), Some(0));
     map.check();
 
     // 1 key-value pair:
     assert_eq!(map.len(), 1);
-    assert_eq!(map.get(&1), None);
-    assert_eq!(map.get_mut(&1), None);
-    assert_eq!(map.get(&2), Some(&4));
-    assert_eq!(map.get_mut(&2), Some(&mut 4));
+    assert_eq!(map.get(|k| 1.cmp(k)), None);
+    assert_eq!(map.get_mut(|k| 1.cmp(k)), None);
+    assert_eq!(map.get(|k| 2.cmp(k)), Some(&4));
+    assert_eq!(map.get_mut(|k| 2.cmp(k)), Some(&mut 4));
     assert_eq!(map.first_key_value(), Some((&2, &4)));
     assert_eq!(map.last_key_value(), Some((&2, &4)));
     assert_eq!(map.keys().collect::<Vec<_>>(), vec![&2]);
     assert_eq!(map.values().collect::<Vec<_>>(), vec![&4]);
-    assert_eq!(map.remove(&2), Some(4));
+    assert_eq!(map.remove(|k| 2.cmp(k)), Some(4));
     assert_eq!(map.height(), Some(0));
     map.check();
 
     // Empty but root is owned (Some(...)):
     assert_eq!(map.len(), 0);
-    assert_eq!(map.get(&1), None);
-    assert_eq!(map.get_mut(&1), None);
+    assert_eq!(map.get(|k| 1.cmp(k)), None);
+    assert_eq!(map.get_mut(|k| 1.cmp(k)), None);
     assert_eq!(map.first_key_value(), None);
     assert_eq!(map.last_key_value(), None);
     assert_eq!(map.keys().count(), 0);
     assert_eq!(map.values().count(), 0);
-    assert_eq!(map.range::<i32, _>(..).next(), None);
-    assert_eq!(map.range(..1).next(), None);
-    assert_eq!(map.range(1..).next(), None);
-    assert_eq!(map.range(1..=1).next(), None);
-    assert_eq!(map.range(1..2).next(), None);
-    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.range_ord::<i32, _>(..).next(), None);
+    assert_eq!(map.range_ord(..1).next(), None);
+    assert_eq!(map.range_ord(1..).next(), None);
+    assert_eq!(map.range_ord(1..=1).next(), None);
+    assert_eq!(map.range_ord(1..2).next(), None);
+    assert_eq!(map.remove(|k| 1.cmp(k)), None);
     assert_eq!(map.height(), Some(0));
     map.check();
 }
@@ -379,8 +520,7 @@ fn test_iter_rev() {
 // Specifically tests iter_mut's ability to mutate the value of pairs in-line.
 fn do_test_iter_mut_mutation<T>(size: usize)
 where
-    T: Copy + OrdStoredKey + TryFrom<usize>,
-    T::OrdKeyType: Debug,
+    T: Copy + Debug + Ord + TryFrom<usize>,
     <T as TryFrom<usize>>::Error: Debug,
 {
     let zero = T::try_from(0).unwrap();
@@ -392,22 +532,22 @@ where
 
     // Iterate forwards, trying to mutate to unique values
     for (i, (k, v)) in map.iter_mut().enumerate() {
-        assert_eq!(k.sort_key(), T::try_from(i).unwrap().sort_key());
-        assert_eq!((*v).sort_key(), zero.sort_key());
+        assert_eq!(*k, T::try_from(i).unwrap());
+        assert_eq!(*v, zero);
         *v = T::try_from(i + 1).unwrap();
     }
 
     // Iterate backwards, checking that mutations succeeded and trying to mutate again
     for (i, (k, v)) in map.iter_mut().rev().enumerate() {
-        assert_eq!(k.sort_key(), T::try_from(size - i - 1).unwrap().sort_key());
-        assert_eq!((*v).sort_key(), T::try_from(size - i).unwrap().sort_key());
+        assert_eq!(*k, T::try_from(size - i - 1).unwrap());
+        assert_eq!(*v, T::try_from(size - i).unwrap());
         *v = T::try_from(2 * size - i).unwrap();
     }
 
     // Check that backward mutations succeeded
     for (i, (k, v)) in map.iter_mut().enumerate() {
-        assert_eq!(k.sort_key(), T::try_from(i).unwrap().sort_key());
-        assert_eq!((*v).sort_key(), T::try_from(size + i + 1).unwrap().sort_key());
+        assert_eq!(*k, T::try_from(i).unwrap());
+        assert_eq!(*v, T::try_from(size + i + 1).unwrap());
     }
     map.check();
 }
@@ -416,10 +556,6 @@ where
 #[repr(align(32))]
 struct Align32(usize);
 
-impl OrdStoredKey for Align32 {
-    type OrdKeyType = Self;
-}
-
 impl TryFrom<usize> for Align32 {
     type Error = ();
 
@@ -462,8 +598,8 @@ fn test_values_mut() {
 #[test]
 fn test_values_mut_mutation() {
     let mut a = BTreeMap::default();
-    a.insert(1, String::from("hello"));
-    a.insert(2, String::from("goodbye"));
+    a.insert(1, String::from("hello"), Ord::cmp);
+    a.insert(2, String::from("goodbye"), Ord::cmp);
 
     for value in a.values_mut() {
         value.push_str("!");
@@ -541,26 +677,26 @@ fn test_iter_min_max() {
     assert_eq!(a.iter().max(), None);
     assert_eq!(a.iter_mut().min(), None);
     assert_eq!(a.iter_mut().max(), None);
-    assert_eq!(a.range::<i32, _>(..).min(), None);
-    assert_eq!(a.range::<i32, _>(..).max(), None);
-    assert_eq!(a.range_mut::<i32, _>(..).min(), None);
-    assert_eq!(a.range_mut::<i32, _>(..).max(), None);
+    assert_eq!(a.range_ord::<i32, _>(..).min(), None);
+    assert_eq!(a.range_ord::<i32, _>(..).max(), None);
+    assert_eq!(a.range_mut_ord::<i32, _>(..).min(), None);
+    assert_eq!(a.range_mut_ord::<i32, _>(..).max(), None);
     assert_eq!(a.keys().min(), None);
     assert_eq!(a.keys().max(), None);
     assert_eq!(a.values().min(), None);
     assert_eq!(a.values().max(), None);
     assert_eq!(a.values_mut().min(), None);
     assert_eq!(a.values_mut().max(), None);
-    a.insert(1, 42);
-    a.insert(2, 24);
+    a.insert(1, 42, Ord::cmp);
+    a.insert(2, 24, Ord::cmp);
     assert_eq!(a.iter().min(), Some((&1, &42)));
     assert_eq!(a.iter().max(), Some((&2, &24)));
     assert_eq!(a.iter_mut().min(), Some((&1, &mut 42)));
     assert_eq!(a.iter_mut().max(), Some((&2, &mut 24)));
-    assert_eq!(a.range::<i32, _>(..).min(), Some((&1, &42)));
-    assert_eq!(a.range::<i32, _>(..).max(), Some((&2, &24)));
-    assert_eq!(a.range_mut::<i32, _>(..).min(), Some((&1, &mut 42)));
-    assert_eq!(a.range_mut::<i32, _>(..).max(), Some((&2, &mut 24)));
+    assert_eq!(a.range_ord::<i32, _>(..).min(), Some((&1, &42)));
+    assert_eq!(a.range_ord::<i32, _>(..).max(), Some((&2, &24)));
+    assert_eq!(a.range_mut_ord::<i32, _>(..).min(), Some((&1, &mut 42)));
+    assert_eq!(a.range_mut_ord::<i32, _>(..).max(), Some((&2, &mut 24)));
     assert_eq!(a.keys().min(), Some(&1));
     assert_eq!(a.keys().max(), Some(&2));
     assert_eq!(a.values().min(), Some(&24));
@@ -571,7 +707,7 @@ fn test_iter_min_max() {
 }
 
 fn range_keys(map: &BTreeMap<i32, i32>, range: impl RangeBounds<i32>) -> Vec<i32> {
-    Vec::from_iter(map.range(range).map(|(&k, &v)| {
+    Vec::from_iter(map.range_ord(range).map(|(&k, &v)| {
         assert_eq!(k, v);
         k
     }))
@@ -717,69 +853,69 @@ fn test_range_large() {
         assert_eq!(Vec::from_iter(lhs), Vec::from_iter(rhs));
     }
 
-    check(map.range(..=100), map.range(..101));
-    check(map.range(5..=8), vec![(&5, &5), (&6, &6), (&7, &7), (&8, &8)]);
-    check(map.range(-1..=2), vec![(&1, &1), (&2, &2)]);
+    check(map.range_ord(..=100), map.range_ord(..101));
+    check(map.range_ord(5..=8), vec![(&5, &5), (&6, &6), (&7, &7), (&8, &8)]);
+    check(map.range_ord(-1..=2), vec![(&1, &1), (&2, &2)]);
 }
 
 #[test]
 fn test_range_inclusive_max_value() {
     let max = usize::MAX;
     let map = BTreeMap::from([(max, 0)]);
-    assert_eq!(Vec::from_iter(map.range(max..=max)), &[(&max, &0)]);
+    assert_eq!(Vec::from_iter(map.range_ord(max..=max)), &[(&max, &0)]);
 }
 
 #[test]
 fn test_range_equal_empty_cases() {
     let map = BTreeMap::from_iter((0..5).map(|i| (i, i)));
-    assert_eq!(map.range((Included(2), Excluded(2))).next(), None);
-    assert_eq!(map.range((Excluded(2), Included(2))).next(), None);
+    assert_eq!(map.range_ord((Included(2), Excluded(2))).next(), None);
+    assert_eq!(map.range_ord((Excluded(2), Included(2))).next(), None);
 }
 
 #[test]
 #[should_panic]
 fn test_range_equal_excluded() {
     let map = BTreeMap::from_iter((0..5).map(|i| (i, i)));
-    let _ = map.range((Excluded(2), Excluded(2)));
+    let _ = map.range_ord((Excluded(2), Excluded(2)));
 }
 
 #[test]
 #[should_panic]
 fn test_range_backwards_1() {
     let map = BTreeMap::from_iter((0..5).map(|i| (i, i)));
-    let _ = map.range((Included(3), Included(2)));
+    let _ = map.range_ord((Included(3), Included(2)));
 }
 
 #[test]
 #[should_panic]
 fn test_range_backwards_2() {
     let map = BTreeMap::from_iter((0..5).map(|i| (i, i)));
-    let _ = map.range((Included(3), Excluded(2)));
+    let _ = map.range_ord((Included(3), Excluded(2)));
 }
 
 #[test]
 #[should_panic]
 fn test_range_backwards_3() {
     let map = BTreeMap::from_iter((0..5).map(|i| (i, i)));
-    let _ = map.range((Excluded(3), Included(2)));
+    let _ = map.range_ord((Excluded(3), Included(2)));
 }
 
 #[test]
 #[should_panic]
 fn test_range_backwards_4() {
     let map = BTreeMap::from_iter((0..5).map(|i| (i, i)));
-    let _ = map.range((Excluded(3), Excluded(2)));
+    let _ = map.range_ord((Excluded(3), Excluded(2)));
 }
 
 #[test]
 fn test_range_finding_ill_order_in_map() {
     let mut map = BTreeMap::default();
-    map.insert(Cyclic3::B, ());
+    map.insert(Cyclic3::B, (), Ord::cmp);
     // Lacking static_assert, call `range` conditionally, to emphasise that
     // we cause a different panic than `test_range_backwards_1` does.
     // A more refined `should_panic` would be welcome.
     if Cyclic3::C < Cyclic3::A {
-        let _ = map.range(Cyclic3::C..=Cyclic3::A);
+        let _ = map.range_ord(Cyclic3::C..=Cyclic3::A);
     }
 }
 
@@ -814,14 +950,14 @@ fn test_range_finding_ill_order_in_range_ord() {
     #[derive(PartialEq, Eq, PartialOrd, Ord)]
     struct CompositeKey(i32, EvilTwin);
 
-    impl SortableBy<OrdTotalOrder<EvilTwin>> for CompositeKey {
-        fn sort_key(&self) -> &EvilTwin {
+    impl Borrow<EvilTwin> for CompositeKey {
+        fn borrow(&self) -> &EvilTwin {
             &self.1
         }
     }
 
     let map = BTreeMap::from_iter((0..12).map(|i| (CompositeKey(i, EvilTwin(i)), ())));
-    let _ = map.range(EvilTwin(5)..=EvilTwin(7));
+    let _ = map.range_ord(EvilTwin(5)..=EvilTwin(7));
 }
 
 #[test]
@@ -831,7 +967,7 @@ fn test_range_1000() {
     let map = BTreeMap::from_iter((0..size).map(|i| (i, i)));
 
     fn test(map: &BTreeMap<u32, u32>, size: u32, min: Bound<&u32>, max: Bound<&u32>) {
-        let mut kvs = map.range::<u32, _>((min, max)).map(|(&k, &v)| (k, v));
+        let mut kvs = map.range_ord::<u32, _>((min, max)).map(|(&k, &v)| (k, v));
         let mut pairs = (0..size).map(|i| (i, i));
 
         for (kv, pair) in kvs.by_ref().zip(pairs.by_ref()) {
@@ -851,12 +987,12 @@ fn test_range_1000() {
 #[test]
 fn test_range_borrowed_key() {
     let mut map = BTreeMap::default();
-    map.insert("aardvark".to_string(), 1);
-    map.insert("baboon".to_string(), 2);
-    map.insert("coyote".to_string(), 3);
-    map.insert("dingo".to_string(), 4);
+    map.insert("aardvark".to_string(), 1, Ord::cmp);
+    map.insert("baboon".to_string(), 2, Ord::cmp);
+    map.insert("coyote".to_string(), 3, Ord::cmp);
+    map.insert("dingo".to_string(), 4, Ord::cmp);
     // NOTE: would like to use simply "b".."d" here...
-    let mut iter = map.range::<str, _>((Included("b"), Excluded("d")));
+    let mut iter = map.range_ord::<str, _>((Included("b"), Excluded("d")));
     assert_eq!(iter.next(), Some((&"baboon".to_string(), &2)));
     assert_eq!(iter.next(), Some((&"coyote".to_string(), &3)));
     assert_eq!(iter.next(), None);
@@ -871,7 +1007,7 @@ fn test_range() {
 
     for i in (0..size).step_by(step) {
         for j in (i..size).step_by(step) {
-            let mut kvs = map.range::<i32, _>((Included(&i), Included(&j))).map(|(&k, &v)| (k, v));
+            let mut kvs = map.range_ord::<i32, _>((Included(&i), Included(&j))).map(|(&k, &v)| (k, v));
             let mut pairs = (i..=j).map(|i| (i, i));
 
             for (kv, pair) in kvs.by_ref().zip(pairs.by_ref()) {
@@ -893,7 +1029,7 @@ fn test_range_mut() {
     for i in (0..size).step_by(step) {
         for j in (i..size).step_by(step) {
             let mut kvs =
-                map.range_mut::<i32, _>((Included(&i), Included(&j))).map(|(&k, &mut v)| (k, v));
+                map.range_mut_ord::<i32, _>((Included(&i), Included(&j))).map(|(&k, &mut v)| (k, v));
             let mut pairs = (i..=j).map(|i| (i, i));
 
             for (kv, pair) in kvs.by_ref().zip(pairs.by_ref()) {
@@ -917,11 +1053,11 @@ fn test_range_mut() {
 #[test]
 fn test_range_panic_1() {
     let mut map = BTreeMap::default();
-    map.insert(3, "a");
-    map.insert(5, "b");
-    map.insert(8, "c");
+    map.insert(3, "a", Ord::cmp);
+    map.insert(5, "b", Ord::cmp);
+    map.insert(8, "c", Ord::cmp);
 
-    let _invalid_range = map.range::<i32, _>((Included(&8), Included(&3)));
+    let _invalid_range = map.range_ord::<i32, _>((Included(&8), Included(&3)));
 }
 
 #[cfg_attr(
@@ -935,11 +1071,11 @@ fn test_range_panic_1() {
 #[test]
 fn test_range_panic_2() {
     let mut map = BTreeMap::default();
-    map.insert(3, "a");
-    map.insert(5, "b");
-    map.insert(8, "c");
+    map.insert(3, "a", Ord::cmp);
+    map.insert(5, "b", Ord::cmp);
+    map.insert(8, "c", Ord::cmp);
 
-    let _invalid_range = map.range::<i32, _>((Excluded(&5), Excluded(&5)));
+    let _invalid_range = map.range_ord::<i32, _>((Excluded(&5), Excluded(&5)));
 }
 
 #[cfg_attr(
@@ -953,11 +1089,11 @@ fn test_range_panic_2() {
 #[test]
 fn test_range_panic_3() {
     let mut map: BTreeMap<i32, ()> = BTreeMap::default();
-    map.insert(3, ());
-    map.insert(5, ());
-    map.insert(8, ());
+    map.insert(3, (), Ord::cmp);
+    map.insert(5, (), Ord::cmp);
+    map.insert(8, (), Ord::cmp);
 
-    let _invalid_range = map.range::<i32, _>((Excluded(&5), Excluded(&5)));
+    let _invalid_range = map.range_ord::<i32, _>((Excluded(&5), Excluded(&5)));
 }
 
 #[test]
@@ -966,9 +1102,9 @@ fn test_retain() {
 
     map.retain(|&k, _| k % 2 == 0);
     assert_eq!(map.len(), 50);
-    assert_eq!(map[&2], 20);
-    assert_eq!(map[&4], 40);
-    assert_eq!(map[&6], 60);
+    assert_eq!(map.get(|k| 2.cmp(k)), Some(&20));
+    assert_eq!(map.get(|k| 4.cmp(k)), Some(&40));
+    assert_eq!(map.get(|k| 6.cmp(k)), Some(&60));
 }
 
 mod test_drain_filter {
@@ -1076,28 +1212,28 @@ mod test_drain_filter {
 
     #[test]
     fn height_0_keeping_all() {
-        let pairs = (0..node::CAPACITY).map(|i| (i, i));
+        let pairs = (0..Consts::CAPACITY).map(|i| (i, i));
         let mut map = BTreeMap::from_iter(pairs);
         map.drain_filter(|_, _| false);
-        assert!(map.keys().copied().eq(0..node::CAPACITY));
+        assert!(map.keys().copied().eq(0..Consts::CAPACITY));
         map.check();
     }
 
     #[test]
     fn height_0_removing_one() {
-        let pairs = (0..node::CAPACITY).map(|i| (i, i));
-        for doomed in 0..node::CAPACITY {
+        let pairs = (0..Consts::CAPACITY).map(|i| (i, i));
+        for doomed in 0..Consts::CAPACITY {
             let mut map = BTreeMap::from_iter(pairs.clone());
             map.drain_filter(|i, _| *i == doomed);
-            assert_eq!(map.len(), node::CAPACITY - 1);
+            assert_eq!(map.len(), Consts::CAPACITY - 1);
             map.check();
         }
     }
 
     #[test]
     fn height_0_keeping_one() {
-        let pairs = (0..node::CAPACITY).map(|i| (i, i));
-        for sacred in 0..node::CAPACITY {
+        let pairs = (0..Consts::CAPACITY).map(|i| (i, i));
+        for sacred in 0..Consts::CAPACITY {
             let mut map = BTreeMap::from_iter(pairs.clone());
             map.drain_filter(|i, _| *i != sacred);
             assert!(map.keys().copied().eq(sacred..=sacred));
@@ -1107,7 +1243,7 @@ mod test_drain_filter {
 
     #[test]
     fn height_0_removing_all() {
-        let pairs = (0..node::CAPACITY).map(|i| (i, i));
+        let pairs = (0..Consts::CAPACITY).map(|i| (i, i));
         let mut map = BTreeMap::from_iter(pairs);
         map.drain_filter(|_, _| true);
         assert!(map.is_empty());
@@ -1190,9 +1326,9 @@ mod test_drain_filter {
         let b = CrashTestDummy::new(1);
         let c = CrashTestDummy::new(2);
         let mut map = BTreeMap::default();
-        map.insert(a.spawn(Panic::Never), ());
-        map.insert(b.spawn(Panic::InDrop), ());
-        map.insert(c.spawn(Panic::Never), ());
+        map.insert(a.spawn(Panic::Never), (), Ord::cmp);
+        map.insert(b.spawn(Panic::InDrop), (), Ord::cmp);
+        map.insert(c.spawn(Panic::Never), (), Ord::cmp);
 
         catch_unwind(move || drop(map.drain_filter(|dummy, _| dummy.query(true)))).unwrap_err();
 
@@ -1210,9 +1346,9 @@ mod test_drain_filter {
         let b = CrashTestDummy::new(1);
         let c = CrashTestDummy::new(2);
         let mut map = BTreeMap::default();
-        map.insert(a.spawn(Panic::Never), ());
-        map.insert(b.spawn(Panic::InQuery), ());
-        map.insert(c.spawn(Panic::InQuery), ());
+        map.insert(a.spawn(Panic::Never), (), Ord::cmp);
+        map.insert(b.spawn(Panic::InQuery), (), Ord::cmp);
+        map.insert(c.spawn(Panic::InQuery), (), Ord::cmp);
 
         catch_unwind(AssertUnwindSafe(|| drop(map.drain_filter(|dummy, _| dummy.query(true)))))
             .unwrap_err();
@@ -1236,9 +1372,9 @@ mod test_drain_filter {
         let b = CrashTestDummy::new(1);
         let c = CrashTestDummy::new(2);
         let mut map = BTreeMap::default();
-        map.insert(a.spawn(Panic::Never), ());
-        map.insert(b.spawn(Panic::InQuery), ());
-        map.insert(c.spawn(Panic::InQuery), ());
+        map.insert(a.spawn(Panic::Never), (), Ord::cmp);
+        map.insert(b.spawn(Panic::InQuery), (), Ord::cmp);
+        map.insert(c.spawn(Panic::InQuery), (), Ord::cmp);
 
         {
             let mut it = map.drain_filter(|dummy, _| dummy.query(true));
@@ -1262,76 +1398,195 @@ mod test_drain_filter {
     }
 }
 
+mod test_extract_if {
+    use super::*;
+
+    impl<K: Ord, V> BTreeMap<K, V> {
+        fn extract_if_ord<R, F>(&mut self, range: R, pred: F) -> Vec<(K, V)>
+        where
+            R: RangeBounds<K>,
+            F: FnMut(&K, &mut V) -> bool,
+        {
+            let (start, lower_bound, end, upper_bound) = ord_bounds(&range);
+            self.extract_if(
+                |k| start.map_or(Ordering::Equal, |s| s.cmp(k)),
+                lower_bound,
+                |k| end.map_or(Ordering::Equal, |e| e.cmp(k)),
+                upper_bound,
+                pred,
+            )
+            .collect()
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let mut map: BTreeMap<i32, i32> = BTreeMap::default();
+        let extracted = map.extract_if_ord(.., |_, _| unreachable!("there's nothing to decide on"));
+        assert!(extracted.is_empty());
+        map.check();
+    }
+
+    // Visits every key in the range exactly once, and no key outside of it.
+    #[test]
+    fn visits_only_range() {
+        let size = MIN_INSERTS_HEIGHT_2 as u32;
+        for range in [
+            (Included(10), Included(60)),
+            (Excluded(10), Excluded(60)),
+            (Excluded(10), Unbounded),
+            (Unbounded, Included(0)),
+            (Included(size), Unbounded),
+            (Unbounded, Unbounded),
+        ] {
+            let mut map = BTreeMap::from_iter((0..size).map(|i| (i, i)));
+            let mut visited = Vec::new();
+            let extracted = map.extract_if_ord(range, |&k, v| {
+                visited.push(k);
+                *v += 100;
+                k % 3 == 0
+            });
+            map.check();
+            assert!(visited.iter().copied().eq((0..size).filter(|k| range.contains(k))));
+            let expected = visited.iter().filter(|&k| k % 3 == 0).map(|&k| (k, k + 100));
+            assert!(extracted.into_iter().eq(expected));
+            for (&k, &v) in &map {
+                assert_eq!(v, if range.contains(&k) { k + 100 } else { k });
+            }
+        }
+    }
+
+    #[test]
+    fn drop_visits_rest_of_range() {
+        let mut map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_1).map(|i| (i, i)));
+        let mut iter = map.extract_if(
+            |k| 2.cmp(k),
+            SearchBoundCustom::Included,
+            |k| 8.cmp(k),
+            SearchBoundCustom::Excluded,
+            |_, _| true,
+        );
+        assert_eq!(iter.next(), Some((2, 2)));
+        drop(iter);
+        map.check();
+        assert!(map.keys().copied().eq((0..2).chain(8..MIN_INSERTS_HEIGHT_1)));
+    }
+
+    #[test]
+    fn pred_panic_leak() {
+        let dummies = Vec::from_iter((0..4).map(CrashTestDummy::new));
+        let mut map = BTreeMap::default();
+        for (i, dummy) in dummies.iter().enumerate() {
+            let panic = if i == 2 { Panic::InQuery } else { Panic::Never };
+            map.insert(dummy.spawn(panic), (), |a, b| a.id().cmp(&b.id()));
+        }
+
+        catch_unwind(AssertUnwindSafe(|| {
+            drop(map.extract_if(
+                |k| 1.cmp(&k.id()),
+                SearchBoundCustom::Included,
+                |_| Ordering::Equal,
+                SearchBoundCustom::AllIncluded,
+                |dummy, _| dummy.query(true),
+            ))
+        }))
+        .unwrap_err();
+
+        assert_eq!(dummies[0].queried(), 0);
+        assert_eq!(dummies[1].queried(), 1);
+        assert_eq!(dummies[2].queried(), 1);
+        assert_eq!(dummies[3].queried(), 0);
+        assert_eq!(dummies[1].dropped(), 1);
+        assert_eq!(map.len(), 3);
+        map.check();
+    }
+
+    #[test]
+    fn retain_range() {
+        let mut map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_2).map(|i| (i, i)));
+        map.retain_range(
+            |k| 20.cmp(k),
+            SearchBoundCustom::Excluded,
+            |k| 70.cmp(k),
+            SearchBoundCustom::Included,
+            |&k, _| k % 2 == 0,
+        );
+        map.check();
+        let expected = (0..MIN_INSERTS_HEIGHT_2).filter(|&k| k <= 20 || k > 70 || k % 2 == 0);
+        assert!(map.keys().copied().eq(expected));
+    }
+}
+
 #[test]
 fn test_borrow() {
     // make sure these compile -- using the Borrow trait
     {
         let mut map = BTreeMap::default();
-        map.insert("0".to_string(), 1);
-        assert_eq!(map["0"], 1);
+        map.insert("0".to_string(), 1, Ord::cmp);
+        assert_eq!(map.get(|k| "0".cmp(k)), Some(&1));
     }
 
     {
         let mut map = BTreeMap::default();
-        map.insert(Box::new(0), 1);
-        assert_eq!(map[&0], 1);
+        map.insert(Box::new(0), 1, Ord::cmp);
+        assert_eq!(map.get(|k| 0.cmp(k)), Some(&1));
     }
 
     {
         let mut map = BTreeMap::default();
-        map.insert(Box::new([0, 1]) as Box<[i32]>, 1);
-        assert_eq!(map[&[0, 1][..]], 1);
+        map.insert(Box::new([0, 1]) as Box<[i32]>, 1, Ord::cmp);
+        assert_eq!(map.get(|k| [0, 1][..].cmp(k)), Some(&1));
     }
 
     {
         let mut map = BTreeMap::default();
-        map.insert(Rc::new(0), 1);
-        assert_eq!(map[&0], 1);
+        map.insert(Rc::new(0), 1, Ord::cmp);
+        assert_eq!(map.get(|k| 0.cmp(k)), Some(&1));
     }
 
     #[allow(dead_code)]
-    fn get<T: Ord>(v: &BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: &T) {
-        let _ = v.get(t);
+    fn get<T: Ord>(v: &BTreeMap<Box<T>, ()>, t: &T) {
+        let _ = v.get(|k| t.cmp(k));
     }
 
     #[allow(dead_code)]
-    fn get_mut<T: Ord>(v: &mut BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: &T) {
-        let _ = v.get_mut(t);
+    fn get_mut<T: Ord>(v: &mut BTreeMap<Box<T>, ()>, t: &T) {
+        let _ = v.get_mut(|k| t.cmp(k));
     }
 
     #[allow(dead_code)]
-    fn get_key_value<T: Ord>(v: &BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: &T) {
-        let _ = v.get_key_value(t);
+    fn get_key_value<T: Ord>(v: &BTreeMap<Box<T>, ()>, t: &T) {
+        let _ = v.get_key_value(|k| t.cmp(k));
     }
 
     #[allow(dead_code)]
-    fn contains_key<T: Ord>(v: &BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: &T) {
-        let _ = v.contains_key(t);
+    fn contains_key<T: Ord>(v: &BTreeMap<Box<T>, ()>, t: &T) {
+        let _ = v.contains_key(|k| t.cmp(k));
     }
 
     #[allow(dead_code)]
-    fn range<T: Ord>(v: &BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: T) {
-        let _ = v.range(t..);
+    fn range<T: Ord>(v: &BTreeMap<Box<T>, ()>, t: T) {
+        let _ = v.range_ord(t..);
     }
 
     #[allow(dead_code)]
-    fn range_mut<T: Ord>(v: &mut BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: T) {
-        let _ = v.range_mut(t..);
+    fn range_mut<T: Ord>(v: &mut BTreeMap<Box<T>, ()>, t: T) {
+        let _ = v.range_mut_ord(t..);
     }
 
     #[allow(dead_code)]
-    fn remove<T: Ord>(v: &mut BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: &T) {
-        v.remove(t);
+    fn remove<T: Ord>(v: &mut BTreeMap<Box<T>, ()>, t: &T) {
+        v.remove(|k| t.cmp(k));
     }
 
     #[allow(dead_code)]
-    fn remove_entry<T: Ord>(v: &mut BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: &T) {
-        v.remove_entry(t);
+    fn remove_entry<T: Ord>(v: &mut BTreeMap<Box<T>, ()>, t: &T) {
+        v.remove_entry(|k| t.cmp(k));
     }
 
     #[allow(dead_code)]
-    fn split_off<T: Ord>(v: &mut BTreeMap<Box<T>, (), OrdTotalOrder<T>>, t: &T) {
-        v.split_off(t);
+    fn split_off<T: Ord>(v: &mut BTreeMap<Box<T>, ()>, t: &T) {
+        v.split_off(|k| t.cmp(k));
     }
 }
 
@@ -1342,77 +1597,60 @@ fn test_entry() {
     let mut map = BTreeMap::from(xs);
 
     // Existing key (insert)
-    match map.entry(1) {
+    match map.entry(1, Ord::cmp) {
         Vacant(_) => unreachable!(),
         Occupied(mut view) => {
             assert_eq!(view.get(), &10);
             assert_eq!(view.insert(100), 10);
         }
     }
-    assert_eq!(map.get(&1).unwrap(), &100);
+    assert_eq!(map.get(|k| 1.cmp(k)).unwrap(), &100);
     assert_eq!(map.len(), 6);
 
     // Existing key (update)
-    match map.entry(2) {
+    match map.entry(2, Ord::cmp) {
         Vacant(_) => unreachable!(),
         Occupied(mut view) => {
             let v = view.get_mut();
             *v *= 10;
         }
     }
-    assert_eq!(map.get(&2).unwrap(), &200);
+    assert_eq!(map.get(|k| 2.cmp(k)).unwrap(), &200);
     assert_eq!(map.len(), 6);
     map.check();
 
     // Existing key (take)
-    match map.entry(3) {
+    match map.entry(3, Ord::cmp) {
         Vacant(_) => unreachable!(),
         Occupied(view) => {
             assert_eq!(view.remove(), 30);
         }
     }
-    assert_eq!(map.get(&3), None);
+    assert_eq!(map.get(|k| 3.cmp(k)), None);
     assert_eq!(map.len(), 5);
     map.check();
 
     // Inexistent key (insert)
-    match map.entry(10) {
+    match map.entry(10, Ord::cmp) {
         Occupied(_) => unreachable!(),
         Vacant(view) => {
             assert_eq!(*view.insert(1000), 1000);
         }
     }
-    assert_eq!(map.get(&10).unwrap(), &1000);
+    assert_eq!(map.get(|k| 10.cmp(k)).unwrap(), &1000);
     assert_eq!(map.len(), 6);
     map.check();
 }
 
-#[test]
-fn test_extend_ref() {
-    let mut a = BTreeMap::default();
-    a.insert(1, "one");
-    let mut b = BTreeMap::default();
-    b.insert(2, "two");
-    b.insert(3, "three");
-
-    a.extend(&b);
-
-    assert_eq!(a.len(), 3);
-    assert_eq!(a[&1], "one");
-    assert_eq!(a[&2], "two");
-    assert_eq!(a[&3], "three");
-    a.check();
-}
-
 #[test]
 fn test_zst() {
     let mut m = BTreeMap::default();
     assert_eq!(m.len(), 0);
 
-    assert_eq!(m.insert((), ()), None);
+    assert_eq!(m.insert((), (), Ord::cmp), None);
     assert_eq!(m.len(), 1);
 
-    assert_eq!(m.insert((), ()), Some(()));
+    assert_eq!(m.insert((), (), Ord::cmp), Some(()));
     assert_eq!(m.len(), 1);
     assert_eq!(m.iter().count(), 1);
 
@@ -1420,7 +1658,7 @@ fn test_zst() {
     assert_eq!(m.len(), 0);
 
     for _ in 0..100 {
-        m.insert((), ());
+        m.insert((), (), Ord::cmp);
     }
 
     assert_eq!(m.len(), 1);
@@ -1436,10 +1674,6 @@ fn test_bad_zst() {
     #[derive(Clone, Copy, Debug)]
     struct Bad;
 
-    impl OrdStoredKey for Bad {
-        type OrdKeyType = Self;
-    }
-
     impl PartialEq for Bad {
         fn eq(&self, _: &Self) -> bool {
             false
@@ -1463,7 +1697,7 @@ fn test_bad_zst() {
     let mut m = BTreeMap::default();
 
     for _ in 0..100 {
-        m.insert(Bad, Bad);
+        m.insert(Bad, Bad, Ord::cmp);
     }
     m.check();
 }
@@ -1471,9 +1705,9 @@ fn test_bad_zst() {
 #[test]
 fn test_clear() {
     let mut map = BTreeMap::default();
-    for &len in &[MIN_INSERTS_HEIGHT_1, MIN_INSERTS_HEIGHT_2, 0, node::CAPACITY] {
+    for &len in &[MIN_INSERTS_HEIGHT_1, MIN_INSERTS_HEIGHT_2, 0, Consts::CAPACITY] {
         for i in 0..len {
-            map.insert(i, ());
+            map.insert(i, (), Ord::cmp);
         }
         assert_eq!(map.len(), len);
         map.clear();
@@ -1489,9 +1723,9 @@ fn test_clear_drop_panic_leak() {
     let c = CrashTestDummy::new(2);
 
     let mut map = BTreeMap::default();
-    map.insert(a.spawn(Panic::Never), ());
-    map.insert(b.spawn(Panic::InDrop), ());
-    map.insert(c.spawn(Panic::Never), ());
+    map.insert(a.spawn(Panic::Never), (), Ord::cmp);
+    map.insert(b.spawn(Panic::InDrop), (), Ord::cmp);
+    map.insert(c.spawn(Panic::Never), (), Ord::cmp);
 
     catch_unwind(AssertUnwindSafe(|| map.clear())).unwrap_err();
     assert_eq!(a.dropped(), 1);
@@ -1512,29 +1746,29 @@ fn test_clone() {
     assert_eq!(map.len(), 0);
 
     for i in 0..size {
-        assert_eq!(map.insert(i, 10 * i), None);
+        assert_eq!(map.insert(i, 10 * i, Ord::cmp), None);
         assert_eq!(map.len(), i + 1);
         map.check();
         assert_eq!(map, map.clone());
     }
 
     for i in 0..size {
-        assert_eq!(map.insert(i, 100 * i), Some(10 * i));
+        assert_eq!(map.insert(i, 100 * i, Ord::cmp), Some(10 * i));
         assert_eq!(map.len(), size);
         map.check();
         assert_eq!(map, map.clone());
     }
 
     for i in 0..size / 2 {
-        assert_eq!(map.remove(&(i * 2)), Some(i * 200));
+        assert_eq!(map.remove(|k| (i * 2).cmp(k)), Some(i * 200));
         assert_eq!(map.len(), size - i - 1);
         map.check();
         assert_eq!(map, map.clone());
     }
 
     for i in 0..size / 2 {
-        assert_eq!(map.remove(&(2 * i)), None);
-        assert_eq!(map.remove(&(2 * i + 1)), Some(i * 200 + 100));
+        assert_eq!(map.remove(|k| (2 * i).cmp(k)), None);
+        assert_eq!(map.remove(|k| (2 * i + 1).cmp(k)), Some(i * 200 + 100));
         assert_eq!(map.len(), size / 2 - i - 1);
         map.check();
         assert_eq!(map, map.clone());
@@ -1544,7 +1778,7 @@ fn test_clone() {
     map = BTreeMap::from_iter((1..MIN_INSERTS_HEIGHT_2).map(|i| (i, i)));
     assert_eq!(map.len(), MIN_INSERTS_HEIGHT_2 - 1);
     assert_eq!(map, map.clone());
-    map.insert(0, 0);
+    map.insert(0, 0, Ord::cmp);
     assert_eq!(map.len(), MIN_INSERTS_HEIGHT_2);
     assert_eq!(map, map.clone());
     map.check();
@@ -1598,19 +1832,19 @@ fn test_clone_from() {
             let mut map2_copy = map1.clone();
             map2_copy.clone_from(&map2); // large cloned from small
             assert_eq!(map2_copy, map2);
-            map2.insert(100 * j + 1, 2 * j + 1);
+            map2.insert(100 * j + 1, 2 * j + 1, Ord::cmp);
         }
         map2.clone_from(&map1); // same length
         map2.check();
         assert_eq!(map2, map1);
-        map1.insert(i, 10 * i);
+        map1.insert(i, 10 * i, Ord::cmp);
         map1.check();
     }
 }
 
 #[allow(dead_code)]
 fn assert_covariance() {
-    fn map_key<'new>(v: BTreeMap<&'static str, (), ()>) -> BTreeMap<&'new str, (), ()> {
+    fn map_key<'new>(v: BTreeMap<&'static str, ()>) -> BTreeMap<&'new str, ()> {
         v
     }
     fn map_val<'new>(v: BTreeMap<(), &'static str>) -> BTreeMap<(), &'new str> {
@@ -1669,67 +1903,67 @@ fn assert_covariance() {
 
 #[allow(dead_code)]
 fn assert_sync() {
-    fn map<T: Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
+    fn map<T: Sync + Ord>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
         v
     }
 
-    fn into_iter<T: Sync + OrdStoredKey>(v: BTreeMap<T, T>) -> impl Sync {
+    fn into_iter<T: Sync + Ord>(v: BTreeMap<T, T>) -> impl Sync {
         v.into_iter()
     }
 
-    fn into_keys<T: Sync + OrdStoredKey>(v: BTreeMap<T, T>) -> impl Sync {
+    fn into_keys<T: Sync + Ord>(v: BTreeMap<T, T>) -> impl Sync {
         v.into_keys()
     }
 
-    fn into_values<T: Sync + OrdStoredKey>(v: BTreeMap<T, T>) -> impl Sync {
+    fn into_values<T: Sync + Ord>(v: BTreeMap<T, T>) -> impl Sync {
         v.into_values()
     }
 
-    fn drain_filter<T: Sync + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
+    fn drain_filter<T: Sync + Ord>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
         v.drain_filter(|_, _| false)
     }
 
-    fn iter<T: Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
+    fn iter<T: Sync + Ord>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
         v.iter()
     }
 
-    fn iter_mut<T: Sync + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
+    fn iter_mut<T: Sync + Ord>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
         v.iter_mut()
     }
 
-    fn keys<T: Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
+    fn keys<T: Sync + Ord>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
         v.keys()
     }
 
-    fn values<T: Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
+    fn values<T: Sync + Ord>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
         v.values()
     }
 
-    fn values_mut<T: Sync + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
+    fn values_mut<T: Sync + Ord>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
         v.values_mut()
     }
 
-    fn range<T: Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
-        v.range::<T, _>(..)
+    fn range<T: Sync + Ord>(v: &BTreeMap<T, T>) -> impl Sync + '_ {
+        v.range_ord::<T, _>(..)
     }
 
-    fn range_mut<T: Sync + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
-        v.range_mut::<T, _>(..)
+    fn range_mut<T: Sync + Ord>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
+        v.range_mut_ord::<T, _>(..)
     }
 
-    fn entry<T: Sync + OrdStoredKey + Default>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
-        v.entry(Default::default())
+    fn entry<T: Sync + Ord + Default>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
+        v.entry(Default::default(), Ord::cmp)
     }
 
-    fn occupied_entry<T: Sync + OrdStoredKey + Default>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
-        match v.entry(Default::default()) {
+    fn occupied_entry<T: Sync + Ord + Default>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
+        match v.entry(Default::default(), Ord::cmp) {
             Occupied(entry) => entry,
             _ => unreachable!(),
         }
     }
 
-    fn vacant_entry<T: Sync + OrdStoredKey + Default>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
-        match v.entry(Default::default()) {
+    fn vacant_entry<T: Sync + Ord + Default>(v: &mut BTreeMap<T, T>) -> impl Sync + '_ {
+        match v.entry(Default::default(), Ord::cmp) {
             Vacant(entry) => entry,
             _ => unreachable!(),
         }
@@ -1738,67 +1972,67 @@ fn assert_sync() {
 
 #[allow(dead_code)]
 fn assert_send() {
-    fn map<T: Send + OrdStoredKey>(v: BTreeMap<T, T>) -> impl Send {
+    fn map<T: Send + Ord>(v: BTreeMap<T, T>) -> impl Send {
         v
     }
 
-    fn into_iter<T: Send + OrdStoredKey>(v: BTreeMap<T, T>) -> impl Send {
+    fn into_iter<T: Send + Ord>(v: BTreeMap<T, T>) -> impl Send {
         v.into_iter()
     }
 
-    fn into_keys<T: Send + OrdStoredKey>(v: BTreeMap<T, T>) -> impl Send {
+    fn into_keys<T: Send + Ord>(v: BTreeMap<T, T>) -> impl Send {
         v.into_keys()
     }
 
-    fn into_values<T: Send + OrdStoredKey>(v: BTreeMap<T, T>) -> impl Send {
+    fn into_values<T: Send + Ord>(v: BTreeMap<T, T>) -> impl Send {
         v.into_values()
     }
 
-    fn drain_filter<T: Send + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
+    fn drain_filter<T: Send + Ord>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
         v.drain_filter(|_, _| false)
     }
 
-    fn iter<T: Send + Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Send + '_ {
+    fn iter<T: Send + Sync + Ord>(v: &BTreeMap<T, T>) -> impl Send + '_ {
         v.iter()
     }
 
-    fn iter_mut<T: Send + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
+    fn iter_mut<T: Send + Ord>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
         v.iter_mut()
     }
 
-    fn keys<T: Send + Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Send + '_ {
+    fn keys<T: Send + Sync + Ord>(v: &BTreeMap<T, T>) -> impl Send + '_ {
         v.keys()
     }
 
-    fn values<T: Send + Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Send + '_ {
+    fn values<T: Send + Sync + Ord>(v: &BTreeMap<T, T>) -> impl Send + '_ {
         v.values()
     }
 
-    fn values_mut<T: Send + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
+    fn values_mut<T: Send + Ord>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
         v.values_mut()
     }
 
-    fn range<T: Send + Sync + OrdStoredKey>(v: &BTreeMap<T, T>) -> impl Send + '_ {
-        v.range::<T, _>(..)
+    fn range<T: Send + Sync + Ord>(v: &BTreeMap<T, T>) -> impl Send + '_ {
+        v.range_ord::<T, _>(..)
     }
 
-    fn range_mut<T: Send + OrdStoredKey>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
-        v.range_mut::<T, _>(..)
+    fn range_mut<T: Send + Ord>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
+        v.range_mut_ord::<T, _>(..)
     }
 
-    fn entry<T: Send + OrdStoredKey + Default>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
-        v.entry(Default::default())
+    fn entry<T: Send + Ord + Default>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
+        v.entry(Default::default(), Ord::cmp)
     }
 
-    fn occupied_entry<T: Send + OrdStoredKey + Default>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
-        match v.entry(Default::default()) {
+    fn occupied_entry<T: Send + Ord + Default>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
+        match v.entry(Default::default(), Ord::cmp) {
             Occupied(entry) => entry,
             _ => unreachable!(),
         }
     }
 
-    fn vacant_entry<T: Send + OrdStoredKey + Default>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
-        match v.entry(Default::default()) {
+    fn vacant_entry<T: Send + Ord + Default>(v: &mut BTreeMap<T, T>) -> impl Send + '_ {
+        match v.entry(Default::default(), Ord::cmp) {
             Vacant(entry) => entry,
             _ => unreachable!(),
         }
@@ -1807,7 +2041,7 @@ fn assert_send() {
 
 #[test]
 fn test_ord_absence() {
-    fn map<K, O: Clone>(mut map: BTreeMap<K, (), O>) {
+    fn map<K>(mut map: BTreeMap<K, ()>) {
         let _ = map.is_empty();
         let _ = map.len();
         map.clear();
@@ -1825,31 +2059,31 @@ fn test_ord_absence() {
         }
     }
 
-    fn map_debug<K: Debug, O>(mut map: BTreeMap<K, (), O>) {
-        format!("{map:?}");
-        format!("{:?}", map.iter());
-        format!("{:?}", map.iter_mut());
-        format!("{:?}", map.keys());
-        format!("{:?}", map.values());
-        format!("{:?}", map.values_mut());
+    fn map_debug<K: Debug>(mut map: BTreeMap<K, ()>) {
+        let _ = format!("{map:?}");
+        let _ = format!("{:?}", map.iter());
+        let _ = format!("{:?}", map.iter_mut());
+        let _ = format!("{:?}", map.keys());
+        let _ = format!("{:?}", map.values());
+        let _ = format!("{:?}", map.values_mut());
         if true {
-            format!("{:?}", map.into_iter());
+            let _ = format!("{:?}", map.into_iter());
         } else if true {
-            format!("{:?}", map.into_keys());
+            let _ = format!("{:?}", map.into_keys());
         } else {
-            format!("{:?}", map.into_values());
+            let _ = format!("{:?}", map.into_values());
         }
     }
 
-    fn map_clone<K: Clone, O: Clone>(mut map: BTreeMap<K, (), O>) {
+    fn map_clone<K: Clone>(mut map: BTreeMap<K, ()>) {
         map.clone_from(&map.clone());
     }
 
     #[derive(Debug, Clone)]
     struct NonOrd;
-    map(BTreeMap::<NonOrd, _, _>::new(()));
-    map_debug(BTreeMap::<NonOrd, _, _>::new(()));
-    map_clone(BTreeMap::<NonOrd, _, _>::new(()));
+    map(BTreeMap::<NonOrd, _>::new());
+    map_debug(BTreeMap::<NonOrd, _>::new());
+    map_clone(BTreeMap::<NonOrd, _>::new());
 }
 
 #[test]
@@ -1858,16 +2092,16 @@ fn test_occupied_entry_key() {
     let key = "hello there";
     let value = "value goes here";
     assert_eq!(a.height(), None);
-    a.insert(key, value);
+    a.insert(key, value, Ord::cmp);
     assert_eq!(a.len(), 1);
-    assert_eq!(a[key], value);
+    assert_eq!(a.get(|k| key.cmp(k)), Some(&value));
 
-    match a.entry(key) {
+    match a.entry(key, Ord::cmp) {
         Vacant(_) => panic!(),
         Occupied(e) => assert_eq!(key, *e.key()),
     }
     assert_eq!(a.len(), 1);
-    assert_eq!(a[key], value);
+    assert_eq!(a.get(|k| key.cmp(k)), Some(&value));
     a.check();
 }
 
@@ -1878,7 +2112,7 @@ fn test_vacant_entry_key() {
     let value = "value goes here";
 
     assert_eq!(a.height(), None);
-    match a.entry(key) {
+    match a.entry(key, Ord::cmp) {
         Occupied(_) => unreachable!(),
         Vacant(e) => {
             assert_eq!(key, *e.key());
@@ -1886,7 +2120,7 @@ fn test_vacant_entry_key() {
         }
     }
     assert_eq!(a.len(), 1);
-    assert_eq!(a[key], value);
+    assert_eq!(a.get(|k| key.cmp(k)), Some(&value));
     a.check();
 }
 
@@ -1897,7 +2131,7 @@ fn test_vacant_entry_no_insert() {
 
     // Non-allocated
     assert_eq!(a.height(), None);
-    match a.entry(key) {
+    match a.entry(key, Ord::cmp) {
         Occupied(_) => unreachable!(),
         Vacant(e) => assert_eq!(key, *e.key()),
     }
@@ -1906,11 +2140,11 @@ fn test_vacant_entry_no_insert() {
     a.check();
 
     // Allocated but still empty
-    a.insert(key, ());
-    a.remove(&key);
+    a.insert(key, (), Ord::cmp);
+    a.remove(|k| key.cmp(k));
     assert_eq!(a.height(), Some(0));
     assert!(a.is_empty());
-    match a.entry(key) {
+    match a.entry(key, Ord::cmp) {
         Occupied(_) => unreachable!(),
         Vacant(e) => assert_eq!(key, *e.key()),
     }
@@ -1925,13 +2159,13 @@ fn test_first_last_entry() {
     let mut a = BTreeMap::default();
     assert!(a.first_entry().is_none());
     assert!(a.last_entry().is_none());
-    a.insert(1, 42);
+    a.insert(1, 42, Ord::cmp);
     assert_eq!(a.first_entry().unwrap().key(), &1);
     assert_eq!(a.last_entry().unwrap().key(), &1);
-    a.insert(2, 24);
+    a.insert(2, 24, Ord::cmp);
     assert_eq!(a.first_entry().unwrap().key(), &1);
     assert_eq!(a.last_entry().unwrap().key(), &2);
-    a.insert(0, 6);
+    a.insert(0, 6, Ord::cmp);
     assert_eq!(a.first_entry().unwrap().key(), &0);
     assert_eq!(a.last_entry().unwrap().key(), &2);
     let (k1, v1) = a.first_entry().unwrap().remove_entry();
@@ -1951,10 +2185,10 @@ fn test_pop_first_last() {
     assert_eq!(map.pop_first(), None);
     assert_eq!(map.pop_last(), None);
 
-    map.insert(1, 10);
-    map.insert(2, 20);
-    map.insert(3, 30);
-    map.insert(4, 40);
+    map.insert(1, 10, Ord::cmp);
+    map.insert(2, 20, Ord::cmp);
+    map.insert(3, 30, Ord::cmp);
+    map.insert(4, 40, Ord::cmp);
 
     assert_eq!(map.len(), 4);
 
@@ -1972,8 +2206,8 @@ fn test_pop_first_last() {
     assert_eq!(val, 40);
     assert_eq!(map.len(), 1);
 
-    map.insert(5, 50);
-    map.insert(6, 60);
+    map.insert(5, 50, Ord::cmp);
+    map.insert(6, 60, Ord::cmp);
     assert_eq!(map.len(), 3);
 
     let (key, val) = map.pop_first().unwrap();
@@ -1994,8 +2228,8 @@ fn test_pop_first_last() {
     assert_eq!(map.pop_first(), None);
     assert_eq!(map.pop_last(), None);
 
-    map.insert(7, 70);
-    map.insert(8, 80);
+    map.insert(7, 70, Ord::cmp);
+    map.insert(8, 80, Ord::cmp);
 
     let (key, val) = map.pop_last().unwrap();
     assert_eq!(key, 8);
@@ -2016,47 +2250,47 @@ fn test_get_key_value() {
     let mut map = BTreeMap::default();
 
     assert!(map.is_empty());
-    assert_eq!(map.get_key_value(&1), None);
-    assert_eq!(map.get_key_value(&2), None);
+    assert_eq!(map.get_key_value(|k| 1.cmp(k)), None);
+    assert_eq!(map.get_key_value(|k| 2.cmp(k)), None);
 
-    map.insert(1, 10);
-    map.insert(2, 20);
-    map.insert(3, 30);
+    map.insert(1, 10, Ord::cmp);
+    map.insert(2, 20, Ord::cmp);
+    map.insert(3, 30, Ord::cmp);
 
     assert_eq!(map.len(), 3);
-    assert_eq!(map.get_key_value(&1), Some((&1, &10)));
-    assert_eq!(map.get_key_value(&3), Some((&3, &30)));
-    assert_eq!(map.get_key_value(&4), None);
+    assert_eq!(map.get_key_value(|k| 1.cmp(k)), Some((&1, &10)));
+    assert_eq!(map.get_key_value(|k| 3.cmp(k)), Some((&3, &30)));
+    assert_eq!(map.get_key_value(|k| 4.cmp(k)), None);
 
-    map.remove(&3);
+    map.remove(|k| 3.cmp(k));
 
     assert_eq!(map.len(), 2);
-    assert_eq!(map.get_key_value(&3), None);
-    assert_eq!(map.get_key_value(&2), Some((&2, &20)));
+    assert_eq!(map.get_key_value(|k| 3.cmp(k)), None);
+    assert_eq!(map.get_key_value(|k| 2.cmp(k)), Some((&2, &20)));
 }
 
 #[test]
 fn test_insert_into_full_height_0() {
-    let size = node::CAPACITY;
+    let size = Consts::CAPACITY;
     for pos in 0..=size {
         let mut map = BTreeMap::from_iter((0..size).map(|i| (i * 2 + 1, ())));
-        assert!(map.insert(pos * 2, ()).is_none());
+        assert!(map.insert(pos * 2, (), Ord::cmp).is_none());
         map.check();
     }
 }
 
 #[test]
 fn test_insert_into_full_height_1() {
-    let size = node::CAPACITY + 1 + node::CAPACITY;
+    let size = Consts::CAPACITY + 1 + Consts::CAPACITY;
     for pos in 0..=size {
         let mut map = BTreeMap::from_iter((0..size).map(|i| (i * 2 + 1, ())));
         map.compact();
         let root_node = map.root.as_ref().unwrap().reborrow();
         assert_eq!(root_node.len(), 1);
-        assert_eq!(root_node.first_leaf_edge().into_node().len(), node::CAPACITY);
-        assert_eq!(root_node.last_leaf_edge().into_node().len(), node::CAPACITY);
+        assert_eq!(root_node.first_leaf_edge().into_node().len(), Consts::CAPACITY);
+        assert_eq!(root_node.last_leaf_edge().into_node().len(), Consts::CAPACITY);
 
-        assert!(map.insert(pos * 2, ()).is_none());
+        assert!(map.insert(pos * 2, (), Ord::cmp).is_none());
         map.check();
     }
 }
@@ -2068,13 +2302,14 @@ fn test_try_insert() {
 
     assert!(map.is_empty());
 
-    assert_eq!(map.try_insert(1, 10).unwrap(), &10);
-    assert_eq!(map.try_insert(2, 20).unwrap(), &20);
+    assert_eq!(map.try_insert(1, 10, Ord::cmp).unwrap(), &10);
+    assert_eq!(map.try_insert(2, 20, Ord::cmp).unwrap(), &20);
 
-    let err = map.try_insert(2, 200).unwrap_err();
+    let err = map.try_insert(2, 200, Ord::cmp).unwrap_err();
     assert_eq!(err.entry.key(), &2);
     assert_eq!(err.entry.get(), &20);
     assert_eq!(err.value, 200);
+    assert_eq!(err.to_string(), "failed to insert 200, key 2 already exists with value 20");
 }
 
 macro_rules! create_append_test {
@@ -2083,30 +2318,30 @@ macro_rules! create_append_test {
         fn $name() {
             let mut a = BTreeMap::default();
             for i in 0..8 {
-                a.insert(i, i);
+                a.insert(i, i, Ord::cmp);
             }
 
             let mut b = BTreeMap::default();
             for i in 5..$len {
-                b.insert(i, 2 * i);
+                b.insert(i, 2 * i, Ord::cmp);
             }
 
-            a.append(&mut b);
+            a.append(&mut b, |x, y| x.0.cmp(&y.0));
 
             assert_eq!(a.len(), $len);
             assert_eq!(b.len(), 0);
 
             for i in 0..$len {
                 if i < 5 {
-                    assert_eq!(a[&i], i);
+                    assert_eq!(a.get(|k| i.cmp(k)), Some(&i));
                 } else {
-                    assert_eq!(a[&i], 2 * i);
+                    assert_eq!(a.get(|k| i.cmp(k)), Some(&(2 * i)));
                 }
             }
 
             a.check();
-            assert_eq!(a.remove(&($len - 1)), Some(2 * ($len - 1)));
-            assert_eq!(a.insert($len - 1, 20), None);
+            assert_eq!(a.remove(|k| ($len - 1).cmp(k)), Some(2 * ($len - 1)));
+            assert_eq!(a.insert($len - 1, 20, Ord::cmp), None);
             a.check();
         }
     };
@@ -2140,13 +2375,13 @@ fn test_append_drop_leak() {
     let c = CrashTestDummy::new(2);
     let mut left = BTreeMap::default();
     let mut right = BTreeMap::default();
-    left.insert(a.spawn(Panic::Never), ());
-    left.insert(b.spawn(Panic::InDrop), ()); // first duplicate key, dropped during append
-    left.insert(c.spawn(Panic::Never), ());
-    right.insert(b.spawn(Panic::Never), ());
-    right.insert(c.spawn(Panic::Never), ());
+    left.insert(a.spawn(Panic::Never), (), Ord::cmp);
+    left.insert(b.spawn(Panic::InDrop), (), Ord::cmp); // first duplicate key, dropped during append
+    left.insert(c.spawn(Panic::Never), (), Ord::cmp);
+    right.insert(b.spawn(Panic::Never), (), Ord::cmp);
+    right.insert(c.spawn(Panic::Never), (), Ord::cmp);
 
-    catch_unwind(move || left.append(&mut right)).unwrap_err();
+    catch_unwind(move || left.append(&mut right, |x, y| x.0.cmp(&y.0))).unwrap_err();
     assert_eq!(a.dropped(), 1);
     assert_eq!(b.dropped(), 1); // should be 2 were it not for Rust issue #47949
     assert_eq!(c.dropped(), 2);
@@ -2155,24 +2390,348 @@ fn test_append_drop_leak() {
 #[test]
 fn test_append_ord_chaos() {
     let mut map1 = BTreeMap::default();
-    map1.insert(Cyclic3::A, ());
-    map1.insert(Cyclic3::B, ());
+    map1.insert(Cyclic3::A, (), Ord::cmp);
+    map1.insert(Cyclic3::B, (), Ord::cmp);
     let mut map2 = BTreeMap::default();
-    map2.insert(Cyclic3::A, ());
-    map2.insert(Cyclic3::B, ());
-    map2.insert(Cyclic3::C, ()); // lands first, before A
-    map2.insert(Cyclic3::B, ()); // lands first, before C
+    map2.insert(Cyclic3::A, (), Ord::cmp);
+    map2.insert(Cyclic3::B, (), Ord::cmp);
+    map2.insert(Cyclic3::C, (), Ord::cmp); // lands first, before A
+    map2.insert(Cyclic3::B, (), Ord::cmp); // lands first, before C
     map1.check();
     map2.check(); // keys are not unique but still strictly ascending
     assert_eq!(map1.len(), 2);
     assert_eq!(map2.len(), 4);
-    map1.append(&mut map2);
+    map1.append(&mut map2, |x, y| x.0.cmp(&y.0));
     assert_eq!(map1.len(), 5);
     assert_eq!(map2.len(), 0);
     map1.check();
     map2.check();
 }
 
+// Merges two sorted lists of pairs the slow way, adding up the values of
+// common keys.
+fn sum_merged(a: &[(u32, u32)], b: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut merged = Vec::from_iter(a.iter().chain(b).map(|&(k, _)| k));
+    merged.sort();
+    merged.dedup();
+    merged
+        .into_iter()
+        .map(|k| {
+            let sum = a.iter().chain(b).filter(|&&(x, _)| x == k).map(|&(_, v)| v).sum();
+            (k, sum)
+        })
+        .collect()
+}
+
+#[test]
+fn test_merge_with() {
+    let mut rng = DeterministicRng::new();
+    for (len_a, len_b) in [(0, 0), (0, 9), (9, 0), (1, MIN_INSERTS_HEIGHT_2), (300, 200)] {
+        let a = Vec::from_iter((0..len_a).map(|_| (rng.next() % 400, rng.next() % 100)));
+        let b = Vec::from_iter((0..len_b).map(|_| (rng.next() % 400, rng.next() % 100)));
+        let map_a = BTreeMap::from_iter(a);
+        let map_b = BTreeMap::from_iter(b);
+        let pairs_a = Vec::from_iter(map_a.iter().map(|(&k, &v)| (k, v)));
+        let pairs_b = Vec::from_iter(map_b.iter().map(|(&k, &v)| (k, v)));
+        let expected = sum_merged(&pairs_a, &pairs_b);
+
+        let union = map_a.union_with(&map_b, Ord::cmp, |_, x, y| x + y);
+        union.check();
+        assert!(union.into_iter().eq(expected.iter().copied()));
+
+        let mut merged = map_a;
+        merged.merge_with(map_b, Ord::cmp, |_, x, y| x + y);
+        merged.check();
+        assert!(merged.into_iter().eq(expected));
+    }
+}
+
+#[test]
+fn test_set_algebra_by() {
+    let mut rng = DeterministicRng::new();
+    // Sizes both similar and lopsided enough to look keys up instead.
+    for (len_a, len_b) in [(0, 0), (0, 50), (50, 0), (3, 200), (200, 3), (300, 250)] {
+        let a = BTreeMap::from_iter((0..len_a).map(|_| (rng.next() % 500, rng.next())));
+        let b = BTreeMap::from_iter((0..len_b).map(|_| (rng.next() % 500, rng.next())));
+        let in_a = |k: &u32| a.contains_key(|x| k.cmp(x));
+        let in_b = |k: &u32| b.contains_key(|x| k.cmp(x));
+
+        let intersection = a.intersection_by(&b, Ord::cmp, |_, x, y| x ^ y);
+        intersection.check();
+        let expected =
+            a.iter().filter(|(k, _)| in_b(k)).map(|(&k, &v)| (k, v ^ b.get(|x| k.cmp(x)).unwrap()));
+        assert!(intersection.into_iter().eq(expected));
+
+        let difference = a.difference_by(&b, Ord::cmp);
+        difference.check();
+        assert!(difference.iter().eq(a.iter().filter(|(k, _)| !in_b(k))));
+
+        let symmetric_difference = a.symmetric_difference_by(&b, Ord::cmp);
+        symmetric_difference.check();
+        let mut expected = Vec::from_iter(
+            a.iter().filter(|(k, _)| !in_b(k)).chain(b.iter().filter(|(k, _)| !in_a(k))),
+        );
+        expected.sort();
+        assert!(symmetric_difference.iter().eq(expected));
+    }
+}
+
+#[test]
+fn test_concat() {
+    let data = rand_data(if cfg!(miri) { 529 } else { 1529 });
+    let map = BTreeMap::from_iter(data);
+    let keys = Vec::from_iter(map.keys().copied());
+    // Splits at points that leave trees of all sorts of heights on either side.
+    for at in [0, 1, 7, 40, 300, keys.len() / 2, keys.len() - 100, keys.len() - 1, keys.len()] {
+        let mut left = BTreeMap::from_iter(map.iter().map(|(&k, &v)| (k, v)));
+        let right = match keys.get(at) {
+            Some(&key) => left.split_off(|k| key.cmp(k)),
+            None => BTreeMap::new(),
+        };
+        left.concat(right, Ord::cmp);
+        left.check();
+        assert!(left.iter().eq(map.iter()));
+    }
+}
+
+#[test]
+#[should_panic(expected = "keys of the concatenated maps overlap in BTreeMap")]
+fn test_concat_overlap() {
+    let mut left = BTreeMap::from_iter((0..10).map(|i| (i, i)));
+    let right = BTreeMap::from_iter((9..20).map(|i| (i, i)));
+    left.concat(right, Ord::cmp);
+}
+
+#[test]
+fn test_transfer_range() {
+    let size = MIN_INSERTS_HEIGHT_2 as u32;
+    for (start, end) in [(0, 1), (10, 60), (0, size), (size - 5, size), (30, 30)] {
+        let mut a = BTreeMap::from_iter((0..size).map(|i| (i, i)));
+        // Keys on both sides of the range, but none in it.
+        let mut b = BTreeMap::from_iter((0..size * 2).map(|i| (i, i)));
+        drop(b.remove_range(
+            |k| start.cmp(k),
+            SearchBoundCustom::Included,
+            |k| end.cmp(k),
+            SearchBoundCustom::Excluded,
+        ));
+        a.transfer_range(
+            &mut b,
+            |k| start.cmp(k),
+            SearchBoundCustom::Included,
+            |k| end.cmp(k),
+            SearchBoundCustom::Excluded,
+        );
+        a.check();
+        b.check();
+        assert!(a.keys().copied().eq((0..start).chain(end..size)));
+        assert!(b.keys().copied().eq(0..size * 2));
+    }
+}
+
+#[test]
+#[should_panic(expected = "the map to transfer to holds keys in the range")]
+fn test_transfer_range_overlap() {
+    let mut a = BTreeMap::from_iter((0..10).map(|i| (i, i)));
+    let mut b = BTreeMap::from_iter((5..6).map(|i| (i, i)));
+    a.transfer_range(
+        &mut b,
+        |k| 3.cmp(k),
+        SearchBoundCustom::Included,
+        |k| 7.cmp(k),
+        SearchBoundCustom::Included,
+    );
+}
+
+#[test]
+fn test_merge_with_argument_order() {
+    let mut a = BTreeMap::new();
+    a.insert(1, "a1", Ord::cmp);
+    a.insert(2, "a2", Ord::cmp);
+    let mut b = BTreeMap::new();
+    b.insert(2, "b2", Ord::cmp);
+    b.insert(3, "b3", Ord::cmp);
+    let mut resolved = Vec::new();
+    a.merge_with(b, Ord::cmp, |&k, x, y| {
+        resolved.push((k, x, y));
+        y
+    });
+    assert_eq!(resolved, [(2, "a2", "b2")]);
+    assert!(a.into_iter().eq([(1, "a1"), (2, "b2"), (3, "b3")]));
+}
+
+#[test]
+fn test_merge_with_resolve_panic_leak() {
+    let dummies = Vec::from_iter((0..4).map(CrashTestDummy::new));
+    let mut a = BTreeMap::new();
+    let mut b = BTreeMap::new();
+    for i in 0..4 {
+        a.insert(i, dummies[i].spawn(Panic::Never), Ord::cmp);
+        b.insert(i, dummies[i].spawn(Panic::Never), Ord::cmp);
+    }
+
+    catch_unwind(AssertUnwindSafe(|| {
+        a.merge_with(b, Ord::cmp, |&k, x, _| if k == 2 { panic!("resolve") } else { x })
+    }))
+    .unwrap_err();
+
+    // Whatever made it into the map before the panic stays there; the rest is
+    // dropped along the way.
+    a.check();
+    assert!(a.keys().copied().eq(0..2));
+    drop(a);
+    for dummy in &dummies {
+        assert_eq!(dummy.dropped(), 2);
+    }
+}
+
+#[test]
+fn test_from_sorted_iter_by() {
+    let size = if cfg!(miri) { MIN_INSERTS_HEIGHT_2 } else { 10_000 };
+    let map = BTreeMap::from_sorted_iter_by((0..size).map(|i| (i, i * 2)), Ord::cmp, Keep::Last);
+    map.check();
+    assert_eq!(map.len(), size);
+    assert!(map.iter().map(|(&k, &v)| (k, v)).eq((0..size).map(|i| (i, i * 2))));
+
+    let empty = BTreeMap::<i32, ()>::from_sorted_iter_by([], Ord::cmp, Keep::First);
+    empty.check();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_from_sorted_iter_by_duplicates() {
+    let rows = [(1, 'a'), (1, 'b'), (2, 'c'), (3, 'd'), (3, 'e'), (3, 'f')];
+
+    let first = BTreeMap::from_sorted_iter_by(rows, Ord::cmp, Keep::First);
+    first.check();
+    assert_eq!(first.into_iter().collect::<Vec<_>>(), [(1, 'a'), (2, 'c'), (3, 'd')]);
+
+    let last = BTreeMap::from_sorted_iter_by(rows, Ord::cmp, Keep::Last);
+    last.check();
+    assert_eq!(last.into_iter().collect::<Vec<_>>(), [(1, 'b'), (2, 'c'), (3, 'f')]);
+
+    let joined = BTreeMap::from_sorted_iter_by(
+        rows.map(|(k, v)| (k, String::from(v))),
+        Ord::cmp,
+        |kept: &mut String, next: String| kept.push_str(&next),
+    );
+    joined.check();
+    assert_eq!(joined.values().map(String::as_str).collect::<Vec<_>>(), ["ab", "c", "def"]);
+}
+
+#[test]
+fn test_from_sorted_iter_by_keeps_first_key() {
+    // The keys compare equal by length only, so it shows which one survives.
+    let by_len = |a: &&str, b: &&str| a.len().cmp(&b.len());
+    let rows = [("a", 1), ("b", 2), ("cc", 3)];
+
+    let last = BTreeMap::from_sorted_iter_by(rows, by_len, Keep::Last);
+    assert_eq!(last.into_iter().collect::<Vec<_>>(), [("b", 2), ("cc", 3)]);
+
+    let summed = BTreeMap::from_sorted_iter_by(rows, by_len, |sum: &mut i32, n| *sum += n);
+    assert_eq!(summed.into_iter().collect::<Vec<_>>(), [("a", 3), ("cc", 3)]);
+}
+
+#[test]
+fn test_from_sorted_iter_by_drop_panic_leak() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let c = CrashTestDummy::new(2);
+    let rows =
+        [(0, a.spawn(Panic::Never)), (0, b.spawn(Panic::InDrop)), (1, c.spawn(Panic::Never))];
+
+    catch_unwind(move || BTreeMap::from_sorted_iter_by(rows, Ord::cmp, Keep::First)).unwrap_err();
+    assert_eq!(a.dropped(), 1);
+    assert_eq!(b.dropped(), 1);
+    assert_eq!(c.dropped(), 1);
+}
+
+#[test]
+fn test_from_unsorted_by() {
+    let mut data = rand_data(if cfg!(miri) { 50 } else { 1000 });
+    // Repeat a few keys with other values, to check that the sort is stable.
+    let repeats: Vec<_> = data.iter().step_by(7).map(|&(k, v)| (k, !v)).collect();
+    data.extend(repeats);
+
+    let expected = BTreeMap::from_iter(data.clone());
+    let map = BTreeMap::from_unsorted_by(data.clone(), Ord::cmp, Keep::Last);
+    map.check();
+    assert_eq!(map, expected);
+
+    let map = BTreeMap::from_unsorted_by(data.clone(), Ord::cmp, Keep::First);
+    map.check();
+    data.reverse();
+    assert_eq!(map, BTreeMap::from_iter(data));
+
+    let reversed = BTreeMap::from_unsorted_by(
+        vec![(1, ()), (3, ()), (2, ())],
+        |a: &i32, b| b.cmp(a),
+        Keep::First,
+    );
+    assert_eq!(reversed.into_keys().collect::<Vec<_>>(), [3, 2, 1]);
+
+    assert!(BTreeMap::<i32, i32>::from_unsorted_by(Vec::new(), Ord::cmp, Keep::Last).is_empty());
+}
+
+#[test]
+fn test_merge_beats_inserting() {
+    assert!(!merge_beats_inserting::<6>(0, Consts::CAPACITY));
+    assert!(merge_beats_inserting::<6>(0, Consts::CAPACITY + 1));
+    assert!(!merge_beats_inserting::<6>(1_000_000, 1000));
+    assert!(merge_beats_inserting::<6>(1_000_000, 100_000));
+}
+
+#[test]
+fn test_extend_by() {
+    let size = if cfg!(miri) { MIN_INSERTS_HEIGHT_2 } else { 1000 };
+    let mut map = BTreeMap::default();
+    // Small batches are inserted.
+    map.extend_by((0..size).step_by(2).take(5).map(|i| (i, 0)), Ord::cmp);
+    map.check();
+    assert_eq!(map.len(), 5);
+    // Large batches are merged.
+    map.extend_by((0..size).rev().map(|i| (i, i)), Ord::cmp);
+    map.check();
+    assert!(map.iter().map(|(&k, &v)| (k, v)).eq((0..size).map(|i| (i, i))));
+    // Into an empty map, large batches are built in bulk.
+    let mut map = BTreeMap::default();
+    map.extend_by((0..size).map(|i| (i, ())), Ord::cmp);
+    map.check();
+    assert_eq!(map.len(), size);
+}
+
+#[test]
+fn test_extend_by_duplicates() {
+    // The keys compare equal by length only, so it shows which one survives.
+    let by_len = |a: &&str, b: &&str| a.len().cmp(&b.len());
+    for filler in [0, 100] {
+        let mut map = BTreeMap::default();
+        map.insert("a", 0, by_len);
+        let batch = ["bb", "cc", "d"].into_iter().enumerate().map(|(i, k)| (k, i + 1));
+        let padding = (0..filler).map(|_| ("eee", 9));
+        map.extend_by(batch.chain(padding), by_len);
+        let expected: &[_] =
+            if filler == 0 { &[("d", 3), ("cc", 2)] } else { &[("d", 3), ("cc", 2), ("eee", 9)] };
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn test_extend_by_drop_panic_leak() {
+    let a = CrashTestDummy::new(0);
+    let b = CrashTestDummy::new(1);
+    let c = CrashTestDummy::new(2);
+    let mut map = BTreeMap::default();
+    map.insert(a.spawn(Panic::Never), (), Ord::cmp);
+    map.insert(b.spawn(Panic::InDrop), (), Ord::cmp); // replaced during the merge
+    let batch = (0..20).map(|_| (b.spawn(Panic::Never), ())).chain([(c.spawn(Panic::Never), ())]);
+
+    catch_unwind(move || map.extend_by(batch, Ord::cmp)).unwrap_err();
+    assert_eq!(a.dropped(), 1);
+    assert_eq!(b.dropped(), 20); // should be 21 were it not for Rust issue #47949
+    assert_eq!(c.dropped(), 1);
+}
+
 fn rand_data(len: usize) -> Vec<(u32, u32)> {
     let mut rng = DeterministicRng::new();
     Vec::from_iter((0..len).map(|_| (rng.next(), rng.next())))
@@ -2183,7 +2742,7 @@ fn test_split_off_empty_right() {
     let mut data = rand_data(173);
 
     let mut map = BTreeMap::from_iter(data.clone());
-    let right = map.split_off(&(data.iter().max().unwrap().0 + 1));
+    let right = map.split_off(|k| (data.iter().max().unwrap().0 + 1).cmp(k));
     map.check();
     right.check();
 
@@ -2197,7 +2756,7 @@ fn test_split_off_empty_left() {
     let mut data = rand_data(314);
 
     let mut map = BTreeMap::from_iter(data.clone());
-    let right = map.split_off(&data.iter().min().unwrap().0);
+    let right = map.split_off(|k| (data.iter().min().unwrap().0).cmp(k));
     map.check();
     right.check();
 
@@ -2212,7 +2771,7 @@ fn test_split_off_empty_left() {
 fn test_split_off_tiny_left_height_2() {
     let pairs = (0..MIN_INSERTS_HEIGHT_2).map(|i| (i, i));
     let mut left = BTreeMap::from_iter(pairs.clone());
-    let right = left.split_off(&1);
+    let right = left.split_off(|k| 1.cmp(k));
     left.check();
     right.check();
     assert_eq!(left.len(), 1);
@@ -2229,7 +2788,7 @@ fn test_split_off_tiny_right_height_2() {
     let last = MIN_INSERTS_HEIGHT_2 - 1;
     let mut left = BTreeMap::from_iter(pairs.clone());
     assert_eq!(*left.last_key_value().unwrap().0, last);
-    let right = left.split_off(&last);
+    let right = left.split_off(|k| last.cmp(k));
     left.check();
     right.check();
     assert_eq!(left.len(), MIN_INSERTS_HEIGHT_2 - 1);
@@ -2241,7 +2800,7 @@ fn test_split_off_tiny_right_height_2() {
 #[test]
 fn test_split_off_halfway() {
     let mut rng = DeterministicRng::new();
-    for &len in &[node::CAPACITY, 25, 50, 75, 100] {
+    for &len in &[Consts::CAPACITY, 25, 50, 75, 100] {
         let mut data = Vec::from_iter((0..len).map(|_| (rng.next(), ())));
         // Insertion in non-ascending order creates some variation in node length.
         let mut map = BTreeMap::from_iter(data.iter().copied());
@@ -2249,7 +2808,7 @@ fn test_split_off_halfway() {
         let small_keys = data.iter().take(len / 2).map(|kv| kv.0);
         let large_keys = data.iter().skip(len / 2).map(|kv| kv.0);
         let split_key = large_keys.clone().next().unwrap();
-        let right = map.split_off(&split_key);
+        let right = map.split_off(|k| split_key.cmp(k));
         map.check();
         right.check();
         assert!(map.keys().copied().eq(small_keys));
@@ -2266,7 +2825,7 @@ fn test_split_off_large_random_sorted() {
 
     let mut map = BTreeMap::from_iter(data.clone());
     let key = data[data.len() / 2].0;
-    let right = map.split_off(&key);
+    let right = map.split_off(|k| key.cmp(k));
     map.check();
     right.check();
 
@@ -2274,6 +2833,280 @@ fn test_split_off_large_random_sorted() {
     assert!(right.into_iter().eq(data.into_iter().filter(|x| x.0 >= key)));
 }
 
+impl<K: Ord, V> BTreeMap<K, V> {
+    fn remove_range_ord<R: RangeBounds<K>>(&mut self, range: R) -> Self {
+        let (start, lower_bound, end, upper_bound) = ord_bounds(&range);
+        self.remove_range(
+            |k| start.map_or(Ordering::Equal, |s| s.cmp(k)),
+            lower_bound,
+            |k| end.map_or(Ordering::Equal, |e| e.cmp(k)),
+            upper_bound,
+        )
+    }
+}
+
+#[test]
+fn test_remove_range() {
+    for size in [0, 1, Consts::CAPACITY, MIN_INSERTS_HEIGHT_1, MIN_INSERTS_HEIGHT_2] {
+        let pairs = Vec::from_iter((0..size as u32).map(|i| (i * 2, i)));
+        let limit = size as u32 * 2 + 2;
+        for start in (0..limit).step_by(3) {
+            for end in (start..limit).step_by(5) {
+                for range in [
+                    (Included(start), Included(end)),
+                    (Included(start), Excluded(end)),
+                    (Excluded(start), Included(end)),
+                    (Unbounded, Included(end)),
+                    (Excluded(start), Unbounded),
+                    (Unbounded, Unbounded),
+                ] {
+                    if start == end && range == (Excluded(start), Excluded(end)) {
+                        continue;
+                    }
+                    let mut map = BTreeMap::from_iter(pairs.iter().copied());
+                    let removed = map.remove_range_ord(range);
+                    map.check();
+                    removed.check();
+                    let (inside, outside): (Vec<_>, Vec<_>) =
+                        pairs.iter().copied().partition(|(k, _)| range.contains(k));
+                    assert!(removed.into_iter().eq(inside));
+                    assert!(map.into_iter().eq(outside));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_remove_range_random() {
+    let mut rng = DeterministicRng::new();
+    // Miri is too slow
+    let len = if cfg!(miri) { 529 } else { 2529 };
+    let mut map = BTreeMap::from_iter(rand_data(len));
+    let mut expected = Vec::from_iter(map.iter().map(|(&k, &v)| (k, v)));
+    while !map.is_empty() {
+        // Cuts out ranges of all sizes, leaving trees of different heights
+        // on either side to be joined.
+        let i = rng.next() as usize % expected.len();
+        let count = rng.next() as usize % (expected.len() - i).min(len / 8) + 1;
+        let range = expected[i].0..=expected[i + count - 1].0;
+        let removed = map.remove_range_ord(range);
+        map.check();
+        removed.check();
+        assert!(removed.into_iter().eq(expected.drain(i..i + count)));
+        assert!(map.iter().map(|(&k, &v)| (k, v)).eq(expected.iter().copied()));
+    }
+}
+
+#[test]
+fn test_remove_range_backwards() {
+    let mut map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_2).map(|i| (i, i)));
+    let removed = map.remove_range(
+        |k| 60.cmp(k),
+        SearchBoundCustom::Included,
+        |k| 30.cmp(k),
+        SearchBoundCustom::Included,
+    );
+    map.check();
+    removed.check();
+    assert!(removed.is_empty());
+    assert_eq!(map.len(), MIN_INSERTS_HEIGHT_2);
+}
+
+#[test]
+fn test_truncate() {
+    let pairs = (0..MIN_INSERTS_HEIGHT_2).map(|i| (i, i));
+    for at in [0, 1, MIN_INSERTS_HEIGHT_2 / 2, MIN_INSERTS_HEIGHT_2 - 1, MIN_INSERTS_HEIGHT_2] {
+        let mut map = BTreeMap::from_iter(pairs.clone());
+        map.truncate_front(|k| at.cmp(k));
+        map.check();
+        assert!(map.keys().copied().eq(at..MIN_INSERTS_HEIGHT_2));
+
+        let mut map = BTreeMap::from_iter(pairs.clone());
+        map.truncate_back(|k| at.cmp(k));
+        map.check();
+        assert!(map.keys().copied().eq(0..at));
+    }
+    let mut map = BTreeMap::<usize, usize>::new();
+    map.truncate_front(|k| 0.cmp(k));
+    map.truncate_back(|k| 0.cmp(k));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_drain_range() {
+    let size = MIN_INSERTS_HEIGHT_2;
+    let mut map = BTreeMap::from_iter((0..size).map(|i| (i, i)));
+    let mut drained = map.drain_range(
+        |k| 10.cmp(k),
+        SearchBoundCustom::Excluded,
+        |k| 70.cmp(k),
+        SearchBoundCustom::Included,
+    );
+    assert_eq!(drained.len(), 60);
+    let mut expected = 11..=70;
+    // Takes from both ends in turn.
+    loop {
+        let front = drained.next();
+        assert_eq!(front.map(|(k, _)| k), expected.next());
+        let back = drained.next_back();
+        assert_eq!(back.map(|(k, _)| k), expected.next_back());
+        if back.is_none() {
+            break;
+        }
+    }
+    assert_eq!(drained.next(), None);
+    drop(drained);
+    map.check();
+    assert!(map.keys().copied().eq((0..=10).chain(71..size)));
+}
+
+#[test]
+fn test_drain_range_drop_and_forget() {
+    let dummies = Vec::from_iter((0..5).map(CrashTestDummy::new));
+    let mut map = BTreeMap::from_iter((0..5).map(|i| (i, dummies[i].spawn(Panic::Never))));
+    let mut drained = map.drain_range(
+        |k| 1.cmp(k),
+        SearchBoundCustom::Included,
+        |k| 3.cmp(k),
+        SearchBoundCustom::Included,
+    );
+    drop(drained.next_back());
+    assert_eq!(dummies[3].dropped(), 1);
+    // Whatever the iterator still holds is dropped with it.
+    drop(drained);
+    assert_eq!(dummies[1].dropped(), 1);
+    assert_eq!(dummies[2].dropped(), 1);
+    map.check();
+    assert!(map.keys().copied().eq([0, 4]));
+
+    // A leaked iterator leaks what it holds, but leaves the map in order.
+    mem::forget(map.drain_range(
+        |k| 4.cmp(k),
+        SearchBoundCustom::Included,
+        |_| Ordering::Equal,
+        SearchBoundCustom::AllIncluded,
+    ));
+    assert_eq!(dummies[4].dropped(), 0);
+    map.check();
+    assert!(map.keys().copied().eq([0]));
+}
+
+#[cfg(feature = "order_statistics")]
+fn range_len_ord<R: RangeBounds<u32>>(map: &BTreeMap<u32, u32>, range: R) -> usize {
+    let (start, lower_bound, end, upper_bound) = ord_bounds(&range);
+    map.range_len(
+        |k| start.map_or(Ordering::Equal, |s| s.cmp(k)),
+        lower_bound,
+        |k| end.map_or(Ordering::Equal, |e| e.cmp(k)),
+        upper_bound,
+    )
+}
+
+#[test]
+#[cfg(feature = "order_statistics")]
+fn test_get_index_and_rank() {
+    let mut map = BTreeMap::from_iter(rand_data(1000));
+    // Removal in non-ascending order exercises all of the rebalancing code.
+    for (k, _) in rand_data(500) {
+        map.remove(|x| k.cmp(x));
+    }
+    map.check();
+    let pairs = Vec::from_iter(map.iter().map(|(&k, &v)| (k, v)));
+    for (i, (k, v)) in pairs.iter().enumerate() {
+        assert_eq!(map.get_index(i), Some((k, v)));
+        assert_eq!(map.rank(|x| k.cmp(x)), Ok(i));
+        assert_eq!(map.rank(|x| (*k as u64 * 2 + 1).cmp(&(*x as u64 * 2))), Err(i + 1));
+    }
+    assert_eq!(map.get_index(pairs.len()), None);
+    assert_eq!(map.rank(|x| 0.cmp(x)), if pairs[0].0 == 0 { Ok(0) } else { Err(0) });
+    assert_eq!(BTreeMap::<u32, u32>::new().get_index(0), None);
+    assert_eq!(BTreeMap::<u32, u32>::new().rank(|x| 0.cmp(x)), Err(0));
+}
+
+#[test]
+#[cfg(feature = "order_statistics")]
+fn test_range_len() {
+    let map = BTreeMap::from_iter((0..MIN_INSERTS_HEIGHT_2 as u32).map(|i| (i * 2, i)));
+    let size = MIN_INSERTS_HEIGHT_2 as u32 * 2;
+    for start in (0..size + 2).step_by(3) {
+        for end in (start..size + 2).step_by(5) {
+            for range in [
+                (Included(start), Included(end)),
+                (Included(start), Excluded(end)),
+                (Excluded(start), Included(end)),
+                (Unbounded, Included(end)),
+                (Excluded(start), Unbounded),
+            ] {
+                if start == end && range == (Excluded(start), Excluded(end)) {
+                    continue;
+                }
+                assert_eq!(range_len_ord(&map, range), map.range_ord(range).count());
+            }
+        }
+    }
+    assert_eq!(range_len_ord(&BTreeMap::new(), ..), 0);
+}
+
+#[test]
+#[cfg(feature = "order_statistics")]
+fn test_iter_nth() {
+    let size = MIN_INSERTS_HEIGHT_2;
+    let mut map = BTreeMap::from_iter((0..size).map(|i| (i, i)));
+    for step in [0, 1, 5, Consts::CAPACITY, size / 2] {
+        let mut iter = map.iter();
+        let mut expected = 0..size;
+        loop {
+            assert_eq!(iter.nth(step).map(|(&k, _)| k), expected.nth(step));
+            assert_eq!(iter.nth_back(step).map(|(&k, _)| k), expected.nth_back(step));
+            assert_eq!(iter.len(), expected.len());
+            if expected.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = map.iter_mut();
+        let mut expected = 0..size;
+        while let Some((&k, v)) = iter.nth(step) {
+            assert_eq!(Some(k), expected.nth(step));
+            *v += 1;
+        }
+        assert_eq!(expected.nth(step), None);
+    }
+    assert!(map.keys().zip(map.values()).all(|(k, v)| v - k <= 5));
+    assert_eq!(map.keys().nth(size - 1), Some(&(size - 1)));
+    assert_eq!(map.keys().nth(size), None);
+    assert_eq!(map.values().nth_back(size - 1), map.get(|k| 0.cmp(k)));
+    map.check();
+}
+
+#[test]
+#[cfg(feature = "order_statistics")]
+fn test_split_off_at_index() {
+    let data = rand_data(MIN_INSERTS_HEIGHT_2);
+    let mut sorted = data.clone();
+    sorted.sort();
+    sorted.dedup_by_key(|kv| kv.0);
+    for at in 0..=sorted.len() {
+        let mut left = BTreeMap::from_iter(data.clone());
+        let right = left.split_off_at_index(at);
+        left.check();
+        right.check();
+        assert!(left.into_iter().eq(sorted[..at].iter().copied()));
+        assert!(right.into_iter().eq(sorted[at..].iter().copied()));
+    }
+}
+
+#[test]
+#[cfg(feature = "order_statistics")]
+#[should_panic(expected = "`at` split index (is 4) should be <= len (is 3)")]
+fn test_split_off_at_index_out_of_bounds() {
+    let mut map = BTreeMap::from([(1, ()), (2, ()), (3, ())]);
+    map.split_off_at_index(4);
+}
+
 #[test]
 fn test_into_iter_drop_leak_height_0() {
     let a = CrashTestDummy::new(0);
@@ -2281,12 +3114,12 @@ fn test_into_iter_drop_leak_height_0() {
     let c = CrashTestDummy::new(2);
     let d = CrashTestDummy::new(3);
     let e = CrashTestDummy::new(4);
-    let mut map = BTreeMap::<_, _, OrdTotalOrder<str>>::default();
-    map.insert("a", a.spawn(Panic::Never));
-    map.insert("b", b.spawn(Panic::Never));
-    map.insert("c", c.spawn(Panic::Never));
-    map.insert("d", d.spawn(Panic::InDrop));
-    map.insert("e", e.spawn(Panic::Never));
+    let mut map = BTreeMap::default();
+    map.insert("a", a.spawn(Panic::Never), Ord::cmp);
+    map.insert("b", b.spawn(Panic::Never), Ord::cmp);
+    map.insert("c", c.spawn(Panic::Never), Ord::cmp);
+    map.insert("d", d.spawn(Panic::InDrop), Ord::cmp);
+    map.insert("e", e.spawn(Panic::Never), Ord::cmp);
 
     catch_unwind(move || drop(map.into_iter())).unwrap_err();
 
@@ -2343,8 +3176,8 @@ fn test_insert_remove_intertwined() {
     let offset = 165; // somewhat arbitrarily chosen to cover some code paths
     for _ in 0..loops {
         i = (i + offset) & 0xFF;
-        map.insert(i, i);
-        map.remove(&(0xFF - i));
+        map.insert(i, i, Ord::cmp);
+        map.remove(|k| (0xFF - i).cmp(k));
     }
     map.check();
 }
@@ -2358,8 +3191,8 @@ fn test_insert_remove_intertwined_ord_chaos() {
     let offset = 165; // more arbitrarily copied from above
     for _ in 0..loops {
         i = (i + offset) & 0xFF;
-        map.insert(Governed(i, &gov), ());
-        map.remove(&Governed(0xFF - i, &gov));
+        map.insert(Governed(i, &gov), (), Ord::cmp);
+        map.remove(|k| Governed(0xFF - i, &gov).cmp(k));
         gov.flip();
     }
     map.check_invariants();
@@ -2377,7 +3210,7 @@ fn from_array() {
 fn test_cursor() {
     let map = BTreeMap::from([(1, 'a'), (2, 'b'), (3, 'c')]);
 
-    let mut cur = map.lower_bound(Bound::<&i32>::Unbounded);
+    let mut cur = map.lower_bound(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
     assert_eq!(cur.key(), Some(&1));
     cur.move_next();
     assert_eq!(cur.key(), Some(&2));
@@ -2386,7 +3219,7 @@ fn test_cursor() {
     assert_eq!(cur.key(), Some(&1));
     assert_eq!(cur.peek_prev(), None);
 
-    let mut cur = map.upper_bound(Bound::<&i32>::Excluded(&1));
+    let mut cur = map.upper_bound(|k| 1.cmp(k), SearchBoundCustom::Excluded);
     assert_eq!(cur.key(), None);
     cur.move_next();
     assert_eq!(cur.key(), Some(&1));
@@ -2399,7 +3232,7 @@ fn test_cursor() {
 #[cfg(feature = "btree_cursors")]
 fn test_cursor_mut() {
     let mut map = BTreeMap::from([(1, 'a'), (3, 'c'), (5, 'e')]);
-    let mut cur = map.lower_bound_mut(Bound::<&i32>::Excluded(&3));
+    let mut cur = map.lower_bound_mut(|k| 3.cmp(k), SearchBoundCustom::Excluded);
     assert_eq!(cur.key(), Some(&5));
     cur.insert_before(4, 'd');
     assert_eq!(cur.key(), Some(&5));
@@ -2414,7 +3247,7 @@ fn test_cursor_mut() {
     assert_eq!(cur.key(), None);
     assert_eq!(map, BTreeMap::from([(0, '?'), (1, 'a'), (3, 'c'), (4, 'd'), (5, 'e'), (6, 'f')]));
 
-    let mut cur = map.upper_bound_mut(Bound::<&i32>::Included(&5));
+    let mut cur = map.upper_bound_mut(|k| 5.cmp(k), SearchBoundCustom::Included);
     assert_eq!(cur.key(), Some(&5));
     assert_eq!(cur.remove_current(), Some((5, 'e')));
     assert_eq!(cur.key(), Some(&6));
@@ -2422,3 +3255,573 @@ fn test_cursor_mut() {
     assert_eq!(cur.key(), Some(&4));
     assert_eq!(map, BTreeMap::from([(0, '?'), (1, 'a'), (3, 'c'), (4, 'd')]));
 }
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_peek_prev_from_ghost() {
+    let mut map = BTreeMap::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+    let mut cur = map.upper_bound_mut(|k| 1.cmp(k), SearchBoundCustom::Excluded);
+    assert_eq!(cur.key(), None);
+    assert_eq!(cur.peek_prev(), Some((&3, &mut 'c')));
+    assert_eq!(cur.peek_next(), Some((&1, &mut 'a')));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_insert_by() {
+    let mut map = BTreeMap::from([(2, 'b'), (4, 'd')]);
+    let mut cur = map.lower_bound_mut(|k| 2.cmp(k), SearchBoundCustom::Included);
+    let err = cur.insert_after_by(2, 'x', Ord::cmp).unwrap_err();
+    assert_eq!((err.key, err.value), (2, 'x'));
+    assert_eq!(cur.insert_after_by(4, 'x', Ord::cmp).unwrap_err().key, 4);
+    assert_eq!(cur.insert_after_by(1, 'x', Ord::cmp).unwrap_err().key, 1);
+    assert_eq!(cur.insert_before_by(2, 'x', Ord::cmp).unwrap_err().key, 2);
+    assert_eq!(cur.insert_before_by(3, 'x', Ord::cmp).unwrap_err().key, 3);
+    assert!(cur.insert_before_by(1, 'a', Ord::cmp).is_ok());
+    assert!(cur.insert_after_by(3, 'c', Ord::cmp).is_ok());
+    assert_eq!(cur.key(), Some(&2));
+    assert_eq!(cur.insert_before_by(0, 'x', Ord::cmp).unwrap_err().key, 0);
+
+    // The ghost sits between the last and the first element.
+    cur.move_prev();
+    cur.move_prev();
+    assert_eq!(cur.key(), None);
+    assert_eq!(cur.insert_after_by(1, 'x', Ord::cmp).unwrap_err().key, 1);
+    assert!(cur.insert_after_by(0, '0', Ord::cmp).is_ok());
+    assert_eq!(cur.insert_before_by(4, 'x', Ord::cmp).unwrap_err().key, 4);
+    assert!(cur.insert_before_by(5, 'e', Ord::cmp).is_ok());
+    assert_eq!(cur.key(), None);
+    assert_eq!(map, BTreeMap::from([(0, '0'), (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd'), (5, 'e')]));
+
+    let mut map = BTreeMap::default();
+    let mut cur = map.lower_bound_mut(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
+    assert!(cur.insert_before_by(7, 'g', Ord::cmp).is_ok());
+    assert_eq!(cur.insert_after_by(7, 'x', Ord::cmp).unwrap_err().key, 7);
+    assert_eq!(map, BTreeMap::from([(7, 'g')]));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_try_insert() {
+    let mut map = BTreeMap::default();
+    let mut cur = map.lower_bound_mut(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
+    assert_eq!(cur.try_insert(5, 50, Ord::cmp), Ok(&mut 50));
+    assert_eq!(cur.key(), Some(&5));
+
+    // Enough elements to grow the tree, inserted in an order that keeps
+    // landing on both sides of the cursor.
+    for i in (0..MIN_INSERTS_HEIGHT_2).rev().chain(0..10) {
+        match cur.try_insert(i, i * 10, Ord::cmp) {
+            Ok(v) => assert_eq!(*v, i * 10),
+            Err(v) => {
+                assert_eq!(v, i * 10);
+                assert!(i < MIN_INSERTS_HEIGHT_2);
+            }
+        }
+        assert_eq!(cur.key(), Some(&i));
+    }
+    assert_eq!(cur.try_insert(5, 0, Ord::cmp), Err(0));
+    assert_eq!(cur.key_value(), Some((&5, &50)));
+    cur.move_prev();
+    assert_eq!(cur.key(), Some(&4));
+
+    map.check();
+    assert_eq!(map.len(), MIN_INSERTS_HEIGHT_2);
+    assert_eq!(map.height(), Some(2));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_split() {
+    let len = MIN_INSERTS_HEIGHT_2;
+    let mut map = BTreeMap::default();
+    for i in 0..len {
+        map.insert(i, i * 10, Ord::cmp);
+    }
+    map.check();
+    // Cuts right next to elements in leaves and in internal nodes at both levels.
+    for at in (0..len).step_by(7).chain([len - 1]) {
+        let mut left = map.clone();
+        let mut cur = left.lower_bound_mut(|k| at.cmp(k), SearchBoundCustom::Included);
+        let mut right = cur.split_after();
+        assert_eq!(cur.key_value(), Some((&at, &(at * 10))));
+        cur.move_next();
+        assert_eq!(cur.key(), None);
+        left.check();
+        right.check();
+        assert!(left.keys().copied().eq(0..=at));
+        assert!(right.keys().copied().eq(at + 1..len));
+
+        let mut cur = right.lower_bound_mut(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
+        cur.move_prev();
+        cur.splice_after(left, Ord::cmp).unwrap();
+        assert_eq!(cur.key(), None);
+        right.check();
+        assert_eq!(right, map);
+
+        let mut right = map.clone();
+        let mut cur = right.lower_bound_mut(|k| at.cmp(k), SearchBoundCustom::Included);
+        let mut left = cur.split_before();
+        assert_eq!(cur.key(), Some(&at));
+        cur.move_prev();
+        assert_eq!(cur.key(), None);
+        left.check();
+        right.check();
+        assert!(left.keys().copied().eq(0..at));
+        assert!(right.keys().copied().eq(at..len));
+
+        let mut cur = left.lower_bound_mut(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
+        cur.move_prev();
+        cur.splice_before(right, Ord::cmp).unwrap();
+        assert_eq!(cur.key(), None);
+        left.check();
+        assert_eq!(left, map);
+    }
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_split_at_ghost() {
+    let mut map = BTreeMap::from([(1, 'a'), (2, 'b')]);
+    let mut cur = map.upper_bound_mut(|k| 1.cmp(k), SearchBoundCustom::Excluded);
+    let all = cur.split_after();
+    assert_eq!(cur.key(), None);
+    assert_eq!(cur.peek_next(), None);
+    let none = cur.split_before();
+    assert_eq!(map.len(), 0);
+    assert_eq!(none.len(), 0);
+    assert_eq!(all, BTreeMap::from([(1, 'a'), (2, 'b')]));
+
+    let mut cur = map.lower_bound_mut(|_| Ordering::Equal, SearchBoundCustom::AllIncluded);
+    cur.splice_after(all, Ord::cmp).unwrap();
+    cur.splice_before(BTreeMap::from([(3, 'c')]), Ord::cmp).unwrap();
+    assert_eq!(cur.key(), None);
+    map.check();
+    assert_eq!(map, BTreeMap::from([(1, 'a'), (2, 'b'), (3, 'c')]));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_splice_middle() {
+    let mut map = BTreeMap::from([(0, ()), (100, ())]);
+    let mut other = BTreeMap::default();
+    for i in 1..MIN_INSERTS_HEIGHT_1 + 5 {
+        other.insert(i, (), Ord::cmp);
+    }
+    let mut cur = map.lower_bound_mut(|k| 100.cmp(k), SearchBoundCustom::Included);
+    cur.splice_before(other.clone(), Ord::cmp).unwrap();
+    assert_eq!(cur.key(), Some(&100));
+    cur.move_prev();
+    assert_eq!(cur.key(), other.keys().last());
+    map.check();
+    assert!(map.keys().copied().eq([0].into_iter().chain(other.keys().copied()).chain([100])));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_splice_unordered() {
+    let mut map = BTreeMap::from([(0, ()), (10, ()), (20, ())]);
+    let mut cur = map.lower_bound_mut(|k| 10.cmp(k), SearchBoundCustom::Included);
+    for other in [[(5, ()), (10, ())], [(10, ()), (15, ())], [(15, ()), (25, ())]] {
+        let err = cur.splice_after(BTreeMap::from(other), Ord::cmp).unwrap_err();
+        assert_eq!(err.map, BTreeMap::from(other));
+    }
+    for other in [[(0, ()), (5, ())], [(5, ()), (10, ())], [(-5, ()), (5, ())]] {
+        let err = cur.splice_before(BTreeMap::from(other), Ord::cmp).unwrap_err();
+        assert_eq!(err.map, BTreeMap::from(other));
+    }
+    assert_eq!(cur.key(), Some(&10));
+    cur.splice_after(BTreeMap::default(), Ord::cmp).unwrap();
+    cur.splice_after(BTreeMap::from([(12, ()), (15, ())]), Ord::cmp).unwrap();
+    cur.splice_before(BTreeMap::from([(5, ())]), Ord::cmp).unwrap();
+    assert_eq!(cur.key(), Some(&10));
+    map.check();
+    assert!(map.keys().copied().eq([0, 5, 10, 12, 15, 20]));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursor_mut_splice_heights() {
+    let len = MIN_INSERTS_HEIGHT_2;
+    // Splices maps of all sorts of heights into the middle of maps of all sorts of heights.
+    for (map_len, other_len) in [(2, 1), (2, len), (len, 1), (len, 2), (len, len), (len * 3, len)] {
+        let mut map = BTreeMap::default();
+        for i in 0..map_len {
+            map.insert(if i < map_len / 2 { i } else { i + other_len }, (), Ord::cmp);
+        }
+        let mut other = BTreeMap::default();
+        for i in map_len / 2..map_len / 2 + other_len {
+            other.insert(i, (), Ord::cmp);
+        }
+        let mut after = map.clone();
+        let current = map_len / 2 - 1;
+        let mut cur = after.lower_bound_mut(|k| current.cmp(k), SearchBoundCustom::Included);
+        cur.splice_after(other.clone(), Ord::cmp).unwrap();
+        assert_eq!(cur.key(), Some(&current));
+        after.check();
+        assert!(after.keys().copied().eq(0..map_len + other_len));
+
+        let mut before = map;
+        let current = map_len / 2 + other_len;
+        let mut cur = before.lower_bound_mut(|k| current.cmp(k), SearchBoundCustom::Included);
+        cur.splice_before(other, Ord::cmp).unwrap();
+        assert_eq!(cur.key(), Some(&current));
+        before.check();
+        assert!(before.keys().copied().eq(0..map_len + other_len));
+    }
+}
+
+fn test_branching_factor_with<const B: usize>() {
+    let mut rng = DeterministicRng::new();
+    let mut map = BTreeMap::<u32, u32, Global, B>::with_branching_factor();
+    let mut expected = Vec::new();
+    for _ in 0..1000 {
+        let k = rng.next() % 500;
+        if map.insert(k, k * 2, Ord::cmp).is_none() {
+            expected.push(k);
+        }
+    }
+    expected.sort_unstable();
+    map.check();
+    assert!(map.keys().copied().eq(expected.iter().copied()));
+
+    let mut right = map.split_off(|k| 250.cmp(k));
+    map.check();
+    right.check();
+    assert!(map.keys().chain(right.keys()).copied().eq(expected.iter().copied()));
+    map.append(&mut right, |a, b| a.0.cmp(&b.0));
+    map.check();
+    assert!(right.is_empty());
+
+    for &k in expected.iter().step_by(3) {
+        assert_eq!(map.remove(|x| k.cmp(x)), Some(k * 2));
+    }
+    map.retain(|k, _| k % 2 == 0);
+    map.check();
+    let remaining = expected.iter().enumerate().filter(|&(i, k)| i % 3 != 0 && k % 2 == 0);
+    assert!(map.keys().copied().eq(remaining.map(|(_, &k)| k)));
+
+    while let Some((k, v)) = map.pop_first() {
+        assert_eq!(v, k * 2);
+    }
+    map.check();
+}
+
+#[test]
+fn test_branching_factor_2() {
+    test_branching_factor_with::<2>();
+}
+
+#[test]
+fn test_branching_factor_3() {
+    test_branching_factor_with::<3>();
+}
+
+#[test]
+fn test_branching_factor_large() {
+    test_branching_factor_with::<64>();
+}
+
+#[test]
+fn test_branching_factor_helper() {
+    assert_eq!(branching_factor::<u64, u64>(2), 4);
+    assert_eq!(branching_factor::<u32, u32>(4), 15);
+    assert_eq!(branching_factor::<[u8; 4096], ()>(1), 2);
+    assert_eq!(branching_factor::<(), ()>(1), 1024);
+    assert_eq!(branching_factor::<u8, ()>(64), 1024);
+}
+
+fn test_node_search_with<N: NodeSearch<u32>>() {
+    let mut rng = DeterministicRng::new();
+    let mut map = BTreeMap::<u32, u32, Global, 3>::with_branching_factor().with_node_search::<N>();
+    let mut expected = Vec::new();
+    for _ in 0..500 {
+        let k = rng.next() % 1000;
+        *map.entry(k, Ord::cmp).or_insert(0) += 1;
+        expected.push(k);
+    }
+    expected.sort_unstable();
+    expected.dedup();
+    map.check();
+
+    for k in 0..1000 {
+        assert_eq!(map.contains_key(|x| k.cmp(x)), expected.binary_search(&k).is_ok());
+    }
+    for (lo, hi) in [(0, 1000), (100, 101), (250, 750), (999, 1000)] {
+        let range = map.range(
+            |k| lo.cmp(k),
+            SearchBoundCustom::Included,
+            |k| hi.cmp(k),
+            SearchBoundCustom::Excluded,
+        );
+        assert!(range
+            .map(|(k, _)| *k)
+            .eq(expected.iter().copied().filter(|k| (lo..hi).contains(k))));
+    }
+
+    let right = map.split_off(|k| 500.cmp(k));
+    assert!(map.keys().all(|&k| k < 500));
+    assert!(right.keys().all(|&k| k >= 500));
+    assert_eq!(map.len() + right.len(), expected.len());
+    for &k in expected.iter().filter(|&&k| k < 500) {
+        assert!(map.remove(|x| k.cmp(x)).is_some());
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_node_search_binary() {
+    test_node_search_with::<Binary>();
+}
+
+#[test]
+fn test_node_search_midrange() {
+    test_node_search_with::<Midrange>();
+}
+
+#[test]
+fn test_int_search() {
+    let mut rng = DeterministicRng::new();
+    let mut map = BTreeMap::<u32, u32, Global, 3>::with_branching_factor();
+    for _ in 0..500 {
+        let k = rng.next() % 1000;
+        map.insert(k, k * 2, Ord::cmp);
+    }
+    map.check();
+
+    for k in 0..1001 {
+        assert_eq!(map.get_int(k), map.get(|x| k.cmp(x)));
+    }
+    for (lo, hi) in [(0, 1000), (100, 101), (250, 750), (999, 1000), (1000, 2000)] {
+        let expected = map.range(
+            |k| lo.cmp(k),
+            SearchBoundCustom::Excluded,
+            |k| hi.cmp(k),
+            SearchBoundCustom::Included,
+        );
+        assert!(map.range_int((Excluded(lo), Included(hi))).eq(expected));
+        assert!(map
+            .range_int(lo..hi)
+            .map(|(k, _)| *k)
+            .eq(map.keys().copied().filter(|k| (lo..hi).contains(k))));
+    }
+    assert!(map.range_int(..).eq(map.iter()));
+    assert!(map.range_int(..=0u32).eq(map.range_int(0..1)));
+    assert_eq!(BTreeMap::<i8, ()>::new().range_int(..).next(), None);
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+fn test_int_search_range_backwards() {
+    let map = BTreeMap::<u32, ()>::new();
+    let _ = map.range_int((Included(5), Excluded(3)));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_int_search_cursors() {
+    let mut map = BTreeMap::<i64, ()>::new();
+    for k in -50..50 {
+        map.insert(k * 2, (), Ord::cmp);
+    }
+    let custom = |bound: Bound<i64>| match bound {
+        Included(_) => SearchBoundCustom::Included,
+        Excluded(_) => SearchBoundCustom::Excluded,
+        Unbounded => SearchBoundCustom::AllIncluded,
+    };
+    for k in -101..101 {
+        for bound in [Included(k), Excluded(k), Unbounded] {
+            let lower = map.lower_bound(|x| k.cmp(x), custom(bound));
+            assert_eq!(map.lower_bound_int(bound).key(), lower.key());
+            let upper = map.upper_bound(|x| k.cmp(x), custom(bound));
+            assert_eq!(map.upper_bound_int(bound).key(), upper.key());
+        }
+    }
+}
+
+#[test]
+fn test_range_by() {
+    let mut map = BTreeMap::<u32, u32, Global, 3>::with_branching_factor();
+    for i in 0..100 {
+        map.insert(i * 2, i, Ord::cmp);
+    }
+    let bounds = |x| [Included(x), Excluded(x), Unbounded];
+    for lo in (0..=200).step_by(7) {
+        for hi in (lo..=201).step_by(11) {
+            for (start, end) in bounds(lo).into_iter().flat_map(|s| bounds(hi).map(|e| (s, e))) {
+                if matches!((start, end), (Excluded(s), Excluded(e)) if s == e) {
+                    continue;
+                }
+                let expected: Vec<_> =
+                    map.iter().filter(|(k, _)| (start, end).contains(*k)).collect();
+                assert!(map.range_by((start, end), Ord::cmp).eq(expected.iter().copied()));
+                assert!(map.range_by((start, end), Ord::cmp).rev().eq(expected.into_iter().rev()));
+            }
+        }
+    }
+    for (_, v) in map.range_mut_by(10..20, Ord::cmp) {
+        *v = 0;
+    }
+    assert_eq!(map.values().filter(|&&v| v == 0).count(), 6);
+}
+
+#[test]
+fn test_range_by_borrowed() {
+    let mut map = BTreeMap::new();
+    for word in ["ant", "bee", "cat", "dog"] {
+        map.insert(String::from(word), (), Ord::cmp);
+    }
+    let found: Vec<_> =
+        map.range_by((Included("b"), Included("cat")), str::cmp).map(|(k, _)| k).collect();
+    assert_eq!(found, ["bee", "cat"]);
+    assert_eq!(map.range_by::<str, _, _>(.., Ord::cmp).count(), 4);
+    let by_case = |a: &str, b: &str| a.to_lowercase().cmp(&b.to_lowercase());
+    assert_eq!(map.range_by((Excluded("ANT"), Unbounded), by_case).count(), 3);
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+fn test_range_by_backwards() {
+    let mut map = BTreeMap::new();
+    for i in 1..=3 {
+        map.insert(i, (), Ord::cmp);
+    }
+    let _ = map.range_by((Included(3), Included(2)), Ord::cmp);
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+fn test_range_mut_by_backwards_between_keys() {
+    let mut map = BTreeMap::new();
+    map.insert(2, (), Ord::cmp);
+    let _ = map.range_mut_by((Included(3), Excluded(2)), Ord::cmp);
+}
+
+#[test]
+#[should_panic(expected = "range start and end are equal and excluded in BTreeMap")]
+fn test_range_by_equal_excluded() {
+    let mut map = BTreeMap::new();
+    map.insert(2, (), Ord::cmp);
+    let _ = map.range_by((Excluded(2), Excluded(2)), Ord::cmp);
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in BTreeMap")]
+fn test_range_by_backwards_without_keys_between() {
+    // No key lies between the bounds, nor in the map at all.
+    let mut map = BTreeMap::<i32, (), Global, 3>::with_branching_factor();
+    let _ = map.range_mut_by((Included(5), Included(3)), Ord::cmp);
+}
+
+#[test]
+fn test_range_by_backwards_whatever_keys() {
+    for keys in [&[][..], &[1, 9], &[3, 4, 5], &[4]] {
+        let mut map = BTreeMap::new();
+        for &k in keys {
+            map.insert(k, (), Ord::cmp);
+        }
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            map.range_by((Included(5), Excluded(3)), Ord::cmp).count()
+        }));
+        assert!(result.is_err());
+    }
+}
+
+// An allocator that panics when asked to free memory allocated by another instance.
+#[cfg(feature = "btreemap_alloc")]
+#[derive(Clone, Default)]
+struct Tracking(Rc<core::cell::RefCell<std::collections::HashSet<usize>>>);
+
+#[cfg(feature = "btreemap_alloc")]
+impl Tracking {
+    // Whether all memory allocated by this instance has been freed.
+    fn is_idle(&self) -> bool {
+        self.0.borrow_mut().is_empty()
+    }
+}
+
+#[cfg(feature = "btreemap_alloc")]
+unsafe impl Allocator for Tracking {
+    fn allocate(
+        &self,
+        layout: alloc::alloc::Layout,
+    ) -> Result<core::ptr::NonNull<[u8]>, alloc::alloc::AllocError> {
+        let ptr = Global.allocate(layout)?;
+        self.0.borrow_mut().insert(ptr.as_ptr() as *mut u8 as usize);
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: core::ptr::NonNull<u8>, layout: alloc::alloc::Layout) {
+        assert!(self.0.borrow_mut().remove(&(ptr.as_ptr() as usize)), "freed by a stranger");
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+#[cfg(feature = "btreemap_alloc")]
+fn test_concat_separate_allocators() {
+    let size = MIN_INSERTS_HEIGHT_2;
+    let (alloc_a, alloc_b) = (Tracking::default(), Tracking::default());
+    let mut a = BTreeMap::new_in(alloc_a.clone());
+    let mut b = BTreeMap::new_in(alloc_b.clone());
+    for i in 0..size {
+        a.insert(i, i, Ord::cmp);
+        b.insert(size + i, i, Ord::cmp);
+    }
+    a.concat(b, Ord::cmp);
+    assert_eq!(a.len(), size * 2);
+    assert!(a.keys().copied().eq(0..size * 2));
+    assert!(alloc_b.is_idle());
+    drop(a);
+    assert!(alloc_a.is_idle());
+}
+
+#[test]
+#[cfg(feature = "btreemap_alloc")]
+fn test_transfer_range_separate_allocators() {
+    let size = MIN_INSERTS_HEIGHT_2;
+    let (alloc_a, alloc_b) = (Tracking::default(), Tracking::default());
+    let mut a = BTreeMap::new_in(alloc_a.clone());
+    let mut b = BTreeMap::new_in(alloc_b.clone());
+    for i in 0..size {
+        a.insert(i, i, Ord::cmp);
+    }
+    b.insert(size, size, Ord::cmp);
+    a.transfer_range(
+        &mut b,
+        |k| 10.cmp(k),
+        SearchBoundCustom::Included,
+        |k| (size - 10).cmp(k),
+        SearchBoundCustom::Excluded,
+    );
+    assert_eq!(a.len(), 20);
+    assert!(a.keys().copied().eq((0..10).chain(size - 10..size)));
+    assert_eq!(b.len(), size - 19);
+    assert!(b.keys().copied().eq((10..size - 10).chain(iter::once(size))));
+    drop(a);
+    assert!(alloc_a.is_idle());
+    drop(b);
+    assert!(alloc_b.is_idle());
+}
+
+#[test]
+#[cfg(all(feature = "btreemap_alloc", feature = "btree_cursors"))]
+fn test_cursor_mut_splice_separate_allocators() {
+    let size = MIN_INSERTS_HEIGHT_2;
+    let (alloc_a, alloc_b) = (Tracking::default(), Tracking::default());
+    let mut a = BTreeMap::new_in(alloc_a.clone());
+    let mut b = BTreeMap::new_in(alloc_b.clone());
+    for i in 0..size {
+        a.insert(i * 3, i, Ord::cmp);
+    }
+    let current = size / 2 * 3;
+    b.insert(current + 1, 0, Ord::cmp);
+    b.insert(current + 2, 0, Ord::cmp);
+    let mut cur = a.lower_bound_mut(|k| current.cmp(k), SearchBoundCustom::Included);
+    cur.splice_after(b, Ord::cmp).unwrap();
+    assert_eq!(cur.key(), Some(&current));
+    assert!(alloc_b.is_idle());
+    assert_eq!(a.len(), size + 2);
+    drop(a);
+    assert!(alloc_a.is_idle());
+}
@@ -51,9 +51,9 @@ impl<I: Iterator> MergeIterInner<I> {
     /// the sources are not strictly ascending). If neither returned option
     /// contains a value, iteration has finished and subsequent calls will
     /// return the same empty pair.
-    pub fn nexts<Cmp: Fn(&I::Item, &I::Item) -> Ordering>(
+    pub fn nexts<Cmp: FnMut(&I::Item, &I::Item) -> Ordering>(
         &mut self,
-        cmp: Cmp,
+        mut cmp: Cmp,
     ) -> (Option<I::Item>, Option<I::Item>)
     where
         I: FusedIterator,
@@ -1,23 +1,39 @@
+use core::cmp::Ordering;
+
+mod aggregate;
 mod append;
 mod borrow;
+mod check;
 mod dedup_sorted_iter;
 mod fix;
+mod int_search;
+pub mod interval_map;
+mod join;
 pub mod map;
 mod mem;
 mod merge_iter;
 mod navigate;
 mod node;
+#[cfg(feature = "order_statistics")]
+mod order_statistics;
 mod remove;
 mod search;
-//pub mod set;
+pub mod set;
 mod set_val;
 mod split;
+pub mod summary_map;
 
 #[doc(hidden)]
-trait Recover<C> {
+trait Recover {
     type Key;
 
-    fn get(&self, comp: C) -> Option<&Self::Key>;
-    fn take(&mut self, comp: C) -> Option<Self::Key>;
-    fn replace(&mut self, key: Self::Key, comp: C) -> Option<Self::Key>;
+    fn get<C>(&self, comp: C) -> Option<&Self::Key>
+    where
+        C: FnMut(&Self::Key) -> Ordering;
+    fn take<C>(&mut self, comp: C) -> Option<Self::Key>
+    where
+        C: FnMut(&Self::Key) -> Ordering;
+    fn replace<C>(&mut self, key: Self::Key, double_comp: C) -> Option<Self::Key>
+    where
+        C: FnMut(&Self::Key, &Self::Key) -> Ordering;
 }
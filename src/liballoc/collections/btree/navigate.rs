@@ -4,23 +4,23 @@ use core::ops::RangeBounds;
 use core::ptr;
 
 use super::node::{marker, ForceResult::*, Handle, NodeRef};
-use super::search::SearchBound;
+use super::search::{NodeSearch, SearchBound, Seek, SeekMut};
 
 use crate::btree_map::SearchBoundCustom;
 use crate::polyfill::*;
 // `front` and `back` are always both `None` or both `Some`.
-pub struct LeafRange<BorrowType, K, V> {
-    front: Option<Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>>,
-    back: Option<Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>>,
+pub struct LeafRange<BorrowType, K, V, S = (), const B: usize = 6> {
+    front: Option<Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>>,
+    back: Option<Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>>,
 }
 
-impl<'a, K: 'a, V: 'a> Clone for LeafRange<marker::Immut<'a>, K, V> {
+impl<'a, K: 'a, V: 'a, S, const B: usize> Clone for LeafRange<marker::Immut<'a>, K, V, S, B> {
     fn clone(&self) -> Self {
         LeafRange { front: self.front.clone(), back: self.back.clone() }
     }
 }
 
-impl<BorrowType, K, V> LeafRange<BorrowType, K, V> {
+impl<BorrowType, K, V, S, const B: usize> LeafRange<BorrowType, K, V, S, B> {
     pub fn none() -> Self {
         LeafRange { front: None, back: None }
     }
@@ -30,7 +30,7 @@ impl<BorrowType, K, V> LeafRange<BorrowType, K, V> {
     }
 
     /// Temporarily takes out another, immutable equivalent of the same range.
-    pub fn reborrow(&self) -> LeafRange<marker::Immut<'_>, K, V> {
+    pub fn reborrow(&self) -> LeafRange<marker::Immut<'_>, K, V, S, B> {
         LeafRange {
             front: self.front.as_ref().map(|f| f.reborrow()),
             back: self.back.as_ref().map(|b| b.reborrow()),
@@ -38,7 +38,7 @@ impl<BorrowType, K, V> LeafRange<BorrowType, K, V> {
     }
 }
 
-impl<'a, K, V> LeafRange<marker::Immut<'a>, K, V> {
+impl<'a, K, V, S, const B: usize> LeafRange<marker::Immut<'a>, K, V, S, B> {
     #[inline]
     pub fn next_checked(&mut self) -> Option<(&'a K, &'a V)> {
         self.perform_next_checked(|kv| kv.into_kv())
@@ -50,7 +50,7 @@ impl<'a, K, V> LeafRange<marker::Immut<'a>, K, V> {
     }
 }
 
-impl<'a, K, V> LeafRange<marker::ValMut<'a>, K, V> {
+impl<'a, K, V, S, const B: usize> LeafRange<marker::ValMut<'a>, K, V, S, B> {
     #[inline]
     pub fn next_checked(&mut self) -> Option<(&'a K, &'a mut V)> {
         self.perform_next_checked(|kv| unsafe { ptr::read(kv) }.into_kv_valmut())
@@ -62,11 +62,24 @@ impl<'a, K, V> LeafRange<marker::ValMut<'a>, K, V> {
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V> LeafRange<BorrowType, K, V> {
+#[cfg(feature = "order_statistics")]
+impl<'a, K, V, S, const B: usize> LeafRange<marker::Immut<'a>, K, V, S, B> {
+    /// Counts the key-value pairs in the range.
+    pub fn count(&self) -> usize {
+        match (self.front, self.back) {
+            (Some(front), Some(back)) => {
+                back.forget_node_type().position() - front.forget_node_type().position()
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize> LeafRange<BorrowType, K, V, S, B> {
     /// If possible, extract some result from the following KV and move to the edge beyond it.
     fn perform_next_checked<F, R>(&mut self, f: F) -> Option<R>
     where
-        F: Fn(&Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>) -> R,
+        F: Fn(&Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV>) -> R,
     {
         if self.is_empty() {
             None
@@ -82,7 +95,7 @@ impl<BorrowType: marker::BorrowType, K, V> LeafRange<BorrowType, K, V> {
     /// If possible, extract some result from the preceding KV and move to the edge beyond it.
     fn perform_next_back_checked<F, R>(&mut self, f: F) -> Option<R>
     where
-        F: Fn(&Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>) -> R,
+        F: Fn(&Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV>) -> R,
     {
         if self.is_empty() {
             None
@@ -96,12 +109,12 @@ impl<BorrowType: marker::BorrowType, K, V> LeafRange<BorrowType, K, V> {
     }
 }
 
-enum LazyLeafHandle<BorrowType, K, V> {
-    Root(NodeRef<BorrowType, K, V, marker::LeafOrInternal>), // not yet descended
-    Edge(Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>),
+enum LazyLeafHandle<BorrowType, K, V, S = (), const B: usize = 6> {
+    Root(NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>), // not yet descended
+    Edge(Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>),
 }
 
-impl<'a, K: 'a, V: 'a> Clone for LazyLeafHandle<marker::Immut<'a>, K, V> {
+impl<'a, K: 'a, V: 'a, S, const B: usize> Clone for LazyLeafHandle<marker::Immut<'a>, K, V, S, B> {
     fn clone(&self) -> Self {
         match self {
             LazyLeafHandle::Root(root) => LazyLeafHandle::Root(*root),
@@ -110,8 +123,8 @@ impl<'a, K: 'a, V: 'a> Clone for LazyLeafHandle<marker::Immut<'a>, K, V> {
     }
 }
 
-impl<BorrowType, K, V> LazyLeafHandle<BorrowType, K, V> {
-    fn reborrow(&self) -> LazyLeafHandle<marker::Immut<'_>, K, V> {
+impl<BorrowType, K, V, S, const B: usize> LazyLeafHandle<BorrowType, K, V, S, B> {
+    fn reborrow(&self) -> LazyLeafHandle<marker::Immut<'_>, K, V, S, B> {
         match self {
             LazyLeafHandle::Root(root) => LazyLeafHandle::Root(root.reborrow()),
             LazyLeafHandle::Edge(edge) => LazyLeafHandle::Edge(edge.reborrow()),
@@ -120,24 +133,24 @@ impl<BorrowType, K, V> LazyLeafHandle<BorrowType, K, V> {
 }
 
 // `front` and `back` are always both `None` or both `Some`.
-pub struct LazyLeafRange<BorrowType, K, V> {
-    front: Option<LazyLeafHandle<BorrowType, K, V>>,
-    back: Option<LazyLeafHandle<BorrowType, K, V>>,
+pub struct LazyLeafRange<BorrowType, K, V, S = (), const B: usize = 6> {
+    front: Option<LazyLeafHandle<BorrowType, K, V, S, B>>,
+    back: Option<LazyLeafHandle<BorrowType, K, V, S, B>>,
 }
 
-impl<'a, K: 'a, V: 'a> Clone for LazyLeafRange<marker::Immut<'a>, K, V> {
+impl<'a, K: 'a, V: 'a, S, const B: usize> Clone for LazyLeafRange<marker::Immut<'a>, K, V, S, B> {
     fn clone(&self) -> Self {
         LazyLeafRange { front: self.front.clone(), back: self.back.clone() }
     }
 }
 
-impl<BorrowType, K, V> LazyLeafRange<BorrowType, K, V> {
+impl<BorrowType, K, V, S, const B: usize> LazyLeafRange<BorrowType, K, V, S, B> {
     pub fn none() -> Self {
         LazyLeafRange { front: None, back: None }
     }
 
     /// Temporarily takes out another, immutable equivalent of the same range.
-    pub fn reborrow(&self) -> LazyLeafRange<marker::Immut<'_>, K, V> {
+    pub fn reborrow(&self) -> LazyLeafRange<marker::Immut<'_>, K, V, S, B> {
         LazyLeafRange {
             front: self.front.as_ref().map(|f| f.reborrow()),
             back: self.back.as_ref().map(|b| b.reborrow()),
@@ -145,7 +158,7 @@ impl<BorrowType, K, V> LazyLeafRange<BorrowType, K, V> {
     }
 }
 
-impl<'a, K, V> LazyLeafRange<marker::Immut<'a>, K, V> {
+impl<'a, K, V, S, const B: usize> LazyLeafRange<marker::Immut<'a>, K, V, S, B> {
     #[inline]
     pub unsafe fn next_unchecked(&mut self) -> (&'a K, &'a V) {
         unsafe { self.init_front().unwrap().next_unchecked() }
@@ -157,7 +170,7 @@ impl<'a, K, V> LazyLeafRange<marker::Immut<'a>, K, V> {
     }
 }
 
-impl<'a, K, V> LazyLeafRange<marker::ValMut<'a>, K, V> {
+impl<'a, K, V, S, const B: usize> LazyLeafRange<marker::ValMut<'a>, K, V, S, B> {
     #[inline]
     pub unsafe fn next_unchecked(&mut self) -> (&'a K, &'a mut V) {
         unsafe { self.init_front().unwrap().next_unchecked() }
@@ -167,12 +180,90 @@ impl<'a, K, V> LazyLeafRange<marker::ValMut<'a>, K, V> {
     pub unsafe fn next_back_unchecked(&mut self) -> (&'a K, &'a mut V) {
         unsafe { self.init_back().unwrap().next_back_unchecked() }
     }
+
+    /// Returns a dormant copy of the leaf edge the front has moved to, or
+    /// `None` if the front has not moved.
+    pub fn dormant_front(
+        &self,
+    ) -> Option<Handle<NodeRef<marker::DormantMut, K, V, marker::Leaf, S, B>, marker::Edge>> {
+        match &self.front {
+            Some(LazyLeafHandle::Edge(edge)) => Some(edge.dormant()),
+            _ => None,
+        }
+    }
+
+    /// Returns a dormant copy of the leaf edge the back has moved to, or
+    /// `None` if the back has not moved.
+    pub fn dormant_back(
+        &self,
+    ) -> Option<Handle<NodeRef<marker::DormantMut, K, V, marker::Leaf, S, B>, marker::Edge>> {
+        match &self.back {
+            Some(LazyLeafHandle::Edge(edge)) => Some(edge.dormant()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "order_statistics")]
+impl<'a, K, V, S, const B: usize> LazyLeafRange<marker::Immut<'a>, K, V, S, B> {
+    /// Skips `n` key-value pairs at the front, then acts like `next_unchecked`.
+    ///
+    /// # Safety
+    /// There must be more than `n` KVs left in the range.
+    #[inline]
+    pub unsafe fn nth_unchecked(&mut self, n: usize) -> (&'a K, &'a V) {
+        super::mem::replace(self.init_front().unwrap(), |leaf_edge| {
+            let kv = leaf_edge.nth_kv(n).ok().unwrap();
+            (kv.next_leaf_edge(), kv.into_kv())
+        })
+    }
+
+    /// Skips `n` key-value pairs at the back, then acts like `next_back_unchecked`.
+    ///
+    /// # Safety
+    /// There must be more than `n` KVs left in the range.
+    #[inline]
+    pub unsafe fn nth_back_unchecked(&mut self, n: usize) -> (&'a K, &'a V) {
+        super::mem::replace(self.init_back().unwrap(), |leaf_edge| {
+            let kv = leaf_edge.nth_back_kv(n).ok().unwrap();
+            (kv.next_back_leaf_edge(), kv.into_kv())
+        })
+    }
 }
 
-impl<K, V> LazyLeafRange<marker::Dying, K, V> {
+#[cfg(feature = "order_statistics")]
+impl<'a, K, V, S, const B: usize> LazyLeafRange<marker::ValMut<'a>, K, V, S, B> {
+    /// Skips `n` key-value pairs at the front, then acts like `next_unchecked`.
+    ///
+    /// # Safety
+    /// There must be more than `n` KVs left in the range.
+    #[inline]
+    pub unsafe fn nth_unchecked(&mut self, n: usize) -> (&'a K, &'a mut V) {
+        let kv = super::mem::replace(self.init_front().unwrap(), |leaf_edge| {
+            let kv = leaf_edge.nth_kv(n).ok().unwrap();
+            (unsafe { ptr::read(&kv) }.next_leaf_edge(), kv)
+        });
+        kv.into_kv_valmut()
+    }
+
+    /// Skips `n` key-value pairs at the back, then acts like `next_back_unchecked`.
+    ///
+    /// # Safety
+    /// There must be more than `n` KVs left in the range.
+    #[inline]
+    pub unsafe fn nth_back_unchecked(&mut self, n: usize) -> (&'a K, &'a mut V) {
+        let kv = super::mem::replace(self.init_back().unwrap(), |leaf_edge| {
+            let kv = leaf_edge.nth_back_kv(n).ok().unwrap();
+            (unsafe { ptr::read(&kv) }.next_back_leaf_edge(), kv)
+        });
+        kv.into_kv_valmut()
+    }
+}
+
+impl<K, V, S, const B: usize> LazyLeafRange<marker::Dying, K, V, S, B> {
     fn take_front(
         &mut self,
-    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge>> {
+    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::Leaf, S, B>, marker::Edge>> {
         match self.front.take()? {
             LazyLeafHandle::Root(root) => Some(root.first_leaf_edge()),
             LazyLeafHandle::Edge(edge) => Some(edge),
@@ -183,7 +274,7 @@ impl<K, V> LazyLeafRange<marker::Dying, K, V> {
     pub unsafe fn deallocating_next_unchecked<A: Allocator + Clone>(
         &mut self,
         alloc: A,
-    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV> {
+    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B>, marker::KV> {
         debug_assert!(self.front.is_some());
         let front = self.init_front().unwrap();
         unsafe { front.deallocating_next_unchecked(alloc) }
@@ -193,7 +284,7 @@ impl<K, V> LazyLeafRange<marker::Dying, K, V> {
     pub unsafe fn deallocating_next_back_unchecked<A: Allocator + Clone>(
         &mut self,
         alloc: A,
-    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV> {
+    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B>, marker::KV> {
         debug_assert!(self.back.is_some());
         let back = self.init_back().unwrap();
         unsafe { back.deallocating_next_back_unchecked(alloc) }
@@ -207,10 +298,12 @@ impl<K, V> LazyLeafRange<marker::Dying, K, V> {
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V> LazyLeafRange<BorrowType, K, V> {
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    LazyLeafRange<BorrowType, K, V, S, B>
+{
     fn init_front(
         &mut self,
-    ) -> Option<&mut Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>> {
+    ) -> Option<&mut Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>> {
         if let Some(LazyLeafHandle::Root(root)) = &self.front {
             self.front = Some(LazyLeafHandle::Edge(unsafe { ptr::read(root) }.first_leaf_edge()));
         }
@@ -224,7 +317,7 @@ impl<BorrowType: marker::BorrowType, K, V> LazyLeafRange<BorrowType, K, V> {
 
     fn init_back(
         &mut self,
-    ) -> Option<&mut Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>> {
+    ) -> Option<&mut Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>> {
         if let Some(LazyLeafHandle::Root(root)) = &self.back {
             self.back = Some(LazyLeafHandle::Edge(unsafe { ptr::read(root) }.last_leaf_edge()));
         }
@@ -237,7 +330,9 @@ impl<BorrowType: marker::BorrowType, K, V> LazyLeafRange<BorrowType, K, V> {
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>
+{
     /// Finds the distinct leaf edges delimiting a specified range in a tree.
     ///
     /// If such distinct edges exist, returns them in ascending order, meaning
@@ -251,21 +346,21 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
     /// # Safety
     /// Unless `BorrowType` is `Immut`, do not use the handles to visit the same
     /// KV twice.
-    unsafe fn find_leaf_edges_spanning_range<C1, C2>(
+    unsafe fn find_leaf_edges_spanning_range<N: NodeSearch<K>, C1, C2>(
         self,
         mut lower_comp: C1,
         lower_bound: SearchBound,
         mut upper_comp: C2,
         upper_bound: SearchBound,
-    ) -> LeafRange<BorrowType, K, V>
+    ) -> LeafRange<BorrowType, K, V, S, B>
     where
-        C1: FnMut(&K) -> Ordering,
-        C2: FnMut(&K) -> Ordering,
+        C1: Seek<K>,
+        C2: Seek<K>,
     {
-        match self.search_tree_for_bifurcation(
-            &mut lower_comp,
+        match self.search_tree_for_bifurcation::<N, _, _>(
+            SeekMut(&mut lower_comp),
             lower_bound,
-            &mut upper_comp,
+            SeekMut(&mut upper_comp),
             upper_bound,
         ) {
             Err(_) => LeafRange::none(),
@@ -282,12 +377,16 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
                     match (lower_edge.force(), upper_edge.force()) {
                         (Leaf(f), Leaf(b)) => return LeafRange { front: Some(f), back: Some(b) },
                         (Internal(f), Internal(b)) => {
-                            (lower_edge, lower_child_bound) = f
-                                .descend()
-                                .find_lower_bound_edge(&mut lower_comp, lower_child_bound);
-                            (upper_edge, upper_child_bound) = b
-                                .descend()
-                                .find_upper_bound_edge(&mut upper_comp, upper_child_bound);
+                            (lower_edge, lower_child_bound) =
+                                f.descend().find_lower_bound_edge::<N, _>(
+                                    SeekMut(&mut lower_comp),
+                                    lower_child_bound,
+                                );
+                            (upper_edge, upper_child_bound) =
+                                b.descend().find_upper_bound_edge::<N, _>(
+                                    SeekMut(&mut upper_comp),
+                                    upper_child_bound,
+                                );
                         }
                         _ => unreachable!("BTreeMap has different depths"),
                     }
@@ -297,45 +396,54 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
     }
 }
 
-fn full_range<BorrowType: marker::BorrowType, K, V>(
-    root1: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
-    root2: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
-) -> LazyLeafRange<BorrowType, K, V> {
+fn full_range<BorrowType: marker::BorrowType, K, V, S, const B: usize>(
+    root1: NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>,
+    root2: NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>,
+) -> LazyLeafRange<BorrowType, K, V, S, B> {
     LazyLeafRange {
         front: Some(LazyLeafHandle::Root(root1)),
         back: Some(LazyLeafHandle::Root(root2)),
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
     /// Finds the pair of leaf edges delimiting a specific range in a tree.
     ///
     /// The result is meaningful only if the tree is ordered by key, like the tree
     /// in a `BTreeMap` is.
-    pub fn range_search<C1, C2>(
+    pub fn range_search<N: NodeSearch<K>, C1, C2>(
         self,
         lower_comp: C1,
         lower_bound: SearchBound,
         upper_comp: C2,
         upper_bound: SearchBound,
-    ) -> LeafRange<marker::Immut<'a>, K, V>
+    ) -> LeafRange<marker::Immut<'a>, K, V, S, B>
     where
-        C1: FnMut(&K) -> Ordering,
-        C2: FnMut(&K) -> Ordering,
+        C1: Seek<K>,
+        C2: Seek<K>,
     {
         // SAFETY: our borrow type is immutable.
         unsafe {
-            self.find_leaf_edges_spanning_range(lower_comp, lower_bound, upper_comp, upper_bound)
+            self.find_leaf_edges_spanning_range::<N, _, _>(
+                lower_comp,
+                lower_bound,
+                upper_comp,
+                upper_bound,
+            )
         }
     }
 
     /// Finds the pair of leaf edges delimiting an entire tree.
-    pub fn full_range(self) -> LazyLeafRange<marker::Immut<'a>, K, V> {
+    pub fn full_range(self) -> LazyLeafRange<marker::Immut<'a>, K, V, S, B> {
         full_range(self, self)
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::ValMut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    NodeRef<marker::ValMut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
     /// Splits a unique reference into a pair of leaf edges delimiting a specified range.
     /// The result are non-unique references allowing (some) mutation, which must be used
     /// carefully.
@@ -345,26 +453,31 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::ValMut<'a>, K, V, marker::LeafOrInternal>
     ///
     /// # Safety
     /// Do not use the duplicate handles to visit the same KV twice.
-    pub fn range_search<C1, C2>(
+    pub fn range_search<N: NodeSearch<K>, C1, C2>(
         self,
         lower_comp: C1,
         lower_bound: SearchBound,
         upper_comp: C2,
         upper_bound: SearchBound,
-    ) -> LeafRange<marker::ValMut<'a>, K, V>
+    ) -> LeafRange<marker::ValMut<'a>, K, V, S, B>
     where
-        C1: FnMut(&K) -> Ordering,
-        C2: FnMut(&K) -> Ordering,
+        C1: Seek<K>,
+        C2: Seek<K>,
     {
         unsafe {
-            self.find_leaf_edges_spanning_range(lower_comp, lower_bound, upper_comp, upper_bound)
+            self.find_leaf_edges_spanning_range::<N, _, _>(
+                lower_comp,
+                lower_bound,
+                upper_comp,
+                upper_bound,
+            )
         }
     }
 
     /// Splits a unique reference into a pair of leaf edges delimiting the full range of the tree.
     /// The results are non-unique references allowing mutation (of values only), so must be used
     /// with care.
-    pub fn full_range(self) -> LazyLeafRange<marker::ValMut<'a>, K, V> {
+    pub fn full_range(self) -> LazyLeafRange<marker::ValMut<'a>, K, V, S, B> {
         // We duplicate the root NodeRef here -- we will never visit the same KV
         // twice, and never end up with overlapping value references.
         let self2 = unsafe { ptr::read(&self) };
@@ -372,11 +485,11 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::ValMut<'a>, K, V, marker::LeafOrInternal>
     }
 }
 
-impl<K, V> NodeRef<marker::Dying, K, V, marker::LeafOrInternal> {
+impl<K, V, S, const B: usize> NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B> {
     /// Splits a unique reference into a pair of leaf edges delimiting the full range of the tree.
     /// The results are non-unique references allowing massively destructive mutation, so must be
     /// used with the utmost care.
-    pub fn full_range(self) -> LazyLeafRange<marker::Dying, K, V> {
+    pub fn full_range(self) -> LazyLeafRange<marker::Dying, K, V, S, B> {
         // We duplicate the root NodeRef here -- we will never access it in a way
         // that overlaps references obtained from the root.
         let self2 = unsafe { ptr::read(&self) };
@@ -384,8 +497,8 @@ impl<K, V> NodeRef<marker::Dying, K, V, marker::LeafOrInternal> {
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V>
-    Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>
 {
     /// Given a leaf edge handle, returns [`Result::Ok`] with a handle to the neighboring KV
     /// on the right side, which is either in the same leaf node or in an ancestor node.
@@ -393,8 +506,8 @@ impl<BorrowType: marker::BorrowType, K, V>
     pub fn next_kv(
         self,
     ) -> Result<
-        Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>,
-        NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+        Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+        NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>,
     > {
         let mut edge = self.forget_node_type();
         loop {
@@ -414,8 +527,8 @@ impl<BorrowType: marker::BorrowType, K, V>
     pub fn next_back_kv(
         self,
     ) -> Result<
-        Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>,
-        NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+        Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+        NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>,
     > {
         let mut edge = self.forget_node_type();
         loop {
@@ -430,8 +543,8 @@ impl<BorrowType: marker::BorrowType, K, V>
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V>
-    Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::Edge>
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::Internal, S, B>, marker::Edge>
 {
     /// Given an internal edge handle, returns [`Result::Ok`] with a handle to the neighboring KV
     /// on the right side, which is either in the same internal node or in an ancestor node.
@@ -439,8 +552,8 @@ impl<BorrowType: marker::BorrowType, K, V>
     fn next_kv(
         self,
     ) -> Result<
-        Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::KV>,
-        NodeRef<BorrowType, K, V, marker::Internal>,
+        Handle<NodeRef<BorrowType, K, V, marker::Internal, S, B>, marker::KV>,
+        NodeRef<BorrowType, K, V, marker::Internal, S, B>,
     > {
         let mut edge = self;
         loop {
@@ -455,7 +568,9 @@ impl<BorrowType: marker::BorrowType, K, V>
     }
 }
 
-impl<K, V> Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge> {
+impl<K, V, S, const B: usize>
+    Handle<NodeRef<marker::Dying, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     /// Given a leaf edge handle into a dying tree, returns the next leaf edge
     /// on the right side, and the key-value pair in between, if they exist.
     ///
@@ -472,8 +587,10 @@ impl<K, V> Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge> {
     unsafe fn deallocating_next<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> Option<(Self, Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV>)>
-    {
+    ) -> Option<(
+        Self,
+        Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+    )> {
         let mut edge = self.forget_node_type();
         loop {
             edge = match edge.right_kv() {
@@ -504,8 +621,10 @@ impl<K, V> Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge> {
     unsafe fn deallocating_next_back<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> Option<(Self, Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV>)>
-    {
+    ) -> Option<(
+        Self,
+        Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+    )> {
         let mut edge = self.forget_node_type();
         loop {
             edge = match edge.left_kv() {
@@ -536,7 +655,9 @@ impl<K, V> Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge> {
     }
 }
 
-impl<'a, K, V> Handle<NodeRef<marker::Immut<'a>, K, V, marker::Leaf>, marker::Edge> {
+impl<'a, K, V, S, const B: usize>
+    Handle<NodeRef<marker::Immut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     /// Moves the leaf edge handle to the next leaf edge and returns references to the
     /// key and value in between.
     ///
@@ -562,7 +683,9 @@ impl<'a, K, V> Handle<NodeRef<marker::Immut<'a>, K, V, marker::Leaf>, marker::Ed
     }
 }
 
-impl<'a, K, V> Handle<NodeRef<marker::ValMut<'a>, K, V, marker::Leaf>, marker::Edge> {
+impl<'a, K, V, S, const B: usize>
+    Handle<NodeRef<marker::ValMut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     /// Moves the leaf edge handle to the next leaf edge and returns references to the
     /// key and value in between.
     ///
@@ -592,7 +715,9 @@ impl<'a, K, V> Handle<NodeRef<marker::ValMut<'a>, K, V, marker::Leaf>, marker::E
     }
 }
 
-impl<K, V> Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge> {
+impl<K, V, S, const B: usize>
+    Handle<NodeRef<marker::Dying, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     /// Moves the leaf edge handle to the next leaf edge and returns the key and value
     /// in between, deallocating any node left behind while leaving the corresponding
     /// edge in its parent node dangling.
@@ -608,7 +733,7 @@ impl<K, V> Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge> {
     unsafe fn deallocating_next_unchecked<A: Allocator + Clone>(
         &mut self,
         alloc: A,
-    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV> {
+    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B>, marker::KV> {
         super::mem::replace(self, |leaf_edge| unsafe {
             leaf_edge.deallocating_next(alloc).unwrap()
         })
@@ -629,18 +754,22 @@ impl<K, V> Handle<NodeRef<marker::Dying, K, V, marker::Leaf>, marker::Edge> {
     unsafe fn deallocating_next_back_unchecked<A: Allocator + Clone>(
         &mut self,
         alloc: A,
-    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal>, marker::KV> {
+    ) -> Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B>, marker::KV> {
         super::mem::replace(self, |leaf_edge| unsafe {
             leaf_edge.deallocating_next_back(alloc).unwrap()
         })
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>
+{
     /// Returns the leftmost leaf edge in or underneath a node - in other words, the edge
     /// you need first when navigating forward (or last when navigating backward).
     #[inline]
-    pub fn first_leaf_edge(self) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge> {
+    pub fn first_leaf_edge(
+        self,
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge> {
         let mut node = self;
         loop {
             match node.force() {
@@ -653,7 +782,9 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
     /// Returns the rightmost leaf edge in or underneath a node - in other words, the edge
     /// you need last when navigating forward (or first when navigating backward).
     #[inline]
-    pub fn last_leaf_edge(self) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge> {
+    pub fn last_leaf_edge(
+        self,
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge> {
         let mut node = self;
         loop {
             match node.force() {
@@ -664,19 +795,22 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
     }
 }
 
-pub enum Position<BorrowType, K, V> {
-    Leaf(NodeRef<BorrowType, K, V, marker::Leaf>),
-    Internal(NodeRef<BorrowType, K, V, marker::Internal>),
-    InternalKV(Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::KV>),
+pub enum Position<BorrowType, K, V, S = (), const B: usize = 6> {
+    Leaf(NodeRef<BorrowType, K, V, marker::Leaf, S, B>),
+    Internal(NodeRef<BorrowType, K, V, marker::Internal, S, B>),
+    #[allow(dead_code)] // Only inspected by tests
+    InternalKV(Handle<NodeRef<BorrowType, K, V, marker::Internal, S, B>, marker::KV>),
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
     /// Visits leaf nodes and internal KVs in order of ascending keys, and also
     /// visits internal nodes as a whole in a depth first order, meaning that
     /// internal nodes precede their individual KVs and their child nodes.
     pub fn visit_nodes_in_order<F>(self, mut visit: F)
     where
-        F: FnMut(Position<marker::Immut<'a>, K, V>),
+        F: FnMut(Position<marker::Immut<'a>, K, V, S, B>),
     {
         match self.force() {
             Leaf(leaf) => visit(Position::Leaf(leaf)),
@@ -717,11 +851,13 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V>
-    Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV>
 {
     /// Returns the leaf edge closest to a KV for forward navigation.
-    pub fn next_leaf_edge(self) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge> {
+    pub fn next_leaf_edge(
+        self,
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge> {
         match self.force() {
             Leaf(leaf_kv) => leaf_kv.right_edge(),
             Internal(internal_kv) => {
@@ -734,7 +870,7 @@ impl<BorrowType: marker::BorrowType, K, V>
     /// Returns the leaf edge closest to a KV for backward navigation.
     pub fn next_back_leaf_edge(
         self,
-    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge> {
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge> {
         match self.force() {
             Leaf(leaf_kv) => leaf_kv.left_edge(),
             Internal(internal_kv) => {
@@ -745,21 +881,22 @@ impl<BorrowType: marker::BorrowType, K, V>
     }
 }
 
-#[cfg(feature = "btree_cursors")]
-impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>
+{
     /// Returns the leaf edge corresponding to the first point at which the
     /// given bound is true.
-    pub fn lower_bound<C>(
+    pub fn lower_bound<N: NodeSearch<K>, C>(
         self,
         mut comp: C,
         mut bound: SearchBound,
-    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
         let mut node = self;
         loop {
-            let (edge, new_bound) = node.find_lower_bound_edge(&mut comp, bound);
+            let (edge, new_bound) = node.find_lower_bound_edge::<N, _>(SeekMut(&mut comp), bound);
             match edge.force() {
                 Leaf(edge) => return edge,
                 Internal(edge) => {
@@ -772,17 +909,18 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
 
     /// Returns the leaf edge corresponding to the last point at which the
     /// given bound is true.
-    pub fn upper_bound<C>(
+    #[cfg(feature = "btree_cursors")]
+    pub fn upper_bound<N: NodeSearch<K>, C>(
         self,
         mut comp: C,
         mut bound: SearchBound,
-    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
         let mut node = self;
         loop {
-            let (edge, new_bound) = node.find_upper_bound_edge(&mut comp, bound);
+            let (edge, new_bound) = node.find_upper_bound_edge::<N, _>(SeekMut(&mut comp), bound);
             match edge.force() {
                 Leaf(edge) => return edge,
                 Internal(edge) => {
@@ -34,24 +34,67 @@
 use cfg_if::cfg_if;
 use core::marker::PhantomData;
 use core::mem::{self, MaybeUninit};
+use core::ops::{Deref, DerefMut};
 use core::ptr::{self, NonNull};
-use core::slice::SliceIndex;
+use core::slice::{self, SliceIndex};
 
+use super::summary_map::Summary;
 use crate::polyfill::*;
 use alloc::alloc::Layout;
 use alloc::boxed::Box;
 
-const B: usize = 6;
-pub const CAPACITY: usize = 2 * B - 1;
-pub const MIN_LEN_AFTER_SPLIT: usize = B - 1;
-const KV_IDX_CENTER: usize = B - 1;
-const EDGE_IDX_LEFT_OF_CENTER: usize = B - 1;
-const EDGE_IDX_RIGHT_OF_CENTER: usize = B;
+/// The sizes and indices of nodes in a tree with branching factor `B`.
+pub struct Consts<const B: usize>;
+
+impl<const B: usize> Consts<B> {
+    /// Evaluating this fails to compile if `B` is too small for nodes to be
+    /// split and merged, or too large for their lengths to fit in a `u16`.
+    pub const VALID: () = assert!(B >= 2 && 2 * B <= u16::MAX as usize, "unsupported B");
+    pub const CAPACITY: usize = 2 * B - 1;
+    pub const MIN_LEN_AFTER_SPLIT: usize = B - 1;
+    const KV_IDX_CENTER: usize = B - 1;
+    const EDGE_IDX_LEFT_OF_CENTER: usize = B - 1;
+    const EDGE_IDX_RIGHT_OF_CENTER: usize = B;
+}
+
+/// Storage for `2 * B` elements in a row, since an array whose length is
+/// computed from `B` cannot be declared yet. Keys and values only use the
+/// first `2 * B - 1` slots.
+#[repr(C)]
+struct Slots<T, const B: usize>([T; B], [T; B]);
+
+impl<T, const B: usize> Slots<T, B> {
+    /// Returns a raw pointer to the slot at `idx`, without creating a reference
+    /// to any other slot.
+    ///
+    /// # Safety
+    /// `this` points to valid storage and `idx` is less than `2 * B`.
+    unsafe fn slot(this: *mut Self, idx: usize) -> *mut T {
+        debug_assert!(idx < 2 * B);
+        // SAFETY: `repr(C)` lays out both halves back to back, without padding
+        // because both have the size and alignment of an array of `T`.
+        unsafe { this.cast::<T>().add(idx) }
+    }
+}
+
+impl<T, const B: usize> Deref for Slots<T, B> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts((self as *const Self).cast::<T>(), 2 * B) }
+    }
+}
+
+impl<T, const B: usize> DerefMut for Slots<T, B> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut((self as *mut Self).cast::<T>(), 2 * B) }
+    }
+}
 
 /// The underlying representation of leaf nodes and part of the representation of internal nodes.
-struct LeafNode<K, V> {
+struct LeafNode<K, V, S = (), const B: usize = 6> {
     /// We want to be covariant in `K` and `V`.
-    parent: Option<NonNull<InternalNode<K, V>>>,
+    parent: Option<NonNull<InternalNode<K, V, S, B>>>,
 
     /// This node's index into the parent node's `edges` array.
     /// `*node.parent.edges[node.parent_idx]` should be the same thing as `node`.
@@ -61,21 +104,28 @@ struct LeafNode<K, V> {
     /// The number of keys and values this node stores.
     len: u16,
 
+    /// The summary of all key-value pairs in the subtree headed by this node,
+    /// which is zero-sized unless the tree belongs to a `SummaryMap`.
+    summary: S,
+
     /// The arrays storing the actual data of the node. Only the first `len` elements of each
     /// array are initialized and valid.
-    keys: [MaybeUninit<K>; CAPACITY],
-    vals: [MaybeUninit<V>; CAPACITY],
+    keys: Slots<MaybeUninit<K>, B>,
+    vals: Slots<MaybeUninit<V>, B>,
 }
 
-impl<K, V> LeafNode<K, V> {
+impl<K, V, S: Summary<K, V>, const B: usize> LeafNode<K, V, S, B> {
     /// Initializes a new `LeafNode` in-place.
     unsafe fn init(this: *mut Self) {
+        #[allow(clippy::let_unit_value)]
+        let () = Consts::<B>::VALID;
         // As a general policy, we leave fields uninitialized if they can be, as this should
         // be both slightly faster and easier to track in Valgrind.
         unsafe {
             // parent_idx, keys, and vals are all MaybeUninit
             ptr::addr_of_mut!((*this).parent).write(None);
             ptr::addr_of_mut!((*this).len).write(0);
+            ptr::addr_of_mut!((*this).summary).write(S::empty());
         }
     }
 
@@ -96,16 +146,21 @@ impl<K, V> LeafNode<K, V> {
 /// which of the two a pointer is pointing at. This property is enabled by the use of `repr(C)`.
 #[repr(C)]
 // gdb_providers.py uses this type name for introspection.
-struct InternalNode<K, V> {
-    data: LeafNode<K, V>,
+struct InternalNode<K, V, S = (), const B: usize = 6> {
+    data: LeafNode<K, V, S, B>,
+
+    /// The number of keys and values stored in the subtree headed by this node,
+    /// including those of the node itself.
+    #[cfg(feature = "order_statistics")]
+    count: usize,
 
     /// The pointers to the children of this node. `len + 1` of these are considered
     /// initialized and valid, except that near the end, while the tree is held
     /// through borrow type `Dying`, some of these pointers are dangling.
-    edges: [MaybeUninit<BoxedNode<K, V>>; 2 * B],
+    edges: Slots<MaybeUninit<BoxedNode<K, V, S, B>>, B>,
 }
 
-impl<K, V> InternalNode<K, V> {
+impl<K, V, S: Summary<K, V>, const B: usize> InternalNode<K, V, S, B> {
     /// Creates a new boxed `InternalNode`.
     ///
     /// # Safety
@@ -117,6 +172,8 @@ impl<K, V> InternalNode<K, V> {
             let mut node = <A!(Box<Self, _>)>::new_uninit_in(alloc);
             // We only need to initialize the data; the edges are MaybeUninit.
             LeafNode::init(ptr::addr_of_mut!((*node.as_mut_ptr()).data));
+            #[cfg(feature = "order_statistics")]
+            ptr::addr_of_mut!((*node.as_mut_ptr()).count).write(0);
             node.assume_init()
         }
     }
@@ -128,7 +185,7 @@ impl<K, V> InternalNode<K, V> {
 /// However, `BoxedNode` contains no information as to which of the two types
 /// of nodes it actually contains, and, partially due to this lack of information,
 /// is not a separate type and has no destructor.
-type BoxedNode<K, V> = NonNull<LeafNode<K, V>>;
+type BoxedNode<K, V, S = (), const B: usize = 6> = NonNull<LeafNode<K, V, S, B>>;
 
 // N.B. `NodeRef` is always covariant in `K` and `V`, even when the `BorrowType`
 // is `Mut`. This is technically wrong, but cannot result in any unsafety due to
@@ -181,7 +238,7 @@ type BoxedNode<K, V> = NonNull<LeafNode<K, V>>;
 ///   as the returned reference is used.
 ///   The methods supporting insert bend this rule by returning a raw pointer,
 ///   i.e., a reference without any lifetime.
-pub struct NodeRef<BorrowType, K, V, Type> {
+pub struct NodeRef<BorrowType, K, V, Type, S = (), const B: usize = 6> {
     /// The number of levels that the node and the level of leaves are apart, a
     /// constant of the node that cannot be entirely described by `Type`, and that
     /// the node itself does not store. We only need to store the height of the root
@@ -190,42 +247,66 @@ pub struct NodeRef<BorrowType, K, V, Type> {
     height: usize,
     /// The pointer to the leaf or internal node. The definition of `InternalNode`
     /// ensures that the pointer is valid either way.
-    node: NonNull<LeafNode<K, V>>,
+    node: NonNull<LeafNode<K, V, S, B>>,
     _marker: PhantomData<(BorrowType, Type)>,
 }
 
 /// The root node of an owned tree.
 ///
 /// Note that this does not have a destructor, and must be cleaned up manually.
-pub type Root<K, V> = NodeRef<marker::Owned, K, V, marker::LeafOrInternal>;
+pub type Root<K, V, S = (), const B: usize = 6> =
+    NodeRef<marker::Owned, K, V, marker::LeafOrInternal, S, B>;
 
-impl<'a, K: 'a, V: 'a, Type> Copy for NodeRef<marker::Immut<'a>, K, V, Type> {}
-impl<'a, K: 'a, V: 'a, Type> Clone for NodeRef<marker::Immut<'a>, K, V, Type> {
+impl<'a, K: 'a, V: 'a, Type, S, const B: usize> Copy
+    for NodeRef<marker::Immut<'a>, K, V, Type, S, B>
+{
+}
+impl<'a, K: 'a, V: 'a, Type, S, const B: usize> Clone
+    for NodeRef<marker::Immut<'a>, K, V, Type, S, B>
+{
     fn clone(&self) -> Self {
         *self
     }
 }
 
-unsafe impl<BorrowType, K: Sync, V: Sync, Type> Sync for NodeRef<BorrowType, K, V, Type> {}
+unsafe impl<BorrowType, K: Sync, V: Sync, Type, S, const B: usize> Sync
+    for NodeRef<BorrowType, K, V, Type, S, B>
+{
+}
 
-unsafe impl<K: Sync, V: Sync, Type> Send for NodeRef<marker::Immut<'_>, K, V, Type> {}
-unsafe impl<K: Send, V: Send, Type> Send for NodeRef<marker::Mut<'_>, K, V, Type> {}
-unsafe impl<K: Send, V: Send, Type> Send for NodeRef<marker::ValMut<'_>, K, V, Type> {}
-unsafe impl<K: Send, V: Send, Type> Send for NodeRef<marker::Owned, K, V, Type> {}
-unsafe impl<K: Send, V: Send, Type> Send for NodeRef<marker::Dying, K, V, Type> {}
+unsafe impl<K: Sync, V: Sync, Type, S, const B: usize> Send
+    for NodeRef<marker::Immut<'_>, K, V, Type, S, B>
+{
+}
+unsafe impl<K: Send, V: Send, Type, S, const B: usize> Send
+    for NodeRef<marker::Mut<'_>, K, V, Type, S, B>
+{
+}
+unsafe impl<K: Send, V: Send, Type, S, const B: usize> Send
+    for NodeRef<marker::ValMut<'_>, K, V, Type, S, B>
+{
+}
+unsafe impl<K: Send, V: Send, Type, S, const B: usize> Send
+    for NodeRef<marker::Owned, K, V, Type, S, B>
+{
+}
+unsafe impl<K: Send, V: Send, Type, S, const B: usize> Send
+    for NodeRef<marker::Dying, K, V, Type, S, B>
+{
+}
 
-impl<K, V> NodeRef<marker::Owned, K, V, marker::Leaf> {
+impl<K, V, S: Summary<K, V>, const B: usize> NodeRef<marker::Owned, K, V, marker::Leaf, S, B> {
     pub fn new_leaf<A: Allocator + Clone>(alloc: A) -> Self {
         Self::from_new_leaf::<A>(LeafNode::new(alloc))
     }
 
-    fn from_new_leaf<A: Allocator + Clone>(leaf: A!(Box<LeafNode<K, V>, A>)) -> Self {
+    fn from_new_leaf<A: Allocator + Clone>(leaf: A!(Box<LeafNode<K, V, S, B>, A>)) -> Self {
         NodeRef { height: 0, node: NonNull::from(Box::leak(leaf)), _marker: PhantomData }
     }
 }
 
-impl<K, V> NodeRef<marker::Owned, K, V, marker::Internal> {
-    fn new_internal<A: Allocator + Clone>(child: Root<K, V>, alloc: A) -> Self {
+impl<K, V, S: Summary<K, V>, const B: usize> NodeRef<marker::Owned, K, V, marker::Internal, S, B> {
+    fn new_internal<A: Allocator + Clone>(child: Root<K, V, S, B>, alloc: A) -> Self {
         let mut new_node = unsafe { InternalNode::new(alloc) };
         new_node.edges[0].write(child.node);
         unsafe { NodeRef::from_new_internal::<A>(new_node, child.height + 1) }
@@ -234,44 +315,45 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::Internal> {
     /// # Safety
     /// `height` must not be zero.
     unsafe fn from_new_internal<A: Allocator + Clone>(
-        internal: A!(Box<InternalNode<K, V>, A>),
+        internal: A!(Box<InternalNode<K, V, S, B>, A>),
         height: usize,
     ) -> Self {
         debug_assert!(height > 0);
         let node = NonNull::from(Box::leak(internal)).cast();
         let mut this = NodeRef { height, node, _marker: PhantomData };
         this.borrow_mut().correct_all_childrens_parent_links();
+        this.borrow_mut().update_cache();
         this
     }
 }
 
-impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Internal> {
+impl<BorrowType, K, V, S, const B: usize> NodeRef<BorrowType, K, V, marker::Internal, S, B> {
     /// Unpack a node reference that was packed as `NodeRef::parent`.
-    fn from_internal(node: NonNull<InternalNode<K, V>>, height: usize) -> Self {
+    fn from_internal(node: NonNull<InternalNode<K, V, S, B>>, height: usize) -> Self {
         debug_assert!(height > 0);
         NodeRef { height, node: node.cast(), _marker: PhantomData }
     }
 }
 
-impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Internal> {
+impl<BorrowType, K, V, S, const B: usize> NodeRef<BorrowType, K, V, marker::Internal, S, B> {
     /// Exposes the data of an internal node.
     ///
     /// Returns a raw ptr to avoid invalidating other references to this node.
-    fn as_internal_ptr(this: &Self) -> *mut InternalNode<K, V> {
+    fn as_internal_ptr(this: &Self) -> *mut InternalNode<K, V, S, B> {
         // SAFETY: the static node type is `Internal`.
-        this.node.as_ptr() as *mut InternalNode<K, V>
+        this.node.as_ptr() as *mut InternalNode<K, V, S, B>
     }
 }
 
-impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
+impl<'a, K, V, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B> {
     /// Borrows exclusive access to the data of an internal node.
-    fn as_internal_mut(&mut self) -> &mut InternalNode<K, V> {
+    fn as_internal_mut(&mut self) -> &mut InternalNode<K, V, S, B> {
         let ptr = Self::as_internal_ptr(self);
         unsafe { &mut *ptr }
     }
 }
 
-impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
+impl<BorrowType, K, V, Type, S, const B: usize> NodeRef<BorrowType, K, V, Type, S, B> {
     /// Finds the length of the node. This is the number of keys or values.
     /// The number of edges is `len() + 1`.
     /// Note that, despite being safe, calling this function can have the side effect
@@ -291,15 +373,29 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
         self.height
     }
 
+    /// Finds the number of keys or values in the subtree headed by the node.
+    /// Like `len`, this only accesses length fields, and so does not
+    /// invalidate references to keys or values anywhere in the tree.
+    #[cfg(feature = "order_statistics")]
+    pub fn subtree_len(&self) -> usize {
+        if self.height == 0 {
+            self.len()
+        } else {
+            let internal = self.node.as_ptr() as *const InternalNode<K, V, S, B>;
+            // SAFETY: the node is internal, because its height is not zero.
+            unsafe { *ptr::addr_of!((*internal).count) }
+        }
+    }
+
     /// Temporarily takes out another, immutable reference to the same node.
-    pub fn reborrow(&self) -> NodeRef<marker::Immut<'_>, K, V, Type> {
+    pub fn reborrow(&self) -> NodeRef<marker::Immut<'_>, K, V, Type, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 
     /// Exposes the leaf portion of any leaf or internal node.
     ///
     /// Returns a raw ptr to avoid invalidating other references to this node.
-    fn as_leaf_ptr(this: &Self) -> *mut LeafNode<K, V> {
+    fn as_leaf_ptr(this: &Self) -> *mut LeafNode<K, V, S, B> {
         // The node must be valid for at least the LeafNode portion.
         // This is not a reference in the NodeRef type because we don't know if
         // it should be unique or shared.
@@ -307,7 +403,9 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
+impl<BorrowType: marker::BorrowType, K, V, Type, S, const B: usize>
+    NodeRef<BorrowType, K, V, Type, S, B>
+{
     /// Finds the parent of the current node. Returns `Ok(handle)` if the current
     /// node actually has a parent, where `handle` points to the edge of the parent
     /// that points to the current node. Returns `Err(self)` if the current node has
@@ -319,7 +417,7 @@ impl<BorrowType: marker::BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type>
     /// both, upon success, do nothing.
     pub fn ascend(
         self,
-    ) -> Result<Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::Edge>, Self> {
+    ) -> Result<Handle<NodeRef<BorrowType, K, V, marker::Internal, S, B>, marker::Edge>, Self> {
         cfg_if! {
             if #[cfg(feature = "inline_const")] {
                 const {
@@ -367,7 +465,7 @@ impl<BorrowType: marker::BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type>
     }
 }
 
-impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
+impl<BorrowType, K, V, Type, S, const B: usize> NodeRef<BorrowType, K, V, Type, S, B> {
     /// Could be a public implementation of PartialEq, but only used in this module.
     fn eq(&self, other: &Self) -> bool {
         let Self { node, height, _marker } = self;
@@ -380,9 +478,12 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
     }
 }
 
-impl<'a, K: 'a, V: 'a, Type> NodeRef<marker::Immut<'a>, K, V, Type> {
+impl<'a, K: 'a, V: 'a, Type, S, const B: usize> NodeRef<marker::Immut<'a>, K, V, Type, S, B> {
     /// Exposes the leaf portion of any leaf or internal node in an immutable tree.
-    fn into_leaf(self) -> &'a LeafNode<K, V> {
+    fn into_leaf(self) -> &'a LeafNode<K, V, S, B>
+    where
+        S: 'a,
+    {
         let ptr = Self::as_leaf_ptr(&self);
         // SAFETY: there can be no mutable references into this tree borrowed as `Immut`.
         unsafe { &*ptr }
@@ -390,31 +491,40 @@ impl<'a, K: 'a, V: 'a, Type> NodeRef<marker::Immut<'a>, K, V, Type> {
 
     /// Borrows a view into the keys stored in the node.
     pub fn keys(&self) -> &[K] {
-        let leaf = self.into_leaf();
+        let leaf = self.reborrow().into_leaf();
         unsafe {
             MaybeUninit::slice_assume_init_ref(leaf.keys.get_unchecked(..usize::from(leaf.len)))
         }
     }
+
+    /// Borrows the summary of the subtree headed by the node.
+    pub fn summary(self) -> &'a S
+    where
+        S: 'a,
+    {
+        &self.into_leaf().summary
+    }
 }
 
-impl<K, V> NodeRef<marker::Dying, K, V, marker::LeafOrInternal> {
+impl<K, V, S, const B: usize> NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S, B> {
     /// Similar to `ascend`, gets a reference to a node's parent node, but also
     /// deallocates the current node in the process. This is unsafe because the
     /// current node will still be accessible despite being deallocated.
     pub unsafe fn deallocate_and_ascend<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::Internal>, marker::Edge>> {
+    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::Internal, S, B>, marker::Edge>> {
         let height = self.height;
         let node = self.node;
         let ret = self.ascend().ok();
         unsafe {
+            ptr::drop_in_place(ptr::addr_of_mut!((*node.as_ptr()).summary));
             alloc.deallocate(
                 node.cast(),
                 if height > 0 {
-                    Layout::new::<InternalNode<K, V>>()
+                    Layout::new::<InternalNode<K, V, S, B>>()
                 } else {
-                    Layout::new::<LeafNode<K, V>>()
+                    Layout::new::<LeafNode<K, V, S, B>>()
                 },
             );
         }
@@ -422,7 +532,7 @@ impl<K, V> NodeRef<marker::Dying, K, V, marker::LeafOrInternal> {
     }
 }
 
-impl<'a, K, V, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
+impl<'a, K, V, Type, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, Type, S, B> {
     /// Temporarily takes out another mutable reference to the same node. Beware, as
     /// this method is very dangerous, doubly so since it might not immediately appear
     /// dangerous.
@@ -433,53 +543,46 @@ impl<'a, K, V, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
     // FIXME(@gereeter) consider adding yet another type parameter to `NodeRef`
     // that restricts the use of navigation methods on reborrowed pointers,
     // preventing this unsafety.
-    unsafe fn reborrow_mut(&mut self) -> NodeRef<marker::Mut<'_>, K, V, Type> {
+    unsafe fn reborrow_mut(&mut self) -> NodeRef<marker::Mut<'_>, K, V, Type, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 
     /// Borrows exclusive access to the leaf portion of a leaf or internal node.
-    fn as_leaf_mut(&mut self) -> &mut LeafNode<K, V> {
+    fn as_leaf_mut(&mut self) -> &mut LeafNode<K, V, S, B> {
         let ptr = Self::as_leaf_ptr(self);
         // SAFETY: we have exclusive access to the entire node.
         unsafe { &mut *ptr }
     }
 
-    /// Offers exclusive access to the leaf portion of a leaf or internal node.
-    fn into_leaf_mut(mut self) -> &'a mut LeafNode<K, V> {
-        let ptr = Self::as_leaf_ptr(&mut self);
-        // SAFETY: we have exclusive access to the entire node.
-        unsafe { &mut *ptr }
-    }
-
     /// Returns a dormant copy of this node with its lifetime erased which can
     /// be reawakened later.
-    pub fn dormant(&self) -> NodeRef<marker::DormantMut, K, V, Type> {
+    pub fn dormant(&self) -> NodeRef<marker::DormantMut, K, V, Type, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 }
 
-impl<K, V, Type> NodeRef<marker::DormantMut, K, V, Type> {
+impl<K, V, Type, S, const B: usize> NodeRef<marker::DormantMut, K, V, Type, S, B> {
     /// Revert to the unique borrow initially captured.
     ///
     /// # Safety
     ///
     /// The reborrow must have ended, i.e., the reference returned by `new` and
     /// all pointers and references derived from it, must not be used anymore.
-    pub unsafe fn awaken<'a>(self) -> NodeRef<marker::Mut<'a>, K, V, Type> {
+    pub unsafe fn awaken<'a>(self) -> NodeRef<marker::Mut<'a>, K, V, Type, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 }
 
-impl<K, V, Type> NodeRef<marker::Dying, K, V, Type> {
+impl<K, V, Type, S, const B: usize> NodeRef<marker::Dying, K, V, Type, S, B> {
     /// Borrows exclusive access to the leaf portion of a dying leaf or internal node.
-    fn as_leaf_dying(&mut self) -> &mut LeafNode<K, V> {
+    fn as_leaf_dying(&mut self) -> &mut LeafNode<K, V, S, B> {
         let ptr = Self::as_leaf_ptr(self);
         // SAFETY: we have exclusive access to the entire node.
         unsafe { &mut *ptr }
     }
 }
 
-impl<'a, K: 'a, V: 'a, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
+impl<'a, K: 'a, V: 'a, Type, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, Type, S, B> {
     /// Borrows exclusive access to an element of the key storage area.
     ///
     /// # Safety
@@ -491,7 +594,7 @@ impl<'a, K: 'a, V: 'a, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
         // SAFETY: the caller will not be able to call further methods on self
         // until the key slice reference is dropped, as we have unique access
         // for the lifetime of the borrow.
-        unsafe { self.as_leaf_mut().keys.as_mut_slice().get_unchecked_mut(index) }
+        unsafe { self.as_leaf_mut().keys.get_unchecked_mut(index) }
     }
 
     /// Borrows exclusive access to an element or slice of the node's value storage area.
@@ -505,27 +608,27 @@ impl<'a, K: 'a, V: 'a, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
         // SAFETY: the caller will not be able to call further methods on self
         // until the value slice reference is dropped, as we have unique access
         // for the lifetime of the borrow.
-        unsafe { self.as_leaf_mut().vals.as_mut_slice().get_unchecked_mut(index) }
+        unsafe { self.as_leaf_mut().vals.get_unchecked_mut(index) }
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
+impl<'a, K: 'a, V: 'a, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B> {
     /// Borrows exclusive access to an element or slice of the node's storage area for edge contents.
     ///
     /// # Safety
     /// `index` is in bounds of 0..CAPACITY + 1
     unsafe fn edge_area_mut<I, Output: ?Sized>(&mut self, index: I) -> &mut Output
     where
-        I: SliceIndex<[MaybeUninit<BoxedNode<K, V>>], Output = Output>,
+        I: SliceIndex<[MaybeUninit<BoxedNode<K, V, S, B>>], Output = Output>,
     {
         // SAFETY: the caller will not be able to call further methods on self
         // until the edge slice reference is dropped, as we have unique access
         // for the lifetime of the borrow.
-        unsafe { self.as_internal_mut().edges.as_mut_slice().get_unchecked_mut(index) }
+        unsafe { self.as_internal_mut().edges.get_unchecked_mut(index) }
     }
 }
 
-impl<'a, K, V, Type> NodeRef<marker::ValMut<'a>, K, V, Type> {
+impl<'a, K, V, Type, S, const B: usize> NodeRef<marker::ValMut<'a>, K, V, Type, S, B> {
     /// # Safety
     /// - The node has more than `idx` initialized elements.
     unsafe fn into_key_val_mut_at(mut self, idx: usize) -> (&'a K, &'a mut V) {
@@ -533,25 +636,138 @@ impl<'a, K, V, Type> NodeRef<marker::ValMut<'a>, K, V, Type> {
         // to avoid aliasing with outstanding references to other elements,
         // in particular, those returned to the caller in earlier iterations.
         let leaf = Self::as_leaf_ptr(&mut self);
-        let keys = unsafe { ptr::addr_of!((*leaf).keys) };
+        let keys = unsafe { ptr::addr_of_mut!((*leaf).keys) };
         let vals = unsafe { ptr::addr_of_mut!((*leaf).vals) };
-        // We must coerce to unsized array pointers because of Rust issue #74679.
-        let keys: *const [_] = keys;
-        let vals: *mut [_] = vals;
-        let key = unsafe { (&*keys.get_unchecked(idx)).assume_init_ref() };
-        let val = unsafe { (&mut *vals.get_unchecked_mut(idx)).assume_init_mut() };
+        let key = unsafe { (*Slots::slot(keys, idx)).assume_init_ref() };
+        let val = unsafe { (*Slots::slot(vals, idx)).assume_init_mut() };
         (key, val)
     }
 }
 
-impl<'a, K: 'a, V: 'a, Type> NodeRef<marker::Mut<'a>, K, V, Type> {
+impl<'a, K: 'a, V: 'a, Type, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, Type, S, B> {
     /// Borrows exclusive access to the length of the node.
     pub fn len_mut(&mut self) -> &mut u16 {
         &mut self.as_leaf_mut().len
     }
 }
 
-impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
+impl<'a, K: 'a, V: 'a, Type, S: Summary<K, V>, const B: usize>
+    NodeRef<marker::Mut<'a>, K, V, Type, S, B>
+{
+    /// Recomputes the caches of the node, i.e., the count kept with the
+    /// `order_statistics` feature and the summary, from the key-value pairs of
+    /// the node and the caches of its children, which must be up to date.
+    pub fn update_cache(&mut self) {
+        let node: NodeRef<marker::Immut<'_>, K, V, marker::LeafOrInternal, S, B> =
+            NodeRef { height: self.height, node: self.node, _marker: PhantomData };
+        #[cfg(feature = "order_statistics")]
+        if let ForceResult::Internal(internal) = node.force() {
+            let len = internal.len();
+            let mut count = len;
+            for idx in 0..=len {
+                count += unsafe { Handle::new_edge(internal, idx) }.descend().subtree_len();
+            }
+            let ptr = NodeRef::as_internal_ptr(&internal);
+            unsafe { (*ptr).count = count };
+        }
+        if mem::size_of::<S>() == 0 {
+            return;
+        }
+        let leaf = node.into_leaf();
+        let mut summary = S::empty();
+        for idx in 0..usize::from(leaf.len) {
+            if let ForceResult::Internal(internal) = node.force() {
+                summary.combine(unsafe { Handle::new_edge(internal, idx) }.descend().summary());
+            }
+            let (key, val) =
+                unsafe { (leaf.keys[idx].assume_init_ref(), leaf.vals[idx].assume_init_ref()) };
+            summary.combine(&S::of(key, val));
+        }
+        if let ForceResult::Internal(internal) = node.force() {
+            summary
+                .combine(unsafe { Handle::new_edge(internal, internal.len()) }.descend().summary());
+        }
+        unsafe { (*Self::as_leaf_ptr(self)).summary = summary };
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
+    /// Recomputes the caches of the node and of every ancestor, bottom-up, after
+    /// the subtree headed by the node was changed.
+    pub fn update_caches_upward(self) {
+        if !cfg!(feature = "order_statistics") && mem::size_of::<S>() == 0 {
+            return;
+        }
+        let mut node = self;
+        loop {
+            node.update_cache();
+            node = match node.ascend() {
+                Ok(parent) => parent.into_node().forget_type(),
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Recomputes the caches of every node in the subtree headed by the node,
+    /// bottom-up, after any of its values may have been changed.
+    pub fn update_subtree_caches(&mut self) {
+        if let ForceResult::Internal(mut internal) = unsafe { self.reborrow_mut() }.force() {
+            for idx in 0..=internal.len() {
+                let mut child = unsafe { Handle::new_edge(internal.reborrow_mut(), idx) }.descend();
+                child.update_subtree_caches();
+            }
+        }
+        self.update_cache();
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>
+{
+    /// Recomputes the caches of every node holding key-value pairs before the
+    /// edge, bottom-up, after any of those values may have been changed.
+    pub fn update_caches_before(self) {
+        self.update_caches_beside(|idx, _len| 0..idx);
+    }
+
+    /// Recomputes the caches of every node holding key-value pairs after the
+    /// edge, bottom-up, after any of those values may have been changed.
+    pub fn update_caches_after(self) {
+        self.update_caches_beside(|idx, len| idx + 1..len + 1);
+    }
+
+    // Walks up from the edge, updating on each level the subtrees of the
+    // children picked by `children` and then the node on the path itself.
+    fn update_caches_beside<F>(self, children: F)
+    where
+        F: Fn(usize, usize) -> core::ops::Range<usize>,
+    {
+        if !cfg!(feature = "order_statistics") && mem::size_of::<S>() == 0 {
+            return;
+        }
+        let mut edge = self.forget_node_type();
+        loop {
+            let idx = edge.idx();
+            let mut node = edge.into_node();
+            if let ForceResult::Internal(mut internal) = unsafe { node.reborrow_mut() }.force() {
+                for i in children(idx, internal.len()) {
+                    unsafe { Handle::new_edge(internal.reborrow_mut(), i) }
+                        .descend()
+                        .update_subtree_caches();
+                }
+            }
+            node.update_cache();
+            edge = match node.ascend() {
+                Ok(parent) => parent.forget_node_type(),
+                Err(_) => return,
+            };
+        }
+    }
+}
+
+impl<'a, K, V, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B> {
     /// # Safety
     /// Every item returned by `range` is a valid edge index for the node.
     unsafe fn correct_childrens_parent_links<R: Iterator<Item = usize>>(&mut self, range: R) {
@@ -567,17 +783,19 @@ impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
     /// Sets the node's link to its parent edge,
     /// without invalidating other references to the node.
-    fn set_parent_link(&mut self, parent: NonNull<InternalNode<K, V>>, parent_idx: usize) {
+    fn set_parent_link(&mut self, parent: NonNull<InternalNode<K, V, S, B>>, parent_idx: usize) {
         let leaf = Self::as_leaf_ptr(self);
         unsafe { (*leaf).parent = Some(parent) };
         unsafe { (*leaf).parent_idx.write(parent_idx as u16) };
     }
 }
 
-impl<K, V> NodeRef<marker::Owned, K, V, marker::LeafOrInternal> {
+impl<K, V, S, const B: usize> NodeRef<marker::Owned, K, V, marker::LeafOrInternal, S, B> {
     /// Clears the root's link to its parent edge.
     fn clear_parent_link(&mut self) {
         let mut root_node = self.borrow_mut();
@@ -586,7 +804,9 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::LeafOrInternal> {
     }
 }
 
-impl<K, V> NodeRef<marker::Owned, K, V, marker::LeafOrInternal> {
+impl<K, V, S: Summary<K, V>, const B: usize>
+    NodeRef<marker::Owned, K, V, marker::LeafOrInternal, S, B>
+{
     /// Returns a new owned tree, with its own root node that is initially empty.
     pub fn new<A: Allocator + Clone>(alloc: A) -> Self {
         NodeRef::new_leaf(alloc).forget_type()
@@ -598,7 +818,7 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::LeafOrInternal> {
     pub fn push_internal_level<A: Allocator + Clone>(
         &mut self,
         alloc: A,
-    ) -> NodeRef<marker::Mut<'_>, K, V, marker::Internal> {
+    ) -> NodeRef<marker::Mut<'_>, K, V, marker::Internal, S, B> {
         super::mem::take_mut(self, |old_root| NodeRef::new_internal(old_root, alloc).forget_type());
 
         // `self.borrow_mut()`, except that we just forgot we're internal now:
@@ -629,38 +849,40 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::LeafOrInternal> {
         self.clear_parent_link();
 
         unsafe {
-            alloc.deallocate(top.cast(), Layout::new::<InternalNode<K, V>>());
+            ptr::drop_in_place(ptr::addr_of_mut!((*top.as_ptr()).summary));
+            alloc.deallocate(top.cast(), Layout::new::<InternalNode<K, V, S, B>>());
         }
     }
 }
 
-impl<K, V, Type> NodeRef<marker::Owned, K, V, Type> {
+impl<K, V, Type, S, const B: usize> NodeRef<marker::Owned, K, V, Type, S, B> {
     /// Mutably borrows the owned root node. Unlike `reborrow_mut`, this is safe
     /// because the return value cannot be used to destroy the root, and there
     /// cannot be other references to the tree.
-    pub fn borrow_mut(&mut self) -> NodeRef<marker::Mut<'_>, K, V, Type> {
+    pub fn borrow_mut(&mut self) -> NodeRef<marker::Mut<'_>, K, V, Type, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 
     /// Slightly mutably borrows the owned root node.
-    pub fn borrow_valmut(&mut self) -> NodeRef<marker::ValMut<'_>, K, V, Type> {
+    pub fn borrow_valmut(&mut self) -> NodeRef<marker::ValMut<'_>, K, V, Type, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 
     /// Irreversibly transitions to a reference that permits traversal and offers
     /// destructive methods and little else.
-    pub fn into_dying(self) -> NodeRef<marker::Dying, K, V, Type> {
+    pub fn into_dying(self) -> NodeRef<marker::Dying, K, V, Type, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::Leaf> {
+impl<'a, K: 'a, V: 'a, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B> {
     /// Adds a key-value pair to the end of the node, and returns
     /// the mutable reference of the inserted value.
+    /// The counts of any ancestors are left for the caller to adjust.
     pub fn push(&mut self, key: K, val: V) -> &mut V {
         let len = self.len_mut();
         let idx = usize::from(*len);
-        assert!(idx < CAPACITY);
+        assert!(idx < Consts::<B>::CAPACITY);
         *len += 1;
         unsafe {
             self.key_area_mut(idx).write(key);
@@ -669,15 +891,17 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::Leaf> {
     }
 }
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>
+{
     /// Adds a key-value pair, and an edge to go to the right of that pair,
     /// to the end of the node.
-    pub fn push(&mut self, key: K, val: V, edge: Root<K, V>) {
+    pub fn push(&mut self, key: K, val: V, edge: Root<K, V, S, B>) {
         assert!(edge.height == self.height - 1);
 
         let len = self.len_mut();
         let idx = usize::from(*len);
-        assert!(idx < CAPACITY);
+        assert!(idx < Consts::<B>::CAPACITY);
         *len += 1;
         unsafe {
             self.key_area_mut(idx).write(key);
@@ -685,30 +909,31 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
             self.edge_area_mut(idx + 1).write(edge.node);
             Handle::new_edge(self.reborrow_mut(), idx + 1).correct_parent_link();
         }
+        self.update_cache();
     }
 }
 
-impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Leaf> {
+impl<BorrowType, K, V, S, const B: usize> NodeRef<BorrowType, K, V, marker::Leaf, S, B> {
     /// Removes any static information asserting that this node is a `Leaf` node.
-    pub fn forget_type(self) -> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+    pub fn forget_type(self) -> NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 }
 
-impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Internal> {
+impl<BorrowType, K, V, S, const B: usize> NodeRef<BorrowType, K, V, marker::Internal, S, B> {
     /// Removes any static information asserting that this node is an `Internal` node.
-    pub fn forget_type(self) -> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+    pub fn forget_type(self) -> NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B> {
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 }
 
-impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+impl<BorrowType, K, V, S, const B: usize> NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B> {
     /// Checks whether a node is an `Internal` node or a `Leaf` node.
     pub fn force(
         self,
     ) -> ForceResult<
-        NodeRef<BorrowType, K, V, marker::Leaf>,
-        NodeRef<BorrowType, K, V, marker::Internal>,
+        NodeRef<BorrowType, K, V, marker::Leaf, S, B>,
+        NodeRef<BorrowType, K, V, marker::Internal, S, B>,
     > {
         if self.height == 0 {
             ForceResult::Leaf(NodeRef {
@@ -726,15 +951,17 @@ impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
     }
 }
 
-impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K, V, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B> {
     /// Unsafely asserts to the compiler the static information that this node is a `Leaf`.
-    unsafe fn cast_to_leaf_unchecked(self) -> NodeRef<marker::Mut<'a>, K, V, marker::Leaf> {
+    unsafe fn cast_to_leaf_unchecked(self) -> NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B> {
         debug_assert!(self.height == 0);
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
 
     /// Unsafely asserts to the compiler the static information that this node is an `Internal`.
-    unsafe fn cast_to_internal_unchecked(self) -> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
+    unsafe fn cast_to_internal_unchecked(
+        self,
+    ) -> NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B> {
         debug_assert!(self.height > 0);
         NodeRef { height: self.height, node: self.node, _marker: PhantomData }
     }
@@ -775,26 +1002,28 @@ impl<Node, Type> Handle<Node, Type> {
     }
 }
 
-impl<BorrowType, K, V, NodeType> Handle<NodeRef<BorrowType, K, V, NodeType>, marker::KV> {
+impl<BorrowType, K, V, NodeType, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, marker::KV>
+{
     /// Creates a new handle to a key-value pair in `node`.
     /// Unsafe because the caller must ensure that `idx < node.len()`.
-    pub unsafe fn new_kv(node: NodeRef<BorrowType, K, V, NodeType>, idx: usize) -> Self {
+    pub unsafe fn new_kv(node: NodeRef<BorrowType, K, V, NodeType, S, B>, idx: usize) -> Self {
         debug_assert!(idx < node.len());
 
         Handle { node, idx, _marker: PhantomData }
     }
 
-    pub fn left_edge(self) -> Handle<NodeRef<BorrowType, K, V, NodeType>, marker::Edge> {
+    pub fn left_edge(self) -> Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, marker::Edge> {
         unsafe { Handle::new_edge(self.node, self.idx) }
     }
 
-    pub fn right_edge(self) -> Handle<NodeRef<BorrowType, K, V, NodeType>, marker::Edge> {
+    pub fn right_edge(self) -> Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, marker::Edge> {
         unsafe { Handle::new_edge(self.node, self.idx + 1) }
     }
 }
 
-impl<BorrowType, K, V, NodeType, HandleType> PartialEq
-    for Handle<NodeRef<BorrowType, K, V, NodeType>, HandleType>
+impl<BorrowType, K, V, NodeType, HandleType, S, const B: usize> PartialEq
+    for Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, HandleType>
 {
     fn eq(&self, other: &Self) -> bool {
         let Self { node, idx, _marker } = self;
@@ -802,17 +1031,19 @@ impl<BorrowType, K, V, NodeType, HandleType> PartialEq
     }
 }
 
-impl<BorrowType, K, V, NodeType, HandleType>
-    Handle<NodeRef<BorrowType, K, V, NodeType>, HandleType>
+impl<BorrowType, K, V, NodeType, HandleType, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, HandleType>
 {
     /// Temporarily takes out another immutable handle on the same location.
-    pub fn reborrow(&self) -> Handle<NodeRef<marker::Immut<'_>, K, V, NodeType>, HandleType> {
+    pub fn reborrow(&self) -> Handle<NodeRef<marker::Immut<'_>, K, V, NodeType, S, B>, HandleType> {
         // We can't use Handle::new_kv or Handle::new_edge because we don't know our type
         Handle { node: self.node.reborrow(), idx: self.idx, _marker: PhantomData }
     }
 }
 
-impl<'a, K, V, NodeType, HandleType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType>, HandleType> {
+impl<'a, K, V, NodeType, HandleType, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, NodeType, S, B>, HandleType>
+{
     /// Temporarily takes out another mutable handle on the same location. Beware, as
     /// this method is very dangerous, doubly so since it might not immediately appear
     /// dangerous.
@@ -820,7 +1051,7 @@ impl<'a, K, V, NodeType, HandleType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeT
     /// For details, see `NodeRef::reborrow_mut`.
     pub unsafe fn reborrow_mut(
         &mut self,
-    ) -> Handle<NodeRef<marker::Mut<'_>, K, V, NodeType>, HandleType> {
+    ) -> Handle<NodeRef<marker::Mut<'_>, K, V, NodeType, S, B>, HandleType> {
         // We can't use Handle::new_kv or Handle::new_edge because we don't know our type
         Handle { node: unsafe { self.node.reborrow_mut() }, idx: self.idx, _marker: PhantomData }
     }
@@ -828,33 +1059,52 @@ impl<'a, K, V, NodeType, HandleType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeT
     /// Returns a dormant copy of this handle which can be reawakened later.
     ///
     /// See `DormantMutRef` for more details.
-    pub fn dormant(&self) -> Handle<NodeRef<marker::DormantMut, K, V, NodeType>, HandleType> {
+    pub fn dormant(&self) -> Handle<NodeRef<marker::DormantMut, K, V, NodeType, S, B>, HandleType> {
         Handle { node: self.node.dormant(), idx: self.idx, _marker: PhantomData }
     }
 }
 
-impl<K, V, NodeType, HandleType> Handle<NodeRef<marker::DormantMut, K, V, NodeType>, HandleType> {
+impl<'a, K, V, NodeType, HandleType, S, const B: usize>
+    Handle<NodeRef<marker::ValMut<'a>, K, V, NodeType, S, B>, HandleType>
+{
+    /// Returns a dormant copy of this handle which can be reawakened later,
+    /// once the references to values obtained through it are gone.
+    pub fn dormant(&self) -> Handle<NodeRef<marker::DormantMut, K, V, NodeType, S, B>, HandleType> {
+        let node = NodeRef { height: self.node.height, node: self.node.node, _marker: PhantomData };
+        Handle { node, idx: self.idx, _marker: PhantomData }
+    }
+}
+
+impl<K, V, NodeType, HandleType, S, const B: usize>
+    Handle<NodeRef<marker::DormantMut, K, V, NodeType, S, B>, HandleType>
+{
     /// Revert to the unique borrow initially captured.
     ///
     /// # Safety
     ///
     /// The reborrow must have ended, i.e., the reference returned by `new` and
     /// all pointers and references derived from it, must not be used anymore.
-    pub unsafe fn awaken<'a>(self) -> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType>, HandleType> {
+    pub unsafe fn awaken<'a>(
+        self,
+    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType, S, B>, HandleType> {
         Handle { node: unsafe { self.node.awaken() }, idx: self.idx, _marker: PhantomData }
     }
 }
 
-impl<BorrowType, K, V, NodeType> Handle<NodeRef<BorrowType, K, V, NodeType>, marker::Edge> {
+impl<BorrowType, K, V, NodeType, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, marker::Edge>
+{
     /// Creates a new handle to an edge in `node`.
     /// Unsafe because the caller must ensure that `idx <= node.len()`.
-    pub unsafe fn new_edge(node: NodeRef<BorrowType, K, V, NodeType>, idx: usize) -> Self {
+    pub unsafe fn new_edge(node: NodeRef<BorrowType, K, V, NodeType, S, B>, idx: usize) -> Self {
         debug_assert!(idx <= node.len());
 
         Handle { node, idx, _marker: PhantomData }
     }
 
-    pub fn left_kv(self) -> Result<Handle<NodeRef<BorrowType, K, V, NodeType>, marker::KV>, Self> {
+    pub fn left_kv(
+        self,
+    ) -> Result<Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, marker::KV>, Self> {
         if self.idx > 0 {
             Ok(unsafe { Handle::new_kv(self.node, self.idx - 1) })
         } else {
@@ -862,7 +1112,9 @@ impl<BorrowType, K, V, NodeType> Handle<NodeRef<BorrowType, K, V, NodeType>, mar
         }
     }
 
-    pub fn right_kv(self) -> Result<Handle<NodeRef<BorrowType, K, V, NodeType>, marker::KV>, Self> {
+    pub fn right_kv(
+        self,
+    ) -> Result<Handle<NodeRef<BorrowType, K, V, NodeType, S, B>, marker::KV>, Self> {
         if self.idx < self.node.len() {
             Ok(unsafe { Handle::new_kv(self.node, self.idx) })
         } else {
@@ -881,24 +1133,26 @@ pub enum LeftOrRight<T> {
 /// The goal of the split point is for its key and value to end up in a parent node;
 /// the keys, values and edges to the left of the split point become the left child;
 /// the keys, values and edges to the right of the split point become the right child.
-fn splitpoint(edge_idx: usize) -> (usize, LeftOrRight<usize>) {
-    debug_assert!(edge_idx <= CAPACITY);
+fn splitpoint<const B: usize>(edge_idx: usize) -> (usize, LeftOrRight<usize>) {
+    debug_assert!(edge_idx <= Consts::<B>::CAPACITY);
 
-    #[cfg(not(feature = "exclusive_range_pattern"))]
-    const EDGE_IDX_LEFT_OF_LEFT_OF_CENTER: usize = EDGE_IDX_LEFT_OF_CENTER - 1;
-    // Rust issue #74834 tries to explain these symmetric rules.
-    match edge_idx {
-        #[cfg(not(feature = "exclusive_range_pattern"))]
-        0..=EDGE_IDX_LEFT_OF_LEFT_OF_CENTER => (KV_IDX_CENTER - 1, LeftOrRight::Left(edge_idx)),
-        #[cfg(feature = "exclusive_range_pattern")]
-        0..EDGE_IDX_LEFT_OF_CENTER => (KV_IDX_CENTER - 1, LeftOrRight::Left(edge_idx)),
-        EDGE_IDX_LEFT_OF_CENTER => (KV_IDX_CENTER, LeftOrRight::Left(edge_idx)),
-        EDGE_IDX_RIGHT_OF_CENTER => (KV_IDX_CENTER, LeftOrRight::Right(0)),
-        _ => (KV_IDX_CENTER + 1, LeftOrRight::Right(edge_idx - (KV_IDX_CENTER + 1 + 1))),
+    let kv_idx_center = Consts::<B>::KV_IDX_CENTER;
+    // Rust issue #74834 tries to explain these symmetric rules. The indices
+    // depend on `B`, so they cannot be used as match patterns.
+    if edge_idx < Consts::<B>::EDGE_IDX_LEFT_OF_CENTER {
+        (kv_idx_center - 1, LeftOrRight::Left(edge_idx))
+    } else if edge_idx == Consts::<B>::EDGE_IDX_LEFT_OF_CENTER {
+        (kv_idx_center, LeftOrRight::Left(edge_idx))
+    } else if edge_idx == Consts::<B>::EDGE_IDX_RIGHT_OF_CENTER {
+        (kv_idx_center, LeftOrRight::Right(0))
+    } else {
+        (kv_idx_center + 1, LeftOrRight::Right(edge_idx - (kv_idx_center + 1 + 1)))
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     /// Inserts a new key-value pair between the key-value pairs to the right and left of
     /// this edge. This method assumes that there is enough space in the node for the new
     /// pair to fit.
@@ -906,21 +1160,24 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
         mut self,
         key: K,
         val: V,
-    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV> {
-        debug_assert!(self.node.len() < CAPACITY);
+    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::KV> {
+        debug_assert!(self.node.len() < Consts::<B>::CAPACITY);
         let new_len = self.node.len() + 1;
 
         unsafe {
             slice_insert(self.node.key_area_mut(..new_len), self.idx, key);
             slice_insert(self.node.val_area_mut(..new_len), self.idx, val);
             *self.node.len_mut() = new_len as u16;
+            self.node.update_cache();
 
             Handle::new_kv(self.node, self.idx)
         }
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     /// Inserts a new key-value pair between the key-value pairs to the right and left of
     /// this edge. This method splits the node if there isn't enough room.
     ///
@@ -932,15 +1189,15 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
         val: V,
         alloc: A,
     ) -> (
-        Option<SplitResult<'a, K, V, marker::Leaf>>,
-        Handle<NodeRef<marker::DormantMut, K, V, marker::Leaf>, marker::KV>,
+        Option<SplitResult<'a, K, V, marker::Leaf, S, B>>,
+        Handle<NodeRef<marker::DormantMut, K, V, marker::Leaf, S, B>, marker::KV>,
     ) {
-        if self.node.len() < CAPACITY {
+        if self.node.len() < Consts::<B>::CAPACITY {
             // SAFETY: There is enough space in the node for insertion.
             let handle = unsafe { self.insert_fit(key, val) };
             (None, handle.dormant())
         } else {
-            let (middle_kv_idx, insertion) = splitpoint(self.idx);
+            let (middle_kv_idx, insertion) = splitpoint::<B>(self.idx);
             let middle = unsafe { Handle::new_kv(self.node, middle_kv_idx) };
             let mut result = middle.split(alloc);
             let insertion_edge = match insertion {
@@ -959,7 +1216,9 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     }
 }
 
-impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::Edge> {
+impl<'a, K, V, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::Edge>
+{
     /// Fixes the parent pointer and index in the child node that this edge
     /// links to. This is useful when the ordering of edges has been changed,
     fn correct_parent_link(self) {
@@ -971,12 +1230,14 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::Edge> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::Edge>
+{
     /// Inserts a new key-value pair and an edge that will go to the right of that new pair
     /// between this edge and the key-value pair to the right of this edge. This method assumes
     /// that there is enough space in the node for the new pair to fit.
-    fn insert_fit(&mut self, key: K, val: V, edge: Root<K, V>) {
-        debug_assert!(self.node.len() < CAPACITY);
+    fn insert_fit(&mut self, key: K, val: V, edge: Root<K, V, S, B>) {
+        debug_assert!(self.node.len() < Consts::<B>::CAPACITY);
         debug_assert!(edge.height == self.node.height - 1);
         let new_len = self.node.len() + 1;
 
@@ -988,6 +1249,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
 
             self.node.correct_childrens_parent_links(self.idx + 1..new_len + 1);
         }
+        self.node.update_cache();
     }
 
     /// Inserts a new key-value pair and an edge that will go to the right of that new pair
@@ -997,16 +1259,16 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
         mut self,
         key: K,
         val: V,
-        edge: Root<K, V>,
+        edge: Root<K, V, S, B>,
         alloc: A,
-    ) -> Option<SplitResult<'a, K, V, marker::Internal>> {
+    ) -> Option<SplitResult<'a, K, V, marker::Internal, S, B>> {
         assert!(edge.height == self.node.height - 1);
 
-        if self.node.len() < CAPACITY {
+        if self.node.len() < Consts::<B>::CAPACITY {
             self.insert_fit(key, val, edge);
             None
         } else {
-            let (middle_kv_idx, insertion) = splitpoint(self.idx);
+            let (middle_kv_idx, insertion) = splitpoint::<B>(self.idx);
             let middle = unsafe { Handle::new_kv(self.node, middle_kv_idx) };
             let mut result = middle.split(alloc);
             let mut insertion_edge = match insertion {
@@ -1023,7 +1285,9 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     /// Inserts a new key-value pair between the key-value pairs to the right and left of
     /// this edge. This method splits the node if there isn't enough room, and tries to
     /// insert the split off portion into the parent node recursively, until the root is reached.
@@ -1036,38 +1300,101 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
         key: K,
         value: V,
         alloc: A,
-        split_root: impl FnOnce(SplitResult<'a, K, V, marker::LeafOrInternal>),
-    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV> {
-        let (mut split, handle) = match self.insert(key, value, alloc.clone()) {
+        split_root: impl FnOnce(SplitResult<'a, K, V, marker::LeafOrInternal, S, B>),
+    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::KV> {
+        let (split, handle) = match self.insert(key, value, alloc.clone()) {
             // SAFETY: we have finished splitting and can now re-awaken the
             // handle to the inserted element.
-            (None, handle) => return unsafe { handle.awaken() },
+            (None, handle) => return unsafe { handle.awaken() }.updating_caches_upward(),
             (Some(split), handle) => (split.forget_node_type(), handle),
         };
+        split.insert_into_parent(alloc, split_root);
+        // SAFETY: we have finished splitting and can now re-awaken the
+        // handle to the inserted element.
+        unsafe { handle.awaken() }.updating_caches_upward()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::Edge>
+{
+    /// Inserts a new key-value pair and an edge that will go to the right of that new pair
+    /// between this edge and the key-value pair to the right of this edge. This method splits
+    /// the node if there isn't enough room, and tries to insert the split off portion into the
+    /// parent node recursively, until the root is reached.
+    ///
+    /// If the root is split, `split_root` receives the `SplitResult` whose `left` field is
+    /// the root node.
+    pub fn insert_recursing<A: Allocator + Clone>(
+        self,
+        key: K,
+        val: V,
+        edge: Root<K, V, S, B>,
+        alloc: A,
+        split_root: impl FnOnce(SplitResult<'a, K, V, marker::LeafOrInternal, S, B>),
+    ) {
+        let node = self.node.dormant();
+        if let Some(split) = self.insert(key, val, edge, alloc.clone()) {
+            split.forget_node_type().insert_into_parent(alloc, split_root);
+        }
+        // SAFETY: we have finished splitting, which keeps the node where it
+        // was, as the left part of any split.
+        unsafe { node.awaken() }.forget_type().update_caches_upward();
+    }
+
+    /// Puts `edge` in place of the child that this edge links to, and returns
+    /// that child as a tree of its own. The caches of the node are left for the
+    /// caller to update.
+    pub fn replace_child(&mut self, edge: Root<K, V, S, B>) -> Root<K, V, S, B> {
+        assert!(edge.height == self.node.height - 1);
+        let height = edge.height;
+        let child = unsafe {
+            mem::replace(self.node.edge_area_mut(self.idx), MaybeUninit::new(edge.node))
+                .assume_init()
+        };
+        unsafe { Handle::new_edge(self.node.reborrow_mut(), self.idx) }.correct_parent_link();
+        let mut child = NodeRef { height, node: child, _marker: PhantomData };
+        child.clear_parent_link();
+        child
+    }
+}
 
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    SplitResult<'a, K, V, marker::LeafOrInternal, S, B>
+{
+    /// Inserts the split off portion into the parent node of the `left` node,
+    /// splitting the parent if there isn't enough room, recursively, until the
+    /// root is reached. If the root is split, `split_root` receives the
+    /// `SplitResult` whose `left` field is the root node.
+    fn insert_into_parent<A: Allocator + Clone>(mut self, alloc: A, split_root: impl FnOnce(Self)) {
         loop {
-            split = match split.left.ascend() {
+            self = match self.left.ascend() {
                 Ok(parent) => {
-                    match parent.insert(split.kv.0, split.kv.1, split.right, alloc.clone()) {
-                        // SAFETY: we have finished splitting and can now re-awaken the
-                        // handle to the inserted element.
-                        None => return unsafe { handle.awaken() },
+                    match parent.insert(self.kv.0, self.kv.1, self.right, alloc.clone()) {
+                        None => return,
                         Some(split) => split.forget_node_type(),
                     }
                 }
-                Err(root) => {
-                    split_root(SplitResult { left: root, ..split });
-                    // SAFETY: we have finished splitting and can now re-awaken the
-                    // handle to the inserted element.
-                    return unsafe { handle.awaken() };
-                }
+                Err(root) => return split_root(SplitResult { left: root, ..self }),
             };
         }
     }
 }
 
-impl<BorrowType: marker::BorrowType, K, V>
-    Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::Edge>
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::KV>
+{
+    /// Brings the caches of the ancestors up to date with the insertion
+    /// that produced this handle. The nodes that were split along the way have
+    /// already recomputed their caches, but the nodes above them have not.
+    fn updating_caches_upward(mut self) -> Self {
+        unsafe { self.reborrow_mut() }.into_node().forget_type().update_caches_upward();
+        self
+    }
+}
+
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::Internal, S, B>, marker::Edge>
 {
     /// Finds the node pointed to by this edge.
     ///
@@ -1075,7 +1402,7 @@ impl<BorrowType: marker::BorrowType, K, V>
     ///
     /// `edge.descend().ascend().unwrap()` and `node.ascend().unwrap().descend()` should
     /// both, upon success, do nothing.
-    pub fn descend(self) -> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+    pub fn descend(self) -> NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B> {
         cfg_if! {
             if #[cfg(feature = "inline_const")] {
                 const {
@@ -1089,54 +1416,67 @@ impl<BorrowType: marker::BorrowType, K, V>
         // We need to use raw pointers to nodes because, if BorrowType is
         // marker::ValMut, there might be outstanding mutable references to
         // values that we must not invalidate. There's no worry accessing the
-        // height field because that value is copied. Beware that, once the
-        // node pointer is dereferenced, we access the edges array with a
-        // reference (Rust issue #73987) and invalidate any other references
-        // to or inside the array, should any be around.
+        // height field because that value is copied. The edge itself is read
+        // through a raw pointer, so no reference to the other edges is created.
         let parent_ptr = NodeRef::as_internal_ptr(&self.node);
-        let node = unsafe { (*parent_ptr).edges.get_unchecked(self.idx).assume_init_read() };
+        let edges = unsafe { ptr::addr_of_mut!((*parent_ptr).edges) };
+        let node = unsafe { (*Slots::slot(edges, self.idx)).assume_init_read() };
         NodeRef { node, height: self.node.height - 1, _marker: PhantomData }
     }
 }
 
-impl<'a, K: 'a, V: 'a, NodeType> Handle<NodeRef<marker::Immut<'a>, K, V, NodeType>, marker::KV> {
+impl<'a, K: 'a, V: 'a, NodeType, S, const B: usize>
+    Handle<NodeRef<marker::Immut<'a>, K, V, NodeType, S, B>, marker::KV>
+{
     pub fn into_kv(self) -> (&'a K, &'a V) {
         debug_assert!(self.idx < self.node.len());
-        let leaf = self.node.into_leaf();
-        let k = unsafe { leaf.keys.get_unchecked(self.idx).assume_init_ref() };
-        let v = unsafe { leaf.vals.get_unchecked(self.idx).assume_init_ref() };
+        // Go through a raw pointer, because the summary need not outlive `'a`.
+        let leaf = NodeRef::as_leaf_ptr(&self.node);
+        let k =
+            unsafe { (*Slots::slot(ptr::addr_of_mut!((*leaf).keys), self.idx)).assume_init_ref() };
+        let v =
+            unsafe { (*Slots::slot(ptr::addr_of_mut!((*leaf).vals), self.idx)).assume_init_ref() };
         (k, v)
     }
 }
 
-impl<'a, K: 'a, V: 'a, NodeType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType>, marker::KV> {
+impl<'a, K: 'a, V: 'a, NodeType, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, NodeType, S, B>, marker::KV>
+{
     pub fn key_mut(&mut self) -> &mut K {
         unsafe { self.node.key_area_mut(self.idx).assume_init_mut() }
     }
 
     pub fn into_val_mut(self) -> &'a mut V {
         debug_assert!(self.idx < self.node.len());
-        let leaf = self.node.into_leaf_mut();
-        unsafe { leaf.vals.get_unchecked_mut(self.idx).assume_init_mut() }
+        // Go through a raw pointer, because the summary need not outlive `'a`.
+        let leaf = NodeRef::as_leaf_ptr(&self.node);
+        unsafe { (*Slots::slot(ptr::addr_of_mut!((*leaf).vals), self.idx)).assume_init_mut() }
     }
 
     #[cfg(feature = "btree_cursors")]
     pub fn into_kv_valmut(self) -> (&'a K, &'a mut V) {
         debug_assert!(self.idx < self.node.len());
-        let leaf = self.node.into_leaf_mut();
-        let k = unsafe { leaf.keys.get_unchecked(self.idx).assume_init_ref() };
-        let v = unsafe { leaf.vals.get_unchecked_mut(self.idx).assume_init_mut() };
+        let leaf = NodeRef::as_leaf_ptr(&self.node);
+        let k =
+            unsafe { (*Slots::slot(ptr::addr_of_mut!((*leaf).keys), self.idx)).assume_init_ref() };
+        let v =
+            unsafe { (*Slots::slot(ptr::addr_of_mut!((*leaf).vals), self.idx)).assume_init_mut() };
         (k, v)
     }
 }
 
-impl<'a, K, V, NodeType> Handle<NodeRef<marker::ValMut<'a>, K, V, NodeType>, marker::KV> {
+impl<'a, K, V, NodeType, S, const B: usize>
+    Handle<NodeRef<marker::ValMut<'a>, K, V, NodeType, S, B>, marker::KV>
+{
     pub fn into_kv_valmut(self) -> (&'a K, &'a mut V) {
         unsafe { self.node.into_key_val_mut_at(self.idx) }
     }
 }
 
-impl<'a, K: 'a, V: 'a, NodeType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType>, marker::KV> {
+impl<'a, K: 'a, V: 'a, NodeType, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, NodeType, S, B>, marker::KV>
+{
     pub fn kv_mut(&mut self) -> (&mut K, &mut V) {
         debug_assert!(self.idx < self.node.len());
         // We cannot call separate key and value methods, because calling the second one
@@ -1156,7 +1496,9 @@ impl<'a, K: 'a, V: 'a, NodeType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType>
     }
 }
 
-impl<K, V, NodeType> Handle<NodeRef<marker::Dying, K, V, NodeType>, marker::KV> {
+impl<K, V, NodeType, S, const B: usize>
+    Handle<NodeRef<marker::Dying, K, V, NodeType, S, B>, marker::KV>
+{
     /// Extracts the key and value that the KV handle refers to.
     /// # Safety
     /// The node that the handle refers to must not yet have been deallocated.
@@ -1184,10 +1526,12 @@ impl<K, V, NodeType> Handle<NodeRef<marker::Dying, K, V, NodeType>, marker::KV>
     }
 }
 
-impl<'a, K: 'a, V: 'a, NodeType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType>, marker::KV> {
+impl<'a, K: 'a, V: 'a, NodeType, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, NodeType, S, B>, marker::KV>
+{
     /// Helps implementations of `split` for a particular `NodeType`,
     /// by taking care of leaf data.
-    fn split_leaf_data(&mut self, new_node: &mut LeafNode<K, V>) -> (K, V) {
+    fn split_leaf_data(&mut self, new_node: &mut LeafNode<K, V, S, B>) -> (K, V) {
         debug_assert!(self.idx < self.node.len());
         let old_len = self.node.len();
         let new_len = old_len - self.idx - 1;
@@ -1211,7 +1555,9 @@ impl<'a, K: 'a, V: 'a, NodeType> Handle<NodeRef<marker::Mut<'a>, K, V, NodeType>
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::KV>
+{
     /// Splits the underlying node into three parts:
     ///
     /// - The node is truncated to only contain the key-value pairs to the left of
@@ -1219,12 +1565,17 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     /// - The key and value pointed to by this handle are extracted.
     /// - All the key-value pairs to the right of this handle are put into a newly
     ///   allocated node.
-    pub fn split<A: Allocator + Clone>(mut self, alloc: A) -> SplitResult<'a, K, V, marker::Leaf> {
+    pub fn split<A: Allocator + Clone>(
+        mut self,
+        alloc: A,
+    ) -> SplitResult<'a, K, V, marker::Leaf, S, B> {
         let mut new_node = LeafNode::new(alloc);
 
         let kv = self.split_leaf_data(&mut new_node);
 
-        let right = NodeRef::from_new_leaf::<A>(new_node);
+        let mut right = NodeRef::from_new_leaf::<A>(new_node);
+        self.node.update_cache();
+        right.borrow_mut().update_cache();
         SplitResult { left: self.node, kv, right }
     }
 
@@ -1232,18 +1583,21 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     /// that the key-value pair collapsed into.
     pub fn remove(
         mut self,
-    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
+    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>) {
         let old_len = self.node.len();
         unsafe {
             let k = slice_remove(self.node.key_area_mut(..old_len), self.idx);
             let v = slice_remove(self.node.val_area_mut(..old_len), self.idx);
             *self.node.len_mut() = (old_len - 1) as u16;
+            self.node.update_cache();
             ((k, v), self.left_edge())
         }
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::KV> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::KV>
+{
     /// Splits the underlying node into three parts:
     ///
     /// - The node is truncated to only contain the edges and key-value pairs to the
@@ -1254,7 +1608,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
     pub fn split<A: Allocator + Clone>(
         mut self,
         alloc: A,
-    ) -> SplitResult<'a, K, V, marker::Internal> {
+    ) -> SplitResult<'a, K, V, marker::Internal, S, B> {
         let old_len = self.node.len();
         unsafe {
             let mut new_node = InternalNode::new(alloc);
@@ -1267,6 +1621,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
 
             let height = self.node.height;
             let right = NodeRef::from_new_internal::<A>(new_node, height);
+            self.node.update_cache();
 
             SplitResult { left: self.node, kv, right }
         }
@@ -1275,14 +1630,16 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
 
 /// Represents a session for evaluating and performing a balancing operation
 /// around an internal key-value pair.
-pub struct BalancingContext<'a, K, V> {
-    parent: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::KV>,
-    left_child: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>,
-    right_child: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>,
+pub struct BalancingContext<'a, K, V, S = (), const B: usize = 6> {
+    parent: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::KV>,
+    left_child: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>,
+    right_child: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>,
 }
 
-impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::KV> {
-    pub fn consider_for_balancing(self) -> BalancingContext<'a, K, V> {
+impl<'a, K, V, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::KV>
+{
+    pub fn consider_for_balancing(self) -> BalancingContext<'a, K, V, S, B> {
         let self1 = unsafe { ptr::read(&self) };
         let self2 = unsafe { ptr::read(&self) };
         BalancingContext {
@@ -1293,7 +1650,7 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
     }
 }
 
-impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+impl<'a, K, V, S, const B: usize> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B> {
     /// Chooses a balancing context involving the node as a child, thus between
     /// the KV immediately to the left or to the right in the parent node.
     /// Returns an `Err` if there is no parent.
@@ -1308,7 +1665,7 @@ impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
     /// typically faster, since we only need to shift the node's N elements to
     /// the right, instead of shifting at least N of the sibling's elements to
     /// the left.
-    pub fn choose_parent_kv(self) -> Result<LeftOrRight<BalancingContext<'a, K, V>>, Self> {
+    pub fn choose_parent_kv(self) -> Result<LeftOrRight<BalancingContext<'a, K, V, S, B>>, Self> {
         match unsafe { ptr::read(&self) }.ascend() {
             Ok(parent_edge) => match parent_edge.left_kv() {
                 Ok(left_parent_kv) => Ok(LeftOrRight::Left(BalancingContext {
@@ -1330,7 +1687,7 @@ impl<'a, K, V> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
     }
 }
 
-impl<'a, K, V> BalancingContext<'a, K, V> {
+impl<'a, K, V, S, const B: usize> BalancingContext<'a, K, V, S, B> {
     pub fn left_child_len(&self) -> usize {
         self.left_child.len()
     }
@@ -1339,27 +1696,27 @@ impl<'a, K, V> BalancingContext<'a, K, V> {
         self.right_child.len()
     }
 
-    pub fn into_left_child(self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    pub fn into_left_child(self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B> {
         self.left_child
     }
 
-    pub fn into_right_child(self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    pub fn into_right_child(self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B> {
         self.right_child
     }
 
     /// Returns whether merging is possible, i.e., whether there is enough room
     /// in a node to combine the central KV with both adjacent child nodes.
     pub fn can_merge(&self) -> bool {
-        self.left_child.len() + 1 + self.right_child.len() <= CAPACITY
+        self.left_child.len() + 1 + self.right_child.len() <= Consts::<B>::CAPACITY
     }
 }
 
-impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize> BalancingContext<'a, K, V, S, B> {
     /// Performs a merge and lets a closure decide what to return.
     fn do_merge<
         F: FnOnce(
-            NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
-            NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>,
+            NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>,
+            NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>,
         ) -> R,
         R,
         A: Allocator,
@@ -1376,7 +1733,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
         let right_len = right_node.len();
         let new_left_len = old_left_len + 1 + right_len;
 
-        assert!(new_left_len <= CAPACITY);
+        assert!(new_left_len <= Consts::<B>::CAPACITY);
 
         unsafe {
             *left_node.len_mut() = new_left_len as u16;
@@ -1411,10 +1768,15 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
 
                 left_node.correct_childrens_parent_links(old_left_len + 1..new_left_len + 1);
 
-                alloc.deallocate(right_node.node.cast(), Layout::new::<InternalNode<K, V>>());
+                ptr::drop_in_place(&mut right_node.as_leaf_mut().summary);
+                alloc.deallocate(right_node.node.cast(), Layout::new::<InternalNode<K, V, S, B>>());
             } else {
-                alloc.deallocate(right_node.node.cast(), Layout::new::<LeafNode<K, V>>());
+                ptr::drop_in_place(&mut right_node.as_leaf_mut().summary);
+                alloc.deallocate(right_node.node.cast(), Layout::new::<LeafNode<K, V, S, B>>());
             }
+            // The parent heads the same elements as before, so only the merged
+            // child needs its caches recomputed.
+            left_node.update_cache();
         }
         result(parent_node, left_node)
     }
@@ -1426,7 +1788,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
     pub fn merge_tracking_parent<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> NodeRef<marker::Mut<'a>, K, V, marker::Internal> {
+    ) -> NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B> {
         self.do_merge(|parent, _child| parent, alloc)
     }
 
@@ -1437,7 +1799,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
     pub fn merge_tracking_child<A: Allocator + Clone>(
         self,
         alloc: A,
-    ) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+    ) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B> {
         self.do_merge(|_parent, child| child, alloc)
     }
 
@@ -1450,7 +1812,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
         self,
         track_edge_idx: LeftOrRight<usize>,
         alloc: A,
-    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::Edge> {
+    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::Edge> {
         let old_left_len = self.left_child.len();
         let right_len = self.right_child.len();
         assert!(match track_edge_idx {
@@ -1472,7 +1834,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
     pub fn steal_left(
         mut self,
         track_right_edge_idx: usize,
-    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::Edge> {
+    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::Edge> {
         self.bulk_steal_left(1);
         unsafe { Handle::new_edge(self.right_child, 1 + track_right_edge_idx) }
     }
@@ -1484,7 +1846,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
     pub fn steal_right(
         mut self,
         track_left_edge_idx: usize,
-    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::Edge> {
+    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::Edge> {
         self.bulk_steal_right(1);
         unsafe { Handle::new_edge(self.left_child, track_left_edge_idx) }
     }
@@ -1499,7 +1861,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
             let old_right_len = right_node.len();
 
             // Make sure that we may steal safely.
-            assert!(old_right_len + count <= CAPACITY);
+            assert!(old_right_len + count <= Consts::<B>::CAPACITY);
             assert!(old_left_len >= count);
 
             let new_left_len = old_left_len - count;
@@ -1549,6 +1911,9 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
                 (ForceResult::Leaf(_), ForceResult::Leaf(_)) => {}
                 _ => unreachable!(),
             }
+            // The parent heads the same elements as before.
+            left_node.update_cache();
+            right_node.update_cache();
         }
     }
 
@@ -1562,7 +1927,7 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
             let old_right_len = right_node.len();
 
             // Make sure that we may steal safely.
-            assert!(old_left_len + count <= CAPACITY);
+            assert!(old_left_len + count <= Consts::<B>::CAPACITY);
             assert!(old_right_len >= count);
 
             let new_left_len = old_left_len + count;
@@ -1613,41 +1978,52 @@ impl<'a, K: 'a, V: 'a> BalancingContext<'a, K, V> {
                 (ForceResult::Leaf(_), ForceResult::Leaf(_)) => {}
                 _ => unreachable!(),
             }
+            // The parent heads the same elements as before.
+            left_node.update_cache();
+            right_node.update_cache();
         }
     }
 }
 
-impl<BorrowType, K, V> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge> {
+impl<BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>
+{
     pub fn forget_node_type(
         self,
-    ) -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::Edge> {
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::Edge> {
         unsafe { Handle::new_edge(self.node.forget_type(), self.idx) }
     }
 }
 
-impl<BorrowType, K, V> Handle<NodeRef<BorrowType, K, V, marker::Internal>, marker::Edge> {
+impl<BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::Internal, S, B>, marker::Edge>
+{
     pub fn forget_node_type(
         self,
-    ) -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::Edge> {
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::Edge> {
         unsafe { Handle::new_edge(self.node.forget_type(), self.idx) }
     }
 }
 
-impl<BorrowType, K, V> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::KV> {
+impl<BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::KV>
+{
     pub fn forget_node_type(
         self,
-    ) -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV> {
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV> {
         unsafe { Handle::new_kv(self.node.forget_type(), self.idx) }
     }
 }
 
-impl<BorrowType, K, V, Type> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, Type> {
+impl<BorrowType, K, V, Type, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, Type>
+{
     /// Checks whether the underlying node is an `Internal` node or a `Leaf` node.
     pub fn force(
         self,
     ) -> ForceResult<
-        Handle<NodeRef<BorrowType, K, V, marker::Leaf>, Type>,
-        Handle<NodeRef<BorrowType, K, V, marker::Internal>, Type>,
+        Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, Type>,
+        Handle<NodeRef<BorrowType, K, V, marker::Internal, S, B>, Type>,
     > {
         match self.node.force() {
             ForceResult::Leaf(node) => {
@@ -1660,22 +2036,27 @@ impl<BorrowType, K, V, Type> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInte
     }
 }
 
-impl<'a, K, V, Type> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, Type> {
+impl<'a, K, V, Type, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>, Type>
+{
     /// Unsafely asserts to the compiler the static information that the handle's node is a `Leaf`.
     pub unsafe fn cast_to_leaf_unchecked(
         self,
-    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, Type> {
+    ) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, Type> {
         let node = unsafe { self.node.cast_to_leaf_unchecked() };
         Handle { node, idx: self.idx, _marker: PhantomData }
     }
 }
 
-impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::Edge> {
+impl<'a, K, V, S, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::Edge>
+{
     /// Move the suffix after `self` from one node to another one. `right` must be empty.
     /// The first edge of `right` remains unchanged.
+    /// The counts of both nodes are left for the caller to recompute.
     pub fn move_suffix(
         &mut self,
-        right: &mut NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>,
+        right: &mut NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>,
     ) {
         unsafe {
             let new_left_len = self.idx;
@@ -1722,23 +2103,23 @@ pub enum ForceResult<Leaf, Internal> {
 }
 
 /// Result of insertion, when a node needed to expand beyond its capacity.
-pub struct SplitResult<'a, K, V, NodeType> {
+pub struct SplitResult<'a, K, V, NodeType, S = (), const B: usize = 6> {
     // Altered node in existing tree with elements and edges that belong to the left of `kv`.
-    pub left: NodeRef<marker::Mut<'a>, K, V, NodeType>,
+    pub left: NodeRef<marker::Mut<'a>, K, V, NodeType, S, B>,
     // Some key and value that existed before and were split off, to be inserted elsewhere.
     pub kv: (K, V),
     // Owned, unattached, new node with elements and edges that belong to the right of `kv`.
-    pub right: NodeRef<marker::Owned, K, V, NodeType>,
+    pub right: NodeRef<marker::Owned, K, V, NodeType, S, B>,
 }
 
-impl<'a, K, V> SplitResult<'a, K, V, marker::Leaf> {
-    pub fn forget_node_type(self) -> SplitResult<'a, K, V, marker::LeafOrInternal> {
+impl<'a, K, V, S, const B: usize> SplitResult<'a, K, V, marker::Leaf, S, B> {
+    pub fn forget_node_type(self) -> SplitResult<'a, K, V, marker::LeafOrInternal, S, B> {
         SplitResult { left: self.left.forget_type(), kv: self.kv, right: self.right.forget_type() }
     }
 }
 
-impl<'a, K, V> SplitResult<'a, K, V, marker::Internal> {
-    pub fn forget_node_type(self) -> SplitResult<'a, K, V, marker::LeafOrInternal> {
+impl<'a, K, V, S, const B: usize> SplitResult<'a, K, V, marker::Internal, S, B> {
+    pub fn forget_node_type(self) -> SplitResult<'a, K, V, marker::LeafOrInternal, S, B> {
         SplitResult { left: self.left.forget_type(), kv: self.kv, right: self.right.forget_type() }
     }
 }
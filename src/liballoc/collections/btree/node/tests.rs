@@ -3,7 +3,11 @@ use super::*;
 use alloc::fmt::Debug;
 use alloc::string::String;
 
-impl<'a, K: 'a, V: 'a> NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal> {
+type Consts = super::Consts<6>;
+
+impl<'a, K: 'a, V: 'a, const B: usize>
+    NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, (), B>
+{
     // Asserts that the back pointer in each reachable node points to its parent.
     pub fn assert_back_pointers(self) {
         if let ForceResult::Internal(node) = self.force() {
@@ -43,12 +47,12 @@ impl<'a, K: 'a, V: 'a> NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>
 
 #[test]
 fn test_splitpoint() {
-    for idx in 0..=CAPACITY {
-        let (middle_kv_idx, insertion) = splitpoint(idx);
+    for idx in 0..=Consts::CAPACITY {
+        let (middle_kv_idx, insertion) = splitpoint::<6>(idx);
 
         // Simulate performing the split:
         let mut left_len = middle_kv_idx;
-        let mut right_len = CAPACITY - middle_kv_idx - 1;
+        let mut right_len = Consts::CAPACITY - middle_kv_idx - 1;
         match insertion {
             LeftOrRight::Left(edge_idx) => {
                 assert!(edge_idx <= left_len);
@@ -59,15 +63,15 @@ fn test_splitpoint() {
                 right_len += 1;
             }
         }
-        assert!(left_len >= MIN_LEN_AFTER_SPLIT);
-        assert!(right_len >= MIN_LEN_AFTER_SPLIT);
-        assert!(left_len + right_len == CAPACITY);
+        assert!(left_len >= Consts::MIN_LEN_AFTER_SPLIT);
+        assert!(right_len >= Consts::MIN_LEN_AFTER_SPLIT);
+        assert!(left_len + right_len == Consts::CAPACITY);
     }
 }
 
 #[test]
 fn test_partial_eq() {
-    let mut root1 = NodeRef::new_leaf(Global);
+    let mut root1 = NodeRef::<_, _, _, _, (), 6>::new_leaf(Global);
     root1.borrow_mut().push(1, ());
     let mut root1 = NodeRef::new_internal(root1.forget_type(), Global).forget_type();
     let root2 = Root::new(Global);
@@ -96,7 +100,16 @@ fn test_partial_eq() {
 #[cfg_attr(miri, ignore)] // We'd like to run Miri with layout randomization
 fn test_sizes() {
     assert_eq!(core::mem::size_of::<LeafNode<(), ()>>(), 16);
-    assert_eq!(core::mem::size_of::<LeafNode<i64, i64>>(), 16 + CAPACITY * 2 * 8);
-    assert_eq!(core::mem::size_of::<InternalNode<(), ()>>(), 16 + (CAPACITY + 1) * 8);
-    assert_eq!(core::mem::size_of::<InternalNode<i64, i64>>(), 16 + (CAPACITY * 3 + 1) * 8);
+    // Keys and values each have one spare slot, see `Slots`.
+    assert_eq!(core::mem::size_of::<LeafNode<i64, i64>>(), 16 + (Consts::CAPACITY + 1) * 2 * 8);
+    // With `order_statistics`, internal nodes also count their subtree.
+    let count_size = if cfg!(feature = "order_statistics") { 8 } else { 0 };
+    assert_eq!(
+        core::mem::size_of::<InternalNode<(), ()>>(),
+        16 + (Consts::CAPACITY + 1) * 8 + count_size
+    );
+    assert_eq!(
+        core::mem::size_of::<InternalNode<i64, i64>>(),
+        16 + (Consts::CAPACITY * 3 + 3) * 8 + count_size
+    );
 }
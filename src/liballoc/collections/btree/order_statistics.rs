@@ -0,0 +1,153 @@
+// Positional queries on trees whose internal nodes count the key-value pairs
+// below them. Every function here takes time proportional to the height of the
+// tree times the node capacity, i.e., O(log n), instead of visiting elements.
+
+use super::node::{marker, ForceResult::*, Handle, NodeRef};
+
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>
+{
+    /// Counts the key-value pairs in the subtree headed by the node that come
+    /// before the edge at `edge_idx`.
+    fn count_before_edge(self, edge_idx: usize) -> usize {
+        debug_assert!(edge_idx <= self.len());
+        match self.force() {
+            Leaf(_) => edge_idx,
+            Internal(internal) => {
+                let children = (0..edge_idx)
+                    .map(|idx| unsafe { Handle::new_edge(internal, idx) }.descend().subtree_len());
+                edge_idx + children.sum::<usize>()
+            }
+        }
+    }
+
+    /// Finds the edge in the node leading to the point that has `index`
+    /// key-value pairs of the subtree before it, and returns its index
+    /// together with the number of pairs before that point within the child
+    /// node, if the node is internal.
+    ///
+    /// `index` must not exceed the length of the subtree.
+    pub fn locate_edge(self, mut index: usize) -> (usize, usize) {
+        debug_assert!(index <= self.subtree_len());
+        match self.force() {
+            Leaf(_) => (index, 0),
+            Internal(internal) => {
+                for edge_idx in 0..internal.len() {
+                    let child_len =
+                        unsafe { Handle::new_edge(internal, edge_idx) }.descend().subtree_len();
+                    if index <= child_len {
+                        return (edge_idx, index);
+                    }
+                    index -= child_len + 1;
+                }
+                (internal.len(), index)
+            }
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S, const B: usize>
+    Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::Edge>
+{
+    /// Counts the key-value pairs in the whole tree that come before the edge.
+    pub fn position(self) -> usize {
+        let mut edge = self;
+        let mut position = 0;
+        loop {
+            position += edge.into_node().count_before_edge(edge.idx());
+            edge = match edge.into_node().ascend() {
+                Ok(parent_edge) => parent_edge.forget_node_type(),
+                Err(_) => return position,
+            }
+        }
+    }
+}
+
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>
+{
+    /// Finds the key-value pair that has `index` other pairs of the subtree
+    /// headed by the node before it.
+    ///
+    /// `index` must be less than the length of the subtree.
+    pub fn select_kv(self, mut index: usize) -> Handle<Self, marker::KV> {
+        debug_assert!(index < self.subtree_len());
+        let mut node = self;
+        loop {
+            let internal = match node.force() {
+                Leaf(leaf) => return unsafe { Handle::new_kv(leaf.forget_type(), index) },
+                Internal(internal) => internal,
+            };
+            let mut edge_idx = 0;
+            loop {
+                let child = unsafe { Handle::new_edge(internal.reborrow(), edge_idx) }.descend();
+                let child_len = child.subtree_len();
+                if index < child_len {
+                    break;
+                }
+                if index == child_len {
+                    return unsafe { Handle::new_kv(internal.forget_type(), edge_idx) };
+                }
+                index -= child_len + 1;
+                edge_idx += 1;
+            }
+            node = unsafe { Handle::new_edge(internal, edge_idx) }.descend();
+        }
+    }
+}
+
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>
+{
+    /// Given a leaf edge handle, returns [`Result::Ok`] with a handle to the
+    /// KV that `n + 1` calls to `next_kv` and `next_leaf_edge` would reach,
+    /// climbing no higher than needed to find it.
+    /// If fewer than `n + 1` KVs follow, returns [`Result::Err`] with the root.
+    pub fn nth_kv(
+        self,
+        n: usize,
+    ) -> Result<
+        Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+        NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>,
+    > {
+        let edge_idx = self.idx();
+        let mut node = self.into_node().forget_type();
+        let mut index = node.reborrow().count_before_edge(edge_idx) + n;
+        while index >= node.subtree_len() {
+            match node.ascend() {
+                Ok(parent_edge) => {
+                    let parent = parent_edge.reborrow().into_node().forget_type();
+                    index += parent.count_before_edge(parent_edge.idx());
+                    node = parent_edge.into_node().forget_type();
+                }
+                Err(root) => return Err(root),
+            }
+        }
+        Ok(node.select_kv(index))
+    }
+
+    /// The mirror image of `nth_kv`, reaching the KV that `n + 1` calls to
+    /// `next_back_kv` and `next_back_leaf_edge` would reach.
+    pub fn nth_back_kv(
+        self,
+        n: usize,
+    ) -> Result<
+        Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+        NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>,
+    > {
+        let edge_idx = self.idx();
+        let mut node = self.into_node().forget_type();
+        let mut before = node.reborrow().count_before_edge(edge_idx);
+        while before <= n {
+            match node.ascend() {
+                Ok(parent_edge) => {
+                    let parent = parent_edge.reborrow().into_node().forget_type();
+                    before += parent.count_before_edge(parent_edge.idx());
+                    node = parent_edge.into_node().forget_type();
+                }
+                Err(root) => return Err(root),
+            }
+        }
+        Ok(node.select_kv(before - n - 1))
+    }
+}
@@ -1,8 +1,10 @@
-use super::map::MIN_LEN;
-use super::node::{marker, ForceResult::*, Handle, LeftOrRight::*, NodeRef};
+use super::node::{marker, Consts, ForceResult::*, Handle, LeftOrRight::*, NodeRef};
+use super::summary_map::Summary;
 use crate::polyfill::*;
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::KV>
+{
     /// Removes a key-value pair from the tree, and returns that pair, as well as
     /// the leaf edge corresponding to that former pair. It's possible this empties
     /// a root node that is internal, which the caller should pop from the map
@@ -11,7 +13,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInter
         self,
         handle_emptied_internal_root: F,
         alloc: A,
-    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
+    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>) {
         match self.force() {
             Leaf(node) => node.remove_leaf_kv(handle_emptied_internal_root, alloc),
             Internal(node) => node.remove_internal_kv(handle_emptied_internal_root, alloc),
@@ -19,34 +21,36 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInter
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::KV>
+{
     fn remove_leaf_kv<F: FnOnce(), A: Allocator + Clone>(
         self,
         handle_emptied_internal_root: F,
         alloc: A,
-    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
+    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>) {
         let (old_kv, mut pos) = self.remove();
         let len = pos.reborrow().into_node().len();
-        if len < MIN_LEN {
+        if len < Consts::<B>::MIN_LEN {
             let idx = pos.idx();
             // We have to temporarily forget the child type, because there is no
             // distinct node type for the immediate parents of a leaf.
             let new_pos = match pos.into_node().forget_type().choose_parent_kv() {
                 Ok(Left(left_parent_kv)) => {
-                    debug_assert!(left_parent_kv.right_child_len() == MIN_LEN - 1);
+                    debug_assert!(left_parent_kv.right_child_len() == Consts::<B>::MIN_LEN - 1);
                     if left_parent_kv.can_merge() {
                         left_parent_kv.merge_tracking_child_edge(Right(idx), alloc.clone())
                     } else {
-                        debug_assert!(left_parent_kv.left_child_len() > MIN_LEN);
+                        debug_assert!(left_parent_kv.left_child_len() > Consts::<B>::MIN_LEN);
                         left_parent_kv.steal_left(idx)
                     }
                 }
                 Ok(Right(right_parent_kv)) => {
-                    debug_assert!(right_parent_kv.left_child_len() == MIN_LEN - 1);
+                    debug_assert!(right_parent_kv.left_child_len() == Consts::<B>::MIN_LEN - 1);
                     if right_parent_kv.can_merge() {
                         right_parent_kv.merge_tracking_child_edge(Left(idx), alloc.clone())
                     } else {
-                        debug_assert!(right_parent_kv.right_child_len() > MIN_LEN);
+                        debug_assert!(right_parent_kv.right_child_len() > Consts::<B>::MIN_LEN);
                         right_parent_kv.steal_right(idx)
                     }
                 }
@@ -68,16 +72,21 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
                 }
             }
         }
+        // Rebalancing kept the caches of every node it touched up to date,
+        // but the ancestors of the leaf still include the removed pair.
+        unsafe { pos.reborrow_mut() }.into_node().forget_type().update_caches_upward();
         (old_kv, pos)
     }
 }
 
-impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::KV> {
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>, const B: usize>
+    Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal, S, B>, marker::KV>
+{
     fn remove_internal_kv<F: FnOnce(), A: Allocator + Clone>(
         self,
         handle_emptied_internal_root: F,
         alloc: A,
-    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
+    ) -> ((K, V), Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf, S, B>, marker::Edge>) {
         // Remove an adjacent KV from its leaf and then put it back in place of
         // the element we were asked to remove. Prefer the left adjacent KV,
         // for the reasons listed in `choose_parent_kv`.
@@ -89,6 +98,7 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>,
         // to find where the original KV ended up.
         let mut internal = unsafe { left_hole.next_kv().ok().unwrap_unchecked() };
         let old_kv = internal.replace_kv(left_kv.0, left_kv.1);
+        unsafe { internal.reborrow_mut() }.into_node().update_caches_upward();
         let pos = internal.next_leaf_edge();
         (old_kv, pos)
     }
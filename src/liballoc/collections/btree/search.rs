@@ -7,6 +7,7 @@ use cfg_if::cfg_if;
 use SearchBound::*;
 use SearchResult::*;
 
+#[derive(Clone, Copy)]
 pub enum SearchBound {
     /// An inclusive bound to look for, just like `Bound::Included(T)`.
     Included,
@@ -28,9 +29,178 @@ impl SearchBound {
     }
 }
 
-pub enum SearchResult<BorrowType, K, V, FoundType, GoDownType> {
-    Found(Handle<NodeRef<BorrowType, K, V, FoundType>, marker::KV>),
-    GoDown(Handle<NodeRef<BorrowType, K, V, GoDownType>, marker::Edge>),
+/// A strategy for finding a key among the sorted keys of a single node.
+///
+/// Every search of a [`BTreeMap`](super::map::BTreeMap) visits one node per
+/// level of the tree, and the strategy decides which keys of the node are
+/// handed to the comparator, and in which order. [`Linear`] is the default;
+/// the others call the comparator fewer times on large nodes, which pays off
+/// when comparisons are expensive.
+///
+/// Whatever a strategy returns, the map does not exhibit undefined behavior,
+/// but returning a wrong index is a logic error that leaves the map in an
+/// unspecified state.
+pub trait NodeSearch<K> {
+    /// Searches the ascending `keys` of a node with `comp`, which returns how
+    /// the sought key compares to the key passed in.
+    ///
+    /// Returns `Ok` with the index of a key for which `comp` returns `Equal`,
+    /// or `Err` with the number of keys for which `comp` returns `Greater`.
+    fn search<C>(keys: &[K], comp: C) -> Result<usize, usize>
+    where
+        C: FnMut(&K) -> Ordering;
+}
+
+/// Compares keys one by one from the start of the node.
+///
+/// This takes up to `2 * B - 1` comparisons per node, but walks memory in order
+/// and is the fastest strategy for small nodes of cheap keys.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Linear;
+
+impl<K> NodeSearch<K> for Linear {
+    fn search<C>(keys: &[K], mut comp: C) -> Result<usize, usize>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        for (idx, k) in keys.iter().enumerate() {
+            match comp(k) {
+                Ordering::Greater => {}
+                Ordering::Equal => return Ok(idx),
+                Ordering::Less => return Err(idx),
+            }
+        }
+        Err(keys.len())
+    }
+}
+
+/// Halves the part of the node that may hold the key on every comparison.
+///
+/// This takes about `log2(2 * B)` comparisons per node.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Binary;
+
+impl<K> NodeSearch<K> for Binary {
+    fn search<C>(keys: &[K], comp: C) -> Result<usize, usize>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        bisect(keys, |_, lo, hi| lo + (hi - lo) / 2, comp)
+    }
+}
+
+/// The position of a key on a number line, used by [`Midrange`].
+///
+/// Positions should grow with the order the map keeps its keys in. Positions
+/// that do not only make the search slower, never wrong.
+pub trait Position {
+    /// Returns the position of `self`.
+    fn position(&self) -> f64;
+}
+
+macro_rules! position_as_f64 {
+    ($($t:ty)*) => {$(
+        impl Position for $t {
+            #[inline]
+            fn position(&self) -> f64 {
+                *self as f64
+            }
+        }
+    )*};
+}
+
+position_as_f64! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64 }
+
+impl<T: Position + ?Sized> Position for &T {
+    #[inline]
+    fn position(&self) -> f64 {
+        (**self).position()
+    }
+}
+
+/// Compares the first key whose [position](Position) reaches the midrange of
+/// the keys that may still match: halfway between the positions of the first
+/// and last of them.
+///
+/// This is a bisection of the range of key positions, not an interpolation
+/// search: the comparator only tells whether the sought key is smaller or
+/// greater, not where the sought key itself lies, so there is no position to
+/// interpolate towards. When keys are spread unevenly and lookups evenly over
+/// the range of positions, halving that range takes fewer comparisons than
+/// halving the range of indices as [`Binary`] does. Finding the key to compare
+/// costs calls to [`Position::position`], but no comparisons.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Midrange;
+
+impl<K: Position> NodeSearch<K> for Midrange {
+    fn search<C>(keys: &[K], comp: C) -> Result<usize, usize>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        let probe = |keys: &[K], lo: usize, hi: usize| {
+            if hi - lo <= 2 {
+                return lo + (hi - lo) / 2;
+            }
+            let first = keys[lo].position();
+            let middle = first + (keys[hi - 1].position() - first) / 2.0;
+            let idx = lo + keys[lo..hi].partition_point(|k| k.position() < middle);
+            idx.clamp(lo, hi - 1)
+        };
+        bisect(keys, probe, comp)
+    }
+}
+
+/// Narrows down `keys` by comparing the key at the index `probe` picks within
+/// `lo..hi`, until `comp` returns `Equal` or no keys are left.
+#[inline]
+fn bisect<K, P, C>(keys: &[K], mut probe: P, mut comp: C) -> Result<usize, usize>
+where
+    P: FnMut(&[K], usize, usize) -> usize,
+    C: FnMut(&K) -> Ordering,
+{
+    let (mut lo, mut hi) = (0, keys.len());
+    while lo < hi {
+        let mid = probe(keys, lo, hi);
+        debug_assert!(lo <= mid && mid < hi);
+        match comp(&keys[mid]) {
+            Ordering::Greater => lo = mid + 1,
+            Ordering::Equal => return Ok(mid),
+            Ordering::Less => hi = mid,
+        }
+    }
+    Err(lo)
+}
+
+/// What the search functions below look for in each node: the key a comparator
+/// closure points at, found with a [`NodeSearch`] strategy, or a key that can
+/// be compared to the keys of the node directly, like an `IntTarget`.
+pub trait Seek<K> {
+    /// Returns `Ok` with the index of the key sought in the ascending `keys`,
+    /// or `Err` with the number of keys that are smaller.
+    fn seek<N: NodeSearch<K>>(&mut self, keys: &[K]) -> Result<usize, usize>;
+}
+
+impl<K, C: FnMut(&K) -> Ordering> Seek<K> for C {
+    #[inline]
+    fn seek<N: NodeSearch<K>>(&mut self, keys: &[K]) -> Result<usize, usize> {
+        N::search(keys, self)
+    }
+}
+
+/// Lends a `Seek` to a search that takes it by value, for searches spanning
+/// several calls.
+pub struct SeekMut<'a, Q: ?Sized>(pub &'a mut Q);
+
+impl<K, Q: Seek<K> + ?Sized> Seek<K> for SeekMut<'_, Q> {
+    #[inline]
+    fn seek<N: NodeSearch<K>>(&mut self, keys: &[K]) -> Result<usize, usize> {
+        self.0.seek::<N>(keys)
+    }
+}
+
+pub enum SearchResult<BorrowType, K, V, FoundType, GoDownType, S = (), const B: usize = 6> {
+    Found(Handle<NodeRef<BorrowType, K, V, FoundType, S, B>, marker::KV>),
+    GoDown(Handle<NodeRef<BorrowType, K, V, GoDownType, S, B>, marker::Edge>),
 }
 
 pub enum IndexResult {
@@ -38,22 +208,24 @@ pub enum IndexResult {
     Edge(usize),
 }
 
-impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+impl<BorrowType: marker::BorrowType, K, V, S, const B: usize>
+    NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>
+{
     /// Looks up a given key in a (sub)tree headed by the node, recursively.
     /// Returns a `Found` with the handle of the matching KV, if any. Otherwise,
     /// returns a `GoDown` with the handle of the leaf edge where the key belongs.
     ///
     /// The result is meaningful only if the tree is ordered by key, like the tree
     /// in a `BTreeMap` is.
-    pub fn search_tree<C>(
+    pub fn search_tree<N: NodeSearch<K>, C>(
         mut self,
         mut comp: C,
-    ) -> SearchResult<BorrowType, K, V, marker::LeafOrInternal, marker::Leaf>
+    ) -> SearchResult<BorrowType, K, V, marker::LeafOrInternal, marker::Leaf, S, B>
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
         loop {
-            self = match self.search_node(&mut comp) {
+            self = match self.search_node::<N, _>(SeekMut(&mut comp)) {
                 Found(handle) => return Found(handle),
                 GoDown(handle) => match handle.force() {
                     Leaf(leaf) => return GoDown(leaf),
@@ -78,19 +250,25 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
     /// As a diagnostic service, panics if the range specifies impossible bounds.
     ///
     /// The result is meaningful only if the tree is ordered by key.
-    pub fn search_tree_for_bifurcation<C1, C2>(
+    pub fn search_tree_for_bifurcation<N: NodeSearch<K>, C1, C2>(
         mut self,
         mut lower_comp: C1,
         mut lower_bound: SearchBound,
         mut upper_comp: C2,
         mut upper_bound: SearchBound,
     ) -> Result<
-        (NodeRef<BorrowType, K, V, marker::LeafOrInternal>, usize, usize, SearchBound, SearchBound),
-        Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>,
+        (
+            NodeRef<BorrowType, K, V, marker::LeafOrInternal, S, B>,
+            usize,
+            usize,
+            SearchBound,
+            SearchBound,
+        ),
+        Handle<NodeRef<BorrowType, K, V, marker::Leaf, S, B>, marker::Edge>,
     >
     where
-        C1: FnMut(&K) -> Ordering,
-        C2: FnMut(&K) -> Ordering,
+        C1: Seek<K>,
+        C2: Seek<K>,
     {
         // Determine if map or set is being searched
         #[cfg(feature = "specialization")]
@@ -98,9 +276,14 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
 
         loop {
             let (lower_edge_idx, lower_child_bound) =
-                self.find_lower_bound_index(&mut lower_comp, lower_bound);
-            let (upper_edge_idx, upper_child_bound) =
-                unsafe { self.find_upper_bound_index(&mut upper_comp, upper_bound, lower_edge_idx) };
+                self.find_lower_bound_index::<N, _>(SeekMut(&mut lower_comp), lower_bound);
+            let (upper_edge_idx, upper_child_bound) = unsafe {
+                self.find_upper_bound_index::<N, _>(
+                    SeekMut(&mut upper_comp),
+                    upper_bound,
+                    lower_edge_idx,
+                )
+            };
             if lower_edge_idx < upper_edge_idx {
                 return Ok((
                     self,
@@ -128,35 +311,35 @@ impl<BorrowType: marker::BorrowType, K, V> NodeRef<BorrowType, K, V, marker::Lea
     /// the matching child node, if `self` is an internal node.
     ///
     /// The result is meaningful only if the tree is ordered by key.
-    pub fn find_lower_bound_edge<C>(
+    pub fn find_lower_bound_edge<N: NodeSearch<K>, C>(
         self,
         comp: C,
         bound: SearchBound,
     ) -> (Handle<Self, marker::Edge>, SearchBound)
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
-        let (edge_idx, bound) = self.find_lower_bound_index(comp, bound);
+        let (edge_idx, bound) = self.find_lower_bound_index::<N, _>(comp, bound);
         let edge = unsafe { Handle::new_edge(self, edge_idx) };
         (edge, bound)
     }
 
     /// Clone of `find_lower_bound_edge` for the upper bound.
-    pub fn find_upper_bound_edge<C>(
+    pub fn find_upper_bound_edge<N: NodeSearch<K>, C>(
         self,
         comp: C,
         bound: SearchBound
     ) -> (Handle<Self, marker::Edge>, SearchBound)
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
-        let (edge_idx, bound) = unsafe { self.find_upper_bound_index(comp, bound, 0) };
+        let (edge_idx, bound) = unsafe { self.find_upper_bound_index::<N, _>(comp, bound, 0) };
         let edge = unsafe { Handle::new_edge(self, edge_idx) };
         (edge, bound)
     }
 }
 
-impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
+impl<BorrowType, K, V, Type, S, const B: usize> NodeRef<BorrowType, K, V, Type, S, B> {
     /// Looks up a given key in the node, without recursion.
     /// Returns a `Found` with the handle of the matching KV, if any. Otherwise,
     /// returns a `GoDown` with the handle of the edge where the key might be found
@@ -164,11 +347,14 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
     ///
     /// The result is meaningful only if the tree is ordered by key, like the tree
     /// in a `BTreeMap` is.
-    pub fn search_node<C>(self, comp: C) -> SearchResult<BorrowType, K, V, Type, Type>
+    pub fn search_node<N: NodeSearch<K>, C>(
+        self,
+        comp: C,
+    ) -> SearchResult<BorrowType, K, V, Type, Type, S, B>
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
-        match unsafe { self.find_key_index(comp, 0) } {
+        match unsafe { self.find_key_index::<N, _>(comp, 0) } {
             IndexResult::KV(idx) => Found(unsafe { Handle::new_kv(self, idx) }),
             IndexResult::Edge(idx) => GoDown(unsafe { Handle::new_edge(self, idx) }),
         }
@@ -182,21 +368,24 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
     ///
     /// # Safety
     /// `start_index` must be a valid edge index for the node.
-    unsafe fn find_key_index<C>(&self, mut comp: C, start_index: usize) -> IndexResult
+    unsafe fn find_key_index<N: NodeSearch<K>, C>(
+        &self,
+        mut comp: C,
+        start_index: usize,
+    ) -> IndexResult
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
         let node = self.reborrow();
         let keys = node.keys();
         debug_assert!(start_index <= keys.len());
-        for (offset, k) in unsafe { keys.get_unchecked(start_index..) }.iter().enumerate() {
-            match comp(k) {
-                Ordering::Greater => {}
-                Ordering::Equal => return IndexResult::KV(start_index + offset),
-                Ordering::Less => return IndexResult::Edge(start_index + offset),
-            }
+        let keys = unsafe { keys.get_unchecked(start_index..) };
+        // The strategy is not trusted with the safety of the returned handles.
+        match comp.seek::<N>(keys) {
+            Ok(offset) if offset < keys.len() => IndexResult::KV(start_index + offset),
+            Err(offset) if offset <= keys.len() => IndexResult::Edge(start_index + offset),
+            _ => panic!("node search returned an index out of bounds"),
         }
-        IndexResult::Edge(keys.len())
     }
 
     /// Finds an edge index in the node delimiting the lower bound of a range.
@@ -204,16 +393,20 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
     /// the matching child node, if `self` is an internal node.
     ///
     /// The result is meaningful only if the tree is ordered by key.
-    fn find_lower_bound_index<C>(&self, comp: C, bound: SearchBound) -> (usize, SearchBound)
+    pub fn find_lower_bound_index<N: NodeSearch<K>, C>(
+        &self,
+        comp: C,
+        bound: SearchBound,
+    ) -> (usize, SearchBound)
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
         match bound {
-            Included => match unsafe { self.find_key_index(comp, 0) } {
+            Included => match unsafe { self.find_key_index::<N, _>(comp, 0) } {
                 IndexResult::KV(idx) => (idx, AllExcluded),
                 IndexResult::Edge(idx) => (idx, bound),
             },
-            Excluded => match unsafe { self.find_key_index(comp, 0) } {
+            Excluded => match unsafe { self.find_key_index::<N, _>(comp, 0) } {
                 IndexResult::KV(idx) => (idx + 1, AllIncluded),
                 IndexResult::Edge(idx) => (idx, bound),
             },
@@ -227,21 +420,21 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
     ///
     /// # Safety
     /// `start_index` must be a valid edge index for the node.
-    unsafe fn find_upper_bound_index<C>(
+    pub unsafe fn find_upper_bound_index<N: NodeSearch<K>, C>(
         &self,
         comp: C,
         bound: SearchBound,
         start_index: usize,
     ) -> (usize, SearchBound)
     where
-        C: FnMut(&K) -> Ordering,
+        C: Seek<K>,
     {
         match bound {
-            Included => match unsafe { self.find_key_index(comp, start_index) } {
+            Included => match unsafe { self.find_key_index::<N, _>(comp, start_index) } {
                 IndexResult::KV(idx) => (idx + 1, AllExcluded),
                 IndexResult::Edge(idx) => (idx, bound),
             },
-            Excluded => match unsafe { self.find_key_index(comp, start_index) } {
+            Excluded => match unsafe { self.find_key_index::<N, _>(comp, start_index) } {
                 IndexResult::KV(idx) => (idx, AllIncluded),
                 IndexResult::Edge(idx) => (idx, bound),
             },
@@ -250,3 +443,6 @@ impl<BorrowType, K, V, Type> NodeRef<BorrowType, K, V, Type> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,89 @@
+use super::*;
+use crate::liballoc::testing::rng::DeterministicRng;
+use alloc::vec::Vec;
+
+// Searches `keys` for every key in and around them, checking each answer
+// against `Linear` and returning the total number of comparisons made.
+fn check_against_linear<N: NodeSearch<u32>>(keys: &[u32]) -> usize {
+    let mut comparisons = 0;
+    let max = keys.last().map_or(0, |&k| k + 1);
+    for target in 0..=max {
+        let expected = Linear::search(keys, |k| target.cmp(k));
+        let found = N::search(keys, |k| {
+            comparisons += 1;
+            target.cmp(k)
+        });
+        assert_eq!(found, expected, "searching {:?} for {}", keys, target);
+    }
+    comparisons
+}
+
+fn random_keys(rng: &mut DeterministicRng, len: usize, spread: u32) -> Vec<u32> {
+    let mut keys: Vec<u32> = (0..len).map(|_| rng.next() % spread).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
+
+#[test]
+fn test_empty() {
+    assert_eq!(Linear::search(&[] as &[u32], |_| unreachable!()), Err(0));
+    assert_eq!(Binary::search(&[] as &[u32], |_| unreachable!()), Err(0));
+    assert_eq!(Midrange::search(&[] as &[u32], |_| unreachable!()), Err(0));
+}
+
+#[test]
+fn test_agree_with_linear() {
+    let mut rng = DeterministicRng::new();
+    for len in 0..24 {
+        for spread in [len as u32 + 1, 100, 10_000] {
+            let keys = random_keys(&mut rng, len, spread);
+            check_against_linear::<Binary>(&keys);
+            check_against_linear::<Midrange>(&keys);
+        }
+    }
+}
+
+#[test]
+fn test_binary_comparisons() {
+    let keys: Vec<u32> = (0..11).map(|i| i * 2 + 1).collect();
+    for target in 0..24 {
+        let mut comparisons = 0;
+        Binary::search(&keys, |k| {
+            comparisons += 1;
+            target.cmp(k)
+        })
+        .unwrap_or_else(|idx| idx);
+        assert!(comparisons <= 4, "{} comparisons for {}", comparisons, target);
+    }
+    let binary = check_against_linear::<Binary>(&keys);
+    let linear = check_against_linear::<Linear>(&keys);
+    assert!(binary < linear, "{} >= {}", binary, linear);
+}
+
+#[test]
+fn test_midrange_skewed_keys() {
+    // Most keys are crammed together at the start, so halving the range of
+    // positions narrows down an evenly spread lookup faster than halving
+    // the range of indices.
+    let keys = [0, 1, 2, 3, 4, 5, 6, 7, 8, 1000, 2000];
+    let midrange = check_against_linear::<Midrange>(&keys);
+    let binary = check_against_linear::<Binary>(&keys);
+    assert!(midrange < binary, "{} >= {}", midrange, binary);
+}
+
+#[test]
+fn test_midrange_disordered_positions() {
+    // Positions that run against the order only cost comparisons.
+    struct Backwards(u32);
+    impl Position for Backwards {
+        fn position(&self) -> f64 {
+            -f64::from(self.0)
+        }
+    }
+    let keys: Vec<Backwards> = (0..11).map(Backwards).collect();
+    for target in 0..12 {
+        let expected = if target < 11 { Ok(target as usize) } else { Err(11) };
+        assert_eq!(Midrange::search(&keys, |k| target.cmp(&k.0)), expected);
+    }
+}
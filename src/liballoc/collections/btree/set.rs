@@ -1,20 +1,15 @@
 // This is pretty much entirely stolen from TreeSet, since BTreeMap has an identical interface
 // to TreeMap
 
-use crate::{
-    default::{OrdStoredKey, OrdTotalOrder},
-    SortableBy, SortableByWithOrder, TotalOrder,
-};
-use alloc::vec::Vec;
 use core::cmp::Ordering::{self, Equal, Greater, Less};
 use core::cmp::{max, min};
 use core::fmt::{self, Debug};
 use core::hash::{Hash, Hasher};
-use core::iter::{FromIterator, FusedIterator, Peekable};
-use core::mem::ManuallyDrop;
-use core::ops::{BitAnd, BitOr, BitXor, RangeBounds, Sub};
+use core::iter::{FusedIterator, Peekable};
+#[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+use core::num::NonZeroUsize;
 
-use super::map::{BTreeMap, Keys, OrderMut};
+use super::map::{BTreeMap, Keys, SearchBoundCustom, ITER_PERFORMANCE_TIPPING_SIZE_DIFF};
 use super::merge_iter::MergeIterInner;
 use super::set_val::SetValZST;
 use super::Recover;
@@ -28,13 +23,15 @@ use crate::polyfill::*;
 /// See [`BTreeMap`]'s documentation for a detailed discussion of this collection's performance
 /// benefits and drawbacks.
 ///
-/// It is a logic error for an item or total order to be modified in such a way that the item's
-/// ordering relative to any other item, as determined by that total order, changes while they
-/// are in the set. This is normally only possible through [`Cell`], [`RefCell`], global state,
-/// I/O, or unsafe code. The behavior resulting from such a logic error is not specified, but
-/// will be encapsulated to the `BTreeSet` that observed the logic error and not result in
-/// undefined behavior. This could include panics, incorrect results, aborts, memory leaks, and
-/// non-termination.
+/// Like [`BTreeMap`], the set does not store an ordering of its own: every method that needs to
+/// compare items takes a comparator closure, and it is a logic error to pass comparators that
+/// disagree with each other on the same set. It is also a logic error for an item to be modified
+/// in such a way that the item's ordering relative to any other item, as determined by those
+/// comparators, changes while it is in the set. This is normally only possible through [`Cell`],
+/// [`RefCell`], global state, I/O, or unsafe code. The behavior resulting from such a logic error
+/// is not specified, but will be encapsulated to the `BTreeSet` that observed the logic error and
+/// not result in undefined behavior. This could include panics, incorrect results, aborts,
+/// memory leaks, and non-termination.
 ///
 /// Iterators returned by [`BTreeSet::iter`] produce their items in order, and take worst-case
 /// logarithmic and amortized constant time per item returned.
@@ -45,75 +42,63 @@ use crate::polyfill::*;
 /// # Examples
 ///
 /// ```
-/// use copse::BTreeSet;
+/// use btree_monstrousity::BTreeSet;
 ///
 /// // Type inference lets us omit an explicit type signature (which
 /// // would be `BTreeSet<&str>` in this example).
-/// let mut books = BTreeSet::default();
+/// let mut books = BTreeSet::new();
 ///
 /// // Add some books.
-/// books.insert("A Dance With Dragons");
-/// books.insert("To Kill a Mockingbird");
-/// books.insert("The Odyssey");
-/// books.insert("The Great Gatsby");
+/// books.insert("A Dance With Dragons", Ord::cmp);
+/// books.insert("To Kill a Mockingbird", Ord::cmp);
+/// books.insert("The Odyssey", Ord::cmp);
+/// books.insert("The Great Gatsby", Ord::cmp);
 ///
 /// // Check for a specific one.
-/// if !books.contains("The Winds of Winter") {
+/// if !books.contains(|book| "The Winds of Winter".cmp(book)) {
 ///     println!("We have {} books, but The Winds of Winter ain't one.",
 ///              books.len());
 /// }
 ///
 /// // Remove a book.
-/// books.remove("The Odyssey");
+/// books.remove(|book| "The Odyssey".cmp(book));
 ///
 /// // Iterate over everything.
 /// for book in &books {
 ///     println!("{book}");
 /// }
 /// ```
-///
-/// A `BTreeSet` with a known list of items can be initialized from an array:
-///
-/// ```
-/// use copse::BTreeSet;
-///
-/// let set = BTreeSet::from([1, 2, 3]);
-/// ```
-pub struct BTreeSet<
-    T,
-    O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>,
-    A: Allocator + Clone = Global,
-> {
-    map: BTreeMap<T, SetValZST, O, A>,
+pub struct BTreeSet<T, A: Allocator + Clone = Global> {
+    map: BTreeMap<T, SetValZST, A>,
 }
 
-impl<T: Hash, O, A: Allocator + Clone> Hash for BTreeSet<T, O, A> {
+impl<T: Hash, A: Allocator + Clone> Hash for BTreeSet<T, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.map.hash(state)
     }
 }
 
-impl<T: PartialEq, O, A: Allocator + Clone> PartialEq for BTreeSet<T, O, A> {
-    fn eq(&self, other: &BTreeSet<T, O, A>) -> bool {
+impl<T: PartialEq, A: Allocator + Clone> PartialEq for BTreeSet<T, A> {
+    fn eq(&self, other: &BTreeSet<T, A>) -> bool {
         self.map.eq(&other.map)
     }
 }
 
-impl<T: Eq, O, A: Allocator + Clone> Eq for BTreeSet<T, O, A> {}
+impl<T: Eq, A: Allocator + Clone> Eq for BTreeSet<T, A> {}
 
-impl<T: PartialOrd, O, A: Allocator + Clone> PartialOrd for BTreeSet<T, O, A> {
-    fn partial_cmp(&self, other: &BTreeSet<T, O, A>) -> Option<Ordering> {
+impl<T: PartialOrd, A: Allocator + Clone> PartialOrd for BTreeSet<T, A> {
+    fn partial_cmp(&self, other: &BTreeSet<T, A>) -> Option<Ordering> {
         self.map.partial_cmp(&other.map)
     }
 }
 
-impl<T: Ord, O, A: Allocator + Clone> Ord for BTreeSet<T, O, A> {
-    fn cmp(&self, other: &BTreeSet<T, O, A>) -> Ordering {
+impl<T: Ord, A: Allocator + Clone> Ord for BTreeSet<T, A> {
+    fn cmp(&self, other: &BTreeSet<T, A>) -> Ordering {
         self.map.cmp(&other.map)
     }
 }
 
-impl<T: Clone, O: Clone, A: Allocator + Clone> Clone for BTreeSet<T, O, A> {
+impl<T: Clone, A: Allocator + Clone> Clone for BTreeSet<T, A> {
     fn clone(&self) -> Self {
         BTreeSet { map: self.map.clone() }
     }
@@ -172,16 +157,11 @@ pub struct Range<'a, T: 'a> {
 /// [`difference`]: BTreeSet::difference
 #[must_use = "this returns the difference as an iterator, \
               without modifying either input set"]
-pub struct Difference<
-    'a,
-    T: 'a,
-    O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>,
-    A: Allocator + Clone = Global,
-> {
-    inner: DifferenceInner<'a, T, O, A>,
-    order: &'a O,
+pub struct Difference<'a, T: 'a, C, A: Allocator + Clone = Global> {
+    inner: DifferenceInner<'a, T, A>,
+    comp: C,
 }
-enum DifferenceInner<'a, T: 'a, O, A: Allocator + Clone> {
+enum DifferenceInner<'a, T: 'a, A: Allocator + Clone> {
     Stitch {
         // iterate all of `self` and some of `other`, spotting matches along the way
         self_iter: Iter<'a, T>,
@@ -190,13 +170,13 @@ enum DifferenceInner<'a, T: 'a, O, A: Allocator + Clone> {
     Search {
         // iterate `self`, look up in `other`
         self_iter: Iter<'a, T>,
-        other_set: &'a BTreeSet<T, O, A>,
+        other_set: &'a BTreeSet<T, A>,
     },
     Iterate(Iter<'a, T>), // simply produce all elements in `self`
 }
 
 // Explicit Debug impl necessary because of issue #26925
-impl<T: Debug, O: TotalOrder, A: Allocator + Clone> Debug for DifferenceInner<'_, T, O, A> {
+impl<T: Debug, A: Allocator + Clone> Debug for DifferenceInner<'_, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DifferenceInner::Stitch { self_iter, other_iter } => f
@@ -214,7 +194,7 @@ impl<T: Debug, O: TotalOrder, A: Allocator + Clone> Debug for DifferenceInner<'_
     }
 }
 
-impl<T: fmt::Debug, O: TotalOrder, A: Allocator + Clone> fmt::Debug for Difference<'_, T, O, A> {
+impl<T: fmt::Debug, C, A: Allocator + Clone> fmt::Debug for Difference<'_, T, C, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Difference").field(&self.inner).finish()
     }
@@ -228,12 +208,9 @@ impl<T: fmt::Debug, O: TotalOrder, A: Allocator + Clone> fmt::Debug for Differen
 /// [`symmetric_difference`]: BTreeSet::symmetric_difference
 #[must_use = "this returns the difference as an iterator, \
               without modifying either input set"]
-pub struct SymmetricDifference<'a, T: 'a, O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>>(
-    MergeIterInner<Iter<'a, T>>,
-    &'a O,
-);
+pub struct SymmetricDifference<'a, T: 'a, C>(MergeIterInner<Iter<'a, T>>, C);
 
-impl<T: fmt::Debug, O> fmt::Debug for SymmetricDifference<'_, T, O> {
+impl<T: fmt::Debug, C> fmt::Debug for SymmetricDifference<'_, T, C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("SymmetricDifference").field(&self.0).finish()
     }
@@ -247,16 +224,11 @@ impl<T: fmt::Debug, O> fmt::Debug for SymmetricDifference<'_, T, O> {
 /// [`intersection`]: BTreeSet::intersection
 #[must_use = "this returns the intersection as an iterator, \
               without modifying either input set"]
-pub struct Intersection<
-    'a,
-    T: 'a,
-    O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>,
-    A: Allocator + Clone = Global,
-> {
-    inner: IntersectionInner<'a, T, O, A>,
-    order: &'a O,
+pub struct Intersection<'a, T: 'a, C, A: Allocator + Clone = Global> {
+    inner: IntersectionInner<'a, T, A>,
+    comp: C,
 }
-enum IntersectionInner<'a, T: 'a, O, A: Allocator + Clone> {
+enum IntersectionInner<'a, T: 'a, A: Allocator + Clone> {
     Stitch {
         // iterate similarly sized sets jointly, spotting matches along the way
         a: Iter<'a, T>,
@@ -265,19 +237,21 @@ enum IntersectionInner<'a, T: 'a, O, A: Allocator + Clone> {
     Search {
         // iterate a small set, look up in the large set
         small_iter: Iter<'a, T>,
-        large_set: &'a BTreeSet<T, O, A>,
+        large_set: &'a BTreeSet<T, A>,
+        // whether the small set is `other`, which flips the comparator's arguments
+        swapped: bool,
     },
     Answer(Option<&'a T>), // return a specific element or emptiness
 }
 
 // Explicit Debug impl necessary because of issue #26925
-impl<T: Debug, O: TotalOrder, A: Allocator + Clone> Debug for IntersectionInner<'_, T, O, A> {
+impl<T: Debug, A: Allocator + Clone> Debug for IntersectionInner<'_, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             IntersectionInner::Stitch { a, b } => {
                 f.debug_struct("Stitch").field("a", a).field("b", b).finish()
             }
-            IntersectionInner::Search { small_iter, large_set } => f
+            IntersectionInner::Search { small_iter, large_set, swapped: _ } => f
                 .debug_struct("Search")
                 .field("small_iter", small_iter)
                 .field("large_set", large_set)
@@ -287,7 +261,7 @@ impl<T: Debug, O: TotalOrder, A: Allocator + Clone> Debug for IntersectionInner<
     }
 }
 
-impl<T: Debug, O: TotalOrder, A: Allocator + Clone> Debug for Intersection<'_, T, O, A> {
+impl<T: Debug, C, A: Allocator + Clone> Debug for Intersection<'_, T, C, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Intersection").field(&self.inner).finish()
     }
@@ -301,77 +275,39 @@ impl<T: Debug, O: TotalOrder, A: Allocator + Clone> Debug for Intersection<'_, T
 /// [`union`]: BTreeSet::union
 #[must_use = "this returns the union as an iterator, \
               without modifying either input set"]
-pub struct Union<'a, T: 'a, O = OrdTotalOrder<<T as OrdStoredKey>::OrdKeyType>>(
-    MergeIterInner<Iter<'a, T>>,
-    &'a O,
-);
+pub struct Union<'a, T: 'a, C>(MergeIterInner<Iter<'a, T>>, C);
 
-impl<T: fmt::Debug, O> fmt::Debug for Union<'_, T, O> {
+impl<T: fmt::Debug, C> fmt::Debug for Union<'_, T, C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Union").field(&self.0).finish()
     }
 }
 
-// This constant is used by functions that compare two sets.
-// It estimates the relative size at which searching performs better
-// than iterating, based on the benchmarks in
-// https://github.com/ssomers/rust_bench_btreeset_intersection.
-// It's used to divide rather than multiply sizes, to rule out overflow,
-// and it's a power of two to make that division cheap.
-const ITER_PERFORMANCE_TIPPING_SIZE_DIFF: usize = 16;
-
-impl<T, O> BTreeSet<T, O> {
-    /// Makes a new, empty `BTreeSet` ordered by the given `order`.
+impl<T> BTreeSet<T> {
+    /// Makes a new, empty `BTreeSet`.
     ///
     /// Does not allocate anything on its own.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use copse::{BTreeSet, SortableBy, TotalOrder};
-    /// # use std::cmp::Ordering;
-    /// #
-    /// // define a total order
-    /// struct OrderByNthByte {
-    ///     n: usize, // runtime state
-    /// }
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// impl TotalOrder for OrderByNthByte {
-    ///     // etc
-    /// #     type OrderedType = [u8];
-    /// #     fn cmp(&self, this: &[u8], that: &[u8]) -> Ordering {
-    /// #         this.get(self.n).cmp(&that.get(self.n))
-    /// #     }
-    /// }
-    ///
-    /// // define lookup key types for collections sorted by our total order
-    /// # impl SortableBy<OrderByNthByte> for [u8] {
-    /// #     fn sort_key(&self) -> &[u8] { self }
-    /// # }
-    /// # impl SortableBy<OrderByNthByte> for str {
-    /// #     fn sort_key(&self) -> &[u8] { self.as_bytes() }
-    /// # }
-    /// impl SortableBy<OrderByNthByte> for String {
-    ///     // etc
-    /// #     fn sort_key(&self) -> &[u8] { SortableBy::<OrderByNthByte>::sort_key(self.as_str()) }
-    /// }
-    ///
-    /// // create a set using our total order
-    /// let mut set = BTreeSet::new(OrderByNthByte { n: 9 });
+    /// let mut set: BTreeSet<i32> = BTreeSet::new();
     ///
     /// // entries can now be inserted into the empty set
-    /// assert!(set.insert("abcdefghij".to_string()));
+    /// assert!(set.insert(1, Ord::cmp));
     /// ```
     #[must_use]
-    pub const fn new(order: O) -> BTreeSet<T, O> {
-        BTreeSet { map: BTreeMap::new(order) }
+    pub const fn new() -> BTreeSet<T> {
+        BTreeSet { map: BTreeMap::new() }
     }
 }
 
-impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
+impl<T, A: Allocator + Clone> BTreeSet<T, A> {
     decorate_if! {
         if #[cfg(feature = "btreemap_alloc")] {
-            /// Makes a new `BTreeSet` with a reasonable choice of B ordered by the given `order`.
+            /// Makes a new `BTreeSet` with a reasonable choice of B.
             ///
             /// # Examples
             ///
@@ -379,56 +315,89 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
             ///
             /// ```
             /// # #![feature(allocator_api)]
-            /// use copse::{BTreeSet, default::OrdTotalOrder};
+            /// use btree_monstrousity::BTreeSet;
             /// use std::alloc::Global;
             ///
-            /// let mut set = BTreeSet::<_>::new_in(OrdTotalOrder::default(), Global);
+            /// let mut set = BTreeSet::new_in(Global);
             ///
             /// // entries can now be inserted into the empty set
-            /// set.insert("a".to_string());
+            /// set.insert("a".to_string(), Ord::cmp);
             /// ```
             pub
         }
-        fn new_in(order: O, alloc: A) -> BTreeSet<T, O, A> {
-            BTreeSet { map: BTreeMap::new_in(order, alloc) }
+        fn new_in(alloc: A) -> BTreeSet<T, A> {
+            BTreeSet { map: BTreeMap::new_in(alloc) }
         }
     }
 
     /// Constructs a double-ended iterator over a sub-range of elements in the set.
-    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
-    /// yield elements from min (inclusive) to max (exclusive).
-    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
-    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
-    /// range from 4 to 10.
     ///
-    /// # Panics
-    ///
-    /// Panics if range `start > end`.
-    /// Panics if range `start == end` and both bounds are `Excluded`.
+    /// Each end of the range is given as a comparator closure, which returns how
+    /// the bound compares to the element it is passed, together with a
+    /// [`SearchBoundCustom`] saying whether that bound is included, excluded or
+    /// absent. For example, `|x| 4.cmp(x)` with `SearchBoundCustom::Included` as
+    /// the lower end and `|x| 10.cmp(x)` with `SearchBoundCustom::Excluded` as the
+    /// upper end yields the elements from 4 (inclusive) to 10 (exclusive).
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
-    /// use std::ops::Bound::Included;
-    ///
-    /// let mut set = BTreeSet::default();
-    /// set.insert(3);
-    /// set.insert(5);
-    /// set.insert(8);
-    /// for &elem in set.range::<i32, _>((Included(&4), Included(&8))) {
+    /// use btree_monstrousity::BTreeSet;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{AllIncluded, Included};
+    /// use std::cmp::Ordering;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// set.insert(3, Ord::cmp);
+    /// set.insert(5, Ord::cmp);
+    /// set.insert(8, Ord::cmp);
+    /// for &elem in set.range(|x| 4.cmp(x), Included, |x| 8.cmp(x), Included) {
     ///     println!("{elem}");
     /// }
-    /// assert_eq!(Some(&5), set.range(4..).next());
+    /// let mut tail = set.range(|x| 4.cmp(x), Included, |_| Ordering::Equal, AllIncluded);
+    /// assert_eq!(Some(&5), tail.next());
     /// ```
-    pub fn range<K: ?Sized, R>(&self, range: R) -> Range<'_, T>
+    pub fn range<C1, C2>(
+        &self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+    ) -> Range<'_, T>
     where
-        K: SortableByWithOrder<O>,
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
-        R: RangeBounds<K>,
+        C1: FnMut(&T) -> Ordering,
+        C2: FnMut(&T) -> Ordering,
     {
-        Range { iter: self.map.range(range) }
+        Range { iter: self.map.range(lower_comp, lower_bound, upper_comp, upper_bound) }
+    }
+
+    /// Counts the elements in a sub-range of the set in O(log n) time, with the
+    /// ends of the range given as for [`BTreeSet::range`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeSet;
+    /// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+    ///
+    /// let set: BTreeSet<i32> = (0..100).fold(BTreeSet::new(), |mut set, x| {
+    ///     set.insert(x, Ord::cmp);
+    ///     set
+    /// });
+    /// assert_eq!(set.range_len(|x| 10.cmp(x), Included, |x| 20.cmp(x), Excluded), 10);
+    /// ```
+    #[cfg(feature = "order_statistics")]
+    pub fn range_len<C1, C2>(
+        &self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+    ) -> usize
+    where
+        C1: FnMut(&T) -> Ordering,
+        C2: FnMut(&T) -> Ordering,
+    {
+        self.map.range_len(lower_comp, lower_bound, upper_comp, upper_bound)
     }
 
     /// Visits the elements representing the difference,
@@ -438,68 +407,60 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut a = BTreeSet::default();
-    /// a.insert(1);
-    /// a.insert(2);
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1, Ord::cmp);
+    /// a.insert(2, Ord::cmp);
     ///
-    /// let mut b = BTreeSet::default();
-    /// b.insert(2);
-    /// b.insert(3);
+    /// let mut b = BTreeSet::new();
+    /// b.insert(2, Ord::cmp);
+    /// b.insert(3, Ord::cmp);
     ///
-    /// let diff: Vec<_> = a.difference(&b).cloned().collect();
+    /// let diff: Vec<_> = a.difference(&b, Ord::cmp).cloned().collect();
     /// assert_eq!(diff, [1]);
     /// ```
-    pub fn difference<'a>(&'a self, other: &'a BTreeSet<T, O, A>) -> Difference<'a, T, O, A>
+    pub fn difference<'a, C>(
+        &'a self,
+        other: &'a BTreeSet<T, A>,
+        mut comp: C,
+    ) -> Difference<'a, T, C, A>
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
         let (self_min, self_max) =
             if let (Some(self_min), Some(self_max)) = (self.first(), self.last()) {
                 (self_min, self_max)
             } else {
-                return Difference {
-                    inner: DifferenceInner::Iterate(self.iter()),
-                    order: &self.map.order,
-                };
+                return Difference { inner: DifferenceInner::Iterate(self.iter()), comp };
             };
         let (other_min, other_max) =
             if let (Some(other_min), Some(other_max)) = (other.first(), other.last()) {
                 (other_min, other_max)
             } else {
-                return Difference {
-                    inner: DifferenceInner::Iterate(self.iter()),
-                    order: &self.map.order,
-                };
+                return Difference { inner: DifferenceInner::Iterate(self.iter()), comp };
             };
-        Difference {
-            inner: match (
-                self.map.order.cmp_any(self_min, other_max),
-                self.map.order.cmp_any(self_max, other_min),
-            ) {
-                (Greater, _) | (_, Less) => DifferenceInner::Iterate(self.iter()),
-                (Equal, _) => {
-                    let mut self_iter = self.iter();
-                    self_iter.next();
-                    DifferenceInner::Iterate(self_iter)
-                }
-                (_, Equal) => {
-                    let mut self_iter = self.iter();
-                    self_iter.next_back();
-                    DifferenceInner::Iterate(self_iter)
-                }
-                _ if self.len() <= other.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF => {
-                    DifferenceInner::Search { self_iter: self.iter(), other_set: other }
-                }
-                _ => DifferenceInner::Stitch {
-                    self_iter: self.iter(),
-                    other_iter: other.iter().peekable(),
-                },
+        let inner = match (comp(self_min, other_max), comp(self_max, other_min)) {
+            (Greater, _) | (_, Less) => DifferenceInner::Iterate(self.iter()),
+            (Equal, _) => {
+                let mut self_iter = self.iter();
+                self_iter.next();
+                DifferenceInner::Iterate(self_iter)
+            }
+            (_, Equal) => {
+                let mut self_iter = self.iter();
+                self_iter.next_back();
+                DifferenceInner::Iterate(self_iter)
+            }
+            _ if self.len() <= other.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF => {
+                DifferenceInner::Search { self_iter: self.iter(), other_set: other }
+            }
+            _ => DifferenceInner::Stitch {
+                self_iter: self.iter(),
+                other_iter: other.iter().peekable(),
             },
-            order: &self.map.order,
-        }
+        };
+        Difference { inner, comp }
     }
 
     /// Visits the elements representing the symmetric difference,
@@ -509,28 +470,28 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut a = BTreeSet::default();
-    /// a.insert(1);
-    /// a.insert(2);
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1, Ord::cmp);
+    /// a.insert(2, Ord::cmp);
     ///
-    /// let mut b = BTreeSet::default();
-    /// b.insert(2);
-    /// b.insert(3);
+    /// let mut b = BTreeSet::new();
+    /// b.insert(2, Ord::cmp);
+    /// b.insert(3, Ord::cmp);
     ///
-    /// let sym_diff: Vec<_> = a.symmetric_difference(&b).cloned().collect();
+    /// let sym_diff: Vec<_> = a.symmetric_difference(&b, Ord::cmp).cloned().collect();
     /// assert_eq!(sym_diff, [1, 3]);
     /// ```
-    pub fn symmetric_difference<'a>(
+    pub fn symmetric_difference<'a, C>(
         &'a self,
-        other: &'a BTreeSet<T, O, A>,
-    ) -> SymmetricDifference<'a, T, O>
+        other: &'a BTreeSet<T, A>,
+        comp: C,
+    ) -> SymmetricDifference<'a, T, C>
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
-        SymmetricDifference(MergeIterInner::new(self.iter(), other.iter()), &self.map.order)
+        SymmetricDifference(MergeIterInner::new(self.iter(), other.iter()), comp)
     }
 
     /// Visits the elements representing the intersection,
@@ -540,56 +501,60 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut a = BTreeSet::default();
-    /// a.insert(1);
-    /// a.insert(2);
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1, Ord::cmp);
+    /// a.insert(2, Ord::cmp);
     ///
-    /// let mut b = BTreeSet::default();
-    /// b.insert(2);
-    /// b.insert(3);
+    /// let mut b = BTreeSet::new();
+    /// b.insert(2, Ord::cmp);
+    /// b.insert(3, Ord::cmp);
     ///
-    /// let intersection: Vec<_> = a.intersection(&b).cloned().collect();
+    /// let intersection: Vec<_> = a.intersection(&b, Ord::cmp).cloned().collect();
     /// assert_eq!(intersection, [2]);
     /// ```
-    pub fn intersection<'a>(&'a self, other: &'a BTreeSet<T, O, A>) -> Intersection<'a, T, O, A>
+    pub fn intersection<'a, C>(
+        &'a self,
+        other: &'a BTreeSet<T, A>,
+        mut comp: C,
+    ) -> Intersection<'a, T, C, A>
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
-        let (self_min, self_max) = if let (Some(self_min), Some(self_max)) =
-            (self.first(), self.last())
-        {
-            (self_min, self_max)
-        } else {
-            return Intersection { inner: IntersectionInner::Answer(None), order: &self.map.order };
-        };
-        let (other_min, other_max) = if let (Some(other_min), Some(other_max)) =
-            (other.first(), other.last())
-        {
-            (other_min, other_max)
-        } else {
-            return Intersection { inner: IntersectionInner::Answer(None), order: &self.map.order };
-        };
-        Intersection {
-            inner: match (
-                self.map.order.cmp_any(self_min, other_max),
-                self.map.order.cmp_any(self_max, other_min),
-            ) {
-                (Greater, _) | (_, Less) => IntersectionInner::Answer(None),
-                (Equal, _) => IntersectionInner::Answer(Some(self_min)),
-                (_, Equal) => IntersectionInner::Answer(Some(self_max)),
-                _ if self.len() <= other.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF => {
-                    IntersectionInner::Search { small_iter: self.iter(), large_set: other }
+        let (self_min, self_max) =
+            if let (Some(self_min), Some(self_max)) = (self.first(), self.last()) {
+                (self_min, self_max)
+            } else {
+                return Intersection { inner: IntersectionInner::Answer(None), comp };
+            };
+        let (other_min, other_max) =
+            if let (Some(other_min), Some(other_max)) = (other.first(), other.last()) {
+                (other_min, other_max)
+            } else {
+                return Intersection { inner: IntersectionInner::Answer(None), comp };
+            };
+        let inner = match (comp(self_min, other_max), comp(self_max, other_min)) {
+            (Greater, _) | (_, Less) => IntersectionInner::Answer(None),
+            (Equal, _) => IntersectionInner::Answer(Some(self_min)),
+            (_, Equal) => IntersectionInner::Answer(Some(self_max)),
+            _ if self.len() <= other.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF => {
+                IntersectionInner::Search {
+                    small_iter: self.iter(),
+                    large_set: other,
+                    swapped: false,
                 }
-                _ if other.len() <= self.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF => {
-                    IntersectionInner::Search { small_iter: other.iter(), large_set: self }
+            }
+            _ if other.len() <= self.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF => {
+                IntersectionInner::Search {
+                    small_iter: other.iter(),
+                    large_set: self,
+                    swapped: true,
                 }
-                _ => IntersectionInner::Stitch { a: self.iter(), b: other.iter() },
-            },
-            order: &self.map.order,
-        }
+            }
+            _ => IntersectionInner::Stitch { a: self.iter(), b: other.iter() },
+        };
+        Intersection { inner, comp }
     }
 
     /// Visits the elements representing the union,
@@ -599,23 +564,22 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut a = BTreeSet::default();
-    /// a.insert(1);
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1, Ord::cmp);
     ///
-    /// let mut b = BTreeSet::default();
-    /// b.insert(2);
+    /// let mut b = BTreeSet::new();
+    /// b.insert(2, Ord::cmp);
     ///
-    /// let union: Vec<_> = a.union(&b).cloned().collect();
+    /// let union: Vec<_> = a.union(&b, Ord::cmp).cloned().collect();
     /// assert_eq!(union, [1, 2]);
     /// ```
-    pub fn union<'a>(&'a self, other: &'a BTreeSet<T, O, A>) -> Union<'a, T, O>
+    pub fn union<'a, C>(&'a self, other: &'a BTreeSet<T, A>, comp: C) -> Union<'a, T, C>
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
-        Union(MergeIterInner::new(self.iter(), other.iter()), &self.map.order)
+        Union(MergeIterInner::new(self.iter(), other.iter()), comp)
     }
 
     /// Clears the set, removing all elements.
@@ -623,68 +587,110 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut v = BTreeSet::default();
-    /// v.insert(1);
+    /// let mut v = BTreeSet::new();
+    /// v.insert(1, Ord::cmp);
     /// v.clear();
     /// assert!(v.is_empty());
     /// ```
-    pub fn clear(&mut self)
-    where
-        A: Clone,
-        O: Clone,
-    {
+    pub fn clear(&mut self) {
         self.map.clear()
     }
 
-    /// Returns `true` if the set contains an element equal to the value.
+    /// Returns `true` if the set contains an element for which `comp` returns
+    /// [`Equal`].
     ///
-    /// The value may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
+    /// The comparator is handed the elements of the set and must return how the
+    /// element being looked for compares to them. It may look at any borrowed
+    /// form of the element type, as long as the ordering on that borrowed form
+    /// matches the ordering of the set.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// set.insert(1, Ord::cmp);
+    /// set.insert(2, Ord::cmp);
+    /// set.insert(3, Ord::cmp);
+    /// assert_eq!(set.contains(|x| 1.cmp(x)), true);
+    /// assert_eq!(set.contains(|x| 4.cmp(x)), false);
+    /// ```
+    pub fn contains<C>(&self, comp: C) -> bool
+    where
+        C: FnMut(&T) -> Ordering,
+    {
+        self.map.contains_key(comp)
+    }
+
+    /// Returns a reference to the element in the set, if any, for which `comp`
+    /// returns [`Equal`].
     ///
-    /// let set = BTreeSet::from([1, 2, 3]);
-    /// assert_eq!(set.contains(&1), true);
-    /// assert_eq!(set.contains(&4), false);
+    /// See [`contains`](BTreeSet::contains) for how the comparator is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// set.insert(1, Ord::cmp);
+    /// set.insert(2, Ord::cmp);
+    /// set.insert(3, Ord::cmp);
+    /// assert_eq!(set.get(|x| 2.cmp(x)), Some(&2));
+    /// assert_eq!(set.get(|x| 4.cmp(x)), None);
     /// ```
-    pub fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    pub fn get<C>(&self, comp: C) -> Option<&T>
     where
-        T: SortableByWithOrder<O>,
-        Q: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T) -> Ordering,
     {
-        self.map.contains_key(value)
+        Recover::get(&self.map, comp)
     }
 
-    /// Returns a reference to the element in the set, if any, that is equal to
-    /// the value.
+    /// Returns a reference to the element at position `index` in the set,
+    /// counting from the minimum, or `None` if `index` is out of bounds.
+    /// Takes O(log n) time.
     ///
-    /// The value may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// set.insert(30, Ord::cmp);
+    /// set.insert(10, Ord::cmp);
+    /// set.insert(20, Ord::cmp);
+    /// assert_eq!(set.get_index(1), Some(&20));
+    /// assert_eq!(set.get_index(3), None);
+    /// ```
+    #[cfg(feature = "order_statistics")]
+    pub fn get_index(&self, index: usize) -> Option<&T> {
+        self.map.get_index(index).map(|(k, _)| k)
+    }
+
+    /// Counts the elements in the set that are ordered before the one for
+    /// which `comp` returns [`Equal`]. Returns `Ok` with that count if such an
+    /// element is present and `Err` otherwise. Takes O(log n) time.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let set = BTreeSet::from([1, 2, 3]);
-    /// assert_eq!(set.get(&2), Some(&2));
-    /// assert_eq!(set.get(&4), None);
+    /// let mut set = BTreeSet::new();
+    /// set.insert(10, Ord::cmp);
+    /// set.insert(20, Ord::cmp);
+    /// assert_eq!(set.rank(|x| 20.cmp(x)), Ok(1));
+    /// assert_eq!(set.rank(|x| 15.cmp(x)), Err(1));
     /// ```
-    pub fn get<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    #[cfg(feature = "order_statistics")]
+    pub fn rank<C>(&self, comp: C) -> Result<usize, usize>
     where
-        T: SortableByWithOrder<O>,
-        Q: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T) -> Ordering,
     {
-        Recover::get(&self.map, value)
+        self.map.rank(comp)
     }
 
     /// Returns `true` if `self` has no elements in common with `other`.
@@ -693,24 +699,26 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let a = BTreeSet::from([1, 2, 3]);
-    /// let mut b = BTreeSet::default();
-    ///
-    /// assert_eq!(a.is_disjoint(&b), true);
-    /// b.insert(4);
-    /// assert_eq!(a.is_disjoint(&b), true);
-    /// b.insert(1);
-    /// assert_eq!(a.is_disjoint(&b), false);
+    /// use btree_monstrousity::BTreeSet;
+    ///
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1, Ord::cmp);
+    /// a.insert(2, Ord::cmp);
+    /// a.insert(3, Ord::cmp);
+    /// let mut b = BTreeSet::new();
+    ///
+    /// assert_eq!(a.is_disjoint(&b, Ord::cmp), true);
+    /// b.insert(4, Ord::cmp);
+    /// assert_eq!(a.is_disjoint(&b, Ord::cmp), true);
+    /// b.insert(1, Ord::cmp);
+    /// assert_eq!(a.is_disjoint(&b, Ord::cmp), false);
     /// ```
     #[must_use]
-    pub fn is_disjoint(&self, other: &BTreeSet<T, O, A>) -> bool
+    pub fn is_disjoint<C>(&self, other: &BTreeSet<T, A>, comp: C) -> bool
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
-        self.intersection(other).next().is_none()
+        self.intersection(other, comp).next().is_none()
     }
 
     /// Returns `true` if the set is a subset of another,
@@ -719,22 +727,24 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let sup = BTreeSet::from([1, 2, 3]);
-    /// let mut set = BTreeSet::default();
-    ///
-    /// assert_eq!(set.is_subset(&sup), true);
-    /// set.insert(2);
-    /// assert_eq!(set.is_subset(&sup), true);
-    /// set.insert(4);
-    /// assert_eq!(set.is_subset(&sup), false);
+    /// use btree_monstrousity::BTreeSet;
+    ///
+    /// let mut sup = BTreeSet::new();
+    /// sup.insert(1, Ord::cmp);
+    /// sup.insert(2, Ord::cmp);
+    /// sup.insert(3, Ord::cmp);
+    /// let mut set = BTreeSet::new();
+    ///
+    /// assert_eq!(set.is_subset(&sup, Ord::cmp), true);
+    /// set.insert(2, Ord::cmp);
+    /// assert_eq!(set.is_subset(&sup, Ord::cmp), true);
+    /// set.insert(4, Ord::cmp);
+    /// assert_eq!(set.is_subset(&sup, Ord::cmp), false);
     /// ```
     #[must_use]
-    pub fn is_subset(&self, other: &BTreeSet<T, O, A>) -> bool
+    pub fn is_subset<C>(&self, other: &BTreeSet<T, A>, mut comp: C) -> bool
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
         // Same result as self.difference(other).next().is_none()
         // but the code below is faster (hugely in some cases).
@@ -754,14 +764,14 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
                 return false; // other is empty
             };
         let mut self_iter = self.iter();
-        match self.map.order.cmp_any(self_min, other_min) {
+        match comp(self_min, other_min) {
             Less => return false,
             Equal => {
                 self_iter.next();
             }
             Greater => (),
         }
-        match self.map.order.cmp_any(self_max, other_max) {
+        match comp(self_max, other_max) {
             Greater => return false,
             Equal => {
                 self_iter.next_back();
@@ -770,7 +780,7 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
         }
         if self_iter.len() <= other.len() / ITER_PERFORMANCE_TIPPING_SIZE_DIFF {
             for next in self_iter {
-                if !other.contains(next) {
+                if !other.contains(|k| comp(next, k)) {
                     return false;
                 }
             }
@@ -780,8 +790,7 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
             other_iter.next_back();
             let mut self_next = self_iter.next();
             while let Some(self1) = self_next {
-                match other_iter.next().map_or(Less, |other1| self.map.order.cmp_any(self1, other1))
-                {
+                match other_iter.next().map_or(Less, |other1| comp(self1, other1)) {
                     Less => return false,
                     Equal => self_next = self_iter.next(),
                     Greater => (),
@@ -797,27 +806,28 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let sub = BTreeSet::from([1, 2]);
-    /// let mut set = BTreeSet::default();
+    /// let mut sub = BTreeSet::new();
+    /// sub.insert(1, Ord::cmp);
+    /// sub.insert(2, Ord::cmp);
+    /// let mut set = BTreeSet::new();
     ///
-    /// assert_eq!(set.is_superset(&sub), false);
+    /// assert_eq!(set.is_superset(&sub, Ord::cmp), false);
     ///
-    /// set.insert(0);
-    /// set.insert(1);
-    /// assert_eq!(set.is_superset(&sub), false);
+    /// set.insert(0, Ord::cmp);
+    /// set.insert(1, Ord::cmp);
+    /// assert_eq!(set.is_superset(&sub, Ord::cmp), false);
     ///
-    /// set.insert(2);
-    /// assert_eq!(set.is_superset(&sub), true);
+    /// set.insert(2, Ord::cmp);
+    /// assert_eq!(set.is_superset(&sub, Ord::cmp), true);
     /// ```
     #[must_use]
-    pub fn is_superset(&self, other: &BTreeSet<T, O, A>) -> bool
+    pub fn is_superset<C>(&self, other: &BTreeSet<T, A>, comp: C) -> bool
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
-        other.is_subset(self)
+        other.is_subset(self, comp)
     }
 
     /// Returns a reference to the first element in the set, if any.
@@ -828,21 +838,17 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::default();
+    /// let mut set = BTreeSet::new();
     /// assert_eq!(set.first(), None);
-    /// set.insert(1);
+    /// set.insert(1, Ord::cmp);
     /// assert_eq!(set.first(), Some(&1));
-    /// set.insert(2);
+    /// set.insert(2, Ord::cmp);
     /// assert_eq!(set.first(), Some(&1));
     /// ```
     #[must_use]
-    pub fn first(&self) -> Option<&T>
-    where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
-    {
+    pub fn first(&self) -> Option<&T> {
         self.map.first_key_value().map(|(k, _)| k)
     }
 
@@ -854,21 +860,17 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::default();
+    /// let mut set = BTreeSet::new();
     /// assert_eq!(set.last(), None);
-    /// set.insert(1);
+    /// set.insert(1, Ord::cmp);
     /// assert_eq!(set.last(), Some(&1));
-    /// set.insert(2);
+    /// set.insert(2, Ord::cmp);
     /// assert_eq!(set.last(), Some(&2));
     /// ```
     #[must_use]
-    pub fn last(&self) -> Option<&T>
-    where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
-    {
+    pub fn last(&self) -> Option<&T> {
         self.map.last_key_value().map(|(k, _)| k)
     }
 
@@ -878,21 +880,17 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::default();
+    /// let mut set = BTreeSet::new();
     ///
-    /// set.insert(1);
+    /// set.insert(1, Ord::cmp);
     /// while let Some(n) = set.pop_first() {
     ///     assert_eq!(n, 1);
     /// }
     /// assert!(set.is_empty());
     /// ```
-    pub fn pop_first(&mut self) -> Option<T>
-    where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
-    {
+    pub fn pop_first(&mut self) -> Option<T> {
         self.map.pop_first().map(|kv| kv.0)
     }
 
@@ -902,26 +900,25 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::default();
+    /// let mut set = BTreeSet::new();
     ///
-    /// set.insert(1);
+    /// set.insert(1, Ord::cmp);
     /// while let Some(n) = set.pop_last() {
     ///     assert_eq!(n, 1);
     /// }
     /// assert!(set.is_empty());
     /// ```
-    pub fn pop_last(&mut self) -> Option<T>
-    where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
-    {
+    pub fn pop_last(&mut self) -> Option<T> {
         self.map.pop_last().map(|kv| kv.0)
     }
 
     /// Adds a value to the set.
     ///
+    /// The comparator is called as `double_comp(&value, element)` for the
+    /// elements visited while looking for the place of `value`.
+    ///
     /// Returns whether the value was newly inserted. That is:
     ///
     /// - If the set did not previously contain an equal value, `true` is
@@ -929,27 +926,22 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// - If the set already contained an equal value, `false` is returned, and
     ///   the entry is not updated.
     ///
-    /// See the [module-level documentation] for more.
-    ///
-    /// [module-level documentation]: index.html#insert-and-complex-keys
-    ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::default();
+    /// let mut set = BTreeSet::new();
     ///
-    /// assert_eq!(set.insert(2), true);
-    /// assert_eq!(set.insert(2), false);
+    /// assert_eq!(set.insert(2, Ord::cmp), true);
+    /// assert_eq!(set.insert(2, Ord::cmp), false);
     /// assert_eq!(set.len(), 1);
     /// ```
-    pub fn insert(&mut self, value: T) -> bool
+    pub fn insert<C>(&mut self, value: T, double_comp: C) -> bool
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
-        self.map.insert(value, SetValZST::default()).is_none()
+        self.map.insert(value, SetValZST, double_comp).is_none()
     }
 
     /// Adds a value to the set, replacing the existing element, if any, that is
@@ -958,73 +950,68 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::default();
-    /// set.insert(Vec::<i32>::new());
+    /// let mut set = BTreeSet::new();
+    /// set.insert(Vec::<i32>::new(), Ord::cmp);
     ///
-    /// assert_eq!(set.get(&[][..]).unwrap().capacity(), 0);
-    /// set.replace(Vec::with_capacity(10));
-    /// assert_eq!(set.get(&[][..]).unwrap().capacity(), 10);
+    /// assert_eq!(set.get(|v| [][..].cmp(&v[..])).unwrap().capacity(), 0);
+    /// set.replace(Vec::with_capacity(10), Ord::cmp);
+    /// assert_eq!(set.get(|v| [][..].cmp(&v[..])).unwrap().capacity(), 10);
     /// ```
-    pub fn replace(&mut self, value: T) -> Option<T>
+    pub fn replace<C>(&mut self, value: T, double_comp: C) -> Option<T>
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T, &T) -> Ordering,
     {
-        Recover::<T>::replace(&mut self.map, value)
+        Recover::replace(&mut self.map, value, double_comp)
     }
 
-    /// If the set contains an element equal to the value, removes it from the
-    /// set and drops it. Returns whether such an element was present.
+    /// If the set contains an element for which `comp` returns [`Equal`],
+    /// removes it from the set and drops it. Returns whether such an element
+    /// was present.
     ///
-    /// The value may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
+    /// See [`contains`](BTreeSet::contains) for how the comparator is used.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::default();
+    /// let mut set = BTreeSet::new();
     ///
-    /// set.insert(2);
-    /// assert_eq!(set.remove(&2), true);
-    /// assert_eq!(set.remove(&2), false);
+    /// set.insert(2, Ord::cmp);
+    /// assert_eq!(set.remove(|x| 2.cmp(x)), true);
+    /// assert_eq!(set.remove(|x| 2.cmp(x)), false);
     /// ```
-    pub fn remove<Q: ?Sized>(&mut self, value: &Q) -> bool
+    pub fn remove<C>(&mut self, comp: C) -> bool
     where
-        T: SortableByWithOrder<O>,
-        Q: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T) -> Ordering,
     {
-        self.map.remove(value).is_some()
+        self.map.remove(comp).is_some()
     }
 
-    /// Removes and returns the element in the set, if any, that is equal to
-    /// the value.
+    /// Removes and returns the element in the set, if any, for which `comp`
+    /// returns [`Equal`].
     ///
-    /// The value may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
+    /// See [`contains`](BTreeSet::contains) for how the comparator is used.
     ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let mut set = BTreeSet::from([1, 2, 3]);
-    /// assert_eq!(set.take(&2), Some(2));
-    /// assert_eq!(set.take(&2), None);
+    /// use btree_monstrousity::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    /// set.insert(1, Ord::cmp);
+    /// set.insert(2, Ord::cmp);
+    /// set.insert(3, Ord::cmp);
+    /// assert_eq!(set.take(|x| 2.cmp(x)), Some(2));
+    /// assert_eq!(set.take(|x| 2.cmp(x)), None);
     /// ```
-    pub fn take<Q: ?Sized>(&mut self, value: &Q) -> Option<T>
+    pub fn take<C>(&mut self, comp: C) -> Option<T>
     where
-        T: SortableByWithOrder<O>,
-        Q: SortableByWithOrder<O>,
-        O: TotalOrder,
+        C: FnMut(&T) -> Ordering,
     {
-        Recover::take(&mut self.map, value)
+        Recover::take(&mut self.map, comp)
     }
 
     /// Retains only the elements specified by the predicate.
@@ -1035,17 +1022,18 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut set = BTreeSet::from([1, 2, 3, 4, 5, 6]);
+    /// let mut set = BTreeSet::new();
+    /// for i in 1..=6 {
+    ///     set.insert(i, Ord::cmp);
+    /// }
     /// // Keep only the even numbers.
     /// set.retain(|&k| k % 2 == 0);
     /// assert!(set.iter().eq([2, 4, 6].iter()));
     /// ```
     pub fn retain<F>(&mut self, mut f: F)
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder,
         F: FnMut(&T) -> bool,
     {
         self.drain_filter(|v| !f(v));
@@ -1053,77 +1041,105 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
 
     /// Moves all elements from `other` into `self`, leaving `other` empty.
     ///
+    /// If an element from `other` is already present in `self`, the element
+    /// from `self` is replaced by the one from `other`.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut a = BTreeSet::default();
-    /// a.insert(1);
-    /// a.insert(2);
-    /// a.insert(3);
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1, Ord::cmp);
+    /// a.insert(2, Ord::cmp);
+    /// a.insert(3, Ord::cmp);
     ///
-    /// let mut b = BTreeSet::default();
-    /// b.insert(3);
-    /// b.insert(4);
-    /// b.insert(5);
+    /// let mut b = BTreeSet::new();
+    /// b.insert(3, Ord::cmp);
+    /// b.insert(4, Ord::cmp);
+    /// b.insert(5, Ord::cmp);
     ///
-    /// a.append(&mut b);
+    /// a.append(&mut b, Ord::cmp);
     ///
     /// assert_eq!(a.len(), 5);
     /// assert_eq!(b.len(), 0);
     ///
-    /// assert!(a.contains(&1));
-    /// assert!(a.contains(&2));
-    /// assert!(a.contains(&3));
-    /// assert!(a.contains(&4));
-    /// assert!(a.contains(&5));
+    /// assert!(a.contains(|x| 1.cmp(x)));
+    /// assert!(a.contains(|x| 2.cmp(x)));
+    /// assert!(a.contains(|x| 3.cmp(x)));
+    /// assert!(a.contains(|x| 4.cmp(x)));
+    /// assert!(a.contains(|x| 5.cmp(x)));
     /// ```
-    pub fn append(&mut self, other: &mut Self)
+    pub fn append<C>(&mut self, other: &mut Self, comp: C)
     where
-        T: SortableByWithOrder<O>,
-        O: Clone + TotalOrder,
-        A: Clone,
+        C: Fn(&T, &T) -> Ordering,
     {
-        self.map.append(&mut other.map);
+        self.map.append(&mut other.map, |a, b| comp(&a.0, &b.0));
     }
 
-    /// Splits the collection into two at the value. Returns a new collection
-    /// with all elements greater than or equal to the value.
+    /// Splits the collection into two at the element for which `comp` returns
+    /// [`Equal`], or at the place where such an element would be. Returns a new
+    /// collection with all elements greater than or equal to that split point.
     ///
     /// # Examples
     ///
     /// Basic usage:
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let mut a = BTreeSet::default();
-    /// a.insert(1);
-    /// a.insert(2);
-    /// a.insert(3);
-    /// a.insert(17);
-    /// a.insert(41);
+    /// let mut a = BTreeSet::new();
+    /// a.insert(1, Ord::cmp);
+    /// a.insert(2, Ord::cmp);
+    /// a.insert(3, Ord::cmp);
+    /// a.insert(17, Ord::cmp);
+    /// a.insert(41, Ord::cmp);
     ///
-    /// let b = a.split_off(&3);
+    /// let b = a.split_off(|x| 3.cmp(x));
     ///
     /// assert_eq!(a.len(), 2);
     /// assert_eq!(b.len(), 3);
     ///
-    /// assert!(a.contains(&1));
-    /// assert!(a.contains(&2));
+    /// assert!(a.contains(|x| 1.cmp(x)));
+    /// assert!(a.contains(|x| 2.cmp(x)));
     ///
-    /// assert!(b.contains(&3));
-    /// assert!(b.contains(&17));
-    /// assert!(b.contains(&41));
+    /// assert!(b.contains(|x| 3.cmp(x)));
+    /// assert!(b.contains(|x| 17.cmp(x)));
+    /// assert!(b.contains(|x| 41.cmp(x)));
     /// ```
-    pub fn split_off<Q: ?Sized + SortableByWithOrder<O>>(&mut self, value: &Q) -> Self
+    pub fn split_off<C>(&mut self, comp: C) -> Self
     where
-        T: SortableByWithOrder<O>,
-        O: TotalOrder + Clone,
-        A: Clone,
+        C: FnMut(&T) -> Ordering,
     {
-        BTreeSet { map: self.map.split_off(value) }
+        BTreeSet { map: self.map.split_off(comp) }
+    }
+
+    /// Splits the collection into two at the given position. Returns a new
+    /// collection with all but the first `at` elements. Finds the split point
+    /// in O(log n) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::BTreeSet;
+    ///
+    /// let mut a = BTreeSet::new();
+    /// for x in [1, 2, 3, 17, 41] {
+    ///     a.insert(x, Ord::cmp);
+    /// }
+    ///
+    /// let b = a.split_off_at_index(3);
+    ///
+    /// assert_eq!(a.into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+    /// assert_eq!(b.into_iter().collect::<Vec<_>>(), [17, 41]);
+    /// ```
+    #[cfg(feature = "order_statistics")]
+    pub fn split_off_at_index(&mut self, at: usize) -> Self {
+        BTreeSet { map: self.map.split_off_at_index(at) }
     }
 
     decorate_if! {
@@ -1148,20 +1164,21 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
             /// Splitting a set into even and odd values, reusing the original set:
             ///
             /// ```
-            /// use copse::BTreeSet;
+            /// use btree_monstrousity::BTreeSet;
             ///
-            /// let mut set: BTreeSet<i32> = (0..8).collect();
-            /// let evens: BTreeSet<_> = set.drain_filter(|v| v % 2 == 0).collect();
+            /// let mut set = BTreeSet::new();
+            /// for i in 0..8 {
+            ///     set.insert(i, Ord::cmp);
+            /// }
+            /// let evens: Vec<_> = set.drain_filter(|v| v % 2 == 0).collect();
             /// let odds = set;
-            /// assert_eq!(evens.into_iter().collect::<Vec<_>>(), vec![0, 2, 4, 6]);
+            /// assert_eq!(evens, vec![0, 2, 4, 6]);
             /// assert_eq!(odds.into_iter().collect::<Vec<_>>(), vec![1, 3, 5, 7]);
             /// ```
             pub
         }
         fn drain_filter<'a, F>(&'a mut self, pred: F) -> DrainFilter<'a, T, F, A>
         where
-            T: SortableByWithOrder<O>,
-            O: TotalOrder,
             F: 'a + FnMut(&T) -> bool,
         {
             let (inner, alloc) = self.map.drain_filter_inner();
@@ -1175,9 +1192,12 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let set = BTreeSet::from([1, 2, 3]);
+    /// let mut set = BTreeSet::new();
+    /// set.insert(1, Ord::cmp);
+    /// set.insert(2, Ord::cmp);
+    /// set.insert(3, Ord::cmp);
     /// let mut set_iter = set.iter();
     /// assert_eq!(set_iter.next(), Some(&1));
     /// assert_eq!(set_iter.next(), Some(&2));
@@ -1188,9 +1208,12 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     /// Values returned by the iterator are returned in ascending order:
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let set = BTreeSet::from([3, 1, 2]);
+    /// let mut set = BTreeSet::new();
+    /// set.insert(3, Ord::cmp);
+    /// set.insert(1, Ord::cmp);
+    /// set.insert(2, Ord::cmp);
     /// let mut set_iter = set.iter();
     /// assert_eq!(set_iter.next(), Some(&1));
     /// assert_eq!(set_iter.next(), Some(&2));
@@ -1207,11 +1230,11 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
         /// # Examples
         ///
         /// ```
-        /// use copse::BTreeSet;
+        /// use btree_monstrousity::BTreeSet;
         ///
-        /// let mut v = BTreeSet::default();
+        /// let mut v = BTreeSet::new();
         /// assert_eq!(v.len(), 0);
-        /// v.insert(1);
+        /// v.insert(1, Ord::cmp);
         /// assert_eq!(v.len(), 1);
         /// ```
         #[must_use]
@@ -1228,11 +1251,11 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
         /// # Examples
         ///
         /// ```
-        /// use copse::BTreeSet;
+        /// use btree_monstrousity::BTreeSet;
         ///
-        /// let mut v = BTreeSet::default();
+        /// let mut v = BTreeSet::new();
         /// assert!(v.is_empty());
-        /// v.insert(1);
+        /// v.insert(1, Ord::cmp);
         /// assert!(!v.is_empty());
         /// ```
         #[must_use]
@@ -1244,71 +1267,7 @@ impl<T, O, A: Allocator + Clone> BTreeSet<T, O, A> {
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder + Default> FromIterator<T> for BTreeSet<T, O> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> BTreeSet<T, O> {
-        let mut inputs: Vec<_> = iter.into_iter().collect();
-
-        if inputs.is_empty() {
-            return BTreeSet::default();
-        }
-
-        // use stable sort to preserve the insertion order.
-        let order = O::default();
-        inputs.sort_by(|a, b| order.cmp_any(a, b));
-        BTreeSet::from_sorted_iter(inputs.into_iter(), order, Global)
-    }
-}
-
-impl<T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> BTreeSet<T, O, A> {
-    fn from_sorted_iter<I: Iterator<Item = T>>(iter: I, order: O, alloc: A) -> BTreeSet<T, O, A> {
-        let iter = iter.map(|k| (k, SetValZST::default()));
-        let map = BTreeMap::bulk_build_from_sorted_iter(iter, order, alloc);
-        BTreeSet { map }
-    }
-
-    #[doc(hidden)]
-    pub fn get_order(&self) -> &O {
-        self.map.get_order()
-    }
-
-    #[doc(hidden)]
-    pub fn get_mut_order(&mut self) -> OrderMut<'_, T, SetValZST, O, A> {
-        self.map.get_mut_order()
-    }
-
-    #[doc(hidden)]
-    pub fn get_mut_order_unchecked(&mut self) -> &mut O {
-        self.map.get_mut_order_unchecked()
-    }
-}
-
-impl<T: SortableByWithOrder<O>, O: TotalOrder + Default, const N: usize> From<[T; N]>
-    for BTreeSet<T, O>
-{
-    /// Converts a `[T; N]` into a `BTreeSet<T>`.
-    ///
-    /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let set1 = BTreeSet::from([1, 2, 3, 4]);
-    /// let set2: BTreeSet<_> = [1, 2, 3, 4].into();
-    /// assert_eq!(set1, set2);
-    /// ```
-    fn from(mut arr: [T; N]) -> Self {
-        if N == 0 {
-            return BTreeSet::default();
-        }
-
-        // use stable sort to preserve the insertion order.
-        let order = O::default();
-        arr.sort_by(|a, b| order.cmp_any(a, b));
-        let iter = IntoIterator::into_iter(arr).map(|k| (k, SetValZST::default()));
-        let map = BTreeMap::bulk_build_from_sorted_iter(iter, order, Global);
-        BTreeSet { map }
-    }
-}
-
-impl<T, O, A: Allocator + Clone> IntoIterator for BTreeSet<T, O, A> {
+impl<T, A: Allocator + Clone> IntoIterator for BTreeSet<T, A> {
     type Item = T;
     type IntoIter = IntoIter<T, A>;
 
@@ -1317,9 +1276,12 @@ impl<T, O, A: Allocator + Clone> IntoIterator for BTreeSet<T, O, A> {
     /// # Examples
     ///
     /// ```
-    /// use copse::BTreeSet;
+    /// use btree_monstrousity::BTreeSet;
     ///
-    /// let set = BTreeSet::from([1, 2, 3, 4]);
+    /// let mut set = BTreeSet::new();
+    /// for i in [1, 2, 3, 4] {
+    ///     set.insert(i, Ord::cmp);
+    /// }
     ///
     /// let v: Vec<_> = set.into_iter().collect();
     /// assert_eq!(v, [1, 2, 3, 4]);
@@ -1329,7 +1291,7 @@ impl<T, O, A: Allocator + Clone> IntoIterator for BTreeSet<T, O, A> {
     }
 }
 
-impl<'a, T, O: TotalOrder, A: Allocator + Clone> IntoIterator for &'a BTreeSet<T, O, A> {
+impl<'a, T, A: Allocator + Clone> IntoIterator for &'a BTreeSet<T, A> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -1359,6 +1321,7 @@ where
 {
     fn drop(&mut self) {
         self.for_each(drop);
+        self.inner.paranoid_check();
     }
 }
 
@@ -1372,9 +1335,9 @@ where
     }
 }
 
-impl<'a, T, F, A: Allocator + Clone> Iterator for DrainFilter<'_, T, F, A>
+impl<T, F, A: Allocator + Clone> Iterator for DrainFilter<'_, T, F, A>
 where
-    F: 'a + FnMut(&T) -> bool,
+    F: FnMut(&T) -> bool,
 {
     type Item = T;
 
@@ -1394,153 +1357,14 @@ impl<T, F, A: Allocator + Clone> FusedIterator for DrainFilter<'_, T, F, A> wher
 {
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> Extend<T>
-    for BTreeSet<T, O, A>
-{
-    #[inline]
-    fn extend<Iter: IntoIterator<Item = T>>(&mut self, iter: Iter) {
-        iter.into_iter().for_each(move |elem| {
-            self.insert(elem);
-        });
-    }
-
-    #[inline]
-    #[cfg(feature = "extend_one")]
-    fn extend_one(&mut self, elem: T) {
-        self.insert(elem);
-    }
-}
-
-impl<'a, T: 'a + SortableByWithOrder<O> + Copy, O: TotalOrder, A: Allocator + Clone> Extend<&'a T>
-    for BTreeSet<T, O, A>
-{
-    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
-        self.extend(iter.into_iter().cloned());
-    }
-
-    #[inline]
-    #[cfg(feature = "extend_one")]
-    fn extend_one(&mut self, &elem: &'a T) {
-        self.insert(elem);
-    }
-}
-
-impl<T, O: Default> Default for BTreeSet<T, O> {
-    /// Creates an empty `BTreeSet` ordered by the `Ord` trait.
-    fn default() -> BTreeSet<T, O> {
-        BTreeSet::new(O::default())
-    }
-}
-
-impl<T: SortableByWithOrder<O> + Clone, O: TotalOrder + Clone, A: Allocator + Clone>
-    Sub<&BTreeSet<T, O, A>> for &BTreeSet<T, O, A>
-{
-    type Output = BTreeSet<T, O, A>;
-
-    /// Returns the difference of `self` and `rhs` as a new `BTreeSet<T>`.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let a = BTreeSet::from([1, 2, 3]);
-    /// let b = BTreeSet::from([3, 4, 5]);
-    ///
-    /// let result = &a - &b;
-    /// assert_eq!(result, BTreeSet::from([1, 2]));
-    /// ```
-    fn sub(self, rhs: &BTreeSet<T, O, A>) -> BTreeSet<T, O, A> {
-        BTreeSet::from_sorted_iter(
-            self.difference(rhs).cloned(),
-            self.map.order.clone(),
-            ManuallyDrop::into_inner(self.map.alloc.clone()),
-        )
-    }
-}
-
-impl<T: SortableByWithOrder<O> + Clone, O: TotalOrder + Clone, A: Allocator + Clone>
-    BitXor<&BTreeSet<T, O, A>> for &BTreeSet<T, O, A>
-{
-    type Output = BTreeSet<T, O, A>;
-
-    /// Returns the symmetric difference of `self` and `rhs` as a new `BTreeSet<T>`.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let a = BTreeSet::from([1, 2, 3]);
-    /// let b = BTreeSet::from([2, 3, 4]);
-    ///
-    /// let result = &a ^ &b;
-    /// assert_eq!(result, BTreeSet::from([1, 4]));
-    /// ```
-    fn bitxor(self, rhs: &BTreeSet<T, O, A>) -> BTreeSet<T, O, A> {
-        BTreeSet::from_sorted_iter(
-            self.symmetric_difference(rhs).cloned(),
-            self.map.order.clone(),
-            ManuallyDrop::into_inner(self.map.alloc.clone()),
-        )
-    }
-}
-
-impl<T: SortableByWithOrder<O> + Clone, O: TotalOrder + Clone, A: Allocator + Clone>
-    BitAnd<&BTreeSet<T, O, A>> for &BTreeSet<T, O, A>
-{
-    type Output = BTreeSet<T, O, A>;
-
-    /// Returns the intersection of `self` and `rhs` as a new `BTreeSet<T>`.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let a = BTreeSet::from([1, 2, 3]);
-    /// let b = BTreeSet::from([2, 3, 4]);
-    ///
-    /// let result = &a & &b;
-    /// assert_eq!(result, BTreeSet::from([2, 3]));
-    /// ```
-    fn bitand(self, rhs: &BTreeSet<T, O, A>) -> BTreeSet<T, O, A> {
-        BTreeSet::from_sorted_iter(
-            self.intersection(rhs).cloned(),
-            self.map.order.clone(),
-            ManuallyDrop::into_inner(self.map.alloc.clone()),
-        )
+impl<T> Default for BTreeSet<T> {
+    /// Creates an empty `BTreeSet`.
+    fn default() -> BTreeSet<T> {
+        BTreeSet::new()
     }
 }
 
-impl<T: SortableByWithOrder<O> + Clone, O: TotalOrder + Clone, A: Allocator + Clone>
-    BitOr<&BTreeSet<T, O, A>> for &BTreeSet<T, O, A>
-{
-    type Output = BTreeSet<T, O, A>;
-
-    /// Returns the union of `self` and `rhs` as a new `BTreeSet<T>`.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use copse::BTreeSet;
-    ///
-    /// let a = BTreeSet::from([1, 2, 3]);
-    /// let b = BTreeSet::from([3, 4, 5]);
-    ///
-    /// let result = &a | &b;
-    /// assert_eq!(result, BTreeSet::from([1, 2, 3, 4, 5]));
-    /// ```
-    fn bitor(self, rhs: &BTreeSet<T, O, A>) -> BTreeSet<T, O, A> {
-        BTreeSet::from_sorted_iter(
-            self.union(rhs).cloned(),
-            self.map.order.clone(),
-            ManuallyDrop::into_inner(self.map.alloc.clone()),
-        )
-    }
-}
-
-impl<T: Debug, O, A: Allocator + Clone> Debug for BTreeSet<T, O, A> {
+impl<T: Debug, A: Allocator + Clone> Debug for BTreeSet<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_set().entries(self.iter()).finish()
     }
@@ -1562,6 +1386,16 @@ impl<'a, T> Iterator for Iter<'a, T> {
         self.iter.size_hint()
     }
 
+    #[cfg(feature = "order_statistics")]
+    fn nth(&mut self, n: usize) -> Option<&'a T> {
+        self.iter.nth(n)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.iter.advance_by(n)
+    }
+
     fn last(mut self) -> Option<&'a T> {
         self.next_back()
     }
@@ -1578,6 +1412,16 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
     fn next_back(&mut self) -> Option<&'a T> {
         self.iter.next_back()
     }
+
+    #[cfg(feature = "order_statistics")]
+    fn nth_back(&mut self, n: usize) -> Option<&'a T> {
+        self.iter.nth_back(n)
+    }
+
+    #[cfg(all(feature = "order_statistics", feature = "iter_advance_by"))]
+    fn advance_back_by(&mut self, n: usize) -> Result<(), NonZeroUsize> {
+        self.iter.advance_back_by(n)
+    }
 }
 impl<T> ExactSizeIterator for Iter<'_, T> {
     fn len(&self) -> usize {
@@ -1645,7 +1489,7 @@ impl<'a, T> DoubleEndedIterator for Range<'a, T> {
 
 impl<T> FusedIterator for Range<'_, T> {}
 
-impl<T, O, A: Allocator + Clone> Clone for Difference<'_, T, O, A> {
+impl<T, C: Clone, A: Allocator + Clone> Clone for Difference<'_, T, C, A> {
     fn clone(&self) -> Self {
         Difference {
             inner: match &self.inner {
@@ -1658,23 +1502,23 @@ impl<T, O, A: Allocator + Clone> Clone for Difference<'_, T, O, A> {
                 }
                 DifferenceInner::Iterate(iter) => DifferenceInner::Iterate(iter.clone()),
             },
-            order: self.order,
+            comp: self.comp.clone(),
         }
     }
 }
-impl<'a, T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> Iterator
-    for Difference<'a, T, O, A>
+impl<'a, T, C, A: Allocator + Clone> Iterator for Difference<'a, T, C, A>
+where
+    C: FnMut(&T, &T) -> Ordering,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
+        let comp = &mut self.comp;
         match &mut self.inner {
             DifferenceInner::Stitch { self_iter, other_iter } => {
                 let mut self_next = self_iter.next()?;
                 loop {
-                    match other_iter
-                        .peek()
-                        .map_or(Less, |&other_next| self.order.cmp_any(self_next, other_next))
+                    match other_iter.peek().map_or(Less, |&other_next| comp(self_next, other_next))
                     {
                         Less => return Some(self_next),
                         Equal => {
@@ -1689,7 +1533,7 @@ impl<'a, T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> Iterato
             }
             DifferenceInner::Search { self_iter, other_set } => loop {
                 let self_next = self_iter.next()?;
-                if !other_set.contains(self_next) {
+                if !other_set.contains(|k| comp(self_next, k)) {
                     return Some(self_next);
                 }
             },
@@ -1713,22 +1557,25 @@ impl<'a, T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> Iterato
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> FusedIterator
-    for Difference<'_, T, O, A>
+impl<T, C, A: Allocator + Clone> FusedIterator for Difference<'_, T, C, A> where
+    C: FnMut(&T, &T) -> Ordering
 {
 }
 
-impl<T, O> Clone for SymmetricDifference<'_, T, O> {
+impl<T, C: Clone> Clone for SymmetricDifference<'_, T, C> {
     fn clone(&self) -> Self {
-        SymmetricDifference(self.0.clone(), self.1)
+        SymmetricDifference(self.0.clone(), self.1.clone())
     }
 }
-impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> Iterator for SymmetricDifference<'a, T, O> {
+impl<'a, T, C> Iterator for SymmetricDifference<'a, T, C>
+where
+    C: FnMut(&T, &T) -> Ordering,
+{
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
         loop {
-            let (a_next, b_next) = self.0.nexts(|&a, &b| self.1.cmp_any(a, b));
+            let (a_next, b_next) = self.0.nexts(|&a, &b| (self.1)(a, b));
             if a_next.and(b_next).is_none() {
                 return a_next.or(b_next);
             }
@@ -1748,45 +1595,56 @@ impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> Iterator for SymmetricDiffere
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> FusedIterator for SymmetricDifference<'_, T, O> {}
+impl<T, C> FusedIterator for SymmetricDifference<'_, T, C> where C: FnMut(&T, &T) -> Ordering {}
 
-impl<T, O: Clone, A: Allocator + Clone> Clone for Intersection<'_, T, O, A> {
+impl<T, C: Clone, A: Allocator + Clone> Clone for Intersection<'_, T, C, A> {
     fn clone(&self) -> Self {
         Intersection {
             inner: match &self.inner {
                 IntersectionInner::Stitch { a, b } => {
                     IntersectionInner::Stitch { a: a.clone(), b: b.clone() }
                 }
-                IntersectionInner::Search { small_iter, large_set } => {
-                    IntersectionInner::Search { small_iter: small_iter.clone(), large_set }
+                IntersectionInner::Search { small_iter, large_set, swapped } => {
+                    IntersectionInner::Search {
+                        small_iter: small_iter.clone(),
+                        large_set,
+                        swapped: *swapped,
+                    }
                 }
                 IntersectionInner::Answer(answer) => IntersectionInner::Answer(*answer),
             },
-            order: self.order,
+            comp: self.comp.clone(),
         }
     }
 }
-impl<'a, T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> Iterator
-    for Intersection<'a, T, O, A>
+impl<'a, T, C, A: Allocator + Clone> Iterator for Intersection<'a, T, C, A>
+where
+    C: FnMut(&T, &T) -> Ordering,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
+        let comp = &mut self.comp;
         match &mut self.inner {
             IntersectionInner::Stitch { a, b } => {
                 let mut a_next = a.next()?;
                 let mut b_next = b.next()?;
                 loop {
-                    match self.order.cmp_any(a_next, b_next) {
+                    match comp(a_next, b_next) {
                         Less => a_next = a.next()?,
                         Greater => b_next = b.next()?,
                         Equal => return Some(a_next),
                     }
                 }
             }
-            IntersectionInner::Search { small_iter, large_set } => loop {
+            IntersectionInner::Search { small_iter, large_set, swapped } => loop {
                 let small_next = small_iter.next()?;
-                if large_set.contains(small_next) {
+                let found = if *swapped {
+                    large_set.contains(|k| comp(k, small_next).reverse())
+                } else {
+                    large_set.contains(|k| comp(small_next, k))
+                };
+                if found {
                     return Some(small_next);
                 }
             },
@@ -1808,21 +1666,24 @@ impl<'a, T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> Iterato
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder, A: Allocator + Clone> FusedIterator
-    for Intersection<'_, T, O, A>
+impl<T, C, A: Allocator + Clone> FusedIterator for Intersection<'_, T, C, A> where
+    C: FnMut(&T, &T) -> Ordering
 {
 }
 
-impl<T, O> Clone for Union<'_, T, O> {
+impl<T, C: Clone> Clone for Union<'_, T, C> {
     fn clone(&self) -> Self {
-        Union(self.0.clone(), self.1)
+        Union(self.0.clone(), self.1.clone())
     }
 }
-impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> Iterator for Union<'a, T, O> {
+impl<'a, T, C> Iterator for Union<'a, T, C>
+where
+    C: FnMut(&T, &T) -> Ordering,
+{
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
-        let (a_next, b_next) = self.0.nexts(|&a, &b| self.1.cmp_any(a, b));
+        let (a_next, b_next) = self.0.nexts(|&a, &b| (self.1)(a, b));
         a_next.or(b_next)
     }
 
@@ -1837,7 +1698,7 @@ impl<'a, T: SortableByWithOrder<O>, O: TotalOrder> Iterator for Union<'a, T, O>
     }
 }
 
-impl<T: SortableByWithOrder<O>, O: TotalOrder> FusedIterator for Union<'_, T, O> {}
+impl<T, C> FusedIterator for Union<'_, T, C> where C: FnMut(&T, &T) -> Ordering {}
 
 #[cfg(test)]
 mod tests;
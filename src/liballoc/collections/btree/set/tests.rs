@@ -2,18 +2,70 @@ use super::*;
 use crate::liballoc::testing::crash_test::{CrashTestDummy, Panic};
 use crate::liballoc::testing::rng::DeterministicRng;
 use alloc::vec::Vec;
+use core::borrow::Borrow;
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
-use std::ops::Bound::{Excluded, Included};
+use std::ops::Bound::{Excluded, Included, Unbounded};
+use std::ops::RangeBounds;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 
+// Stand-ins for the parts of the standard library's `Ord` based interface
+// that the tests below were written against, as in map/tests.
+impl<T: Ord> BTreeSet<T> {
+    // Inserts the elements in the order given; later duplicates are ignored.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = BTreeSet::new();
+        for elem in iter {
+            set.insert(elem, Ord::cmp);
+        }
+        set
+    }
+
+    fn from<const N: usize>(arr: [T; N]) -> Self {
+        Self::from_iter(arr)
+    }
+
+    fn range_ord<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Excluded(s), Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in BTreeSet")
+            }
+            (Included(s) | Excluded(s), Included(e) | Excluded(e)) if s > e => {
+                panic!("range start is greater than range end in BTreeSet")
+            }
+            _ => {}
+        }
+        let (start, lower_bound) = match range.start_bound() {
+            Included(s) => (Some(s), SearchBoundCustom::Included),
+            Excluded(s) => (Some(s), SearchBoundCustom::Excluded),
+            Unbounded => (None, SearchBoundCustom::AllIncluded),
+        };
+        let (end, upper_bound) = match range.end_bound() {
+            Included(e) => (Some(e), SearchBoundCustom::Included),
+            Excluded(e) => (Some(e), SearchBoundCustom::Excluded),
+            Unbounded => (None, SearchBoundCustom::AllIncluded),
+        };
+        self.range(
+            |k| start.map_or(Ordering::Equal, |s| s.cmp(k.borrow())),
+            lower_bound,
+            |k| end.map_or(Ordering::Equal, |e| e.cmp(k.borrow())),
+            upper_bound,
+        )
+    }
+}
+
 #[test]
 fn test_clone_eq() {
     let mut m = BTreeSet::default();
 
-    m.insert(1);
-    m.insert(2);
+    m.insert(1, Ord::cmp);
+    m.insert(2, Ord::cmp);
 
     assert_eq!(m.clone(), m);
 }
@@ -23,30 +75,30 @@ fn test_iter_min_max() {
     let mut a = BTreeSet::default();
     assert_eq!(a.iter().min(), None);
     assert_eq!(a.iter().max(), None);
-    assert_eq!(a.range::<i32, _>(..).min(), None);
-    assert_eq!(a.range::<i32, _>(..).max(), None);
-    assert_eq!(a.difference(&BTreeSet::default()).min(), None);
-    assert_eq!(a.difference(&BTreeSet::default()).max(), None);
-    assert_eq!(a.intersection(&a).min(), None);
-    assert_eq!(a.intersection(&a).max(), None);
-    assert_eq!(a.symmetric_difference(&BTreeSet::default()).min(), None);
-    assert_eq!(a.symmetric_difference(&BTreeSet::default()).max(), None);
-    assert_eq!(a.union(&a).min(), None);
-    assert_eq!(a.union(&a).max(), None);
-    a.insert(1);
-    a.insert(2);
+    assert_eq!(a.range_ord::<i32, _>(..).min(), None);
+    assert_eq!(a.range_ord::<i32, _>(..).max(), None);
+    assert_eq!(a.difference(&BTreeSet::default(), Ord::cmp).min(), None);
+    assert_eq!(a.difference(&BTreeSet::default(), Ord::cmp).max(), None);
+    assert_eq!(a.intersection(&a, Ord::cmp).min(), None);
+    assert_eq!(a.intersection(&a, Ord::cmp).max(), None);
+    assert_eq!(a.symmetric_difference(&BTreeSet::default(), Ord::cmp).min(), None);
+    assert_eq!(a.symmetric_difference(&BTreeSet::default(), Ord::cmp).max(), None);
+    assert_eq!(a.union(&a, Ord::cmp).min(), None);
+    assert_eq!(a.union(&a, Ord::cmp).max(), None);
+    a.insert(1, Ord::cmp);
+    a.insert(2, Ord::cmp);
     assert_eq!(a.iter().min(), Some(&1));
     assert_eq!(a.iter().max(), Some(&2));
-    assert_eq!(a.range::<i32, _>(..).min(), Some(&1));
-    assert_eq!(a.range::<i32, _>(..).max(), Some(&2));
-    assert_eq!(a.difference(&BTreeSet::default()).min(), Some(&1));
-    assert_eq!(a.difference(&BTreeSet::default()).max(), Some(&2));
-    assert_eq!(a.intersection(&a).min(), Some(&1));
-    assert_eq!(a.intersection(&a).max(), Some(&2));
-    assert_eq!(a.symmetric_difference(&BTreeSet::default()).min(), Some(&1));
-    assert_eq!(a.symmetric_difference(&BTreeSet::default()).max(), Some(&2));
-    assert_eq!(a.union(&a).min(), Some(&1));
-    assert_eq!(a.union(&a).max(), Some(&2));
+    assert_eq!(a.range_ord::<i32, _>(..).min(), Some(&1));
+    assert_eq!(a.range_ord::<i32, _>(..).max(), Some(&2));
+    assert_eq!(a.difference(&BTreeSet::default(), Ord::cmp).min(), Some(&1));
+    assert_eq!(a.difference(&BTreeSet::default(), Ord::cmp).max(), Some(&2));
+    assert_eq!(a.intersection(&a, Ord::cmp).min(), Some(&1));
+    assert_eq!(a.intersection(&a, Ord::cmp).max(), Some(&2));
+    assert_eq!(a.symmetric_difference(&BTreeSet::default(), Ord::cmp).min(), Some(&1));
+    assert_eq!(a.symmetric_difference(&BTreeSet::default(), Ord::cmp).max(), Some(&2));
+    assert_eq!(a.union(&a, Ord::cmp).min(), Some(&1));
+    assert_eq!(a.union(&a, Ord::cmp).max(), Some(&2));
 }
 
 fn check<F>(a: &[i32], b: &[i32], expected: &[i32], f: F)
@@ -57,10 +109,10 @@ where
     let mut set_b = BTreeSet::default();
 
     for x in a {
-        assert!(set_a.insert(*x))
+        assert!(set_a.insert(*x, Ord::cmp))
     }
     for y in b {
-        assert!(set_b.insert(*y))
+        assert!(set_b.insert(*y, Ord::cmp))
     }
 
     let mut i = 0;
@@ -77,7 +129,7 @@ where
 #[test]
 fn test_intersection() {
     fn check_intersection(a: &[i32], b: &[i32], expected: &[i32]) {
-        check(a, b, expected, |x, y, f| x.intersection(y).all(f))
+        check(a, b, expected, |x, y, f| x.intersection(y, Ord::cmp).all(f))
     }
 
     check_intersection(&[], &[], &[]);
@@ -110,13 +162,13 @@ fn test_intersection() {
 fn test_intersection_size_hint() {
     let x = BTreeSet::from([3, 4]);
     let y = BTreeSet::from([1, 2, 3]);
-    let mut iter = x.intersection(&y);
+    let mut iter = x.intersection(&y, Ord::cmp);
     assert_eq!(iter.size_hint(), (1, Some(1)));
     assert_eq!(iter.next(), Some(&3));
     assert_eq!(iter.size_hint(), (0, Some(0)));
     assert_eq!(iter.next(), None);
 
-    iter = y.intersection(&y);
+    iter = y.intersection(&y, Ord::cmp);
     assert_eq!(iter.size_hint(), (0, Some(3)));
     assert_eq!(iter.next(), Some(&1));
     assert_eq!(iter.size_hint(), (0, Some(2)));
@@ -125,7 +177,7 @@ fn test_intersection_size_hint() {
 #[test]
 fn test_difference() {
     fn check_difference(a: &[i32], b: &[i32], expected: &[i32]) {
-        check(a, b, expected, |x, y, f| x.difference(y).all(f))
+        check(a, b, expected, |x, y, f| x.difference(y, Ord::cmp).all(f))
     }
 
     check_difference(&[], &[], &[]);
@@ -162,42 +214,42 @@ fn test_difference() {
 fn test_difference_size_hint() {
     let s246 = BTreeSet::from([2, 4, 6]);
     let s23456 = BTreeSet::from_iter(2..=6);
-    let mut iter = s246.difference(&s23456);
+    let mut iter = s246.difference(&s23456, Ord::cmp);
     assert_eq!(iter.size_hint(), (0, Some(3)));
     assert_eq!(iter.next(), None);
 
     let s12345 = BTreeSet::from_iter(1..=5);
-    iter = s246.difference(&s12345);
+    iter = s246.difference(&s12345, Ord::cmp);
     assert_eq!(iter.size_hint(), (0, Some(3)));
     assert_eq!(iter.next(), Some(&6));
     assert_eq!(iter.size_hint(), (0, Some(0)));
     assert_eq!(iter.next(), None);
 
     let s34567 = BTreeSet::from_iter(3..=7);
-    iter = s246.difference(&s34567);
+    iter = s246.difference(&s34567, Ord::cmp);
     assert_eq!(iter.size_hint(), (0, Some(3)));
     assert_eq!(iter.next(), Some(&2));
     assert_eq!(iter.size_hint(), (0, Some(2)));
     assert_eq!(iter.next(), None);
 
     let s1 = BTreeSet::from_iter(-9..=1);
-    iter = s246.difference(&s1);
+    iter = s246.difference(&s1, Ord::cmp);
     assert_eq!(iter.size_hint(), (3, Some(3)));
 
     let s2 = BTreeSet::from_iter(-9..=2);
-    iter = s246.difference(&s2);
+    iter = s246.difference(&s2, Ord::cmp);
     assert_eq!(iter.size_hint(), (2, Some(2)));
     assert_eq!(iter.next(), Some(&4));
     assert_eq!(iter.size_hint(), (1, Some(1)));
 
     let s23 = BTreeSet::from([2, 3]);
-    iter = s246.difference(&s23);
+    iter = s246.difference(&s23, Ord::cmp);
     assert_eq!(iter.size_hint(), (1, Some(3)));
     assert_eq!(iter.next(), Some(&4));
     assert_eq!(iter.size_hint(), (1, Some(1)));
 
     let s4 = BTreeSet::from([4]);
-    iter = s246.difference(&s4);
+    iter = s246.difference(&s4, Ord::cmp);
     assert_eq!(iter.size_hint(), (2, Some(3)));
     assert_eq!(iter.next(), Some(&2));
     assert_eq!(iter.size_hint(), (1, Some(2)));
@@ -206,26 +258,26 @@ fn test_difference_size_hint() {
     assert_eq!(iter.next(), None);
 
     let s56 = BTreeSet::from([5, 6]);
-    iter = s246.difference(&s56);
+    iter = s246.difference(&s56, Ord::cmp);
     assert_eq!(iter.size_hint(), (1, Some(3)));
     assert_eq!(iter.next(), Some(&2));
     assert_eq!(iter.size_hint(), (0, Some(2)));
 
     let s6 = BTreeSet::from_iter(6..=19);
-    iter = s246.difference(&s6);
+    iter = s246.difference(&s6, Ord::cmp);
     assert_eq!(iter.size_hint(), (2, Some(2)));
     assert_eq!(iter.next(), Some(&2));
     assert_eq!(iter.size_hint(), (1, Some(1)));
 
     let s7 = BTreeSet::from_iter(7..=19);
-    iter = s246.difference(&s7);
+    iter = s246.difference(&s7, Ord::cmp);
     assert_eq!(iter.size_hint(), (3, Some(3)));
 }
 
 #[test]
 fn test_symmetric_difference() {
     fn check_symmetric_difference(a: &[i32], b: &[i32], expected: &[i32]) {
-        check(a, b, expected, |x, y, f| x.symmetric_difference(y).all(f))
+        check(a, b, expected, |x, y, f| x.symmetric_difference(y, Ord::cmp).all(f))
     }
 
     check_symmetric_difference(&[], &[], &[]);
@@ -238,7 +290,7 @@ fn test_symmetric_difference() {
 fn test_symmetric_difference_size_hint() {
     let x = BTreeSet::from([2, 4]);
     let y = BTreeSet::from([1, 2, 3]);
-    let mut iter = x.symmetric_difference(&y);
+    let mut iter = x.symmetric_difference(&y, Ord::cmp);
     assert_eq!(iter.size_hint(), (0, Some(5)));
     assert_eq!(iter.next(), Some(&1));
     assert_eq!(iter.size_hint(), (0, Some(4)));
@@ -249,7 +301,7 @@ fn test_symmetric_difference_size_hint() {
 #[test]
 fn test_union() {
     fn check_union(a: &[i32], b: &[i32], expected: &[i32]) {
-        check(a, b, expected, |x, y, f| x.union(y).all(f))
+        check(a, b, expected, |x, y, f| x.union(y, Ord::cmp).all(f))
     }
 
     check_union(&[], &[], &[]);
@@ -266,7 +318,7 @@ fn test_union() {
 fn test_union_size_hint() {
     let x = BTreeSet::from([2, 4]);
     let y = BTreeSet::from([1, 2, 3]);
-    let mut iter = x.union(&y);
+    let mut iter = x.union(&y, Ord::cmp);
     assert_eq!(iter.size_hint(), (3, Some(5)));
     assert_eq!(iter.next(), Some(&1));
     assert_eq!(iter.size_hint(), (2, Some(4)));
@@ -279,16 +331,16 @@ fn test_union_size_hint() {
 fn test_is_disjoint() {
     let one = BTreeSet::from([1]);
     let two = BTreeSet::from([2]);
-    assert!(one.is_disjoint(&two));
+    assert!(one.is_disjoint(&two, Ord::cmp));
 }
 
 #[test]
 // Also implicitly tests the trivial function definition of is_superset
 fn test_is_subset() {
     fn is_subset(a: &[i32], b: &[i32]) -> bool {
-        let set_a = BTreeSet::<_, OrdTotalOrder<i32>>::from_iter(a.iter());
+        let set_a = BTreeSet::from_iter(a.iter());
         let set_b = BTreeSet::from_iter(b.iter());
-        set_a.is_subset(&set_b)
+        set_a.is_subset(&set_b, Ord::cmp)
     }
 
     assert_eq!(is_subset(&[], &[]), true);
@@ -323,9 +375,9 @@ fn test_is_subset() {
 #[test]
 fn test_is_superset() {
     fn is_superset(a: &[i32], b: &[i32]) -> bool {
-        let set_a = BTreeSet::<_, OrdTotalOrder<i32>>::from_iter(a.iter());
+        let set_a = BTreeSet::from_iter(a.iter());
         let set_b = BTreeSet::from_iter(b.iter());
-        set_a.is_superset(&set_b)
+        set_a.is_superset(&set_b, Ord::cmp)
     }
 
     assert_eq!(is_superset(&[], &[]), true);
@@ -361,9 +413,9 @@ fn test_retain() {
     let mut set = BTreeSet::from([1, 2, 3, 4, 5, 6]);
     set.retain(|&k| k % 2 == 0);
     assert_eq!(set.len(), 3);
-    assert!(set.contains(&2));
-    assert!(set.contains(&4));
-    assert!(set.contains(&6));
+    assert!(set.contains(|k| 2.cmp(k)));
+    assert!(set.contains(|k| 4.cmp(k)));
+    assert!(set.contains(|k| 6.cmp(k)));
 }
 
 #[test]
@@ -383,9 +435,9 @@ fn test_drain_filter_drop_panic_leak() {
     let b = CrashTestDummy::new(1);
     let c = CrashTestDummy::new(2);
     let mut set = BTreeSet::default();
-    set.insert(a.spawn(Panic::Never));
-    set.insert(b.spawn(Panic::InDrop));
-    set.insert(c.spawn(Panic::Never));
+    set.insert(a.spawn(Panic::Never), Ord::cmp);
+    set.insert(b.spawn(Panic::InDrop), Ord::cmp);
+    set.insert(c.spawn(Panic::Never), Ord::cmp);
 
     catch_unwind(move || drop(set.drain_filter(|dummy| dummy.query(true)))).ok();
 
@@ -403,9 +455,9 @@ fn test_drain_filter_pred_panic_leak() {
     let b = CrashTestDummy::new(1);
     let c = CrashTestDummy::new(2);
     let mut set = BTreeSet::default();
-    set.insert(a.spawn(Panic::Never));
-    set.insert(b.spawn(Panic::InQuery));
-    set.insert(c.spawn(Panic::InQuery));
+    set.insert(a.spawn(Panic::Never), Ord::cmp);
+    set.insert(b.spawn(Panic::InQuery), Ord::cmp);
+    set.insert(c.spawn(Panic::InQuery), Ord::cmp);
 
     catch_unwind(AssertUnwindSafe(|| drop(set.drain_filter(|dummy| dummy.query(true))))).ok();
 
@@ -423,7 +475,7 @@ fn test_drain_filter_pred_panic_leak() {
 #[test]
 fn test_clear() {
     let mut x = BTreeSet::default();
-    x.insert(1);
+    x.insert(1, Ord::cmp);
 
     x.clear();
     assert!(x.is_empty());
@@ -433,32 +485,32 @@ fn test_remove() {
     let mut x = BTreeSet::default();
     assert!(x.is_empty());
 
-    x.insert(1);
-    x.insert(2);
-    x.insert(3);
-    x.insert(4);
-
-    assert_eq!(x.remove(&2), true);
-    assert_eq!(x.remove(&0), false);
-    assert_eq!(x.remove(&5), false);
-    assert_eq!(x.remove(&1), true);
-    assert_eq!(x.remove(&2), false);
-    assert_eq!(x.remove(&3), true);
-    assert_eq!(x.remove(&4), true);
-    assert_eq!(x.remove(&4), false);
+    x.insert(1, Ord::cmp);
+    x.insert(2, Ord::cmp);
+    x.insert(3, Ord::cmp);
+    x.insert(4, Ord::cmp);
+
+    assert_eq!(x.remove(|k| 2.cmp(k)), true);
+    assert_eq!(x.remove(|k| 0.cmp(k)), false);
+    assert_eq!(x.remove(|k| 5.cmp(k)), false);
+    assert_eq!(x.remove(|k| 1.cmp(k)), true);
+    assert_eq!(x.remove(|k| 2.cmp(k)), false);
+    assert_eq!(x.remove(|k| 3.cmp(k)), true);
+    assert_eq!(x.remove(|k| 4.cmp(k)), true);
+    assert_eq!(x.remove(|k| 4.cmp(k)), false);
     assert!(x.is_empty());
 }
 
 #[test]
 fn test_zip() {
     let mut x = BTreeSet::default();
-    x.insert(5);
-    x.insert(12);
-    x.insert(11);
+    x.insert(5, Ord::cmp);
+    x.insert(12, Ord::cmp);
+    x.insert(11, Ord::cmp);
 
-    let mut y = BTreeSet::<_, OrdTotalOrder<str>>::default();
-    y.insert("foo");
-    y.insert("bar");
+    let mut y = BTreeSet::default();
+    y.insert("foo", Ord::cmp);
+    y.insert("bar", Ord::cmp);
 
     let x = x;
     let y = y;
@@ -473,10 +525,10 @@ fn test_zip() {
 fn test_from_iter() {
     let xs = [1, 2, 3, 4, 5, 6, 7, 8, 9];
 
-    let set = BTreeSet::<_, OrdTotalOrder<i32>>::from_iter(xs.iter());
+    let set = BTreeSet::from_iter(xs.iter());
 
     for x in &xs {
-        assert!(set.contains(x));
+        assert!(set.contains(|k| x.cmp(k)));
     }
 }
 
@@ -485,8 +537,8 @@ fn test_show() {
     let mut set = BTreeSet::default();
     let empty = BTreeSet::<i32>::default();
 
-    set.insert(1);
-    set.insert(2);
+    set.insert(1, Ord::cmp);
+    set.insert(2, Ord::cmp);
 
     let set_str = format!("{set:?}");
 
@@ -494,37 +546,10 @@ fn test_show() {
     assert_eq!(format!("{empty:?}"), "{}");
 }
 
-#[test]
-fn test_extend_ref() {
-    let mut a = BTreeSet::default();
-    a.insert(1);
-
-    a.extend(&[2, 3, 4]);
-
-    assert_eq!(a.len(), 4);
-    assert!(a.contains(&1));
-    assert!(a.contains(&2));
-    assert!(a.contains(&3));
-    assert!(a.contains(&4));
-
-    let mut b = BTreeSet::default();
-    b.insert(5);
-    b.insert(6);
-
-    a.extend(&b);
-
-    assert_eq!(a.len(), 6);
-    assert!(a.contains(&1));
-    assert!(a.contains(&2));
-    assert!(a.contains(&3));
-    assert!(a.contains(&4));
-    assert!(a.contains(&5));
-    assert!(a.contains(&6));
-}
-
 #[test]
 fn test_recovery() {
     #[derive(Debug)]
+    #[allow(dead_code)]
     struct Foo(&'static str, i32);
 
     impl PartialEq for Foo {
@@ -548,9 +573,9 @@ fn test_recovery() {
     }
 
     let mut s = BTreeSet::default();
-    assert_eq!(s.replace(Foo("a", 1)), None);
+    assert_eq!(s.replace(Foo("a", 1), Ord::cmp), None);
     assert_eq!(s.len(), 1);
-    assert_eq!(s.replace(Foo("a", 2)), Some(Foo("a", 1)));
+    assert_eq!(s.replace(Foo("a", 2), Ord::cmp), Some(Foo("a", 1)));
     assert_eq!(s.len(), 1);
 
     {
@@ -559,19 +584,19 @@ fn test_recovery() {
         assert_eq!(it.next(), None);
     }
 
-    assert_eq!(s.get(&Foo("a", 1)), Some(&Foo("a", 2)));
-    assert_eq!(s.take(&Foo("a", 1)), Some(Foo("a", 2)));
+    assert_eq!(s.get(|k| Foo("a", 1).cmp(k)), Some(&Foo("a", 2)));
+    assert_eq!(s.take(|k| Foo("a", 1).cmp(k)), Some(Foo("a", 2)));
     assert_eq!(s.len(), 0);
 
-    assert_eq!(s.get(&Foo("a", 1)), None);
-    assert_eq!(s.take(&Foo("a", 1)), None);
+    assert_eq!(s.get(|k| Foo("a", 1).cmp(k)), None);
+    assert_eq!(s.take(|k| Foo("a", 1).cmp(k)), None);
 
     assert_eq!(s.iter().next(), None);
 }
 
 #[allow(dead_code)]
 fn assert_covariance() {
-    fn set<'new>(v: BTreeSet<&'static str, ()>) -> BTreeSet<&'new str, ()> {
+    fn set<'new>(v: BTreeSet<&'static str>) -> BTreeSet<&'new str> {
         v
     }
     fn iter<'a, 'new>(v: Iter<'a, &'static str>) -> Iter<'a, &'new str> {
@@ -588,79 +613,79 @@ fn assert_covariance() {
 
 #[allow(dead_code)]
 fn assert_sync() {
-    fn set<T: Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Sync + '_ {
+    fn set<T: Sync + Ord>(v: &BTreeSet<T>) -> impl Sync + '_ {
         v
     }
 
-    fn iter<T: Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Sync + '_ {
+    fn iter<T: Sync + Ord>(v: &BTreeSet<T>) -> impl Sync + '_ {
         v.iter()
     }
 
-    fn into_iter<T: Sync + OrdStoredKey>(v: BTreeSet<T>) -> impl Sync {
+    fn into_iter<T: Sync + Ord>(v: BTreeSet<T>) -> impl Sync {
         v.into_iter()
     }
 
-    fn range<T: Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Sync + '_ {
-        v.range::<T, _>(..)
+    fn range<T: Sync + Ord>(v: &BTreeSet<T>) -> impl Sync + '_ {
+        v.range_ord::<T, _>(..)
     }
 
-    fn drain_filter<T: Sync + OrdStoredKey>(v: &mut BTreeSet<T>) -> impl Sync + '_ {
+    fn drain_filter<T: Sync + Ord>(v: &mut BTreeSet<T>) -> impl Sync + '_ {
         v.drain_filter(|_| false)
     }
 
-    fn difference<T: Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Sync + '_ {
-        v.difference(&v)
+    fn difference<T: Sync + Ord>(v: &BTreeSet<T>) -> impl Sync + '_ {
+        v.difference(&v, Ord::cmp)
     }
 
-    fn intersection<T: Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Sync + '_ {
-        v.intersection(&v)
+    fn intersection<T: Sync + Ord>(v: &BTreeSet<T>) -> impl Sync + '_ {
+        v.intersection(&v, Ord::cmp)
     }
 
-    fn symmetric_difference<T: Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Sync + '_ {
-        v.symmetric_difference(&v)
+    fn symmetric_difference<T: Sync + Ord>(v: &BTreeSet<T>) -> impl Sync + '_ {
+        v.symmetric_difference(&v, Ord::cmp)
     }
 
-    fn union<T: Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Sync + '_ {
-        v.union(&v)
+    fn union<T: Sync + Ord>(v: &BTreeSet<T>) -> impl Sync + '_ {
+        v.union(&v, Ord::cmp)
     }
 }
 
 #[allow(dead_code)]
 fn assert_send() {
-    fn set<T: Send + OrdStoredKey>(v: BTreeSet<T>) -> impl Send {
+    fn set<T: Send + Ord>(v: BTreeSet<T>) -> impl Send {
         v
     }
 
-    fn iter<T: Send + Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Send + '_ {
+    fn iter<T: Send + Sync + Ord>(v: &BTreeSet<T>) -> impl Send + '_ {
         v.iter()
     }
 
-    fn into_iter<T: Send + OrdStoredKey>(v: BTreeSet<T>) -> impl Send {
+    fn into_iter<T: Send + Ord>(v: BTreeSet<T>) -> impl Send {
         v.into_iter()
     }
 
-    fn range<T: Send + Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Send + '_ {
-        v.range::<T, _>(..)
+    fn range<T: Send + Sync + Ord>(v: &BTreeSet<T>) -> impl Send + '_ {
+        v.range_ord::<T, _>(..)
     }
 
-    fn drain_filter<T: Send + OrdStoredKey>(v: &mut BTreeSet<T>) -> impl Send + '_ {
+    fn drain_filter<T: Send + Ord>(v: &mut BTreeSet<T>) -> impl Send + '_ {
         v.drain_filter(|_| false)
     }
 
-    fn difference<T: Send + Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Send + '_ {
-        v.difference(&v)
+    fn difference<T: Send + Sync + Ord>(v: &BTreeSet<T>) -> impl Send + '_ {
+        v.difference(&v, Ord::cmp)
     }
 
-    fn intersection<T: Send + Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Send + '_ {
-        v.intersection(&v)
+    fn intersection<T: Send + Sync + Ord>(v: &BTreeSet<T>) -> impl Send + '_ {
+        v.intersection(&v, Ord::cmp)
     }
 
-    fn symmetric_difference<T: Send + Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Send + '_ {
-        v.symmetric_difference(&v)
+    fn symmetric_difference<T: Send + Sync + Ord>(v: &BTreeSet<T>) -> impl Send + '_ {
+        v.symmetric_difference(&v, Ord::cmp)
     }
 
-    fn union<T: Send + Sync + OrdStoredKey>(v: &BTreeSet<T>) -> impl Send + '_ {
-        v.union(&v)
+    fn union<T: Send + Sync + Ord>(v: &BTreeSet<T>) -> impl Send + '_ {
+        v.union(&v, Ord::cmp)
     }
 }
 
@@ -668,36 +693,36 @@ fn assert_send() {
 // Check that the member-like functions conditionally provided by #[derive()]
 // are not overridden by genuine member functions with a different signature.
 fn assert_derives() {
-    fn hash<T: Hash + OrdStoredKey, H: Hasher>(v: BTreeSet<T>, state: &mut H) {
+    fn hash<T: Hash + Ord, H: Hasher>(v: BTreeSet<T>, state: &mut H) {
         v.hash(state);
         // Tested much more thoroughly outside the crate in btree_set_hash.rs
     }
-    fn eq<T: Ord + OrdStoredKey>(v: BTreeSet<T>) {
+    fn eq<T: Ord>(v: BTreeSet<T>) {
         let _ = v.eq(&v);
     }
-    fn ne<T: Ord + OrdStoredKey>(v: BTreeSet<T>) {
+    fn ne<T: Ord>(v: BTreeSet<T>) {
         let _ = v.ne(&v);
     }
-    fn cmp<T: Ord + OrdStoredKey>(v: BTreeSet<T>) {
+    fn cmp<T: Ord>(v: BTreeSet<T>) {
         let _ = v.cmp(&v);
     }
-    fn min<T: Ord + OrdStoredKey>(v: BTreeSet<T>, w: BTreeSet<T>) {
+    fn min<T: Ord>(v: BTreeSet<T>, w: BTreeSet<T>) {
         let _ = v.min(w);
     }
-    fn max<T: Ord + OrdStoredKey>(v: BTreeSet<T>, w: BTreeSet<T>) {
+    fn max<T: Ord>(v: BTreeSet<T>, w: BTreeSet<T>) {
         let _ = v.max(w);
     }
-    fn clamp<T: Ord + OrdStoredKey>(v: BTreeSet<T>, w: BTreeSet<T>, x: BTreeSet<T>) {
+    fn clamp<T: Ord>(v: BTreeSet<T>, w: BTreeSet<T>, x: BTreeSet<T>) {
         let _ = v.clamp(w, x);
     }
-    fn partial_cmp<T: Ord + OrdStoredKey>(v: &BTreeSet<T>) {
+    fn partial_cmp<T: Ord>(v: &BTreeSet<T>) {
         let _ = v.partial_cmp(&v);
     }
 }
 
 #[test]
 fn test_ord_absence() {
-    fn set<K, O: Copy>(mut set: BTreeSet<K, O>) {
+    fn set<K>(mut set: BTreeSet<K>) {
         let _ = set.is_empty();
         let _ = set.len();
         set.clear();
@@ -705,45 +730,45 @@ fn test_ord_absence() {
         let _ = set.into_iter();
     }
 
-    fn set_debug<K: Debug, O>(set: BTreeSet<K, O>) {
-        format!("{set:?}");
-        format!("{:?}", set.iter());
-        format!("{:?}", set.into_iter());
+    fn set_debug<K: Debug>(set: BTreeSet<K>) {
+        let _ = format!("{set:?}");
+        let _ = format!("{:?}", set.iter());
+        let _ = format!("{:?}", set.into_iter());
     }
 
-    fn set_clone<K: Clone, O: Clone>(mut set: BTreeSet<K, O>) {
+    fn set_clone<K: Clone>(mut set: BTreeSet<K>) {
         set.clone_from(&set.clone());
     }
 
     #[derive(Debug, Clone)]
     struct NonOrd;
-    set(BTreeSet::<NonOrd, _>::new(()));
-    set_debug(BTreeSet::<NonOrd, _>::new(()));
-    set_clone(BTreeSet::<NonOrd, _>::new(()));
+    set(BTreeSet::<NonOrd>::new());
+    set_debug(BTreeSet::<NonOrd>::new());
+    set_clone(BTreeSet::<NonOrd>::new());
 }
 
 #[test]
 fn test_append() {
     let mut a = BTreeSet::default();
-    a.insert(1);
-    a.insert(2);
-    a.insert(3);
+    a.insert(1, Ord::cmp);
+    a.insert(2, Ord::cmp);
+    a.insert(3, Ord::cmp);
 
     let mut b = BTreeSet::default();
-    b.insert(3);
-    b.insert(4);
-    b.insert(5);
+    b.insert(3, Ord::cmp);
+    b.insert(4, Ord::cmp);
+    b.insert(5, Ord::cmp);
 
-    a.append(&mut b);
+    a.append(&mut b, Ord::cmp);
 
     assert_eq!(a.len(), 5);
     assert_eq!(b.len(), 0);
 
-    assert_eq!(a.contains(&1), true);
-    assert_eq!(a.contains(&2), true);
-    assert_eq!(a.contains(&3), true);
-    assert_eq!(a.contains(&4), true);
-    assert_eq!(a.contains(&5), true);
+    assert_eq!(a.contains(|k| 1.cmp(k)), true);
+    assert_eq!(a.contains(|k| 2.cmp(k)), true);
+    assert_eq!(a.contains(|k| 3.cmp(k)), true);
+    assert_eq!(a.contains(|k| 4.cmp(k)), true);
+    assert_eq!(a.contains(|k| 5.cmp(k)), true);
 }
 
 #[test]
@@ -751,14 +776,14 @@ fn test_first_last() {
     let mut a = BTreeSet::default();
     assert_eq!(a.first(), None);
     assert_eq!(a.last(), None);
-    a.insert(1);
+    a.insert(1, Ord::cmp);
     assert_eq!(a.first(), Some(&1));
     assert_eq!(a.last(), Some(&1));
-    a.insert(2);
+    a.insert(2, Ord::cmp);
     assert_eq!(a.first(), Some(&1));
     assert_eq!(a.last(), Some(&2));
     for i in 3..=12 {
-        a.insert(i);
+        a.insert(i, Ord::cmp);
     }
     assert_eq!(a.first(), Some(&1));
     assert_eq!(a.last(), Some(&12));
@@ -792,7 +817,7 @@ fn test_split_off_empty_right() {
     let mut data = rand_data(173);
 
     let mut set = BTreeSet::from_iter(data.clone());
-    let right = set.split_off(&(data.iter().max().unwrap() + 1));
+    let right = set.split_off(|k| (data.iter().max().unwrap() + 1).cmp(k));
 
     data.sort();
     assert!(set.into_iter().eq(data));
@@ -804,7 +829,7 @@ fn test_split_off_empty_left() {
     let mut data = rand_data(314);
 
     let mut set = BTreeSet::from_iter(data.clone());
-    let right = set.split_off(data.iter().min().unwrap());
+    let right = set.split_off(|k| data.iter().min().unwrap().cmp(k));
 
     data.sort();
     assert!(set.into_iter().eq(None));
@@ -820,7 +845,7 @@ fn test_split_off_large_random_sorted() {
 
     let mut set = BTreeSet::from_iter(data.clone());
     let key = data[data.len() / 2];
-    let right = set.split_off(&key);
+    let right = set.split_off(|k| key.cmp(k));
 
     assert!(set.into_iter().eq(data.clone().into_iter().filter(|x| *x < key)));
     assert!(right.into_iter().eq(data.into_iter().filter(|x| *x >= key)));
@@ -844,11 +869,11 @@ fn from_array() {
 #[test]
 fn test_range_panic_1() {
     let mut set = BTreeSet::default();
-    set.insert(3);
-    set.insert(5);
-    set.insert(8);
+    set.insert(3, Ord::cmp);
+    set.insert(5, Ord::cmp);
+    set.insert(8, Ord::cmp);
 
-    let _invalid_range = set.range::<i32, _>((Included(&8), Included(&3)));
+    let _invalid_range = set.range_ord::<i32, _>((Included(&8), Included(&3)));
 }
 
 #[cfg_attr(
@@ -862,9 +887,9 @@ fn test_range_panic_1() {
 #[test]
 fn test_range_panic_2() {
     let mut set = BTreeSet::default();
-    set.insert(3);
-    set.insert(5);
-    set.insert(8);
+    set.insert(3, Ord::cmp);
+    set.insert(5, Ord::cmp);
+    set.insert(8, Ord::cmp);
 
-    let _invalid_range = set.range::<i32, _>((Excluded(&5), Excluded(&5)));
+    let _invalid_range = set.range_ord::<i32, _>((Excluded(&5), Excluded(&5)));
 }
@@ -10,6 +10,7 @@ pub struct SetValZST;
 /// `TypeId` requires a `'static` lifetime, use of this trait avoids that restriction.
 ///
 /// [`TypeId`]: std::any::TypeId
+#[allow(dead_code)] // Only used with the `specialization` feature
 pub trait IsSetVal {
     fn is_set_val() -> bool;
 }
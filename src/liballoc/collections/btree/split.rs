@@ -1,26 +1,42 @@
+#[cfg(feature = "btree_cursors")]
+use alloc::vec::Vec;
+use cfg_if::cfg_if;
 use core::cmp::Ordering;
+use core::marker::PhantomData;
 
-use super::node::{ForceResult::*, Root};
-use super::search::SearchResult::*;
+use super::summary_map::Summary;
+use super::node::{marker, ForceResult::*, Handle, NodeRef, Root};
+use super::search::{NodeSearch, SearchBound, SearchResult::*};
 use crate::polyfill::*;
 
-impl<K, V> Root<K, V> {
+impl<K, V, S: Summary<K, V>, const B: usize> Root<K, V, S, B> {
     /// Calculates the length of both trees that result from splitting up
     /// a given number of distinct key-value pairs.
     pub fn calc_split_length(
         total_num: usize,
-        root_a: &Root<K, V>,
-        root_b: &Root<K, V>,
+        root_a: &Root<K, V, S, B>,
+        root_b: &Root<K, V, S, B>,
     ) -> (usize, usize) {
         let (length_a, length_b);
-        if root_a.height() < root_b.height() {
-            length_a = root_a.reborrow().calc_length();
-            length_b = total_num - length_a;
-            debug_assert_eq!(length_b, root_b.reborrow().calc_length());
-        } else {
-            length_b = root_b.reborrow().calc_length();
-            length_a = total_num - length_b;
-            debug_assert_eq!(length_a, root_a.reborrow().calc_length());
+        cfg_if! {
+            if #[cfg(feature = "order_statistics")] {
+                // Both roots count their elements, so nothing needs walking.
+                length_a = root_a.subtree_len();
+                length_b = root_b.subtree_len();
+                debug_assert_eq!(length_a + length_b, total_num);
+                debug_assert_eq!(length_a, root_a.reborrow().calc_length());
+                debug_assert_eq!(length_b, root_b.reborrow().calc_length());
+            } else {
+                if root_a.height() < root_b.height() {
+                    length_a = root_a.reborrow().calc_length();
+                    length_b = total_num - length_a;
+                    debug_assert_eq!(length_b, root_b.reborrow().calc_length());
+                } else {
+                    length_b = root_b.reborrow().calc_length();
+                    length_a = total_num - length_b;
+                    debug_assert_eq!(length_a, root_a.reborrow().calc_length());
+                }
+            }
         }
         (length_a, length_b)
     }
@@ -30,25 +46,88 @@ impl<K, V> Root<K, V> {
     /// and if the ordering of `Q` corresponds to that of `K`.
     /// If `self` respects all `BTreeMap` tree invariants, then both
     /// `self` and the returned tree will respect those invariants.
-    pub fn split_off<C, A: Allocator + Clone>(
+    pub fn split_off<N: NodeSearch<K>, C, A: Allocator + Clone>(
+        &mut self,
+        comp: C,
+        alloc: A,
+    ) -> Self
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        self.split_off_at(SplitKey(comp, PhantomData::<N>), alloc)
+    }
+
+    /// Split off a tree with the key-value pairs from the lower end of a range
+    /// onward, the way `range_search` finds that end.
+    /// If `self` respects all `BTreeMap` tree invariants, then both
+    /// `self` and the returned tree will respect those invariants.
+    pub fn split_off_lower_bound<N: NodeSearch<K>, C, A: Allocator + Clone>(
+        &mut self,
+        comp: C,
+        bound: SearchBound,
+        alloc: A,
+    ) -> Self
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        self.split_off_at(
+            SplitBound { comp, bound, upper: false, _marker: PhantomData::<N> },
+            alloc,
+        )
+    }
+
+    /// Split off a tree with the key-value pairs beyond the upper end of a
+    /// range, the way `range_search` finds that end.
+    /// If `self` respects all `BTreeMap` tree invariants, then both
+    /// `self` and the returned tree will respect those invariants.
+    pub fn split_off_upper_bound<N: NodeSearch<K>, C, A: Allocator + Clone>(
         &mut self,
-        mut comp: C,
+        comp: C,
+        bound: SearchBound,
         alloc: A,
     ) -> Self
     where
         C: FnMut(&K) -> Ordering,
     {
+        self.split_off_at(SplitBound { comp, bound, upper: true, _marker: PhantomData::<N> }, alloc)
+    }
+
+    /// Split off a tree with the key-value pairs from position `index` onward.
+    /// `index` must not exceed the length of the tree.
+    /// If `self` respects all `BTreeMap` tree invariants, then both
+    /// `self` and the returned tree will respect those invariants.
+    #[cfg(feature = "order_statistics")]
+    pub fn split_off_at_index<A: Allocator + Clone>(&mut self, index: usize, alloc: A) -> Self {
+        self.split_off_at(SplitIndex(index), alloc)
+    }
+
+    /// Split off a tree with the key-value pairs from the position that
+    /// `split_path` leads to onward. The path must have been taken in this
+    /// tree, since it was last changed.
+    /// If `self` respects all `BTreeMap` tree invariants, then both
+    /// `self` and the returned tree will respect those invariants.
+    #[cfg(feature = "btree_cursors")]
+    pub fn split_off_along<A: Allocator + Clone>(
+        &mut self,
+        split_path: SplitPath,
+        alloc: A,
+    ) -> Self {
+        self.split_off_at(split_path, alloc)
+    }
+
+    fn split_off_at<P: SplitPoint<K, V>, A: Allocator + Clone>(
+        &mut self,
+        mut split_point: P,
+        alloc: A,
+    ) -> Self {
         let left_root = self;
         let mut right_root = Root::new_pillar(left_root.height(), alloc.clone());
         let mut left_node = left_root.borrow_mut();
         let mut right_node = right_root.borrow_mut();
 
         loop {
-            let mut split_edge = match left_node.search_node(&mut comp) {
-                // key is going to the right tree
-                Found(kv) => kv.left_edge(),
-                GoDown(edge) => edge,
-            };
+            let edge_idx = split_point.edge_idx(left_node.reborrow());
+            let mut split_edge = unsafe { Handle::new_edge(left_node, edge_idx) };
 
             split_edge.move_suffix(&mut right_node);
 
@@ -57,7 +136,12 @@ impl<K, V> Root<K, V> {
                     left_node = edge.descend();
                     right_node = node.first_edge().descend();
                 }
-                (Leaf(_), Leaf(_)) => break,
+                (Leaf(edge), Leaf(node)) => {
+                    // Caches along both cut borders are off, bottom-up.
+                    edge.into_node().forget_type().update_caches_upward();
+                    node.forget_type().update_caches_upward();
+                    break;
+                }
                 _ => unreachable!(),
             }
         }
@@ -76,3 +160,154 @@ impl<K, V> Root<K, V> {
         root
     }
 }
+
+/// Picks the edge of each node, from the root down, along which `split_off_at`
+/// cuts a tree in two.
+trait SplitPoint<K, V> {
+    fn edge_idx<'a, S, const B: usize>(
+        &mut self,
+        node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>,
+    ) -> usize
+    where
+        K: 'a,
+        V: 'a;
+}
+
+/// Splits before the first key that `self.0` does not find to be greater,
+/// searching each node with `N`.
+struct SplitKey<C, N>(C, PhantomData<N>);
+
+impl<K, V, C, N> SplitPoint<K, V> for SplitKey<C, N>
+where
+    C: FnMut(&K) -> Ordering,
+    N: NodeSearch<K>,
+{
+    fn edge_idx<'a, S, const B: usize>(
+        &mut self,
+        node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>,
+    ) -> usize
+    where
+        K: 'a,
+        V: 'a,
+    {
+        match node.search_node::<N, _>(&mut self.0) {
+            // key is going to the right tree
+            Found(kv) => kv.idx(),
+            GoDown(edge) => edge.idx(),
+        }
+    }
+}
+
+/// Splits along one end of a range, narrowing the bound on the way down just
+/// like a range search does, searching each node with `N`.
+struct SplitBound<C, N> {
+    comp: C,
+    bound: SearchBound,
+    upper: bool,
+    _marker: PhantomData<N>,
+}
+
+impl<K, V, C, N> SplitPoint<K, V> for SplitBound<C, N>
+where
+    C: FnMut(&K) -> Ordering,
+    N: NodeSearch<K>,
+{
+    fn edge_idx<'a, S, const B: usize>(
+        &mut self,
+        node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>,
+    ) -> usize
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let (edge_idx, child_bound) = if self.upper {
+            unsafe { node.find_upper_bound_index::<N, _>(&mut self.comp, self.bound, 0) }
+        } else {
+            node.find_lower_bound_index::<N, _>(&mut self.comp, self.bound)
+        };
+        self.bound = child_bound;
+        edge_idx
+    }
+}
+
+/// Splits before the key-value pair at this position of the tree, following
+/// the subtree counts down and keeping track of the position within the child.
+#[cfg(feature = "order_statistics")]
+struct SplitIndex(usize);
+
+#[cfg(feature = "order_statistics")]
+impl<K, V> SplitPoint<K, V> for SplitIndex {
+    fn edge_idx<'a, S, const B: usize>(
+        &mut self,
+        node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>,
+    ) -> usize
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let (edge_idx, child_index) = node.locate_edge(self.0);
+        self.0 = child_index;
+        edge_idx
+    }
+}
+
+/// Splits right before or right after a key-value pair, following the edges
+/// that lead down to it from the root.
+#[cfg(feature = "btree_cursors")]
+pub struct SplitPath {
+    // The edge to split along in each node from the key-value pair up to
+    // the root, bottom-up.
+    edge_idxs: Vec<usize>,
+    // Whether the subtree below the key-value pair goes to the right tree
+    // entirely, rather than staying in the left tree.
+    below_goes_right: bool,
+}
+
+#[cfg(feature = "btree_cursors")]
+impl SplitPath {
+    /// Leads to the edge right before `kv`, so that `kv` goes to the right tree.
+    pub fn before<'a, K: 'a, V: 'a, S, const B: usize>(
+        kv: Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+    ) -> Self {
+        Self::leading_to(kv.left_edge(), false)
+    }
+
+    /// Leads to the edge right after `kv`, so that `kv` stays in the left tree.
+    pub fn after<'a, K: 'a, V: 'a, S, const B: usize>(
+        kv: Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::KV>,
+    ) -> Self {
+        Self::leading_to(kv.right_edge(), true)
+    }
+
+    fn leading_to<'a, K: 'a, V: 'a, S, const B: usize>(
+        edge: Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>, marker::Edge>,
+        below_goes_right: bool,
+    ) -> Self {
+        let mut edge_idxs = Vec::new();
+        edge_idxs.push(edge.idx());
+        let mut node = edge.into_node();
+        while let Ok(parent) = node.ascend() {
+            edge_idxs.push(parent.idx());
+            node = parent.into_node().forget_type();
+        }
+        SplitPath { edge_idxs, below_goes_right }
+    }
+}
+
+#[cfg(feature = "btree_cursors")]
+impl<K, V> SplitPoint<K, V> for SplitPath {
+    fn edge_idx<'a, S, const B: usize>(
+        &mut self,
+        node: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal, S, B>,
+    ) -> usize
+    where
+        K: 'a,
+        V: 'a,
+    {
+        match self.edge_idxs.pop() {
+            Some(edge_idx) => edge_idx,
+            None if self.below_goes_right => 0,
+            None => node.len(),
+        }
+    }
+}
@@ -0,0 +1,989 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::iter::{FusedIterator, Map};
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+use crate::polyfill::*;
+
+use super::borrow::DormantMutRef;
+use super::check::paranoid_check;
+use super::map::SearchBoundCustom;
+use super::navigate::LazyLeafRange;
+use super::node::{marker, Handle, NodeRef, Root};
+use super::search::{Linear, SearchBound, SearchResult::*};
+
+#[cfg(test)]
+mod tests;
+
+/// A monoid over the key-value pairs of a [`SummaryMap`], summarizing any run
+/// of consecutive pairs.
+///
+/// Combining must be associative, and `empty()` must be its identity, but it
+/// need not be commutative: summaries are always combined in the order of the
+/// keys they summarize.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::btree_summary_map::Summary;
+///
+/// /// The number of pairs and the sum of their values.
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct CountSum(usize, u64);
+///
+/// impl<K> Summary<K, u64> for CountSum {
+///     fn empty() -> Self {
+///         CountSum(0, 0)
+///     }
+///
+///     fn of(_key: &K, value: &u64) -> Self {
+///         CountSum(1, *value)
+///     }
+///
+///     fn combine(&mut self, next: &Self) {
+///         self.0 += next.0;
+///         self.1 += next.1;
+///     }
+/// }
+/// ```
+pub trait Summary<K, V>: Sized {
+    /// Returns the summary of no pairs at all.
+    fn empty() -> Self;
+
+    /// Returns the summary of a single pair.
+    fn of(key: &K, value: &V) -> Self;
+
+    /// Appends `next`, the summary of the pairs right after those that `self`
+    /// summarizes.
+    fn combine(&mut self, next: &Self);
+}
+
+/// The summary of trees that need none, which takes up no space in the nodes.
+impl<K, V> Summary<K, V> for () {
+    #[inline]
+    fn empty() {}
+
+    #[inline]
+    fn of(_key: &K, _value: &V) {}
+
+    #[inline]
+    fn combine(&mut self, _next: &()) {}
+}
+
+/// An ordered map that keeps a [`Summary`] of its pairs in every node of its
+/// B-Tree, so that the summary of any range of keys can be computed in
+/// logarithmic time.
+///
+/// Like a [`BTreeMap`](super::map::BTreeMap), the map does not store its order:
+/// every method that searches for keys takes a comparator. It is a logic error
+/// to use comparators that disagree with each other on the same map, and to
+/// modify a key in a way that changes its order.
+///
+/// Every change to the map brings the summaries along the way up to date.
+/// Values can be changed through [`get_mut`], [`iter_mut`] and [`values_mut`],
+/// which return guards that update the summaries when they are dropped.
+///
+/// [`get_mut`]: SummaryMap::get_mut
+/// [`iter_mut`]: SummaryMap::iter_mut
+/// [`values_mut`]: SummaryMap::values_mut
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+/// use btree_monstrousity::btree_summary_map::{Summary, SummaryMap};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Sum(u64);
+///
+/// impl<K> Summary<K, u64> for Sum {
+///     fn empty() -> Self {
+///         Sum(0)
+///     }
+///
+///     fn of(_key: &K, value: &u64) -> Self {
+///         Sum(*value)
+///     }
+///
+///     fn combine(&mut self, next: &Self) {
+///         self.0 += next.0;
+///     }
+/// }
+///
+/// let mut sales = SummaryMap::<u32, u64, Sum>::new();
+/// for (day, amount) in [(1, 10), (2, 5), (4, 20), (7, 1)] {
+///     sales.insert(day, amount, Ord::cmp);
+/// }
+///
+/// // The total of the sales from day 2 up to, but not including, day 7.
+/// assert_eq!(sales.aggregate(|k| 2.cmp(k), Included, |k| 7.cmp(k), Excluded), Sum(25));
+///
+/// // The first day by which the sales add up to more than 15.
+/// assert_eq!(sales.find_by_prefix_summary(|sum| sum.0 > 15), Some((&4, &20)));
+/// ```
+pub struct SummaryMap<K, V, S, A: Allocator + Clone = Global> {
+    pub(super) root: Option<Root<K, V, S>>,
+    length: usize,
+    /// `ManuallyDrop` to control drop order (needs to be dropped after all the nodes).
+    alloc: ManuallyDrop<A>,
+    // For dropck; the `Box` avoids making the `Unpin` impl more strict than before
+    _marker: PhantomData<alloc::boxed::Box<(K, V, S)>>,
+}
+
+impl<K, V, S, A: Allocator + Clone> Drop for SummaryMap<K, V, S, A> {
+    fn drop(&mut self) {
+        drop(unsafe { ptr::read(self) }.into_iter())
+    }
+}
+
+impl<K, V, S> SummaryMap<K, V, S> {
+    /// Makes a new, empty `SummaryMap`.
+    ///
+    /// Does not allocate anything on its own.
+    #[must_use]
+    pub const fn new() -> SummaryMap<K, V, S> {
+        SummaryMap { root: None, length: 0, alloc: ManuallyDrop::new(Global), _marker: PhantomData }
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> SummaryMap<K, V, S, A> {
+    decorate_if! {
+        if #[cfg(feature = "btreemap_alloc")] {
+            /// Makes a new empty `SummaryMap` allocating in `alloc`.
+            pub
+        }
+        fn new_in(alloc: A) -> SummaryMap<K, V, S, A> {
+            SummaryMap {
+                root: None,
+                length: 0,
+                alloc: ManuallyDrop::new(alloc),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Returns the number of elements in the map.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clears the map, removing all elements.
+    pub fn clear(&mut self) {
+        // avoid moving the allocator
+        drop(SummaryMap {
+            root: self.root.take(),
+            length: mem::replace(&mut self.length, 0),
+            alloc: self.alloc.clone(),
+            _marker: PhantomData,
+        });
+    }
+
+    /// Returns a reference to the value corresponding to the key that `comp`
+    /// reports equal.
+    pub fn get<C>(&self, comp: C) -> Option<&V>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        self.get_key_value(comp).map(|(_, v)| v)
+    }
+
+    /// Returns the key-value pair corresponding to the key that `comp`
+    /// reports equal.
+    pub fn get_key_value<C>(&self, comp: C) -> Option<(&K, &V)>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        let root_node = self.root.as_ref()?.reborrow();
+        match root_node.search_tree::<Linear, _>(comp) {
+            Found(handle) => Some(handle.into_kv()),
+            GoDown(_) => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a key that `comp` reports equal.
+    pub fn contains_key<C>(&self, comp: C) -> bool
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        self.get_key_value(comp).is_some()
+    }
+
+    /// Returns the first key-value pair in the map.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let root_node = self.root.as_ref()?.reborrow();
+        root_node.first_leaf_edge().right_kv().ok().map(Handle::into_kv)
+    }
+
+    /// Returns the last key-value pair in the map.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let root_node = self.root.as_ref()?.reborrow();
+        root_node.last_leaf_edge().left_kv().ok().map(Handle::into_kv)
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    pub fn iter(&self) -> Iter<'_, K, V, S> {
+        if let Some(root) = &self.root {
+            Iter { range: root.reborrow().full_range(), length: self.length }
+        } else {
+            Iter { range: LazyLeafRange::none(), length: 0 }
+        }
+    }
+
+    /// Gets an iterator over the keys of the map, in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V, S> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Gets an iterator over the values of the map, in order by key.
+    pub fn values(&self) -> Values<'_, K, V, S> {
+        Values { inner: self.iter() }
+    }
+}
+
+impl<K, V, S: Summary<K, V>, A: Allocator + Clone> SummaryMap<K, V, S, A> {
+    /// Inserts a key-value pair into the map, ordered by `double_comp`.
+    ///
+    /// If the map did not have this key present, `None` is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned. The key is not updated, though.
+    pub fn insert<C>(&mut self, key: K, value: V, mut double_comp: C) -> Option<V>
+    where
+        C: FnMut(&K, &K) -> Ordering,
+    {
+        let (map, mut dormant_map) = DormantMutRef::new(self);
+        let root = match &mut map.root {
+            None => {
+                let mut root = NodeRef::new_leaf((*map.alloc).clone());
+                let mut leaf = root.borrow_mut();
+                leaf.push(key, value);
+                leaf.update_cache();
+                map.root = Some(root.forget_type());
+                map.length = 1;
+                map.paranoid_check();
+                return None;
+            }
+            Some(root) => root,
+        };
+        let alloc = (*map.alloc).clone();
+        match root.borrow_mut().search_tree::<Linear, _>(|k: &K| double_comp(&key, k)) {
+            Found(mut handle) => {
+                let old_value = mem::replace(handle.kv_mut().1, value);
+                handle.into_node().update_caches_upward();
+                Some(old_value)
+            }
+            GoDown(handle) => {
+                handle.insert_recursing(key, value, alloc.clone(), |ins| {
+                    drop(ins.left);
+                    // SAFETY: Pushing a new root node doesn't invalidate
+                    // handles to existing nodes.
+                    let map = unsafe { dormant_map.reborrow() };
+                    let root = map.root.as_mut().unwrap(); // same as ins.left
+                    root.push_internal_level(alloc).push(ins.kv.0, ins.kv.1, ins.right)
+                });
+                // SAFETY: We are done with the handle into the tree.
+                let map = unsafe { dormant_map.awaken() };
+                map.length += 1;
+                map.paranoid_check();
+                None
+            }
+        }
+    }
+
+    /// Removes the key that `comp` reports equal from the map, returning the
+    /// value at the key if the key was previously in the map.
+    pub fn remove<C>(&mut self, comp: C) -> Option<V>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        self.remove_entry(comp).map(|(_, v)| v)
+    }
+
+    /// Removes the key that `comp` reports equal from the map, returning the
+    /// stored key and value if the key was previously in the map.
+    pub fn remove_entry<C>(&mut self, comp: C) -> Option<(K, V)>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let alloc = (*map.alloc).clone();
+        let handle = match map.root.as_mut()?.borrow_mut().search_tree::<Linear, _>(comp) {
+            Found(handle) => handle,
+            GoDown(_) => return None,
+        };
+        let mut emptied_internal_root = false;
+        let (old_kv, _) = handle.remove_kv_tracking(|| emptied_internal_root = true, alloc.clone());
+        // SAFETY: we consumed the intermediate root borrow, `handle`.
+        let map = unsafe { dormant_map.awaken() };
+        map.length -= 1;
+        if emptied_internal_root {
+            let root = map.root.as_mut().unwrap();
+            root.pop_internal_level(alloc);
+        }
+        map.paranoid_check();
+        Some(old_kv)
+    }
+
+    /// Returns a guard through which the value corresponding to the key that
+    /// `comp` reports equal can be changed. Dropping the guard updates the
+    /// summaries of the nodes from the value's node up to the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::btree_summary_map::{Summary, SummaryMap};
+    ///
+    /// #[derive(Clone)]
+    /// struct Max(i32);
+    ///
+    /// impl<K> Summary<K, i32> for Max {
+    ///     fn empty() -> Self {
+    ///         Max(i32::MIN)
+    ///     }
+    ///
+    ///     fn of(_key: &K, value: &i32) -> Self {
+    ///         Max(*value)
+    ///     }
+    ///
+    ///     fn combine(&mut self, next: &Self) {
+    ///         self.0 = self.0.max(next.0);
+    ///     }
+    /// }
+    ///
+    /// let mut map = SummaryMap::<u8, i32, Max>::new();
+    /// map.insert(1, 10, Ord::cmp);
+    /// map.insert(2, 20, Ord::cmp);
+    /// *map.get_mut(|k| 2.cmp(k)).unwrap() = 5;
+    /// assert_eq!(map.summary().0, 10);
+    /// ```
+    pub fn get_mut<C>(&mut self, comp: C) -> Option<ValueMut<'_, K, V, S>>
+    where
+        C: FnMut(&K) -> Ordering,
+    {
+        let root_node = self.root.as_mut()?.borrow_mut();
+        match root_node.search_tree::<Linear, _>(comp) {
+            Found(handle) => Some(ValueMut { handle }),
+            GoDown(_) => None,
+        }
+    }
+
+    /// Returns a guard through which the values of the map can be changed
+    /// while iterating over its entries, sorted by key. Dropping the guard
+    /// updates the summaries of the nodes holding the entries visited, in time
+    /// linear in their number plus logarithmic in the length of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use btree_monstrousity::btree_summary_map::{Summary, SummaryMap};
+    ///
+    /// #[derive(Clone)]
+    /// struct Sum(i32);
+    ///
+    /// impl<K> Summary<K, i32> for Sum {
+    ///     fn empty() -> Self {
+    ///         Sum(0)
+    ///     }
+    ///
+    ///     fn of(_key: &K, value: &i32) -> Self {
+    ///         Sum(*value)
+    ///     }
+    ///
+    ///     fn combine(&mut self, next: &Self) {
+    ///         self.0 += next.0;
+    ///     }
+    /// }
+    ///
+    /// let mut map = SummaryMap::<u8, i32, Sum>::new();
+    /// map.insert(1, 10, Ord::cmp);
+    /// map.insert(2, 20, Ord::cmp);
+    /// for (key, value) in &mut map.iter_mut() {
+    ///     *value *= i32::from(*key);
+    /// }
+    /// assert_eq!(map.summary().0, 50);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, S> {
+        let length = self.length;
+        match &mut self.root {
+            Some(root) => {
+                IterMut { range: root.borrow_valmut().full_range(), length, _marker: PhantomData }
+            }
+            None => IterMut { range: LazyLeafRange::none(), length, _marker: PhantomData },
+        }
+    }
+
+    /// Returns a guard through which the values of the map can be changed
+    /// while iterating over them, in order by key. Dropping the guard updates
+    /// the summaries of the nodes holding the values visited, like an
+    /// [`IterMut`] guard does.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V, S> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    /// Returns the summary of all pairs in the map.
+    pub fn summary(&self) -> S
+    where
+        S: Clone,
+    {
+        match &self.root {
+            Some(root) => root.reborrow().summary().clone(),
+            None => S::empty(),
+        }
+    }
+
+    /// Returns the summary of the pairs whose keys lie within a range.
+    ///
+    /// The ends of the range are given as for
+    /// [`BTreeMap::range`](super::map::BTreeMap::range). The summary is combined
+    /// from those cached in the nodes, in time logarithmic in the length of the
+    /// map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range specifies impossible bounds.
+    pub fn aggregate<C1, C2>(
+        &self,
+        lower_comp: C1,
+        lower_bound: SearchBoundCustom,
+        upper_comp: C2,
+        upper_bound: SearchBoundCustom,
+    ) -> S
+    where
+        C1: FnMut(&K) -> Ordering,
+        C2: FnMut(&K) -> Ordering,
+    {
+        let mut acc = S::empty();
+        if let Some(root) = &self.root {
+            root.reborrow().fold_range(
+                lower_comp,
+                SearchBound::from(lower_bound),
+                upper_comp,
+                SearchBound::from(upper_bound),
+                &mut acc,
+            );
+        }
+        acc
+    }
+
+    /// Returns the first key-value pair for which `pred` holds on the summary of
+    /// all pairs up to and including it, in time logarithmic in the length of
+    /// the map.
+    ///
+    /// `pred` must be monotone: once it holds on the summary of some pairs, it
+    /// must hold on the summaries of all longer runs of pairs from the start.
+    /// For instance, with a summary holding the sum of non-negative values, the
+    /// predicate `|sum| sum > x` finds the first key where the running sum
+    /// exceeds `x`.
+    pub fn find_by_prefix_summary<P>(&self, pred: P) -> Option<(&K, &V)>
+    where
+        P: FnMut(&S) -> bool,
+        S: Clone,
+    {
+        let root_node = self.root.as_ref()?.reborrow();
+        root_node.find_by_prefix_summary(pred).map(Handle::into_kv)
+    }
+
+    /// With the `paranoid` feature, panics if the tree is malformed.
+    #[inline]
+    fn paranoid_check(&self) {
+        paranoid_check(self.root.as_ref(), self.length);
+    }
+}
+
+impl<K, V, S> Default for SummaryMap<K, V, S> {
+    /// Creates an empty `SummaryMap`.
+    fn default() -> SummaryMap<K, V, S> {
+        SummaryMap::new()
+    }
+}
+
+impl<K: Clone, V: Clone, S: Summary<K, V>, A: Allocator + Clone> Clone for SummaryMap<K, V, S, A> {
+    fn clone(&self) -> SummaryMap<K, V, S, A> {
+        let mut root = Root::new((*self.alloc).clone());
+        let mut length = 0;
+        root.bulk_push(
+            self.iter().map(|(k, v)| (k.clone(), v.clone())),
+            &mut length,
+            (*self.alloc).clone(),
+        );
+        SummaryMap {
+            root: Some(root),
+            length,
+            alloc: ManuallyDrop::new((*self.alloc).clone()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S, A: Allocator + Clone> Debug for SummaryMap<K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V, S, A: Allocator + Clone> IntoIterator for &'a SummaryMap<K, V, S, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, S>;
+
+    fn into_iter(self) -> Iter<'a, K, V, S> {
+        self.iter()
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> IntoIterator for SummaryMap<K, V, S, A> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, S, A>;
+
+    fn into_iter(self) -> IntoIter<K, V, S, A> {
+        let mut me = ManuallyDrop::new(self);
+        let alloc = unsafe { ManuallyDrop::take(&mut me.alloc) };
+        if let Some(root) = me.root.take() {
+            IntoIter { range: root.into_dying().full_range(), length: me.length, alloc }
+        } else {
+            IntoIter { range: LazyLeafRange::none(), length: 0, alloc }
+        }
+    }
+}
+
+/// A guard through which a value in a [`SummaryMap`] can be changed.
+///
+/// This `struct` is created by the [`get_mut`] method on [`SummaryMap`]. See
+/// its documentation for more.
+///
+/// [`get_mut`]: SummaryMap::get_mut
+pub struct ValueMut<'a, K, V, S: Summary<K, V>> {
+    handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal, S>, marker::KV>,
+}
+
+impl<K, V, S: Summary<K, V>> ValueMut<'_, K, V, S> {
+    /// Returns a reference to the key of the value.
+    pub fn key(&self) -> &K {
+        self.handle.reborrow().into_kv().0
+    }
+}
+
+impl<K, V, S: Summary<K, V>> Deref for ValueMut<'_, K, V, S> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        self.handle.reborrow().into_kv().1
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S: Summary<K, V>> DerefMut for ValueMut<'a, K, V, S> {
+    fn deref_mut(&mut self) -> &mut V {
+        self.handle.kv_mut().1
+    }
+}
+
+impl<K, V, S: Summary<K, V>> Drop for ValueMut<'_, K, V, S> {
+    fn drop(&mut self) {
+        unsafe { self.handle.reborrow_mut() }.into_node().update_caches_upward();
+    }
+}
+
+impl<K: Debug, V: Debug, S: Summary<K, V>> Debug for ValueMut<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ValueMut").field(self.key()).field(&**self).finish()
+    }
+}
+
+/// An iterator over the entries of a `SummaryMap`.
+///
+/// This `struct` is created by the [`iter`] method on [`SummaryMap`]. See its
+/// documentation for more.
+///
+/// [`iter`]: SummaryMap::iter
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Iter<'a, K: 'a, V: 'a, S: 'a> {
+    range: LazyLeafRange<marker::Immut<'a>, K, V, S>,
+    length: usize,
+}
+
+impl<K, V, S> Clone for Iter<'_, K, V, S> {
+    fn clone(&self) -> Self {
+        Iter { range: self.range.clone(), length: self.length }
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for Iter<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S> Iterator for Iter<'a, K, V, S> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+            Some(unsafe { self.range.next_unchecked() })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+
+    fn last(mut self) -> Option<(&'a K, &'a V)> {
+        self.next_back()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, S> DoubleEndedIterator for Iter<'a, K, V, S> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+            Some(unsafe { self.range.next_back_unchecked() })
+        }
+    }
+}
+
+impl<K, V, S> ExactSizeIterator for Iter<'_, K, V, S> {
+    fn len(&self) -> usize {
+        self.length
+    }
+}
+
+impl<K, V, S> FusedIterator for Iter<'_, K, V, S> {}
+
+/// An iterator over the keys of a `SummaryMap`.
+///
+/// This `struct` is created by the [`keys`] method on [`SummaryMap`]. See its
+/// documentation for more.
+///
+/// [`keys`]: SummaryMap::keys
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Keys<'a, K, V, S> {
+    inner: Iter<'a, K, V, S>,
+}
+
+impl<K, V, S> Clone for Keys<'_, K, V, S> {
+    fn clone(&self) -> Self {
+        Keys { inner: self.inner.clone() }
+    }
+}
+
+impl<K: Debug, V, S> Debug for Keys<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V, S> Iterator for Keys<'a, K, V, S> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V, S> DoubleEndedIterator for Keys<'a, K, V, S> {
+    fn next_back(&mut self) -> Option<&'a K> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<K, V, S> ExactSizeIterator for Keys<'_, K, V, S> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, S> FusedIterator for Keys<'_, K, V, S> {}
+
+/// An iterator over the values of a `SummaryMap`.
+///
+/// This `struct` is created by the [`values`] method on [`SummaryMap`]. See its
+/// documentation for more.
+///
+/// [`values`]: SummaryMap::values
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Values<'a, K, V, S> {
+    inner: Iter<'a, K, V, S>,
+}
+
+impl<K, V, S> Clone for Values<'_, K, V, S> {
+    fn clone(&self) -> Self {
+        Values { inner: self.inner.clone() }
+    }
+}
+
+impl<K, V: Debug, S> Debug for Values<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V, S> Iterator for Values<'a, K, V, S> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V, S> DoubleEndedIterator for Values<'a, K, V, S> {
+    fn next_back(&mut self) -> Option<&'a V> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, S> ExactSizeIterator for Values<'_, K, V, S> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V, S> FusedIterator for Values<'_, K, V, S> {}
+
+/// A guard through which the values of a `SummaryMap` can be changed while
+/// iterating over its entries.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`SummaryMap`]. See
+/// its documentation for more. A mutable reference to the guard iterates over
+/// the entries that have not been visited yet, so that the references to the
+/// values cannot outlive the guard that updates the summaries.
+///
+/// [`iter_mut`]: SummaryMap::iter_mut
+pub struct IterMut<'a, K: 'a, V: 'a, S: Summary<K, V>> {
+    range: LazyLeafRange<marker::ValMut<'a>, K, V, S>,
+    length: usize,
+
+    // Be invariant in `K` and `V`
+    _marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<K, V, S: Summary<K, V>> Drop for IterMut<'_, K, V, S> {
+    fn drop(&mut self) {
+        // Only the entries before the front and after the back were visited,
+        // so only the nodes holding them need their summaries updated.
+        // SAFETY: the references handed out borrowed the guard, so none of
+        // them are alive anymore.
+        if let Some(front) = self.range.dormant_front() {
+            unsafe { front.awaken() }.update_caches_before();
+        }
+        if let Some(back) = self.range.dormant_back() {
+            unsafe { back.awaken() }.update_caches_after();
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S: Summary<K, V>> Debug for IterMut<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = Iter { range: self.range.reborrow(), length: self.length };
+        f.debug_list().entries(range).finish()
+    }
+}
+
+impl<'b, 'a, K, V, S: Summary<K, V>> IntoIterator for &'b mut IterMut<'a, K, V, S> {
+    type Item = (&'b K, &'b mut V);
+    type IntoIter = EntriesMut<'b, 'a, K, V, S>;
+
+    fn into_iter(self) -> EntriesMut<'b, 'a, K, V, S> {
+        EntriesMut { guard: self }
+    }
+}
+
+/// A mutable iterator over the entries of a `SummaryMap` that have not been
+/// visited through an [`IterMut`] guard yet.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct EntriesMut<'b, 'a, K, V, S: Summary<K, V>> {
+    guard: &'b mut IterMut<'a, K, V, S>,
+}
+
+impl<K: Debug, V: Debug, S: Summary<K, V>> Debug for EntriesMut<'_, '_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.guard.fmt(f)
+    }
+}
+
+impl<'b, K, V, S: Summary<K, V>> Iterator for EntriesMut<'b, '_, K, V, S> {
+    type Item = (&'b K, &'b mut V);
+
+    fn next(&mut self) -> Option<(&'b K, &'b mut V)> {
+        if self.guard.length == 0 {
+            None
+        } else {
+            self.guard.length -= 1;
+            Some(unsafe { self.guard.range.next_unchecked() })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.guard.length, Some(self.guard.length))
+    }
+}
+
+impl<'b, K, V, S: Summary<K, V>> DoubleEndedIterator for EntriesMut<'b, '_, K, V, S> {
+    fn next_back(&mut self) -> Option<(&'b K, &'b mut V)> {
+        if self.guard.length == 0 {
+            None
+        } else {
+            self.guard.length -= 1;
+            Some(unsafe { self.guard.range.next_back_unchecked() })
+        }
+    }
+}
+
+impl<K, V, S: Summary<K, V>> ExactSizeIterator for EntriesMut<'_, '_, K, V, S> {
+    fn len(&self) -> usize {
+        self.guard.length
+    }
+}
+
+impl<K, V, S: Summary<K, V>> FusedIterator for EntriesMut<'_, '_, K, V, S> {}
+
+/// A guard through which the values of a `SummaryMap` can be changed while
+/// iterating over them.
+///
+/// This `struct` is created by the [`values_mut`] method on [`SummaryMap`].
+/// See its documentation for more. Like an [`IterMut`], it is iterated over
+/// through a mutable reference.
+///
+/// [`values_mut`]: SummaryMap::values_mut
+pub struct ValuesMut<'a, K, V, S: Summary<K, V>> {
+    inner: IterMut<'a, K, V, S>,
+}
+
+impl<K, V: Debug, S: Summary<K, V>> Debug for ValuesMut<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = Iter { range: self.inner.range.reborrow(), length: self.inner.length };
+        f.debug_list().entries(range.map(|(_, v)| v)).finish()
+    }
+}
+
+impl<'b, 'a, K, V, S: Summary<K, V>> IntoIterator for &'b mut ValuesMut<'a, K, V, S> {
+    type Item = &'b mut V;
+    #[allow(clippy::type_complexity)]
+    type IntoIter = Map<EntriesMut<'b, 'a, K, V, S>, fn((&'b K, &'b mut V)) -> &'b mut V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (&mut self.inner).into_iter().map(|(_, v)| v)
+    }
+}
+
+/// An owning iterator over the entries of a `SummaryMap`.
+///
+/// This `struct` is created by the [`into_iter`] method on [`SummaryMap`]
+/// (provided by the [`IntoIterator`] trait).
+///
+/// [`into_iter`]: IntoIterator::into_iter
+pub struct IntoIter<K, V, S, A: Allocator + Clone = Global> {
+    range: LazyLeafRange<marker::Dying, K, V, S>,
+    length: usize,
+    /// The SummaryMap will outlive this IntoIter so we don't care about drop order for `alloc`.
+    alloc: A,
+}
+
+impl<K: Debug, V: Debug, S, A: Allocator + Clone> Debug for IntoIter<K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = Iter { range: self.range.reborrow(), length: self.length };
+        f.debug_list().entries(range).finish()
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> Drop for IntoIter<K, V, S, A> {
+    fn drop(&mut self) {
+        struct DropGuard<'a, K, V, S, A: Allocator + Clone>(&'a mut IntoIter<K, V, S, A>);
+
+        impl<'a, K, V, S, A: Allocator + Clone> Drop for DropGuard<'a, K, V, S, A> {
+            fn drop(&mut self) {
+                // Continue the same loop we perform below. This only runs when unwinding, so we
+                // don't have to care about panics this time (they'll abort).
+                while let Some(kv) = self.0.dying_next() {
+                    // SAFETY: we consume the dying handle immediately.
+                    unsafe { kv.drop_key_val() };
+                }
+            }
+        }
+
+        while let Some(kv) = self.dying_next() {
+            let guard = DropGuard(self);
+            // SAFETY: we don't touch the tree before consuming the dying handle.
+            unsafe { kv.drop_key_val() };
+            mem::forget(guard);
+        }
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> IntoIter<K, V, S, A> {
+    /// Core of a `next` method returning a dying KV handle,
+    /// invalidated by further calls to this function and some others.
+    fn dying_next(
+        &mut self,
+    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S>, marker::KV>> {
+        if self.length == 0 {
+            self.range.deallocating_end(self.alloc.clone());
+            None
+        } else {
+            self.length -= 1;
+            Some(unsafe { self.range.deallocating_next_unchecked(self.alloc.clone()) })
+        }
+    }
+
+    /// Core of a `next_back` method returning a dying KV handle,
+    /// invalidated by further calls to this function and some others.
+    fn dying_next_back(
+        &mut self,
+    ) -> Option<Handle<NodeRef<marker::Dying, K, V, marker::LeafOrInternal, S>, marker::KV>> {
+        if self.length == 0 {
+            self.range.deallocating_end(self.alloc.clone());
+            None
+        } else {
+            self.length -= 1;
+            Some(unsafe { self.range.deallocating_next_back_unchecked(self.alloc.clone()) })
+        }
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> Iterator for IntoIter<K, V, S, A> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        // SAFETY: we consume the dying handle immediately.
+        self.dying_next().map(unsafe { |kv| kv.into_key_val() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> DoubleEndedIterator for IntoIter<K, V, S, A> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        // SAFETY: we consume the dying handle immediately.
+        self.dying_next_back().map(unsafe { |kv| kv.into_key_val() })
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> ExactSizeIterator for IntoIter<K, V, S, A> {
+    fn len(&self) -> usize {
+        self.length
+    }
+}
+
+impl<K, V, S, A: Allocator + Clone> FusedIterator for IntoIter<K, V, S, A> {}
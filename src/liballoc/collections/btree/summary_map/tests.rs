@@ -0,0 +1,226 @@
+use super::*;
+use crate::liballoc::testing::rng::DeterministicRng;
+use alloc::vec::Vec;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+// The values of a run of pairs in key order, which is not commutative and so
+// catches summaries combined out of order.
+#[derive(Clone, Debug, PartialEq)]
+struct Concat(Vec<u32>);
+
+impl Summary<u32, u32> for Concat {
+    fn empty() -> Self {
+        Concat(Vec::new())
+    }
+
+    fn of(_key: &u32, value: &u32) -> Self {
+        Concat(vec![*value])
+    }
+
+    fn combine(&mut self, next: &Self) {
+        self.0.extend_from_slice(&next.0);
+    }
+}
+
+fn brute_force(map: &SummaryMap<u32, u32, Concat>, lower: Bound<u32>, upper: Bound<u32>) -> Concat {
+    let within = |k: &u32| {
+        (match lower {
+            Included(b) => b <= *k,
+            Excluded(b) => b < *k,
+            Unbounded => true,
+        }) && (match upper {
+            Included(b) => *k <= b,
+            Excluded(b) => *k < b,
+            Unbounded => true,
+        })
+    };
+    Concat(map.iter().filter(|(k, _)| within(k)).map(|(_, v)| *v).collect())
+}
+
+fn aggregate(map: &SummaryMap<u32, u32, Concat>, lower: Bound<u32>, upper: Bound<u32>) -> Concat {
+    let (lower_key, lower_bound) = match lower {
+        Included(b) => (b, SearchBoundCustom::Included),
+        Excluded(b) => (b, SearchBoundCustom::Excluded),
+        Unbounded => (0, SearchBoundCustom::AllIncluded),
+    };
+    let (upper_key, upper_bound) = match upper {
+        Included(b) => (b, SearchBoundCustom::Included),
+        Excluded(b) => (b, SearchBoundCustom::Excluded),
+        Unbounded => (0, SearchBoundCustom::AllIncluded),
+    };
+    map.aggregate(|k| lower_key.cmp(k), lower_bound, |k| upper_key.cmp(k), upper_bound)
+}
+
+// Checks the summary of the whole map and of a spread of ranges.
+fn check_summaries(map: &SummaryMap<u32, u32, Concat>) {
+    assert_eq!(map.summary(), Concat(map.values().copied().collect()));
+    let keys: Vec<u32> = map.keys().copied().step_by(7).collect();
+    let mut bounds = vec![Unbounded];
+    for &key in &keys {
+        bounds.extend([Included(key), Excluded(key), Included(key + 1)]);
+    }
+    for &lower in bounds.iter().step_by(3) {
+        for &upper in &bounds {
+            let in_order = match (lower, upper) {
+                (Included(l) | Excluded(l), Included(u) | Excluded(u)) => l < u,
+                _ => true,
+            };
+            if in_order {
+                assert_eq!(aggregate(map, lower, upper), brute_force(map, lower, upper));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_empty() {
+    let map = SummaryMap::<u32, u32, Concat>::new();
+    assert_eq!(map.summary(), Concat(vec![]));
+    assert_eq!(aggregate(&map, Unbounded, Unbounded), Concat(vec![]));
+    assert_eq!(map.find_by_prefix_summary(|_| true), None);
+}
+
+#[test]
+fn test_insert_remove() {
+    let mut rng = DeterministicRng::new();
+    let mut map = SummaryMap::<u32, u32, Concat>::new();
+    let mut keys = Vec::new();
+    for i in 0..1000 {
+        let key = rng.next() % 2000;
+        map.insert(key, i, Ord::cmp);
+        keys.push(key);
+    }
+    check_summaries(&map);
+    for key in keys.iter().step_by(2) {
+        map.remove(|k| key.cmp(k));
+    }
+    check_summaries(&map);
+    for key in keys {
+        map.remove(|k| key.cmp(k));
+    }
+    assert!(map.is_empty());
+    assert_eq!(map.summary(), Concat(vec![]));
+}
+
+#[test]
+fn test_get_mut() {
+    let mut map = SummaryMap::<u32, u32, Concat>::new();
+    for i in 0..200 {
+        map.insert(i, i, Ord::cmp);
+    }
+    for i in (0..200).step_by(3) {
+        let mut value = map.get_mut(|k| i.cmp(k)).unwrap();
+        assert_eq!(*value.key(), i);
+        *value += 1000;
+    }
+    assert!(map.get_mut(|k| 200.cmp(k)).is_none());
+    check_summaries(&map);
+}
+
+#[test]
+fn test_iter_mut() {
+    let mut map = SummaryMap::<u32, u32, Concat>::new();
+    for i in 0..200 {
+        map.insert(i, i, Ord::cmp);
+    }
+    let mut iter = map.iter_mut();
+    for (k, v) in (&mut iter).into_iter().take(50) {
+        *v = *k * 2;
+    }
+    for (_, v) in (&mut iter).into_iter().rev() {
+        *v += 1;
+    }
+    drop(iter);
+    check_summaries(&map);
+
+    for v in &mut map.values_mut() {
+        *v = 7;
+    }
+    assert_eq!(map.summary(), Concat(vec![7; 200]));
+    check_summaries(&map);
+}
+
+#[test]
+fn test_iter_mut_partial() {
+    let mut map = SummaryMap::<u32, u32, Concat>::new();
+    for i in 0..300 {
+        map.insert(i, i, Ord::cmp);
+    }
+    for (front, back) in [(0, 1), (1, 0), (3, 0), (0, 40), (100, 7), (200, 99), (300, 0)] {
+        let mut iter = map.iter_mut();
+        for v in (&mut iter).into_iter().take(front).map(|(_, v)| v) {
+            *v += 1;
+        }
+        for v in (&mut iter).into_iter().rev().take(back).map(|(_, v)| v) {
+            *v += 1;
+        }
+        drop(iter);
+        check_summaries(&map);
+    }
+}
+
+thread_local! {
+    static SUMMARIZED: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+// Counts the pairs of a run, and how many pairs get summarized overall.
+#[derive(Clone)]
+struct Counting(usize);
+
+impl Summary<u32, u32> for Counting {
+    fn empty() -> Self {
+        Counting(0)
+    }
+
+    fn of(_key: &u32, _value: &u32) -> Self {
+        SUMMARIZED.with(|n| n.set(n.get() + 1));
+        Counting(1)
+    }
+
+    fn combine(&mut self, next: &Self) {
+        self.0 += next.0;
+    }
+}
+
+#[test]
+fn test_iter_mut_updates_visited_nodes_only() {
+    let mut map = SummaryMap::<u32, u32, Counting>::new();
+    for i in 0..10_000 {
+        map.insert(i, i, Ord::cmp);
+    }
+    for (front, back) in [(0, 0), (1, 0), (0, 1), (1, 1), (20, 20)] {
+        SUMMARIZED.with(|n| n.set(0));
+        let mut iter = map.iter_mut();
+        (&mut iter).into_iter().take(front).for_each(drop);
+        (&mut iter).into_iter().rev().take(back).for_each(drop);
+        drop(iter);
+        // The nodes along both paths, and those in between holding visited pairs.
+        assert!(SUMMARIZED.with(|n| n.get()) < 200 + 10 * (front + back));
+        assert_eq!(map.summary().0, 10_000);
+    }
+}
+
+#[test]
+fn test_find_by_prefix_summary() {
+    let mut map = SummaryMap::<u32, u32, Concat>::new();
+    for i in 0..300 {
+        map.insert(i * 2, i, Ord::cmp);
+    }
+    for n in 1..=300 {
+        let expected = (2 * (n as u32 - 1), n as u32 - 1);
+        let found = map.find_by_prefix_summary(|prefix| prefix.0.len() >= n);
+        assert_eq!(found, Some((&expected.0, &expected.1)));
+    }
+    assert_eq!(map.find_by_prefix_summary(|prefix| prefix.0.len() > 300), None);
+}
+
+#[test]
+fn test_clone() {
+    let mut map = SummaryMap::<u32, u32, Concat>::new();
+    for i in 0..500 {
+        map.insert(i, 500 - i, Ord::cmp);
+    }
+    let clone = map.clone();
+    assert_eq!(clone.len(), 500);
+    check_summaries(&clone);
+}
@@ -1,5 +1,4 @@
-// We avoid relying on anything else in the crate, apart from the `Debug` and `OrdStoredKey` traits.
-use crate::default::OrdStoredKey;
+// We avoid relying on anything else in the crate, apart from the `Debug` trait.
 use alloc::fmt::Debug;
 use std::cmp::Ordering;
 use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
@@ -57,10 +56,6 @@ pub struct Instance<'a> {
     panic: Panic,
 }
 
-impl OrdStoredKey for Instance<'_> {
-    type OrdKeyType = Self;
-}
-
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Panic {
     Never,
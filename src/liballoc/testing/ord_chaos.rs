@@ -1,4 +1,3 @@
-use crate::default::OrdStoredKey;
 use std::cell::Cell;
 use std::cmp::Ordering::{self, *};
 use std::ptr;
@@ -12,10 +11,6 @@ pub enum Cyclic3 {
 }
 use Cyclic3::*;
 
-impl OrdStoredKey for Cyclic3 {
-    type OrdKeyType = Self;
-}
-
 impl PartialOrd for Cyclic3 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -62,10 +57,6 @@ impl Governor {
 #[derive(Debug)]
 pub struct Governed<'a, T>(pub T, pub &'a Governor);
 
-impl<T: Ord> OrdStoredKey for Governed<'_, T> {
-    type OrdKeyType = Self;
-}
-
 impl<T: Ord> PartialOrd for Governed<'_, T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
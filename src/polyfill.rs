@@ -1,4 +1,4 @@
-#![allow(unused_imports)]
+#![allow(dead_code, unused_imports)] // Not all shims are needed in every configuration
 
 #[macro_use]
 #[allow(unstable_name_collisions)]
@@ -39,6 +39,7 @@ mod definitions {
                 }
             }
 
+            /// The global memory allocator.
             #[derive(Copy, Clone, Debug)]
             pub struct Global;
 
@@ -329,6 +330,7 @@ mod definitions {
 #[cfg(test)]
 pub(crate) use definitions::ExactSizeIsEmpty as _;
 pub(crate) use definitions::{
-    intrinsics, Allocator, AssumeInit as _, Global, Hasher as _, MaybeUninitSlice as _,
-    NewUninit as _, SlicePtrGet as _, SlicePtrGetMut as _,
+    intrinsics, Allocator, AssumeInit as _, Hasher as _, MaybeUninitSlice as _, NewUninit as _,
+    SlicePtrGet as _, SlicePtrGetMut as _,
 };
+pub use definitions::Global;
@@ -1,2 +1,10 @@
 //! Ripytide's modifications
 
+pub mod compare;
+mod comparator;
+pub mod ordered_map;
+mod query;
+
+pub use comparator::{Comparator, OrdComparator};
+pub use ordered_map::OrderedMap;
+pub use query::{query, Query};
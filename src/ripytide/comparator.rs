@@ -0,0 +1,38 @@
+use core::cmp::Ordering;
+
+/// A total order over values of type `T`, owned by a collection such as
+/// [`OrderedMap`](super::OrderedMap).
+///
+/// Any `Fn(&T, &T) -> Ordering` closure is a comparator. A comparator may also
+/// implement `Comparator` for several types at once, which is what lets lookups
+/// take a borrowed form of the key: [`OrdComparator`] orders every [`Ord`] type,
+/// so an `OrderedMap<String, V>` can be searched with a `&str`.
+///
+/// It is a logic error for a comparator to disagree with itself across the types
+/// it is implemented for, or to change its answers while a collection depends on
+/// them.
+pub trait Comparator<T: ?Sized> {
+    /// Returns how `this` compares to `that`.
+    fn compare(&self, this: &T, that: &T) -> Ordering;
+}
+
+impl<T: ?Sized, F> Comparator<T> for F
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    #[inline]
+    fn compare(&self, this: &T, that: &T) -> Ordering {
+        self(this, that)
+    }
+}
+
+/// The comparator given by the [`Ord`] implementation of the compared type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OrdComparator;
+
+impl<T: Ord + ?Sized> Comparator<T> for OrdComparator {
+    #[inline]
+    fn compare(&self, this: &T, that: &T) -> Ordering {
+        this.cmp(that)
+    }
+}
@@ -0,0 +1,422 @@
+//! Combinators for building comparators out of smaller ones.
+//!
+//! Every combinator takes [`Comparator`]s and returns a plain
+//! `Fn(&T, &T) -> Ordering` closure, so the result is itself a comparator and
+//! can be passed straight to [`BTreeMap::insert`], [`BTreeMap::entry`] or
+//! [`OrderedMap::new`]. [`against`] then turns a comparator into the
+//! one-argument closure that lookups such as [`BTreeMap::get`] and
+//! [`BTreeMap::range`] take.
+//!
+//! The module also has ready-made comparators for orders that the [`Ord`]
+//! implementations of the standard library do not give: [`float_nan_first`]
+//! and its siblings order floats, [`natural`] orders numbers in text by value,
+//! [`ascii_case_insensitive`] and [`case_insensitive`] ignore case, and
+//! [`os_str_bytes`] orders paths by their bytes. They are generic functions, so
+//! they can be passed by name wherever a comparator goes, and take different
+//! types on each side so that a key can be looked up by a borrowed form.
+//!
+//! # Examples
+//!
+//! ```
+//! use btree_monstrousity::ripytide::compare::{against, by_borrowed_key, by_key, reverse, then};
+//! use btree_monstrousity::BTreeMap;
+//!
+//! // By ascending number, then by descending name.
+//! let by_number = by_key(|k: &(u32, String)| k.0);
+//! let by_name = by_borrowed_key(|k: &(u32, String)| &k.1);
+//! let comp = then(by_number, reverse(by_name));
+//!
+//! let mut map = BTreeMap::new();
+//! map.insert((1, "a".to_string()), 'x', &comp);
+//! map.insert((1, "b".to_string()), 'y', &comp);
+//! map.insert((0, "c".to_string()), 'z', &comp);
+//!
+//! assert_eq!(map.values().collect::<String>(), "zyx");
+//! assert_eq!(map.get(against(&(1, "a".to_string()), &comp)), Some(&'x'));
+//! ```
+//!
+//! [`BTreeMap::insert`]: crate::BTreeMap::insert
+//! [`BTreeMap::entry`]: crate::BTreeMap::entry
+//! [`BTreeMap::get`]: crate::BTreeMap::get
+//! [`BTreeMap::range`]: crate::BTreeMap::range
+//! [`OrderedMap::new`]: super::OrderedMap::new
+
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use std::ffi::OsStr;
+
+use super::Comparator;
+
+#[cfg(test)]
+mod tests;
+
+/// Orders values by the [`Ord`] order of a key computed from them, like
+/// [`slice::sort_by_key`].
+///
+/// The key is computed twice per comparison, so it should be cheap. To compare
+/// a borrowed part of the value, use [`by_borrowed_key`].
+pub fn by_key<T: ?Sized, K: Ord>(key: impl Fn(&T) -> K) -> impl Fn(&T, &T) -> Ordering {
+    move |this, that| key(this).cmp(&key(that))
+}
+
+/// Orders values by the [`Ord`] order of a part of them that `key` borrows.
+pub fn by_borrowed_key<T: ?Sized, K: Ord + ?Sized>(
+    key: impl Fn(&T) -> &K,
+) -> impl Fn(&T, &T) -> Ordering {
+    move |this, that| key(this).cmp(key(that))
+}
+
+/// Reverses the order given by `comp`.
+pub fn reverse<T: ?Sized>(comp: impl Comparator<T>) -> impl Fn(&T, &T) -> Ordering {
+    move |this, that| comp.compare(that, this)
+}
+
+/// Orders values by `first`, breaking ties with `second`, like
+/// [`Ordering::then_with`].
+pub fn then<T: ?Sized>(
+    first: impl Comparator<T>,
+    second: impl Comparator<T>,
+) -> impl Fn(&T, &T) -> Ordering {
+    move |this, that| first.compare(this, that).then_with(|| second.compare(this, that))
+}
+
+/// Orders slices, or anything that dereferences to one, element by element
+/// with `comp`, with a slice ordered before every longer slice it starts.
+///
+/// This is the order [`Ord`] gives slices, with `comp` in place of the [`Ord`]
+/// order of the elements.
+pub fn lexicographic<S, T>(comp: impl Comparator<T>) -> impl Fn(&S, &S) -> Ordering
+where
+    S: AsRef<[T]> + ?Sized,
+{
+    move |this, that| {
+        let (this, that) = (this.as_ref(), that.as_ref());
+        this.iter()
+            .zip(that)
+            .map(|(a, b)| comp.compare(a, b))
+            .find(|&ord| ord != Ordering::Equal)
+            .unwrap_or_else(|| this.len().cmp(&that.len()))
+    }
+}
+
+/// Orders `None` before every `Some`, and `Some` values by `comp`.
+///
+/// This is the order [`Ord`] gives [`Option`], with `comp` in place of the
+/// [`Ord`] order of the contents.
+pub fn nulls_first<T>(comp: impl Comparator<T>) -> impl Fn(&Option<T>, &Option<T>) -> Ordering {
+    move |this, that| match (this, that) {
+        (Some(a), Some(b)) => comp.compare(a, b),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// Orders `None` after every `Some`, and `Some` values by `comp`.
+pub fn nulls_last<T>(comp: impl Comparator<T>) -> impl Fn(&Option<T>, &Option<T>) -> Ordering {
+    move |this, that| match (this, that) {
+        (Some(a), Some(b)) => comp.compare(a, b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    }
+}
+
+/// Orders `(key, value)` pairs by their keys with `comp`, for
+/// [`BTreeMap::append`](crate::BTreeMap::append).
+pub fn on_key<K, V>(comp: impl Comparator<K>) -> impl Fn(&(K, V), &(K, V)) -> Ordering {
+    move |this, that| comp.compare(&this.0, &that.0)
+}
+
+/// Turns `comp` into the closure that lookups take, returning how `query`
+/// compares to each key it is passed.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+/// use btree_monstrousity::ripytide::compare::{against, reverse};
+/// use btree_monstrousity::ripytide::OrdComparator;
+/// use btree_monstrousity::BTreeMap;
+///
+/// let comp = reverse(OrdComparator);
+/// let mut map = BTreeMap::new();
+/// for i in 0..10 {
+///     map.insert(i, (), &comp);
+/// }
+/// let range = map.range(against(&7, &comp), Included, against(&4, &comp), Excluded);
+/// assert_eq!(range.map(|(k, _)| *k).collect::<Vec<_>>(), [7, 6, 5]);
+/// ```
+pub fn against<'a, T: ?Sized>(
+    query: &'a T,
+    comp: impl Comparator<T> + 'a,
+) -> impl FnMut(&T) -> Ordering + 'a {
+    move |key| comp.compare(query, key)
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// A floating-point type, ordered by [`float_nan_first`] and its siblings.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Float: Copy + PartialOrd + sealed::Sealed {
+    /// Returns the IEEE 754 `totalOrder` of `self` and `other`, like
+    /// [`f64::total_cmp`].
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    /// Returns `true` if `self` is NaN.
+    fn is_nan(&self) -> bool;
+}
+
+impl Float for f32 {
+    #[inline]
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+
+    #[inline]
+    fn is_nan(&self) -> bool {
+        f32::is_nan(*self)
+    }
+}
+
+impl Float for f64 {
+    #[inline]
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+
+    #[inline]
+    fn is_nan(&self) -> bool {
+        f64::is_nan(*self)
+    }
+}
+
+/// Orders floats by value, with every NaN equal to every other and ordered
+/// before all numbers.
+///
+/// Zero and negative zero are equal, so they are the same key in a map.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::ripytide::compare::float_nan_first;
+/// use btree_monstrousity::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// for x in [1.5, f64::NAN, -0.5] {
+///     map.insert(x, (), float_nan_first);
+/// }
+/// assert!(map.first_key_value().unwrap().0.is_nan());
+/// assert!(map.contains_key(|k| float_nan_first(&-0.5, k)));
+/// ```
+pub fn float_nan_first<F: Float>(this: &F, that: &F) -> Ordering {
+    match this.partial_cmp(that) {
+        Some(ord) => ord,
+        None => that.is_nan().cmp(&this.is_nan()),
+    }
+}
+
+/// Orders floats by value, with every NaN equal to every other and ordered
+/// after all numbers.
+///
+/// Zero and negative zero are equal, so they are the same key in a map.
+pub fn float_nan_last<F: Float>(this: &F, that: &F) -> Ordering {
+    match this.partial_cmp(that) {
+        Some(ord) => ord,
+        None => this.is_nan().cmp(&that.is_nan()),
+    }
+}
+
+/// Orders floats by value, for maps that must never hold NaN.
+///
+/// # Panics
+///
+/// Panics if either float is NaN, so a NaN key is rejected by the first
+/// comparison that sees it.
+pub fn float_reject_nan<F: Float>(this: &F, that: &F) -> Ordering {
+    this.partial_cmp(that).expect("NaN passed to a comparator that rejects NaN")
+}
+
+/// Orders floats by the IEEE 754 `totalOrder` predicate, like
+/// [`f64::total_cmp`].
+///
+/// Unlike the other float comparators, this tells apart zero and negative
+/// zero, as well as NaNs with different signs or payloads.
+pub fn float_total_order<F: Float>(this: &F, that: &F) -> Ordering {
+    this.total_cmp(that)
+}
+
+/// Orders strings in "natural" order, which compares runs of ASCII digits by
+/// their numeric value, so that `"file9"` comes before `"file10"`.
+///
+/// Everything else is compared byte by byte, which for `str` is the order of
+/// the chars. Numbers of any length are compared exactly, and strings that
+/// differ only in leading zeros are ordered by their bytes, so that only equal
+/// strings compare equal.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::ripytide::compare::natural;
+/// use btree_monstrousity::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// for name in ["file10.txt", "file9.txt", "file1.txt"] {
+///     map.insert(name.to_string(), (), natural);
+/// }
+/// assert!(map.keys().eq(["file1.txt", "file9.txt", "file10.txt"]));
+/// assert!(map.contains_key(|k| natural("file9.txt", k)));
+/// ```
+pub fn natural<A, B>(this: &A, that: &B) -> Ordering
+where
+    A: AsRef<str> + ?Sized,
+    B: AsRef<str> + ?Sized,
+{
+    let (this, that) = (this.as_ref().as_bytes(), that.as_ref().as_bytes());
+    natural_bytes(this, that).then_with(|| this.cmp(that))
+}
+
+// Compares byte strings as sequences of numbers and single non-digit bytes.
+// A number is ordered against a byte by its first digit, which puts all
+// numbers on the same side of any given byte and keeps the order total.
+fn natural_bytes(mut this: &[u8], mut that: &[u8]) -> Ordering {
+    loop {
+        match (this.first(), that.first()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) if a.is_ascii_digit() && b.is_ascii_digit() => {
+                let (a, this_rest) = split_number(this);
+                let (b, that_rest) = split_number(that);
+                match a.len().cmp(&b.len()).then_with(|| a.cmp(b)) {
+                    Ordering::Equal => (this, that) = (this_rest, that_rest),
+                    ord => return ord,
+                }
+            }
+            (Some(a), Some(b)) => match a.cmp(b) {
+                Ordering::Equal => (this, that) = (&this[1..], &that[1..]),
+                ord => return ord,
+            },
+        }
+    }
+}
+
+// Splits the leading digits off `s`, returning them without leading zeros.
+fn split_number(s: &[u8]) -> (&[u8], &[u8]) {
+    let len = s.iter().position(|c| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (digits, rest) = s.split_at(len);
+    let zeros = digits.iter().position(|&c| c != b'0').unwrap_or(digits.len());
+    (&digits[zeros..], rest)
+}
+
+/// Orders strings or byte strings byte by byte, ignoring the case of ASCII
+/// letters, like [`str::eq_ignore_ascii_case`] does.
+///
+/// Strings that differ only in the case of ASCII letters compare equal, so
+/// they are the same key in a map.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::ripytide::compare::ascii_case_insensitive;
+/// use btree_monstrousity::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("Content-Type", 1, ascii_case_insensitive);
+/// map.insert("content-type", 2, ascii_case_insensitive);
+/// assert_eq!(map.len(), 1);
+/// assert_eq!(map.get(|k| ascii_case_insensitive("CONTENT-TYPE", k)), Some(&2));
+/// ```
+pub fn ascii_case_insensitive<A, B>(this: &A, that: &B) -> Ordering
+where
+    A: AsRef<[u8]> + ?Sized,
+    B: AsRef<[u8]> + ?Sized,
+{
+    let this = this.as_ref().iter().map(u8::to_ascii_lowercase);
+    this.cmp(that.as_ref().iter().map(u8::to_ascii_lowercase))
+}
+
+/// Orders strings char by char after Unicode simple case folding, so that
+/// strings differing only in case compare equal, and are the same key in a
+/// map.
+///
+/// Each char is folded to the lowercase of its uppercase, taking either
+/// mapping only where it is a single char. The exceptions are the Turkish
+/// dotless `'ı'`, which is left alone rather than folded to `'i'`, and
+/// Cherokee, which folds to its uppercase. That gives the simple case folding
+/// of the Unicode version that the standard library implements. Folds that
+/// turn one char into several, like `'ß'` into `"ss"`, are not part of simple
+/// case folding and are not applied.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::ripytide::compare::case_insensitive;
+/// use btree_monstrousity::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("Σίσυφος", 1, case_insensitive);
+/// assert_eq!(map.get(|k| case_insensitive("ΣΊΣΥΦΟΣ", k)), Some(&1));
+/// ```
+pub fn case_insensitive<A, B>(this: &A, that: &B) -> Ordering
+where
+    A: AsRef<str> + ?Sized,
+    B: AsRef<str> + ?Sized,
+{
+    this.as_ref().chars().map(simple_fold).cmp(that.as_ref().chars().map(simple_fold))
+}
+
+fn simple_fold(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+    match c {
+        // Only the Turkic mappings, which simple case folding leaves out, fold it.
+        '\u{131}' => c,
+        '\u{13a0}'..='\u{13f5}' | '\u{13f8}'..='\u{13fd}' | '\u{ab70}'..='\u{abbf}' => {
+            single_char(c.to_uppercase()).unwrap_or(c)
+        }
+        _ => {
+            let upper = single_char(c.to_uppercase()).unwrap_or(c);
+            single_char(upper.to_lowercase()).unwrap_or(upper)
+        }
+    }
+}
+
+fn single_char(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Orders OS strings and paths by their bytes, as returned by
+/// [`OsStr::as_encoded_bytes`].
+///
+/// Unlike the [`Ord`] implementation of [`Path`](std::path::Path), which
+/// compares paths component by component, this puts `"a/b"` after `"a-b"`,
+/// and keeps `"a/b"` and `"a//b"` apart.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::ripytide::compare::os_str_bytes;
+/// use btree_monstrousity::BTreeMap;
+/// use std::path::{Path, PathBuf};
+///
+/// let mut map = BTreeMap::new();
+/// map.insert(PathBuf::from("a/b"), 1, os_str_bytes);
+/// map.insert(PathBuf::from("a-b"), 2, os_str_bytes);
+/// assert!(map.keys().eq([Path::new("a-b"), Path::new("a/b")]));
+/// assert_eq!(map.get(|k| os_str_bytes(Path::new("a/b"), k)), Some(&1));
+/// ```
+#[cfg(feature = "std")]
+pub fn os_str_bytes<A, B>(this: &A, that: &B) -> Ordering
+where
+    A: AsRef<OsStr> + ?Sized,
+    B: AsRef<OsStr> + ?Sized,
+{
+    this.as_ref().as_encoded_bytes().cmp(that.as_ref().as_encoded_bytes())
+}
@@ -0,0 +1,159 @@
+use super::*;
+use crate::ripytide::OrdComparator;
+use crate::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[test]
+fn test_by_key_and_reverse() {
+    let comp = by_key(|s: &&str| s.len());
+    assert_eq!(comp(&"ab", &"c"), Ordering::Greater);
+    assert_eq!(comp(&"ab", &"cd"), Ordering::Equal);
+    assert_eq!(reverse(&comp)(&"ab", &"c"), Ordering::Less);
+    let comp = by_borrowed_key(|p: &(u32, String)| p.1.as_str());
+    assert_eq!(comp(&(2, "a".to_string()), &(1, "b".to_string())), Ordering::Less);
+}
+
+#[test]
+fn test_then() {
+    let comp = then(by_key(|p: &(u32, u32)| p.0), reverse(by_key(|p: &(u32, u32)| p.1)));
+    let mut pairs = vec![(1, 1), (0, 5), (1, 3), (0, 2)];
+    pairs.sort_by(&comp);
+    assert_eq!(pairs, [(0, 5), (0, 2), (1, 3), (1, 1)]);
+}
+
+#[test]
+fn test_lexicographic() {
+    let comp = lexicographic::<Vec<i32>, _>(reverse(OrdComparator));
+    assert_eq!(comp(&vec![3, 1], &vec![2, 9]), Ordering::Less);
+    assert_eq!(comp(&vec![3], &vec![3, 0]), Ordering::Less);
+    assert_eq!(comp(&vec![], &vec![]), Ordering::Equal);
+    // Agrees with `Ord` for slices when given the `Ord` order of the elements.
+    let comp = lexicographic::<[u8], _>(OrdComparator);
+    for (a, b) in [(&b"ab"[..], &b"abc"[..]), (b"b", b"abc"), (b"", b"a"), (b"ab", b"ab")] {
+        assert_eq!(comp(a, b), a.cmp(b));
+        assert_eq!(comp(b, a), b.cmp(a));
+    }
+}
+
+#[test]
+fn test_nulls() {
+    let mut values = vec![Some(2), None, Some(1)];
+    values.sort_by(nulls_first(OrdComparator));
+    assert_eq!(values, [None, Some(1), Some(2)]);
+    values.sort_by(nulls_last(reverse(OrdComparator)));
+    assert_eq!(values, [Some(2), Some(1), None]);
+}
+
+#[test]
+fn test_with_map() {
+    let comp = then(by_key(|p: &(u32, u32)| p.0 % 3), OrdComparator);
+    let mut map = BTreeMap::new();
+    for i in 0..10 {
+        map.insert((i, i), i, &comp);
+    }
+    let mut other = BTreeMap::new();
+    other.insert((10, 10), 10, &comp);
+    map.append(&mut other, on_key(&comp));
+    assert_eq!(map.values().copied().collect::<Vec<_>>(), [0, 3, 6, 9, 1, 4, 7, 10, 2, 5, 8]);
+    assert_eq!(map.get(against(&(7, 7), &comp)), Some(&7));
+    assert_eq!(map.get(against(&(7, 8), &comp)), None);
+    *map.entry((7, 8), &comp).or_insert(0) += 1;
+    assert_eq!(map.get(against(&(7, 8), &comp)), Some(&1));
+}
+
+#[test]
+fn test_floats() {
+    let mut values = [1.0, f64::NAN, -0.0, f64::NEG_INFINITY, 0.0, -f64::NAN];
+    values.sort_by(float_nan_first);
+    assert!(values[0].is_nan() && values[1].is_nan());
+    assert_eq!(values[2..], [f64::NEG_INFINITY, -0.0, 0.0, 1.0]);
+    values.sort_by(float_nan_last);
+    assert_eq!(values[..4], [f64::NEG_INFINITY, -0.0, 0.0, 1.0]);
+    assert!(values[4].is_nan() && values[5].is_nan());
+    assert_eq!(float_nan_first(&0.0f32, &-0.0), Ordering::Equal);
+    assert_eq!(float_total_order(&0.0f32, &-0.0), Ordering::Greater);
+    assert_eq!(float_total_order(&-f64::NAN, &f64::NEG_INFINITY), Ordering::Less);
+    assert_eq!(float_reject_nan(&1.0, &2.0), Ordering::Less);
+}
+
+#[test]
+#[should_panic(expected = "NaN passed to a comparator that rejects NaN")]
+fn test_float_reject_nan() {
+    let mut map = BTreeMap::new();
+    map.insert(1.0, (), float_reject_nan);
+    map.insert(f32::NAN, (), float_reject_nan);
+}
+
+#[test]
+fn test_natural() {
+    let mut names =
+        vec!["x10", "x9", "x", "x09", "x9a", "y1", "x1000000000000000000000", "x010", "é2"];
+    names.sort_by(natural);
+    assert_eq!(
+        names,
+        ["x", "x09", "x9", "x9a", "x010", "x10", "x1000000000000000000000", "y1", "é2"]
+    );
+    assert_eq!(natural("a01b", "a1b"), Ordering::Less);
+    assert_eq!(natural("a1b", "a1b"), Ordering::Equal);
+    // Numbers sort before letters and after spaces, whatever their length.
+    assert_eq!(natural("a99", "aa"), Ordering::Less);
+    assert_eq!(natural("a ", "a99"), Ordering::Less);
+}
+
+#[test]
+fn test_case_insensitive() {
+    assert_eq!(ascii_case_insensitive("HeLLo", "hello"), Ordering::Equal);
+    assert_eq!(ascii_case_insensitive("a", "B"), Ordering::Less);
+    assert_eq!(ascii_case_insensitive(&b"Z"[..], "a"), Ordering::Greater);
+    assert_eq!(ascii_case_insensitive("É", "é"), Ordering::Less);
+    assert_eq!(case_insensitive("É", "é"), Ordering::Equal);
+    assert_eq!(case_insensitive("ſ", "S"), Ordering::Equal);
+    assert_eq!(case_insensitive("ΌΣΟΣ", "όσος"), Ordering::Equal);
+    assert_eq!(case_insensitive("ß", "ẞ"), Ordering::Equal);
+    assert_eq!(case_insensitive("ß", "ss"), Ordering::Greater);
+    assert_eq!(case_insensitive("ſ", "s"), Ordering::Equal);
+    assert_ne!(case_insensitive("ı", "i"), Ordering::Equal);
+    assert_ne!(case_insensitive("ı", "I"), Ordering::Equal);
+    assert_ne!(case_insensitive("İ", "i"), Ordering::Equal);
+    assert_eq!(case_insensitive("ᾈ", "ᾀ"), Ordering::Equal);
+    assert_eq!(case_insensitive("ᏼ", "Ᏼ"), Ordering::Equal);
+    assert_eq!(case_insensitive("ꭰ", "Ꭰ"), Ordering::Equal);
+
+    let mut map = BTreeMap::new();
+    for word in ["Straße", "STRASSE", "straße", "Apfel"] {
+        *map.entry(word.to_string(), case_insensitive).or_insert(0) += 1;
+    }
+    assert!(map.iter().eq([
+        (&"Apfel".to_string(), &1),
+        (&"STRASSE".to_string(), &1),
+        (&"Straße".to_string(), &2)
+    ]));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_os_str_bytes() {
+    use std::ffi::OsString;
+    use std::path::Path;
+
+    assert_eq!(os_str_bytes(Path::new("a/b"), "a-b"), Ordering::Greater);
+    assert_eq!(os_str_bytes(Path::new("a//b"), Path::new("a/b")), Ordering::Less);
+    assert_eq!(os_str_bytes(&OsString::from("x"), "x"), Ordering::Equal);
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_prebuilt_with_cursors() {
+    use crate::btree_map::SearchBoundCustom;
+
+    let mut map = BTreeMap::new();
+    for name in ["img12", "img2", "img7"] {
+        map.insert(name, (), natural);
+    }
+    let cursor = map.lower_bound(|k| natural("img3", k), SearchBoundCustom::Included);
+    assert_eq!(cursor.key(), Some(&"img7"));
+    let cursor = map.upper_bound(|k| natural("img12", k), SearchBoundCustom::Excluded);
+    assert_eq!(cursor.key(), Some(&"img7"));
+}
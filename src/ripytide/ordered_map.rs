@@ -0,0 +1,633 @@
+//! A map that owns the comparator it is ordered by.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::{Bound, Deref, DerefMut, Index, RangeBounds};
+
+use super::{Comparator, OrdComparator};
+use crate::btree_map::{
+    Entry, IntoIter, IntoKeys, IntoValues, Iter, IterMut, Keys, Range, RangeMut,
+    SearchBoundCustom, Values, ValuesMut,
+};
+use crate::BTreeMap;
+
+#[cfg(test)]
+mod tests;
+
+/// An ordered map that stores its comparator, so that call sites no longer pass one.
+///
+/// [`BTreeMap`] takes a comparator on every call that needs one, which makes it easy
+/// for a single call site to pass a mismatched comparator and silently corrupt the
+/// tree. `OrderedMap` wraps a `BTreeMap` together with a [`Comparator`] chosen once at
+/// construction and uses it for every insertion, lookup and range query.
+///
+/// Lookups accept any borrowed form `Q` of the key type for which the comparator is
+/// also a `Comparator<Q>`. With the default [`OrdComparator`] that is any form whose
+/// [`Ord`] implementation matches the key's, just like the standard library's map.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::OrderedMap;
+///
+/// // Order the map by descending key.
+/// let mut map = OrderedMap::new(|a: &i32, b: &i32| b.cmp(a));
+/// map.insert(1, "a");
+/// map.insert(3, "c");
+/// map.insert(2, "b");
+///
+/// assert_eq!(map.get(&2), Some(&"b"));
+/// assert_eq!(map[&3], "c");
+/// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [3, 2, 1]);
+/// assert_eq!(map.range(&3..&1).count(), 2);
+/// ```
+///
+/// Maps ordered by [`Ord`] can be collected directly:
+///
+/// ```
+/// use btree_monstrousity::OrderedMap;
+///
+/// let map: OrderedMap<String, usize> =
+///     ["b", "a"].into_iter().map(|s| (s.to_string(), s.len())).collect();
+/// assert_eq!(map["a"], 1);
+/// ```
+pub struct OrderedMap<K, V, C = OrdComparator> {
+    map: BTreeMap<K, V>,
+    comp: C,
+}
+
+impl<K, V, C> OrderedMap<K, V, C> {
+    /// Makes a new, empty `OrderedMap` ordered by `comp`.
+    ///
+    /// Does not allocate anything on its own.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    ///
+    /// let mut map = OrderedMap::new(|a: &&str, b: &&str| a.len().cmp(&b.len()));
+    /// map.insert("three", 3);
+    /// map.insert("one", 1);
+    /// assert_eq!(map.first_key_value(), Some((&"one", &1)));
+    /// ```
+    #[must_use]
+    pub const fn new(comp: C) -> Self {
+        OrderedMap { map: BTreeMap::new(), comp }
+    }
+
+    /// Returns a reference to the comparator the map is ordered by.
+    pub fn comparator(&self) -> &C {
+        &self.comp
+    }
+
+    /// Returns a guard giving mutable access to the comparator.
+    ///
+    /// The map is re-sorted by the modified comparator when the guard is dropped.
+    /// Entries whose keys compare equal under the new comparator are collapsed into
+    /// one, as if they had been inserted one after the other in their previous order.
+    /// If the guard is leaked, the map is left ordered by the previous comparator,
+    /// which is a logic error if the comparator was changed.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    /// use std::cmp::Ordering;
+    ///
+    /// let comp: Box<dyn Fn(&i32, &i32) -> Ordering> = Box::new(|a, b| a.cmp(b));
+    /// let mut map = OrderedMap::new(comp);
+    /// map.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+    ///
+    /// *map.comparator_mut() = Box::new(|a, b| b.cmp(a));
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [3, 2, 1]);
+    /// ```
+    pub fn comparator_mut(&mut self) -> ComparatorMut<'_, K, V, C>
+    where
+        C: Comparator<K>,
+    {
+        ComparatorMut(self)
+    }
+
+    /// Returns the underlying [`BTreeMap`].
+    pub fn as_map(&self) -> &BTreeMap<K, V> {
+        &self.map
+    }
+
+    /// Splits the map into the underlying [`BTreeMap`] and its comparator.
+    pub fn into_parts(self) -> (BTreeMap<K, V>, C) {
+        (self.map, self.comp)
+    }
+
+    /// Clears the map, removing all elements.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Returns the number of elements in the map.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the first key-value pair in the map.
+    /// The key in this pair is the minimum key in the map.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.map.first_key_value()
+    }
+
+    /// Returns the last key-value pair in the map.
+    /// The key in this pair is the maximum key in the map.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.map.last_key_value()
+    }
+
+    /// Removes and returns the first element in the map.
+    /// The key of this element is the minimum key that was in the map.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        self.map.pop_first()
+    }
+
+    /// Removes and returns the last element in the map.
+    /// The key of this element is the maximum key that was in the map.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        self.map.pop_last()
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// See [`BTreeMap::retain`].
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.map.retain(f)
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.map.iter()
+    }
+
+    /// Gets a mutable iterator over the entries of the map, sorted by key.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.map.iter_mut()
+    }
+
+    /// Gets an iterator over the keys of the map, in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        self.map.keys()
+    }
+
+    /// Gets an iterator over the values of the map, in order by key.
+    pub fn values(&self) -> Values<'_, K, V> {
+        self.map.values()
+    }
+
+    /// Gets a mutable iterator over the values of the map, in order by key.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        self.map.values_mut()
+    }
+
+    /// Creates a consuming iterator visiting all the keys, in sorted order.
+    pub fn into_keys(self) -> IntoKeys<K, V> {
+        self.map.into_keys()
+    }
+
+    /// Creates a consuming iterator visiting all the values, in order by key.
+    pub fn into_values(self) -> IntoValues<K, V> {
+        self.map.into_values()
+    }
+}
+
+impl<K, V, C: Comparator<K>> OrderedMap<K, V, C> {
+    /// Makes a new `OrderedMap` ordered by `comp` and fills it from `iter`.
+    ///
+    /// If `iter` yields several equal keys, the last pair is kept.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    ///
+    /// let map = OrderedMap::from_iter_by([(1, 'a'), (3, 'c'), (2, 'b')], |a: &i32, b: &i32| b.cmp(a));
+    /// assert_eq!(map.values().collect::<String>(), "cba");
+    /// ```
+    pub fn from_iter_by<I: IntoIterator<Item = (K, V)>>(iter: I, comp: C) -> Self {
+        let mut map = OrderedMap::new(comp);
+        map.extend(iter);
+        map
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `None` is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned. The key is not updated, though.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    ///
+    /// let mut map: OrderedMap<_, _> = OrderedMap::default();
+    /// assert_eq!(map.insert(37, "a"), None);
+    /// assert_eq!(map.insert(37, "b"), Some("a"));
+    /// assert_eq!(map[&37], "b");
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let comp = &self.comp;
+        self.map.insert(key, value, |a, b| comp.compare(a, b))
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    ///
+    /// let mut count: OrderedMap<_, _> = OrderedMap::default();
+    /// for x in ["a", "b", "a"] {
+    ///     *count.entry(x).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(count["a"], 2);
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let comp = &self.comp;
+        self.map.entry(key, |a, b| comp.compare(a, b))
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// If a key from `other` is already present in `self`, the respective
+    /// value from `self` will be overwritten with the respective value from `other`.
+    /// `self` keeps its own comparator; it is a logic error for `other` to be
+    /// ordered by a comparator that disagrees with it.
+    pub fn append(&mut self, other: &mut Self) {
+        let comp = &self.comp;
+        self.map.append(&mut other.map, |a, b| comp.compare(&a.0, &b.0))
+    }
+}
+
+impl<K, V, C> OrderedMap<K, V, C> {
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, as long as the
+    /// comparator orders that form the same way it orders the keys.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    ///
+    /// let mut map: OrderedMap<_, _> = OrderedMap::default();
+    /// map.insert(String::from("a"), 1);
+    /// assert_eq!(map.get("a"), Some(&1));
+    /// assert_eq!(map.get("b"), None);
+    /// ```
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        self.map.get(|k| self.comp.compare(key, k.borrow()))
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    pub fn get_key_value<Q: ?Sized>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        self.map.get_key_value(|k| self.comp.compare(key, k.borrow()))
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        let comp = &self.comp;
+        self.map.get_mut(|k| comp.compare(key, k.borrow()))
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        self.map.contains_key(|k| self.comp.compare(key, k.borrow()))
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        let comp = &self.comp;
+        self.map.remove(|k| comp.compare(key, k.borrow()))
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the key
+    /// was previously in the map.
+    pub fn remove_entry<Q: ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+    {
+        let comp = &self.comp;
+        self.map.remove_entry(|k| comp.compare(key, k.borrow()))
+    }
+
+    /// Splits the collection into two at the given key. Returns everything after
+    /// the given key, including the key.
+    ///
+    /// The returned map is ordered by a clone of this map's comparator.
+    pub fn split_off<Q: ?Sized>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q> + Clone,
+    {
+        let comp = &self.comp;
+        let map = self.map.split_off(|k| comp.compare(key, k.borrow()));
+        OrderedMap { map, comp: self.comp.clone() }
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the map.
+    ///
+    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)`
+    /// will yield elements from min (inclusive) to max (exclusive). The range may
+    /// also be entered as `(Bound<T>, Bound<T>)`, so for example
+    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive,
+    /// right-inclusive range from 4 to 10.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    /// use std::ops::Bound::Included;
+    ///
+    /// let map: OrderedMap<_, _> = [(3, "a"), (5, "b"), (8, "c")].into_iter().collect();
+    /// for (&key, &value) in map.range((Included(&4), Included(&8))) {
+    ///     println!("{key}: {value}");
+    /// }
+    /// assert_eq!(Some((&5, &"b")), map.range(4..).next());
+    /// ```
+    pub fn range<Q: ?Sized, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+        R: RangeBounds<Q>,
+    {
+        let (start, start_bound, end, end_bound) = range_ends(&self.comp, &range);
+        self.map.range(
+            |k| start.map_or(Ordering::Equal, |s| self.comp.compare(s, k.borrow())),
+            start_bound,
+            |k| end.map_or(Ordering::Equal, |e| self.comp.compare(e, k.borrow())),
+            end_bound,
+        )
+    }
+
+    /// Constructs a mutable double-ended iterator over a sub-range of elements in the map.
+    ///
+    /// The range is given as for [`OrderedMap::range`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    pub fn range_mut<Q: ?Sized, R>(&mut self, range: R) -> RangeMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        C: Comparator<Q>,
+        R: RangeBounds<Q>,
+    {
+        let comp = &self.comp;
+        let (start, start_bound, end, end_bound) = range_ends(comp, &range);
+        self.map.range_mut(
+            |k| start.map_or(Ordering::Equal, |s| comp.compare(s, k.borrow())),
+            start_bound,
+            |k| end.map_or(Ordering::Equal, |e| comp.compare(e, k.borrow())),
+            end_bound,
+        )
+    }
+}
+
+// Splits a range into the values and `SearchBoundCustom`s that `BTreeMap::range`
+// takes, panicking on inverted bounds like the standard library does.
+fn range_ends<'r, Q: ?Sized, C: Comparator<Q>, R: RangeBounds<Q>>(
+    comp: &C,
+    range: &'r R,
+) -> (Option<&'r Q>, SearchBoundCustom, Option<&'r Q>, SearchBoundCustom) {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Excluded(s), Bound::Excluded(e)) if comp.compare(s, e) == Ordering::Equal => {
+            panic!("range start and end are equal and excluded in OrderedMap")
+        }
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e))
+            if comp.compare(s, e) == Ordering::Greater =>
+        {
+            panic!("range start is greater than range end in OrderedMap")
+        }
+        _ => {}
+    }
+    let (start, start_bound) = match range.start_bound() {
+        Bound::Included(s) => (Some(s), SearchBoundCustom::Included),
+        Bound::Excluded(s) => (Some(s), SearchBoundCustom::Excluded),
+        Bound::Unbounded => (None, SearchBoundCustom::AllIncluded),
+    };
+    let (end, end_bound) = match range.end_bound() {
+        Bound::Included(e) => (Some(e), SearchBoundCustom::Included),
+        Bound::Excluded(e) => (Some(e), SearchBoundCustom::Excluded),
+        Bound::Unbounded => (None, SearchBoundCustom::AllIncluded),
+    };
+    (start, start_bound, end, end_bound)
+}
+
+/// A guard giving mutable access to the comparator of an [`OrderedMap`].
+///
+/// This `struct` is created by [`OrderedMap::comparator_mut`]. The map is re-sorted
+/// by the comparator when the guard is dropped.
+pub struct ComparatorMut<'a, K, V, C: Comparator<K>>(&'a mut OrderedMap<K, V, C>);
+
+impl<K, V, C: Comparator<K>> Deref for ComparatorMut<'_, K, V, C> {
+    type Target = C;
+    fn deref(&self) -> &C {
+        &self.0.comp
+    }
+}
+
+impl<K, V, C: Comparator<K>> DerefMut for ComparatorMut<'_, K, V, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.0.comp
+    }
+}
+
+impl<K, V, C: Comparator<K>> Drop for ComparatorMut<'_, K, V, C> {
+    fn drop(&mut self) {
+        let map = &mut *self.0;
+        let entries = mem::take(&mut map.map).into_iter().collect();
+        let comp = &map.comp;
+        map.map = BTreeMap::from_unsorted_by(entries, |a, b| comp.compare(a, b), overwrite);
+    }
+}
+
+// Resolves equal keys the way `insert` does: the first key stays, the last value wins.
+fn overwrite<V>(kept: &mut V, next: V) {
+    *kept = next;
+}
+
+impl<K, V, C: Default> Default for OrderedMap<K, V, C> {
+    /// Creates an empty `OrderedMap`, ordered by the default comparator.
+    fn default() -> Self {
+        OrderedMap::new(C::default())
+    }
+}
+
+impl<K: Clone, V: Clone, C: Clone> Clone for OrderedMap<K, V, C> {
+    fn clone(&self) -> Self {
+        OrderedMap { map: self.map.clone(), comp: self.comp.clone() }
+    }
+}
+
+impl<K: Debug, V: Debug, C> Debug for OrderedMap<K, V, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, C> PartialEq for OrderedMap<K, V, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Eq, V: Eq, C> Eq for OrderedMap<K, V, C> {}
+
+impl<K, Q: ?Sized, V, C> Index<&Q> for OrderedMap<K, V, C>
+where
+    K: Borrow<Q>,
+    C: Comparator<Q>,
+{
+    type Output = V;
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the `OrderedMap`.
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V, C: Comparator<K>> Extend<(K, V)> for OrderedMap<K, V, C> {
+    #[inline]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        let comp = &self.comp;
+        self.map.extend_by(iter, |a, b| comp.compare(a, b));
+    }
+
+    #[inline]
+    #[cfg(feature = "extend_one")]
+    fn extend_one(&mut self, (k, v): (K, V)) {
+        let comp = &self.comp;
+        self.map.replace_or_insert(k, v, |a, b| comp.compare(a, b));
+    }
+}
+
+impl<'a, K: Copy + 'a, V: Copy + 'a, C: Comparator<K>> Extend<(&'a K, &'a V)>
+    for OrderedMap<K, V, C>
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+
+    #[inline]
+    #[cfg(feature = "extend_one")]
+    fn extend_one(&mut self, (&k, &v): (&'a K, &'a V)) {
+        self.extend_one((k, v));
+    }
+}
+
+impl<K, V, C: Comparator<K> + Default> FromIterator<(K, V)> for OrderedMap<K, V, C> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        OrderedMap::from_iter_by(iter, C::default())
+    }
+}
+
+impl<K, V, C: Comparator<K> + Default, const N: usize> From<[(K, V); N]>
+    for OrderedMap<K, V, C>
+{
+    /// ```
+    /// use btree_monstrousity::OrderedMap;
+    ///
+    /// let map1 = OrderedMap::<_, _>::from([(1, 2), (3, 4)]);
+    /// let map2: OrderedMap<_, _> = [(1, 2), (3, 4)].into();
+    /// assert_eq!(map1, map2);
+    /// ```
+    fn from(arr: [(K, V); N]) -> Self {
+        OrderedMap::from_iter(arr)
+    }
+}
+
+impl<K, V, C> IntoIterator for OrderedMap<K, V, C> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        self.map.into_iter()
+    }
+}
+
+impl<'a, K, V, C> IntoIterator for &'a OrderedMap<K, V, C> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, C> IntoIterator for &'a mut OrderedMap<K, V, C> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
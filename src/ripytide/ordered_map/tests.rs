@@ -0,0 +1,147 @@
+use super::*;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::ops::Bound::{Excluded, Included, Unbounded};
+
+#[test]
+fn test_basic_ord() {
+    let mut map: OrderedMap<_, _> = OrderedMap::default();
+    assert_eq!(map.insert(2, 'b'), None);
+    assert_eq!(map.insert(1, 'a'), None);
+    assert_eq!(map.insert(2, 'c'), Some('b'));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&2), Some(&'c'));
+    assert_eq!(map[&1], 'a');
+    assert!(map.contains_key(&1));
+    assert!(!map.contains_key(&3));
+    *map.get_mut(&1).unwrap() = 'z';
+    assert_eq!(map.remove_entry(&1), Some((1, 'z')));
+    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(&2, &'c')]);
+}
+
+#[test]
+fn test_custom_comparator() {
+    let mut map = OrderedMap::new(|a: &i32, b: &i32| b.cmp(a));
+    map.extend([(1, "one"), (3, "three"), (2, "two")]);
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), [3, 2, 1]);
+    assert_eq!(map.first_key_value(), Some((&3, &"three")));
+    assert_eq!(map.get(&2), Some(&"two"));
+
+    *map.entry(4).or_insert("zero") = "four";
+    assert_eq!(map.pop_first(), Some((4, "four")));
+    assert_eq!(map.pop_last(), Some((1, "one")));
+}
+
+#[test]
+fn test_borrowed_lookup() {
+    let mut map: OrderedMap<String, usize> = OrderedMap::default();
+    for s in ["ab", "a", "abc"] {
+        map.insert(s.to_string(), s.len());
+    }
+    assert_eq!(map["abc"], 3);
+    assert_eq!(map.get_key_value("a"), Some((&"a".to_string(), &1)));
+    assert_eq!(map.range::<str, _>((Excluded("a"), Unbounded)).count(), 2);
+    assert_eq!(map.remove("ab"), Some(2));
+}
+
+#[test]
+fn test_range() {
+    let map = OrderedMap::from_iter_by((0..10).map(|i| (i, i * 10)), |a: &i32, b: &i32| b.cmp(a));
+    let keys = |r: Range<'_, i32, i32>| r.map(|(k, _)| *k).collect::<Vec<_>>();
+    assert_eq!(keys(map.range(&7..&4)), [7, 6, 5]);
+    assert_eq!(keys(map.range((Excluded(&7), Included(&4)))), [6, 5, 4]);
+    assert_eq!(keys(map.range(..=&8)), [9, 8]);
+    assert_eq!(keys(map.range(&1..)), [1, 0]);
+
+    let mut map = map;
+    for (_, v) in map.range_mut(&2..=&1) {
+        *v = 0;
+    }
+    assert_eq!(map[&2], 0);
+    assert_eq!(map[&1], 0);
+    assert_eq!(map[&3], 30);
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end in OrderedMap")]
+fn test_range_inverted() {
+    let map = OrderedMap::from_iter_by([(1, ()), (2, ())], |a: &i32, b: &i32| b.cmp(a));
+    // In this map 1 sorts after 2.
+    let _ = map.range(&1..&2);
+}
+
+#[test]
+#[should_panic(expected = "range start and end are equal and excluded in OrderedMap")]
+fn test_range_equal_excluded() {
+    let map: OrderedMap<i32, ()> = OrderedMap::default();
+    let _ = map.range((Excluded(&1), Excluded(&1)));
+}
+
+#[test]
+fn test_append_and_split_off() {
+    let reversed = |a: &i32, b: &i32| b.cmp(a);
+    let mut a = OrderedMap::from_iter_by([(1, 'a'), (3, 'c')], reversed);
+    let mut b = OrderedMap::from_iter_by([(2, 'b'), (3, 'C'), (4, 'd')], reversed);
+    a.append(&mut b);
+    assert!(b.is_empty());
+    assert_eq!(a.iter().collect::<Vec<_>>(), [(&4, &'d'), (&3, &'C'), (&2, &'b'), (&1, &'a')]);
+
+    let low = a.split_off(&2);
+    assert_eq!(a.keys().copied().collect::<Vec<_>>(), [4, 3]);
+    assert_eq!(low.keys().copied().collect::<Vec<_>>(), [2, 1]);
+}
+
+#[test]
+fn test_comparator_mut() {
+    let mut map: OrderedMap<i32, char, fn(&i32, &i32) -> Ordering> = OrderedMap::new(i32::cmp);
+    map.extend([(-2, 'a'), (1, 'b'), (2, 'c'), (3, 'd')]);
+
+    fn by_abs(a: &i32, b: &i32) -> Ordering {
+        a.abs().cmp(&b.abs())
+    }
+    *map.comparator_mut() = by_abs;
+
+    // -2 and 2 now compare equal; the later entry's value wins.
+    assert_eq!(map.iter().collect::<Vec<_>>(), [(&1, &'b'), (&-2, &'c'), (&3, &'d')]);
+    assert_eq!(map.get(&2), Some(&'c'));
+    assert_eq!(map.get(&-3), Some(&'d'));
+}
+
+#[test]
+#[should_panic(expected = "no entry found for key")]
+fn test_index_missing_panics() {
+    let map: OrderedMap<i32, i32> = OrderedMap::from([(1, 1)]);
+    let _ = map[&2];
+}
+
+#[test]
+fn test_extend_ref_and_collect() {
+    let mut map: OrderedMap<i32, i32> = vec![(3, 30), (1, 10)].into_iter().collect();
+    map.extend([(&2, &20), (&1, &11)]);
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 11), (2, 20), (3, 30)]);
+}
+
+// Keys that compare equal by their first field, so a test can tell which one
+// ends up in the map.
+fn by_first(a: &(i32, char), b: &(i32, char)) -> Ordering {
+    a.0.cmp(&b.0)
+}
+
+#[test]
+fn test_extend_replaces_key() {
+    let mut map = OrderedMap::new(by_first);
+    map.insert((1, 'a'), 10);
+    map.extend([((1, 'b'), 11)]);
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [((1, 'b'), 11)]);
+}
+
+#[test]
+#[cfg(feature = "extend_one")]
+fn test_extend_one_replaces_key() {
+    let mut map = OrderedMap::new(by_first);
+    map.insert((1, 'a'), 10);
+    map.extend_one(((1, 'b'), 11));
+    map.extend_one((&(1, 'c'), &12));
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [((1, 'c'), 12)]);
+}
@@ -0,0 +1,147 @@
+//! Queries: what a lookup looks for, compared against the keys of a map.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+
+use super::Comparator;
+
+#[cfg(test)]
+mod tests;
+
+/// What a lookup in a map looks for, which can tell how it compares to each
+/// key of the map.
+///
+/// Queries come in three forms:
+/// - an `FnMut(&K) -> Ordering` closure, the form [`BTreeMap`] lookups take;
+/// - a `(&Q, comparator)` pair, which compares `&Q` to the [borrowed](Borrow)
+///   form of each key with a two-argument comparator, so the comparator used to
+///   insert keys can be used to find them as well;
+/// - a reference to a standard library type, which is compared to the borrowed
+///   form of each key by its [`Ord`] implementation, like `&str` for `String`
+///   keys. A reference to a type of another crate can be made a query by
+///   implementing this trait for it.
+///
+/// [`BTreeMap::get_q`], [`BTreeMap::get_mut_q`], [`BTreeMap::contains_key_q`],
+/// [`BTreeMap::remove_q`], [`BTreeMap::range_q`], [`BTreeMap::split_off_q`],
+/// [`BTreeMap::lower_bound_q`] and [`BTreeMap::upper_bound_q`] take any query.
+/// Their counterparts without the `_q` suffix take closures only, so that the
+/// type of the closure's argument can be left out, as in `|k| 1.cmp(k)`; a
+/// closure passed for a parameter bounded by `Query` rather than `FnMut` would
+/// need it spelled out, as in `|k: &i32| 1.cmp(k)`. [`query`] turns any query
+/// into a closure, for the methods that only take closures.
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::BTreeMap;
+///
+/// let by_len = |a: &String, b: &String| a.len().cmp(&b.len()).then(a.cmp(b));
+/// let mut map = BTreeMap::new();
+/// for word in ["ccc", "a", "bb"] {
+///     map.insert(word.to_string(), word.len(), by_len);
+/// }
+///
+/// // Looks up a key with the comparator the map was built with.
+/// assert_eq!(map.get_q((&"bb".to_string(), by_len)), Some(&2));
+/// // Closures are queries too.
+/// assert_eq!(map.remove_q(|k: &String| 3.cmp(&k.len())), Some(3));
+/// ```
+///
+/// [`BTreeMap`]: crate::BTreeMap
+/// [`BTreeMap::get_q`]: crate::BTreeMap::get_q
+/// [`BTreeMap::get_mut_q`]: crate::BTreeMap::get_mut_q
+/// [`BTreeMap::contains_key_q`]: crate::BTreeMap::contains_key_q
+/// [`BTreeMap::remove_q`]: crate::BTreeMap::remove_q
+/// [`BTreeMap::range_q`]: crate::BTreeMap::range_q
+/// [`BTreeMap::split_off_q`]: crate::BTreeMap::split_off_q
+/// [`BTreeMap::lower_bound_q`]: crate::BTreeMap::lower_bound_q
+/// [`BTreeMap::upper_bound_q`]: crate::BTreeMap::upper_bound_q
+pub trait Query<K: ?Sized> {
+    /// Returns how the sought key compares to `key`.
+    fn compare(&mut self, key: &K) -> Ordering;
+}
+
+impl<K: ?Sized, F> Query<K> for F
+where
+    F: FnMut(&K) -> Ordering,
+{
+    #[inline]
+    fn compare(&mut self, key: &K) -> Ordering {
+        self(key)
+    }
+}
+
+impl<K, Q, C> Query<K> for (&Q, C)
+where
+    K: Borrow<Q> + ?Sized,
+    Q: ?Sized,
+    C: Comparator<Q>,
+{
+    #[inline]
+    fn compare(&mut self, key: &K) -> Ordering {
+        self.1.compare(self.0, key.borrow())
+    }
+}
+
+// A blanket impl for `&Q` where `Q: Ord` would overlap with the one for
+// closures, since `&F` is a closure whenever `F` is. The standard library
+// types below are known not to be closures.
+macro_rules! query_by_ord {
+    ($([$($params:tt)*] $t:ty),* $(,)?) => {$(
+        impl<K, $($params)*> Query<K> for &$t
+        where
+            K: Borrow<$t> + ?Sized,
+        {
+            #[inline]
+            fn compare(&mut self, key: &K) -> Ordering {
+                Ord::cmp(*self, key.borrow())
+            }
+        }
+    )*};
+}
+
+query_by_ord! {
+    [] u8, [] u16, [] u32, [] u64, [] u128, [] usize,
+    [] i8, [] i16, [] i32, [] i64, [] i128, [] isize,
+    [] bool, [] char, [] str, [] alloc::string::String,
+    [T: Ord] [T], [T: Ord, const N: usize] [T; N], [T: Ord] alloc::vec::Vec<T>,
+}
+
+#[cfg(feature = "std")]
+query_by_ord! {
+    [] std::ffi::OsStr, [] std::ffi::OsString, [] std::path::Path, [] std::path::PathBuf,
+}
+
+/// Turns `query` into a closure, for the methods of [`BTreeMap`](crate::BTreeMap)
+/// that take closures rather than any [`Query`].
+///
+/// # Examples
+///
+/// ```
+/// use btree_monstrousity::btree_map::SearchBoundCustom::{Excluded, Included};
+/// use btree_monstrousity::ripytide::{query, OrdComparator};
+/// use btree_monstrousity::BTreeMap;
+///
+/// let by_len = |a: &String, b: &String| a.len().cmp(&b.len()).then(a.cmp(b));
+/// let mut map = BTreeMap::new();
+/// for word in ["ccc", "a", "bb"] {
+///     map.insert(word.to_string(), word.len(), by_len);
+/// }
+///
+/// // Looks up a key with the comparator the map was built with.
+/// assert_eq!(map.get(query((&"bb".to_string(), by_len))), Some(&2));
+/// // Closures still work, with or without `query`.
+/// assert_eq!(map.get(query(|k: &String| 3.cmp(&k.len()))), Some(&3));
+///
+/// // Compares a borrowed form of the keys by `Ord`, for a map ordered by `Ord`.
+/// let mut map = BTreeMap::new();
+/// for i in 0..10 {
+///     map.insert(i.to_string(), i, Ord::cmp);
+/// }
+/// assert_eq!(map.get(query("7")), Some(&7));
+/// let range = map.range(query("3"), Included, query(("6", OrdComparator)), Excluded);
+/// assert_eq!(range.count(), 3);
+/// ```
+pub fn query<K: ?Sized>(mut query: impl Query<K>) -> impl FnMut(&K) -> Ordering {
+    move |key| query.compare(key)
+}
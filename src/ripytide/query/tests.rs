@@ -0,0 +1,74 @@
+use super::*;
+use crate::btree_map::SearchBoundCustom;
+use crate::ripytide::compare::{ascii_case_insensitive, reverse};
+use crate::ripytide::OrdComparator;
+use crate::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+fn compare<K: ?Sized>(mut q: impl Query<K>, key: &K) -> Ordering {
+    q.compare(key)
+}
+
+#[test]
+fn test_forms() {
+    let key = "b".to_string();
+    assert_eq!(compare(|k: &String| "a".cmp(k.as_str()), &key), Ordering::Less);
+    assert_eq!(compare("c", &key), Ordering::Greater);
+    assert_eq!(compare(&"b".to_string(), &key), Ordering::Equal);
+    assert_eq!(compare(("B", ascii_case_insensitive::<str, str>), &key), Ordering::Equal);
+    assert_eq!(compare((&1, reverse(OrdComparator)), &2), Ordering::Greater);
+    assert_eq!(compare(&[1, 2][..], &vec![1, 3]), Ordering::Less);
+    assert_eq!(compare(&[1, 2], &[1, 2]), Ordering::Equal);
+}
+
+#[test]
+fn test_lookups() {
+    let comp = reverse(OrdComparator);
+    let mut map = BTreeMap::new();
+    for i in 0..100u32 {
+        map.insert(i, i * 10, &comp);
+    }
+    assert_eq!(map.get_q((&7, &comp)), Some(&70));
+    assert!(!map.contains_key_q((&100, &comp)));
+    *map.get_mut_q((&7, &comp)).unwrap() += 1;
+    assert_eq!(map.remove_q((&7, &comp)), Some(71));
+    let range = map.range_q(
+        (&9, &comp),
+        SearchBoundCustom::Included,
+        (&5, &comp),
+        SearchBoundCustom::Excluded,
+    );
+    assert_eq!(range.map(|(k, _)| *k).collect::<Vec<_>>(), [9, 8, 6]);
+    let low = map.split_off_q((&50, &comp));
+    assert_eq!(map.len(), 49);
+    assert!(low.keys().eq((0..=50).rev().filter(|&k| k != 7).collect::<Vec<_>>().iter()));
+}
+
+#[test]
+fn test_closures() {
+    let mut map = BTreeMap::new();
+    for i in 0..10 {
+        map.insert(i.to_string(), i, Ord::cmp);
+    }
+    assert_eq!(map.get_q(|k: &String| "3".cmp(k.as_str())), Some(&3));
+    assert_eq!(map.get(query("3")), Some(&3));
+    assert_eq!(map.remove(query(("4", OrdComparator))), Some(4));
+    assert!(!map.contains_key_q("4"));
+}
+
+#[test]
+#[cfg(feature = "btree_cursors")]
+fn test_cursors() {
+    let mut map = BTreeMap::<String, usize>::new();
+    for word in ["apple", "banana", "cherry"] {
+        map.insert(word.to_string(), word.len(), Ord::cmp);
+    }
+    let cursor = map.lower_bound_q("b", SearchBoundCustom::Included);
+    assert_eq!(cursor.key().map(String::as_str), Some("banana"));
+    let cursor = map.upper_bound_q("banana", SearchBoundCustom::Excluded);
+    assert_eq!(cursor.key().map(String::as_str), Some("apple"));
+    let cursor = map.lower_bound(query(("c", OrdComparator)), SearchBoundCustom::Excluded);
+    assert_eq!(cursor.key().map(String::as_str), Some("cherry"));
+}